@@ -0,0 +1,254 @@
+use crate::{CommandError, Data};
+use crate::db::{ActiveGame, Database, EscrowOutcome};
+use super::board_games::correspondence_games;
+use super::challenge::GameRegistry;
+use poise::serenity_prelude as serenity;
+use serde::Serialize;
+
+// Game types saved by games other than the two-player ones, which use their rating keys
+pub(crate) const HANGMAN: &str = "soviet_hangman";
+pub(crate) const KREMLIN_SECRETS: &str = "kremlin_secrets";
+
+// A resumed game left untouched for this long is abandoned on the next press
+const IDLE_TIMEOUT_SECS: i64 = 300;
+
+// A resumed game played by correspondence, whose players may take days over a move,
+// is only abandoned after this long
+pub const CORRESPONDENCE_IDLE_SECS: i64 = 3 * 24 * 60 * 60;
+
+// What a resumed game made of an interaction or message
+pub(crate) enum Resumed {
+    // It wasn't meant for this game, e.g. a press from someone whose turn it isn't
+    Ignored,
+    // It was answered without changing the game, e.g. by opening a pop-up
+    Answered,
+    // The game goes on with this new saved state
+    Continue(String),
+    // The game has ended and its record can be deleted
+    Finished,
+}
+
+// Run at startup: note the channels of saved Kremlin Secrets games, which take their
+// guesses from chat. A game is only resumed once its command has stopped, i.e. after a
+// restart, so no later game needs watching. Returns the number of channels.
+pub async fn watch_resumable_channels(db: &Database, games: &GameRegistry) -> Result<usize, CommandError> {
+    let channels = db.get_active_game_channels(KREMLIN_SECRETS).await?;
+    let channels: Vec<serenity::ChannelId> = channels.iter()
+        .filter_map(|id| id.parse().ok().map(serenity::ChannelId))
+        .collect();
+    
+    let count = channels.len();
+    games.watch_channels(channels);
+    Ok(count)
+}
+
+// Save a game's state after a move so it can be resumed if the bot restarts
+pub(crate) async fn save_game<T: Serialize>(
+    ctx: crate::Context<'_>,
+    message_id: serenity::MessageId,
+    game_type: &str,
+    state: &T,
+    escrow_id: Option<i64>,
+) -> Result<(), CommandError> {
+    let record = ActiveGame {
+        message_id: message_id.to_string(),
+        channel_id: ctx.channel_id().to_string(),
+        server_id: ctx.guild_id().map(|id| id.to_string()).unwrap_or_else(|| "DM".to_string()),
+        game_type: game_type.to_string(),
+        state: serde_json::to_string(state)?,
+        escrow_id,
+        updated_at: chrono::Utc::now().timestamp(),
+    };
+
+    ctx.data().db.save_active_game(&record).await?;
+    Ok(())
+}
+
+// Forget a game once its command has seen it through to the end
+pub(crate) async fn forget_game(ctx: crate::Context<'_>, message_id: serenity::MessageId) -> Result<(), CommandError> {
+    ctx.data().db.delete_active_game(&message_id.to_string()).await?;
+    Ok(())
+}
+
+// Route a button press on a saved game whose command is no longer running,
+// e.g. because the bot restarted mid-game. Presses on other messages are left alone.
+pub async fn route_component_interaction(
+    ctx: &serenity::Context,
+    data: &Data,
+    press: &serenity::MessageComponentInteraction,
+) -> Result<(), CommandError> {
+    // A running command's collector already handles its own game
+    if data.games.is_live(press.message.id) {
+        return Ok(());
+    }
+
+    // Handle one resumed press at a time so two quick presses can't both apply to the same state
+    let _guard = data.games.lock_resumes().await;
+
+    let record = match data.db.get_active_game(&press.message.id.to_string()).await? {
+        Some(record) => record,
+        None => return Ok(()),
+    };
+
+    if is_idle(&record) {
+        let note = abandon_game(data, &record).await?;
+        press.create_interaction_response(ctx, clear_components).await?;
+        press.create_followup_message(ctx, |f| f.content(note)).await?;
+        return Ok(());
+    }
+
+    let resumed = match record.game_type.as_str() {
+        "tictactoe" | "connect4" => super::games::resume_game_press(ctx, data, &record, press).await?,
+        "battleship" => super::battleship::resume_battleship_press(ctx, data, &record, press).await?,
+        "chess" | "checkers" => super::board_games::resume_board_press(ctx, data, &record, press).await?,
+        HANGMAN => super::soviet_hangman::resume_hangman_press(ctx, data, &record, press).await?,
+        KREMLIN_SECRETS => super::kremlin_secrets::resume_kremlin_press(ctx, data, &record, press).await?,
+        _ => return Ok(()),
+    };
+
+    if let Resumed::Ignored = resumed {
+        press.create_interaction_response(ctx, |r| {
+            r.kind(serenity::InteractionResponseType::ChannelMessageWithSource)
+             .interaction_response_data(|d| d.content("That move isn't yours to make, comrade.").ephemeral(true))
+        }).await?;
+    }
+
+    store_resumed(data, record, resumed).await
+}
+
+// Route a word typed into a game's pop-up to its saved game when the command that
+// opened it is no longer running
+pub async fn route_modal_submit(
+    ctx: &serenity::Context,
+    data: &Data,
+    submit: &serenity::ModalSubmitInteraction,
+) -> Result<(), CommandError> {
+    let message_id = match &submit.message {
+        Some(message) => message.id,
+        None => return Ok(()),
+    };
+    if data.games.is_live(message_id) {
+        return Ok(());
+    }
+
+    let guard = data.games.lock_resumes().await;
+
+    let record = match data.db.get_active_game(&message_id.to_string()).await? {
+        Some(record) => record,
+        None => return Ok(()),
+    };
+
+    if is_idle(&record) {
+        let note = abandon_game(data, &record).await?;
+        submit.create_interaction_response(ctx, clear_components).await?;
+        submit.create_followup_message(ctx, |f| f.content(note)).await?;
+        return Ok(());
+    }
+
+    let mut ruling = None;
+    let resumed = match record.game_type.as_str() {
+        HANGMAN => super::soviet_hangman::resume_hangman_word(ctx, data, &record, submit).await?,
+        "chess" | "checkers" => super::board_games::resume_board_move(ctx, data, &record, submit).await?,
+        KREMLIN_SECRETS => {
+            let (resumed, counted) = super::kremlin_secrets::resume_kremlin_submit(ctx, data, &record, submit).await?;
+            ruling = counted;
+            resumed
+        },
+        _ => return Ok(()),
+    };
+
+    store_resumed(data, record, resumed).await?;
+
+    // The Kremlin deliberates over a guess after the lock is released, so it holds up no other game
+    drop(guard);
+    if let Some(ruling) = ruling {
+        ruling.show_in_reply(ctx, submit).await?;
+    }
+
+    Ok(())
+}
+
+// Route a chat message to a saved Kremlin Secrets game in its channel that is
+// no longer being run by its command
+pub async fn route_message(
+    ctx: &serenity::Context,
+    data: &Data,
+    message: &serenity::Message,
+) -> Result<(), CommandError> {
+    // Most messages are sent where no saved game is waiting for them
+    if !data.games.is_watched(message.channel_id) {
+        return Ok(());
+    }
+
+    let records = data.db.get_active_games_in_channel(&message.channel_id.to_string(), KREMLIN_SECRETS).await?;
+    if records.is_empty() {
+        data.games.unwatch_channel(message.channel_id);
+        return Ok(());
+    }
+
+    for record in records {
+        let message_id = serenity::MessageId(record.message_id.parse()?);
+        if data.games.is_live(message_id) {
+            continue;
+        }
+
+        let guard = data.games.lock_resumes().await;
+
+        let (resumed, ruling) = super::kremlin_secrets::resume_kremlin_guess(ctx, data, &record, message).await?;
+        if let Resumed::Ignored = resumed {
+            continue;
+        }
+        store_resumed(data, record, resumed).await?;
+
+        // The Kremlin deliberates over a guess after the lock is released, so it holds up no other game
+        drop(guard);
+        if let Some(ruling) = ruling {
+            ruling.show_in_channel(ctx, message_id, message).await?;
+        }
+        return Ok(());
+    }
+
+    Ok(())
+}
+
+async fn store_resumed(data: &Data, record: ActiveGame, resumed: Resumed) -> Result<(), CommandError> {
+    match resumed {
+        Resumed::Ignored | Resumed::Answered => {},
+        Resumed::Continue(state) => data.db.save_active_game(&ActiveGame { state, ..record }).await?,
+        Resumed::Finished => data.db.delete_active_game(&record.message_id).await?,
+    }
+
+    Ok(())
+}
+
+// Whether a saved game has gone untouched for longer than its players are given.
+// Kremlin Secrets keeps its own clock and settles a game whose time has run out.
+fn is_idle(record: &ActiveGame) -> bool {
+    let idle_timeout = match record.game_type.as_str() {
+        KREMLIN_SECRETS => return false,
+        game_type if correspondence_games().contains(&game_type) => CORRESPONDENCE_IDLE_SECS,
+        _ => IDLE_TIMEOUT_SECS,
+    };
+
+    chrono::Utc::now().timestamp() - record.updated_at > idle_timeout
+}
+
+// End a resumed game nobody has played for too long, returning any stakes.
+// Returns the note to follow up the interaction that found it with.
+async fn abandon_game(data: &Data, record: &ActiveGame) -> Result<String, CommandError> {
+    if let Some(escrow_id) = record.escrow_id {
+        data.db.settle_escrow(escrow_id, &EscrowOutcome::Refund).await?;
+    }
+    data.db.delete_active_game(&record.message_id).await?;
+
+    let refund_note = if record.escrow_id.is_some() { " All stakes have been returned." } else { "" };
+    Ok(format!("This game was abandoned due to inactivity, comrade.{}", refund_note))
+}
+
+// Take the controls off an abandoned game's message
+fn clear_components<'a, 'b>(
+    r: &'a mut serenity::CreateInteractionResponse<'b>,
+) -> &'a mut serenity::CreateInteractionResponse<'b> {
+    r.kind(serenity::InteractionResponseType::UpdateMessage)
+     .interaction_response_data(|d| d.components(|c| c))
+}
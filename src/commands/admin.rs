@@ -5,7 +5,7 @@ use std::time::Duration;
 const OWNER_ID: &str = "1151230208783945818";
 
 /// Check if the user has admin permissions
-async fn check_if_admin(ctx: crate::Context<'_>) -> Result<bool, CommandError> {
+pub(crate) async fn check_if_admin(ctx: crate::Context<'_>) -> Result<bool, CommandError> {
     let member = match ctx.guild_id() {
         Some(_guild_id) => match ctx.author_member().await {
             Some(member) => member,
@@ -16,7 +16,7 @@ async fn check_if_admin(ctx: crate::Context<'_>) -> Result<bool, CommandError> {
         None => return Ok(false),
     };
 
-    Ok(member.permissions.map_or(false, |perms| perms.administrator()))
+    Ok(member.permissions.is_some_and(|perms| perms.administrator()))
 }
 
 /// Clear your cooldowns (Owner only)
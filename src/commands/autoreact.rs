@@ -0,0 +1,400 @@
+use crate::CommandError;
+use crate::db::AutoReactRule;
+use super::admin::check_if_admin;
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+// Maximum number of rules a single server may define
+const MAX_RULES_PER_SERVER: usize = 25;
+
+// Regex size limit so admins can't configure patterns that are expensive to evaluate
+const REGEX_SIZE_LIMIT: usize = 1 << 16;
+
+// Longest Unicode emoji accepted, in chars. Family and skin-toned ZWJ sequences run to about 10.
+const MAX_EMOJI_CHARS: usize = 16;
+
+fn build_regex(pattern: &str) -> Result<regex::Regex, regex::Error> {
+    regex::RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+}
+
+// Regex triggers compiled so far, by rule id, with the pattern each was compiled from
+// in case a rule id is reused. A pattern that fails to compile is kept as None so it
+// isn't retried on every message.
+type CompiledRegexes = Mutex<HashMap<i64, (String, Option<regex::Regex>)>>;
+
+fn compiled_regexes() -> &'static CompiledRegexes {
+    static COMPILED: OnceLock<CompiledRegexes> = OnceLock::new();
+    COMPILED.get_or_init(Default::default)
+}
+
+fn compiled_regex(rule: &AutoReactRule) -> Option<regex::Regex> {
+    let mut compiled = compiled_regexes().lock().unwrap();
+    if let Some((pattern, re)) = compiled.get(&rule.id) {
+        if *pattern == rule.pattern {
+            return re.clone();
+        }
+    }
+
+    let re = build_regex(&rule.pattern).ok();
+    compiled.insert(rule.id, (rule.pattern.clone(), re.clone()));
+    re
+}
+
+// The words of a message or trigger, lowercased, with punctuation and spacing dropped
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Check whether a message matches an auto-reaction rule's trigger
+pub fn autoreact_matches(rule: &AutoReactRule, content: &str) -> bool {
+    if rule.is_regex {
+        return compiled_regex(rule).is_some_and(|re| re.is_match(content));
+    }
+
+    // A trigger of several words matches them in order, as whole words
+    let content = words(content);
+    rule.pattern
+        .split(',')
+        .map(words)
+        .filter(|trigger| !trigger.is_empty())
+        .any(|trigger| content.windows(trigger.len()).any(|window| window == trigger.as_slice()))
+}
+
+// Whether a char is a pictograph that can start an emoji, as in Unicode's
+// Extended_Pictographic property
+fn is_pictograph(c: char) -> bool {
+    matches!(c,
+        '\u{A9}' | '\u{AE}' | '\u{203C}' | '\u{2049}' | '\u{2122}' | '\u{2139}'
+        | '\u{2194}'..='\u{2199}' | '\u{21A9}'..='\u{21AA}' | '\u{231A}'..='\u{231B}'
+        | '\u{2328}' | '\u{23CF}' | '\u{23E9}'..='\u{23F3}' | '\u{23F8}'..='\u{23FA}'
+        | '\u{24C2}' | '\u{25AA}'..='\u{25AB}' | '\u{25B6}' | '\u{25C0}' | '\u{25FB}'..='\u{25FE}'
+        | '\u{2600}'..='\u{27BF}' | '\u{2934}'..='\u{2935}' | '\u{2B05}'..='\u{2B07}'
+        | '\u{2B1B}'..='\u{2B1C}' | '\u{2B50}' | '\u{2B55}' | '\u{3030}' | '\u{303D}'
+        | '\u{3297}' | '\u{3299}' | '\u{1F000}'..='\u{1FAFF}')
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+// One emoji of a sequence joined with zero-width joiners: a pictograph or a keycap,
+// with any presentation selector, skin tone and tag sequence after it
+fn is_emoji_part(part: &str) -> bool {
+    let is_tag = |c: &char| ('\u{E0020}'..='\u{E007E}').contains(c);
+    let mut chars = part.chars().peekable();
+    
+    match chars.next() {
+        Some('0'..='9' | '#' | '*') => {
+            chars.next_if_eq(&'\u{FE0F}');
+            return chars.next() == Some('\u{20E3}') && chars.next().is_none();
+        },
+        Some(c) if is_pictograph(c) => {},
+        _ => return false,
+    }
+    
+    chars.next_if(|c| matches!(c, '\u{FE0E}' | '\u{FE0F}'));
+    chars.next_if(|c| ('\u{1F3FB}'..='\u{1F3FF}').contains(c));
+    chars.next_if_eq(&'\u{FE0F}');
+    
+    // Tag sequences spell out subdivision flags, such as Scotland's
+    if chars.peek().is_some_and(is_tag) {
+        while chars.next_if(is_tag).is_some() {}
+        return chars.next() == Some('\u{E007F}') && chars.next().is_none();
+    }
+    
+    chars.next().is_none()
+}
+
+// Whether text is a single Unicode emoji, which Discord will accept as a reaction
+fn is_unicode_emoji(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() || chars.len() > MAX_EMOJI_CHARS {
+        return false;
+    }
+    
+    // A country flag is a pair of regional indicators
+    if chars.len() == 2 && chars.iter().all(|c| is_regional_indicator(*c)) {
+        return true;
+    }
+    
+    text.split('\u{200D}').all(is_emoji_part)
+}
+
+// Pull channel ids out of a list of channel mentions or raw ids
+pub(crate) fn parse_channel_ids(input: &str) -> Vec<String> {
+    input
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|part| part.trim_start_matches("<#").trim_end_matches('>'))
+        .filter(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        .map(|part| part.to_string())
+        .collect()
+}
+
+/// Manage automatic emoji reactions for this server (Admin only)
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("autoreact_add", "autoreact_remove", "autoreact_list"),
+    check = "check_if_admin"
+)]
+pub async fn autoreact(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    ctx.say("Use `/autoreact add`, `/autoreact remove` or `/autoreact list`, comrade.").await?;
+    Ok(())
+}
+
+/// Add an automatic reaction rule
+#[poise::command(slash_command, prefix_command, rename = "add", check = "check_if_admin")]
+pub async fn autoreact_add(
+    ctx: crate::Context<'_>,
+    #[description = "Trigger words or phrases separated by commas, or a regex pattern"]
+    triggers: String,
+    #[description = "Emoji to react with (unicode or custom server emoji)"]
+    emoji: String,
+    #[description = "Trigger type: words (default) or regex"]
+    mode: Option<String>,
+    #[description = "Chance to react when triggered, in percent (1-100, default: 100)"]
+    probability: Option<f64>,
+    #[description = "Channels to react in, as mentions or IDs (default: all channels)"]
+    channels: Option<String>,
+    #[description = "Seconds before the same comrade can trigger this rule again (default: 60)"]
+    cooldown_seconds: Option<i64>,
+) -> Result<(), CommandError> {
+    let guild_id = match ctx.guild_id() {
+        Some(id) => id,
+        None => return Err("This command can only be used in a server!".into()),
+    };
+    let server_id = guild_id.to_string();
+
+    let is_regex = match mode.as_deref().unwrap_or("words").to_lowercase().as_str() {
+        "regex" => true,
+        "words" | "word" => false,
+        _ => return Err("Trigger type must be either `words` or `regex`, comrade.".into()),
+    };
+
+    // Validate the trigger
+    let pattern = if is_regex {
+        if let Err(e) = build_regex(&triggers) {
+            return Err(format!("That regex is not acceptable to the Party: {}", e).into());
+        }
+        triggers.clone()
+    } else {
+        let words: Vec<String> = triggers
+            .split(',')
+            .map(|w| w.trim().to_lowercase())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        if words.is_empty() {
+            return Err("You must provide at least one trigger word, comrade.".into());
+        }
+
+        // Triggers match on whole words, so one made only of punctuation could never fire
+        if let Some(unmatchable) = words.iter().find(|w| !w.chars().any(char::is_alphanumeric)) {
+            return Err(format!("`{}` has no letters or digits to match, comrade.", unmatchable).into());
+        }
+
+        words.join(",")
+    };
+
+    // Validate the emoji before saving it. Any text parses as a Unicode reaction, so it
+    // must really be one emoji; a custom emoji must be one of this server's own.
+    let reaction = match emoji.trim().parse::<serenity::ReactionType>() {
+        Ok(serenity::ReactionType::Custom { animated, id, name }) => {
+            if !guild_id.emojis(ctx).await?.iter().any(|own| own.id == id) {
+                return Err("That emoji does not belong to this server, comrade. The Party reacts only with its own.".into());
+            }
+            serenity::ReactionType::Custom { animated, id, name }
+        },
+        Ok(reaction) if is_unicode_emoji(emoji.trim()) => reaction,
+        _ => return Err("That is not an emoji, comrade. Use a single standard emoji or one of this server's own.".into()),
+    };
+
+    let db = &ctx.data().db;
+
+    let existing = db.get_autoreact_rules(&server_id).await?;
+    if existing.len() >= MAX_RULES_PER_SERVER {
+        return Err(format!(
+            "This server already has the maximum of {} reaction rules. Remove one with `/autoreact remove` first.",
+            MAX_RULES_PER_SERVER
+        ).into());
+    }
+
+    let rule = AutoReactRule {
+        id: 0,
+        server_id,
+        is_regex,
+        pattern,
+        emoji: reaction.to_string(),
+        probability: probability.unwrap_or(100.0).clamp(1.0, 100.0) / 100.0,
+        channels: channels.as_deref().map(parse_channel_ids).unwrap_or_default(),
+        cooldown_secs: cooldown_seconds.unwrap_or(60).clamp(0, 86400),
+    };
+
+    let rule_id = db.add_autoreact_rule(&rule).await?;
+
+    ctx.say(format!(
+        "☭ **Reaction rule #{} established** ☭\nThe Party will react with {} to {}.",
+        rule_id,
+        rule.emoji,
+        describe_rule(&rule)
+    )).await?;
+
+    Ok(())
+}
+
+/// Remove an automatic reaction rule
+#[poise::command(slash_command, prefix_command, rename = "remove", check = "check_if_admin")]
+pub async fn autoreact_remove(
+    ctx: crate::Context<'_>,
+    #[description = "The rule number shown by /autoreact list"]
+    rule_id: i64,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let db = &ctx.data().db;
+
+    if db.remove_autoreact_rule(&server_id, rule_id).await? {
+        compiled_regexes().lock().unwrap().remove(&rule_id);
+        ctx.say(format!("Reaction rule #{} has been purged from the record.", rule_id)).await?;
+    } else {
+        ctx.say(format!("No reaction rule #{} exists in this server, comrade.", rule_id)).await?;
+    }
+
+    Ok(())
+}
+
+/// List the automatic reaction rules for this server
+#[poise::command(slash_command, prefix_command, rename = "list", check = "check_if_admin")]
+pub async fn autoreact_list(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let db = &ctx.data().db;
+    let rules = db.get_autoreact_rules(&server_id).await?;
+
+    if rules.is_empty() {
+        ctx.say("This server has no reaction rules. Add one with `/autoreact add`.").await?;
+        return Ok(());
+    }
+
+    let mut text = String::new();
+    for rule in &rules {
+        text.push_str(&format!("**#{}** {} - {}\n", rule.id, rule.emoji, describe_rule(rule)));
+    }
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title("☭ Ministry of Reactions ☭")
+             .description(text)
+             .color(serenity::Color::RED)
+             .footer(|f| f.text("Remove a rule with /autoreact remove <number>"))
+        })
+    }).await?;
+
+    Ok(())
+}
+
+fn describe_rule(rule: &AutoReactRule) -> String {
+    let trigger = if rule.is_regex {
+        format!("messages matching `{}`", rule.pattern)
+    } else {
+        format!("the words `{}`", rule.pattern.replace(',', ", "))
+    };
+
+    let channels = if rule.channels.is_empty() {
+        "all channels".to_string()
+    } else {
+        rule.channels.iter().map(|c| format!("<#{}>", c)).collect::<Vec<_>>().join(", ")
+    };
+
+    format!(
+        "{} ({:.0}% chance, {}s cooldown, {})",
+        trigger,
+        rule.probability * 100.0,
+        rule.cooldown_secs,
+        channels
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: i64, is_regex: bool, pattern: &str) -> AutoReactRule {
+        AutoReactRule {
+            id,
+            server_id: "1".to_string(),
+            is_regex,
+            pattern: pattern.to_string(),
+            emoji: "☭".to_string(),
+            probability: 1.0,
+            channels: Vec::new(),
+            cooldown_secs: 60,
+        }
+    }
+
+    #[test]
+    fn single_unicode_emoji_are_accepted() {
+        for emoji in ["☭", "👍", "👍🏽", "❤️", "🇺🇦", "1️⃣", "👨‍👩‍👧‍👦", "🏴󠁧󠁢󠁳󠁣󠁴󠁿"] {
+            assert!(is_unicode_emoji(emoji), "{} was turned away", emoji);
+        }
+    }
+
+    #[test]
+    fn text_that_is_not_one_emoji_is_rejected() {
+        let long = "🔥".repeat(200);
+        for text in ["", "hello", "a", "7", "👍👍", "👍 ", "👍\u{200D}", "<:party:123>", long.as_str()] {
+            assert!(!is_unicode_emoji(text), "{:?} was accepted", text);
+        }
+    }
+
+    #[test]
+    fn words_match_whole_words_only() {
+        let rule = rule(1, false, "lenin,tractor");
+        assert!(autoreact_matches(&rule, "Long live Lenin!"));
+        assert!(autoreact_matches(&rule, "a new tractor, comrade"));
+        assert!(!autoreact_matches(&rule, "Leninism is a doctrine"));
+        assert!(!autoreact_matches(&rule, "tractors"));
+    }
+
+    #[test]
+    fn phrases_match_their_words_in_order() {
+        let rule = rule(2, false, "five year plan");
+        assert!(autoreact_matches(&rule, "The Five-Year  Plan is ahead of schedule."));
+        assert!(autoreact_matches(&rule, "five year plan"));
+        assert!(!autoreact_matches(&rule, "a plan for five year olds"));
+        assert!(!autoreact_matches(&rule, "five years plan"));
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        assert!(autoreact_matches(&rule(3, false, "kolkhoz"), "KOLKHOZ"));
+        assert!(autoreact_matches(&rule(4, true, "glory to (the )?party"), "GLORY TO THE PARTY"));
+    }
+
+    #[test]
+    fn regexes_match_and_follow_their_rule() {
+        let mut numbered = rule(5, true, r"\bgulag\d+\b");
+        assert!(autoreact_matches(&numbered, "sent to gulag7"));
+        assert!(!autoreact_matches(&numbered, "sent to the gulag"));
+
+        // A reused rule id compiles its new pattern rather than keeping the old one
+        numbered.pattern = "^comrade".to_string();
+        assert!(autoreact_matches(&numbered, "comrade, listen"));
+        assert!(!autoreact_matches(&numbered, "sent to gulag7"));
+
+        assert!(!autoreact_matches(&rule(6, true, "(unclosed"), "(unclosed"));
+    }
+}
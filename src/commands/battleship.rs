@@ -0,0 +1,909 @@
+use crate::CommandError;
+use crate::db::ActiveGame;
+use super::active_games::Resumed;
+use super::game_engine::{resume_press, run_game, Game, Outcome, Turn};
+use poise::serenity_prelude as serenity;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Naval Command - Battleship for the Red Banner Fleet
+const SIZE: usize = 10;
+
+// Every admiral's fleet as (class, length), deployed in this order
+const FLEET: [(&str, usize); 5] = [
+    ("Carrier", 5),
+    ("Battleship", 4),
+    ("Cruiser", 3),
+    ("Submarine", 3),
+    ("Destroyer", 2),
+];
+
+const ROW_LABELS: [&str; SIZE] = ["🇦", "🇧", "🇨", "🇩", "🇪", "🇫", "🇬", "🇭", "🇮", "🇯"];
+const COLUMN_LABELS: &str = "⚓1️⃣2️⃣3️⃣4️⃣5️⃣6️⃣7️⃣8️⃣9️⃣🔟";
+
+// How much more the computer counts a way a ship could lie for each unsunk hit it covers
+const HIT_WEIGHT: u64 = 50;
+
+fn row_name(row: usize) -> char {
+    (b'A' + row as u8) as char
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Shot {
+    Miss,
+    Hit,
+}
+
+// A cell as the comrade firing into it sees it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Seen {
+    Unknown,
+    Miss,
+    Hit,
+    // A hit on a ship that has gone down, which is announced to both sides
+    Sunk,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Ship {
+    // Index into FLEET
+    class: usize,
+    // The bow; the rest of the ship lies to its right, or below it if vertical
+    row: usize,
+    col: usize,
+    vertical: bool,
+}
+
+impl Ship {
+    fn name(&self) -> &'static str {
+        FLEET[self.class].0
+    }
+
+    fn cells(&self) -> Vec<(usize, usize)> {
+        (0..FLEET[self.class].1)
+            .map(|i| if self.vertical { (self.row + i, self.col) } else { (self.row, self.col + i) })
+            .collect()
+    }
+}
+
+// One admiral's sea: their ships and the shots fired into it
+#[derive(Clone, Serialize, Deserialize)]
+struct Waters {
+    // Deployed in fleet order, so the next ship to deploy is FLEET[ships.len()]
+    ships: Vec<Ship>,
+    shots: [[Option<Shot>; SIZE]; SIZE],
+}
+
+impl Waters {
+    fn new() -> Self {
+        Waters { ships: Vec::new(), shots: [[None; SIZE]; SIZE] }
+    }
+
+    fn is_deployed(&self) -> bool {
+        self.ships.len() == FLEET.len()
+    }
+
+    fn ship_at(&self, row: usize, col: usize) -> Option<&Ship> {
+        self.ships.iter().find(|ship| ship.cells().contains(&(row, col)))
+    }
+
+    // On the board and clear of every other ship. Ships may touch.
+    fn fits(&self, ship: &Ship) -> bool {
+        ship.cells().iter().all(|&(row, col)| row < SIZE && col < SIZE && self.ship_at(row, col).is_none())
+    }
+
+    // Deploy the next ship with its bow at a cell, or say why it can't go there
+    fn deploy(&mut self, row: usize, col: usize, vertical: bool) -> Result<(), String> {
+        if self.is_deployed() {
+            return Err("Your whole fleet is already deployed.".to_string());
+        }
+
+        let ship = Ship { class: self.ships.len(), row, col, vertical };
+        if !self.fits(&ship) {
+            return Err(format!(
+                "The {} doesn't fit with its bow at {}{}.",
+                ship.name(), row_name(row), col + 1
+            ));
+        }
+
+        self.ships.push(ship);
+        Ok(())
+    }
+
+    fn deploy_at_random(&mut self, rng: &mut impl Rng) {
+        self.ships.clear();
+        while !self.is_deployed() {
+            let vertical = rng.gen_bool(0.5);
+            // Failed tries change nothing, and there is always room
+            let _ = self.deploy(rng.gen_range(0..SIZE), rng.gen_range(0..SIZE), vertical);
+        }
+    }
+
+    fn is_sunk(&self, ship: &Ship) -> bool {
+        ship.cells().iter().all(|&(row, col)| self.shots[row][col] == Some(Shot::Hit))
+    }
+
+    fn afloat(&self) -> usize {
+        self.ships.iter().filter(|ship| !self.is_sunk(ship)).count()
+    }
+
+    // Fire at a cell. None if it has already been fired at.
+    fn fire(&mut self, row: usize, col: usize) -> Option<Shot> {
+        if self.shots[row][col].is_some() {
+            return None;
+        }
+
+        let shot = if self.ship_at(row, col).is_some() { Shot::Hit } else { Shot::Miss };
+        self.shots[row][col] = Some(shot);
+        Some(shot)
+    }
+
+    fn seen(&self, row: usize, col: usize) -> Seen {
+        match self.shots[row][col] {
+            None => Seen::Unknown,
+            Some(Shot::Miss) => Seen::Miss,
+            Some(Shot::Hit) if self.ship_at(row, col).is_some_and(|ship| self.is_sunk(ship)) => Seen::Sunk,
+            Some(Shot::Hit) => Seen::Hit,
+        }
+    }
+
+    // The grid as the enemy sees it, or with every ship showing
+    fn render(&self, show_ships: bool) -> String {
+        let mut grid = format!("{}\n", COLUMN_LABELS);
+
+        for (row, label) in ROW_LABELS.iter().enumerate() {
+            grid.push_str(label);
+            for col in 0..SIZE {
+                grid.push_str(match self.seen(row, col) {
+                    Seen::Unknown if show_ships && self.ship_at(row, col).is_some() => "🟩",
+                    Seen::Unknown => "🟦",
+                    Seen::Miss => "⬜",
+                    Seen::Hit => "🔥",
+                    Seen::Sunk => "💀",
+                });
+            }
+            grid.push('\n');
+        }
+
+        grid
+    }
+}
+
+// How hard the computer plays Naval Command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum BattleshipDifficulty {
+    Cadet,
+    Captain,
+    Admiral,
+}
+
+impl BattleshipDifficulty {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "cadet" | "easy" => Some(BattleshipDifficulty::Cadet),
+            "captain" | "medium" => Some(BattleshipDifficulty::Captain),
+            "admiral" | "hard" => Some(BattleshipDifficulty::Admiral),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            BattleshipDifficulty::Cadet => "Cadet",
+            BattleshipDifficulty::Captain => "Captain",
+            BattleshipDifficulty::Admiral => "Admiral",
+        }
+    }
+
+    // Share of the reward for beating the computer a win at this rank earns.
+    // The Cadet always fires blind, so beating it earns nothing.
+    fn reward_share(&self) -> f64 {
+        match self {
+            BattleshipDifficulty::Cadet => 0.0,
+            BattleshipDifficulty::Captain => 0.5,
+            BattleshipDifficulty::Admiral => 1.0,
+        }
+    }
+
+    // Chance of firing blind while no wounded ship is known
+    fn blind_shot_chance(&self) -> f64 {
+        match self {
+            BattleshipDifficulty::Cadet => 1.0,
+            BattleshipDifficulty::Captain => 0.3,
+            BattleshipDifficulty::Admiral => 0.0,
+        }
+    }
+}
+
+// How likely each cell is to hold a ship, judging only by what the shots have shown:
+// every way each ship still afloat could lie, clear of misses and sunk wrecks, is counted
+// over the cells it covers. Ways through unsunk hits count far more, so a wounded ship
+// gets finished off before the hunt goes on.
+fn probability_density(waters: &Waters) -> [[u64; SIZE]; SIZE] {
+    let mut density = [[0; SIZE]; SIZE];
+
+    for ship in waters.ships.iter().filter(|ship| !waters.is_sunk(ship)) {
+        for vertical in [false, true] {
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    let cells = Ship { vertical, row, col, ..*ship }.cells();
+                    if cells.iter().any(|&(r, c)| r >= SIZE || c >= SIZE) {
+                        continue;
+                    }
+
+                    let seen: Vec<Seen> = cells.iter().map(|&(r, c)| waters.seen(r, c)).collect();
+                    if seen.iter().any(|s| matches!(s, Seen::Miss | Seen::Sunk)) {
+                        continue;
+                    }
+
+                    let hits = seen.iter().filter(|s| **s == Seen::Hit).count() as u32;
+                    let weight = HIT_WEIGHT.pow(hits);
+                    for (&(r, c), s) in cells.iter().zip(&seen) {
+                        if *s == Seen::Unknown {
+                            density[r][c] += weight;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    density
+}
+
+// Where the computer fires into an enemy's waters
+fn choose_target(waters: &Waters, difficulty: BattleshipDifficulty, rng: &mut impl Rng) -> Option<(usize, usize)> {
+    let unknown: Vec<(usize, usize)> = (0..SIZE)
+        .flat_map(|row| (0..SIZE).map(move |col| (row, col)))
+        .filter(|&(row, col)| waters.seen(row, col) == Seen::Unknown)
+        .collect();
+
+    let wounded = (0..SIZE).any(|row| (0..SIZE).any(|col| waters.seen(row, col) == Seen::Hit));
+    let blind = match difficulty {
+        BattleshipDifficulty::Cadet => true,
+        _ => !wounded && rng.gen_bool(difficulty.blind_shot_chance()),
+    };
+    if blind {
+        return unknown.choose(rng).copied();
+    }
+
+    let density = probability_density(waters);
+    let best = unknown.iter().map(|&(row, col)| density[row][col]).max()?;
+    let targets: Vec<(usize, usize)> = unknown
+        .into_iter()
+        .filter(|&(row, col)| density[row][col] == best)
+        .collect();
+    targets.choose(rng).copied()
+}
+
+// A comrade's controls, which only change what they see and where they'll place or fire
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct Helm {
+    // Row picked for the next ship's bow, or for the next shot
+    row: usize,
+    vertical: bool,
+    // Why their last order couldn't be carried out
+    notice: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Battleship {
+    // Each seated comrade; the computer has no seat of its own
+    seats: Vec<serenity::UserId>,
+    // Each side's sea, the computer's second when it plays
+    waters: [Waters; 2],
+    helms: [Helm; 2],
+    ai_mode: bool,
+    difficulty: BattleshipDifficulty,
+    // Side to fire next, once both fleets are deployed
+    shooter: usize,
+    // What the last shot did
+    last_shot: Option<String>,
+    game_over: bool,
+    winner: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum BattleshipMove {
+    // Pick the row for the next ship's bow, or to fire into
+    Row { side: usize, row: usize },
+    // Turn the next ship between horizontal and vertical
+    Rotate { side: usize },
+    // Deploy the next ship with its bow in this column of the picked row
+    Deploy { side: usize, col: usize },
+    RandomFleet { side: usize },
+    ClearFleet { side: usize },
+    Fire { side: usize, row: usize, col: usize },
+}
+
+impl Battleship {
+    fn new(players: &[serenity::User], difficulty: BattleshipDifficulty) -> Self {
+        let ai_mode = players.len() == 1;
+        let mut waters = [Waters::new(), Waters::new()];
+
+        // The computer deploys at once
+        if ai_mode {
+            waters[1].deploy_at_random(&mut rand::thread_rng());
+        }
+
+        Battleship {
+            seats: players.iter().map(|p| p.id).collect(),
+            waters,
+            helms: Default::default(),
+            ai_mode,
+            difficulty,
+            shooter: 0,
+            last_shot: None,
+            game_over: false,
+            winner: None,
+        }
+    }
+
+    fn side_of(&self, user: serenity::UserId) -> Option<usize> {
+        self.seats.iter().position(|&id| id == user)
+    }
+
+    fn deploying(&self) -> bool {
+        !self.waters.iter().all(Waters::is_deployed)
+    }
+
+    fn side_name(&self, side: usize, players: &[serenity::User]) -> String {
+        match players.get(side) {
+            Some(player) => player.name.clone(),
+            None => "The computer".to_string(),
+        }
+    }
+
+    fn fire(&mut self, side: usize, row: usize, col: usize) -> bool {
+        if self.deploying() || self.game_over || side != self.shooter || row >= SIZE || col >= SIZE {
+            return false;
+        }
+
+        let target = 1 - side;
+        let shot = match self.waters[target].fire(row, col) {
+            Some(shot) => shot,
+            None => return false,
+        };
+
+        let waters = &self.waters[target];
+        let result = match shot {
+            Shot::Miss => "miss.".to_string(),
+            Shot::Hit => match waters.ship_at(row, col).filter(|ship| waters.is_sunk(ship)) {
+                Some(ship) => format!("hit! The {} is sunk!", ship.name()),
+                None => "hit!".to_string(),
+            },
+        };
+        self.last_shot = Some(format!("{}{}: {}", row_name(row), col + 1, result));
+        self.helms[side].notice = None;
+
+        if waters.afloat() == 0 {
+            self.game_over = true;
+            self.winner = Some(side);
+        } else {
+            self.shooter = target;
+        }
+        true
+    }
+
+    fn render_deployment(&self, players: &[serenity::User]) -> String {
+        let mut content = "Admirals, deploy your fleets! Pick a row, then press a column to place \
+            each ship's bow there. 🔄 turns the next ship, 🎲 deploys the whole fleet at random. \
+            Press **My Fleet** to see your own waters; nobody else can.\n\n".to_string();
+
+        for (side, waters) in self.waters.iter().enumerate() {
+            let status = if waters.is_deployed() {
+                "fleet deployed ✅".to_string()
+            } else {
+                format!("{}/{} ships deployed", waters.ships.len(), FLEET.len())
+            };
+            content.push_str(&format!("**{}**: {}\n", self.side_name(side, players), status));
+        }
+
+        content
+    }
+}
+
+impl Game for Battleship {
+    type Move = BattleshipMove;
+
+    const KEY: &'static str = "battleship";
+    const NAME: &'static str = "Naval Command";
+
+    // Both admirals deploy and watch their own waters at once, so presses are open
+    // to anyone and the game checks whose they are
+    fn computer_win_reward_share(&self) -> f64 {
+        self.difficulty.reward_share()
+    }
+
+    fn turn(&self) -> Turn {
+        match self.shooter {
+            _ if self.game_over => Turn::Over,
+            _ if self.deploying() => Turn::Open,
+            1 if self.ai_mode => Turn::Computer,
+            _ => Turn::Open,
+        }
+    }
+
+    fn legal_moves(&self) -> Vec<BattleshipMove> {
+        if self.game_over {
+            return Vec::new();
+        }
+
+        if self.deploying() {
+            return (0..self.seats.len())
+                .filter(|&side| !self.waters[side].is_deployed())
+                .map(|side| BattleshipMove::RandomFleet { side })
+                .collect();
+        }
+
+        let waters = &self.waters[1 - self.shooter];
+        (0..SIZE)
+            .flat_map(|row| (0..SIZE).map(move |col| (row, col)))
+            .filter(|&(row, col)| waters.shots[row][col].is_none())
+            .map(|(row, col)| BattleshipMove::Fire { side: self.shooter, row, col })
+            .collect()
+    }
+
+    fn apply_move(&mut self, mv: BattleshipMove) -> bool {
+        if self.game_over {
+            return false;
+        }
+
+        match mv {
+            // While firing, only the comrade about to fire may aim
+            BattleshipMove::Row { side, row } if row < SIZE && (self.deploying() || side == self.shooter) => {
+                self.helms[side].row = row;
+                self.helms[side].notice = None;
+                true
+            },
+            BattleshipMove::Rotate { side } if self.deploying() && !self.waters[side].is_deployed() => {
+                self.helms[side].vertical = !self.helms[side].vertical;
+                self.helms[side].notice = None;
+                true
+            },
+            BattleshipMove::Deploy { side, col } if self.deploying() && col < SIZE => {
+                let helm = &mut self.helms[side];
+                helm.notice = self.waters[side].deploy(helm.row, col, helm.vertical).err();
+                true
+            },
+            BattleshipMove::RandomFleet { side } if self.deploying() => {
+                self.waters[side].deploy_at_random(&mut rand::thread_rng());
+                self.helms[side].notice = None;
+                true
+            },
+            BattleshipMove::ClearFleet { side } if self.deploying() && !self.waters[side].ships.is_empty() => {
+                self.waters[side].ships.clear();
+                self.helms[side].notice = None;
+                true
+            },
+            BattleshipMove::Fire { side, row, col } => self.fire(side, row, col),
+            _ => false,
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.game_over
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        match self.winner {
+            _ if !self.game_over => None,
+            Some(0) => Some(Outcome::Win(0)),
+            Some(_) if self.ai_mode => Some(Outcome::ComputerWin),
+            Some(side) => Some(Outcome::Win(side)),
+            None => Some(Outcome::Draw),
+        }
+    }
+
+    fn computer_move(&self) -> Option<BattleshipMove> {
+        choose_target(&self.waters[0], self.difficulty, &mut rand::thread_rng())
+            .map(|(row, col)| BattleshipMove::Fire { side: 1, row, col })
+    }
+
+    fn parse_move(&self, custom_id: &str, presser: &serenity::User, _think_time: Duration) -> Option<BattleshipMove> {
+        let side = self.side_of(presser.id)?;
+
+        if let Some(row) = custom_id.strip_prefix("bs_row:") {
+            return row.parse().ok().map(|row| BattleshipMove::Row { side, row });
+        }
+        if let Some(col) = custom_id.strip_prefix("bs_col_").and_then(|s| s.parse().ok()) {
+            return Some(match self.deploying() {
+                true => BattleshipMove::Deploy { side, col },
+                false => BattleshipMove::Fire { side, row: self.helms[side].row, col },
+            });
+        }
+
+        // "bs_fleet" moves nothing; it only shows the presser their own waters
+        match custom_id {
+            "bs_rotate" => Some(BattleshipMove::Rotate { side }),
+            "bs_random" => Some(BattleshipMove::RandomFleet { side }),
+            "bs_clear" => Some(BattleshipMove::ClearFleet { side }),
+            _ => None,
+        }
+    }
+
+    fn render(&self, players: &[serenity::User]) -> String {
+        let mut content = "**☭ Naval Command ☭**\n".to_string();
+        if self.ai_mode {
+            content.push_str(&format!("*Computer rank: {}*\n", self.difficulty.name()));
+        }
+        content.push('\n');
+
+        if self.deploying() {
+            content.push_str(&self.render_deployment(players));
+            return content;
+        }
+
+        // Fleets stay hidden until the battle is over
+        for (side, waters) in self.waters.iter().enumerate() {
+            content.push_str(&format!(
+                "**{}'s waters** · {}/{} ships afloat\n{}\n",
+                self.side_name(side, players), waters.afloat(), FLEET.len(), waters.render(self.game_over)
+            ));
+        }
+
+        if let Some(last_shot) = &self.last_shot {
+            let shooter = if self.game_over { self.winner.unwrap_or(0) } else { 1 - self.shooter };
+            content.push_str(&format!("**{}** fired at {}\n", self.side_name(shooter, players), last_shot));
+        }
+
+        match self.winner {
+            Some(side) if side == 1 && self.ai_mode => content.push_str(
+                "\nThe capitalist fleet rules the waves... for now. The Red Banner Fleet will return!"
+            ),
+            Some(side) => content.push_str(&format!(
+                "\n**{}** has sent the enemy fleet to the bottom! Glory to the Red Banner Fleet!",
+                self.side_name(side, players)
+            )),
+            None => content.push_str(&format!(
+                "It's **{}**'s turn to fire, into row **{}**.",
+                self.side_name(self.shooter, players), row_name(self.helms[self.shooter].row)
+            )),
+        }
+
+        content
+    }
+
+    fn render_abandoned(&self, _players: &[serenity::User]) -> String {
+        "**☭ Naval Command ☭**\n\nBattle abandoned due to inactivity! Both fleets return to port in disgrace.".to_string()
+    }
+
+    fn components<'a>(&self, c: &'a mut serenity::CreateComponents) -> &'a mut serenity::CreateComponents {
+        create_battleship_controls(c, self)
+    }
+
+    fn move_timeout(&self) -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn private_view(&self, viewer: &serenity::User, players: &[serenity::User]) -> Option<String> {
+        let side = self.side_of(viewer.id)?;
+        let waters = &self.waters[side];
+        let helm = &self.helms[side];
+
+        let mut content = "**☭ Your Waters ☭**\n".to_string();
+        match FLEET.get(waters.ships.len()) {
+            Some((class, length)) if self.deploying() => content.push_str(&format!(
+                "Next: the **{}** ({} squares), {}, bow in row **{}**.\n",
+                class, length, if helm.vertical { "vertical" } else { "horizontal" }, row_name(helm.row)
+            )),
+            _ if self.deploying() => content.push_str(&format!(
+                "Your fleet is deployed. Waiting for {}...\n",
+                self.side_name(1 - side, players)
+            )),
+            _ => {},
+        }
+        content.push_str(&format!("\n{}\n", waters.render(true)));
+
+        for (class, (name, length)) in FLEET.iter().enumerate() {
+            let status = match waters.ships.iter().find(|ship| ship.class == class) {
+                Some(ship) if waters.is_sunk(ship) => "💀 sunk",
+                Some(_) => "🟩 afloat",
+                None => "⬛ in port",
+            };
+            content.push_str(&format!("{} ({}) - {}\n", name, length, status));
+        }
+
+        if let Some(notice) = &helm.notice {
+            content.push_str(&format!("\n⚠️ {}", notice));
+        }
+
+        Some(content)
+    }
+}
+
+// A row picker, a button per column (which deploys or fires), and the fleet controls.
+// Empty once the battle is over.
+fn create_battleship_controls<'a>(
+    c: &'a mut serenity::CreateComponents,
+    game: &Battleship,
+) -> &'a mut serenity::CreateComponents {
+    if game.game_over {
+        return c;
+    }
+
+    let deploying = game.deploying();
+    let aim = game.helms[game.shooter].row;
+    let target = &game.waters[1 - game.shooter];
+
+    c.create_action_row(|row| {
+        row.create_select_menu(|menu| {
+            menu.custom_id("bs_row")
+                .placeholder(if deploying { "Pick a row for the next ship's bow" } else { "Pick a row to fire into" })
+                .options(|options| {
+                    for r in 0..SIZE {
+                        options.create_option(|o| {
+                            o.label(format!("Row {}", row_name(r)))
+                             .value(r.to_string())
+                             .default_selection(!deploying && r == aim)
+                        });
+                    }
+                    options
+                })
+        })
+    });
+
+    // Columns already fired at in the aimed row can't be fired at again
+    for cols in [0..5, 5..SIZE] {
+        c.create_action_row(|row| {
+            for col in cols {
+                row.create_button(|b| {
+                    b.custom_id(format!("bs_col_{}", col))
+                     .label((col + 1).to_string())
+                     .style(if deploying { serenity::ButtonStyle::Primary } else { serenity::ButtonStyle::Danger })
+                     .disabled(!deploying && target.shots[aim][col].is_some())
+                });
+            }
+            row
+        });
+    }
+
+    c.create_action_row(|row| {
+        if deploying {
+            row.create_button(|b| {
+                b.custom_id("bs_rotate")
+                 .label("Turn Ship")
+                 .style(serenity::ButtonStyle::Secondary)
+                 .emoji('🔄')
+            });
+            row.create_button(|b| {
+                b.custom_id("bs_random")
+                 .label("Random Fleet")
+                 .style(serenity::ButtonStyle::Secondary)
+                 .emoji('🎲')
+            });
+            row.create_button(|b| {
+                b.custom_id("bs_clear")
+                 .label("Clear")
+                 .style(serenity::ButtonStyle::Secondary)
+                 .emoji('🧹')
+            });
+        }
+        row.create_button(|b| {
+            b.custom_id("bs_fleet")
+             .label("My Fleet")
+             .style(serenity::ButtonStyle::Success)
+             .emoji('⚓')
+        })
+    });
+
+    c
+}
+
+// Apply a press to a saved battle whose command is no longer running
+pub(crate) async fn resume_battleship_press(
+    ctx: &serenity::Context,
+    data: &crate::Data,
+    record: &ActiveGame,
+    press: &serenity::MessageComponentInteraction,
+) -> Result<Resumed, CommandError> {
+    resume_press::<Battleship>(ctx, data, record, press).await
+}
+
+/// Naval Command: deploy your fleet in secret and sink the enemy's
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn battleship(
+    ctx: crate::Context<'_>,
+    #[description = "The user to play against (leave empty to play against the computer)"]
+    opponent: Option<serenity::User>,
+    #[description = "Boops each player stakes on the outcome (1-500)"]
+    wager: Option<f64>,
+    #[description = "Computer difficulty: cadet, captain or admiral (default: admiral)"]
+    difficulty: Option<String>,
+) -> Result<(), CommandError> {
+    let difficulty = match difficulty.as_deref().map(BattleshipDifficulty::from_name) {
+        None => BattleshipDifficulty::Admiral,
+        Some(Some(_)) if opponent.is_some() => {
+            ctx.say("Difficulty only applies when playing against the computer, comrade.").await?;
+            return Ok(());
+        },
+        Some(Some(difficulty)) => difficulty,
+        Some(None) => {
+            ctx.say("Unknown difficulty, comrade. Choose `cadet`, `captain` or `admiral`.").await?;
+            return Ok(());
+        }
+    };
+
+    if let Some(ref user) = opponent {
+        if user.id == ctx.author().id {
+            ctx.say("You can't fight a naval battle against yourself, comrade! Choose another player or leave empty to face the capitalist fleet.").await?;
+            return Ok(());
+        }
+
+        if user.bot {
+            ctx.say("You can't play against a bot! Choose a human player or leave empty to play against the computer.").await?;
+            return Ok(());
+        }
+    }
+
+    // The first seat fires first
+    run_game(ctx, opponent, wager, |players| Battleship::new(players, difficulty)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn comrade(id: u64, name: &str) -> serenity::User {
+        let mut user = serenity::User::default();
+        user.id = serenity::UserId(id);
+        user.name = name.to_string();
+        user
+    }
+
+    // A fleet along the top rows, each ship's bow in the first column
+    fn fleet_in_rows() -> Waters {
+        let mut waters = Waters::new();
+        for row in 0..FLEET.len() {
+            waters.deploy(row, 0, false).unwrap();
+        }
+        waters
+    }
+
+    #[test]
+    fn ships_must_fit_on_the_board_without_overlapping() {
+        let mut waters = Waters::new();
+        assert!(waters.deploy(0, 6, false).is_err(), "the carrier runs off the board");
+        assert!(waters.deploy(6, 0, true).is_err());
+        assert!(waters.deploy(0, 5, false).is_ok());
+        assert!(waters.deploy(0, 9, true).is_err(), "overlaps the carrier's stern");
+        assert!(waters.deploy(1, 9, true).is_ok(), "ships may touch");
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            waters.deploy_at_random(&mut rng);
+            assert!(waters.is_deployed());
+            let cells: usize = FLEET.iter().map(|(_, length)| length).sum();
+            let occupied = (0..SIZE).flat_map(|r| (0..SIZE).map(move |c| (r, c))).filter(|&(r, c)| waters.ship_at(r, c).is_some()).count();
+            assert_eq!(occupied, cells);
+        }
+    }
+
+    #[test]
+    fn deploying_and_firing_through_presses() {
+        let (lenin, trotsky, stalin) = (comrade(1, "lenin"), comrade(2, "trotsky"), comrade(3, "stalin"));
+        let players = [lenin.clone(), trotsky.clone()];
+        let mut game = Battleship::new(&players, BattleshipDifficulty::Admiral);
+        let press = |game: &mut Battleship, id: &str, by: &serenity::User| {
+            game.parse_move(id, by, Duration::ZERO).is_some_and(|mv| game.apply_move(mv))
+        };
+
+        assert_eq!(game.turn(), Turn::Open);
+        assert!(!press(&mut game, "bs_random", &stalin), "spectators have no fleet");
+
+        // A carrier with its bow at J1, placed vertically, would run off the board
+        assert!(press(&mut game, "bs_row:9", &lenin));
+        assert!(press(&mut game, "bs_rotate", &lenin));
+        assert!(press(&mut game, "bs_col_0", &lenin));
+        assert!(game.waters[0].ships.is_empty());
+        assert!(game.private_view(&lenin, &players).unwrap().contains("doesn't fit"));
+        assert!(game.private_view(&stalin, &players).is_none());
+
+        assert!(press(&mut game, "bs_random", &lenin));
+        assert!(game.deploying());
+        game.waters[1] = fleet_in_rows();
+        assert!(!game.deploying());
+
+        // Rows are aimed by whoever is firing, and fired at through the column buttons
+        assert!(!press(&mut game, "bs_row:0", &trotsky));
+        assert!(press(&mut game, "bs_row:0", &lenin));
+        assert!(press(&mut game, "bs_col_0", &lenin));
+        assert_eq!(game.waters[1].shots[0][0], Some(Shot::Hit));
+        assert!(!press(&mut game, "bs_col_1", &lenin), "it's trotsky's turn");
+        assert_eq!(game.shooter, 1);
+
+        // The enemy's ships stay hidden from the channel until the end
+        assert!(!game.render(&players).contains("🟩"));
+    }
+
+    #[test]
+    fn sinking_the_last_ship_wins() {
+        let players = [comrade(1, "lenin")];
+        let mut game = Battleship::new(&players, BattleshipDifficulty::Cadet);
+        game.waters[0] = fleet_in_rows();
+        game.waters[1] = fleet_in_rows();
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for ship in game.waters[1].ships.clone() {
+            if game.game_over {
+                break;
+            }
+            for (row, col) in ship.cells() {
+                assert_eq!(game.turn(), Turn::Open);
+                assert!(game.apply_move(BattleshipMove::Fire { side: 0, row, col }));
+                if game.game_over {
+                    break;
+                }
+
+                assert_eq!(game.turn(), Turn::Computer);
+                let (row, col) = choose_target(&game.waters[0], game.difficulty, &mut rng).unwrap();
+                assert!(game.apply_move(BattleshipMove::Fire { side: 1, row, col }));
+            }
+        }
+
+        assert!(game.game_over);
+        assert_eq!(game.outcome(), Some(Outcome::Win(0)));
+        assert!(game.last_shot.as_deref().unwrap().contains("Destroyer is sunk"));
+    }
+
+    #[test]
+    fn the_computer_finishes_off_a_wounded_ship() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut waters = Waters::new();
+        waters.deploy_at_random(&mut rng);
+
+        // Hit the carrier's bow, and every shot after should stay on it until it sinks
+        let carrier = waters.ships[0];
+        waters.fire(carrier.row, carrier.col);
+        for _ in 1..FLEET[0].1 {
+            let (row, col) = choose_target(&waters, BattleshipDifficulty::Admiral, &mut rng).unwrap();
+            let near = carrier.cells().iter().any(|&(r, c)| waters.seen(r, c) == Seen::Hit && r.abs_diff(row) + c.abs_diff(col) == 1);
+            assert!(near, "fired at {}{} away from the wounded carrier", row_name(row), col + 1);
+            waters.fire(row, col);
+            if waters.is_sunk(&carrier) {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn the_computer_never_fires_twice_at_a_cell() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for difficulty in [BattleshipDifficulty::Cadet, BattleshipDifficulty::Captain, BattleshipDifficulty::Admiral] {
+            let mut waters = Waters::new();
+            waters.deploy_at_random(&mut rng);
+
+            let mut shots = 0;
+            while waters.afloat() > 0 {
+                let (row, col) = choose_target(&waters, difficulty, &mut rng).unwrap();
+                assert!(waters.fire(row, col).is_some());
+                shots += 1;
+            }
+            assert!(shots <= SIZE * SIZE);
+        }
+    }
+
+    #[test]
+    fn density_targeting_beats_firing_blind() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut shots_taken = |difficulty| {
+            let mut total = 0;
+            for _ in 0..20 {
+                let mut waters = Waters::new();
+                waters.deploy_at_random(&mut rng);
+                while waters.afloat() > 0 {
+                    let (row, col) = choose_target(&waters, difficulty, &mut rng).unwrap();
+                    waters.fire(row, col);
+                    total += 1;
+                }
+            }
+            total
+        };
+
+        assert!(shots_taken(BattleshipDifficulty::Admiral) < shots_taken(BattleshipDifficulty::Cadet));
+    }
+}
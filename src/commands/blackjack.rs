@@ -0,0 +1,783 @@
+use crate::CommandError;
+use crate::db::EscrowOutcome;
+use poise::serenity_prelude as serenity;
+use rand::seq::SliceRandom;
+use std::fmt;
+use std::time::Duration;
+
+// Betting limits in boops
+const MIN_BET: f64 = 1.0;
+const MAX_BET: f64 = 1000.0;
+
+// Number of decks in the state shoe
+const SHOE_DECKS: usize = 6;
+
+// A hand may be split until the player holds this many hands
+const MAX_HANDS: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl Suit {
+    const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Card {
+    // 1 = Ace, 11 = Jack, 12 = Queen, 13 = King
+    pub rank: u8,
+    pub suit: Suit,
+}
+
+impl Card {
+    pub fn new(rank: u8, suit: Suit) -> Self {
+        Self { rank, suit }
+    }
+
+    // Blackjack value of the card, counting aces as 1
+    pub fn value(&self) -> u32 {
+        match self.rank {
+            1 => 1,
+            11..=13 => 10,
+            r => r as u32,
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rank = match self.rank {
+            1 => "A".to_string(),
+            11 => "J".to_string(),
+            12 => "Q".to_string(),
+            13 => "K".to_string(),
+            r => r.to_string(),
+        };
+        let suit = match self.suit {
+            Suit::Spades => "♠",
+            Suit::Hearts => "♥",
+            Suit::Diamonds => "♦",
+            Suit::Clubs => "♣",
+        };
+        write!(f, "{}{}", rank, suit)
+    }
+}
+
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    // A shuffled shoe of the given number of standard 52-card decks
+    pub fn new_shoe(decks: usize) -> Self {
+        let mut cards = Vec::with_capacity(decks * 52);
+        for _ in 0..decks {
+            for suit in Suit::ALL {
+                for rank in 1..=13 {
+                    cards.push(Card::new(rank, suit));
+                }
+            }
+        }
+        cards.shuffle(&mut rand::thread_rng());
+        Self { cards }
+    }
+
+    // A deck that deals the given cards in order
+    #[cfg(test)]
+    pub fn from_cards(mut cards: Vec<Card>) -> Self {
+        cards.reverse();
+        Self { cards }
+    }
+
+    #[cfg(test)]
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn draw(&mut self) -> Card {
+        // Refill the shoe if the Ministry runs out of cards
+        if self.cards.is_empty() {
+            *self = Deck::new_shoe(SHOE_DECKS);
+        }
+        self.cards.pop().expect("a fresh shoe is never empty")
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Hand {
+    pub cards: Vec<Card>,
+}
+
+impl Hand {
+    // Best total for the hand, and whether an ace is being counted as 11
+    pub fn value(&self) -> (u32, bool) {
+        let hard: u32 = self.cards.iter().map(|c| c.value()).sum();
+        let has_ace = self.cards.iter().any(|c| c.rank == 1);
+
+        if has_ace && hard + 10 <= 21 {
+            (hard + 10, true)
+        } else {
+            (hard, false)
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.value().0
+    }
+
+    pub fn is_bust(&self) -> bool {
+        self.total() > 21
+    }
+
+    pub fn is_natural(&self) -> bool {
+        self.cards.len() == 2 && self.total() == 21
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.cards.len() == 2 && self.cards[0].value() == self.cards[1].value()
+    }
+
+    fn render(&self) -> String {
+        self.cards.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PlayerHand {
+    pub hand: Hand,
+    pub bet: f64,
+    pub doubled: bool,
+    pub from_split: bool,
+    pub done: bool,
+}
+
+impl PlayerHand {
+    fn new(bet: f64, from_split: bool) -> Self {
+        Self { hand: Hand::default(), bet, doubled: false, from_split, done: false }
+    }
+
+    // A natural only counts on the original two cards, not after a split
+    fn is_blackjack(&self) -> bool {
+        !self.from_split && self.hand.is_natural()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HandOutcome {
+    Blackjack,
+    Win,
+    Push,
+    Lose,
+    Bust,
+}
+
+impl HandOutcome {
+    // Total returned to the player for a hand, including the original stake
+    fn payout(&self, bet: f64) -> f64 {
+        match self {
+            HandOutcome::Blackjack => bet * 2.5,
+            HandOutcome::Win => bet * 2.0,
+            HandOutcome::Push => bet,
+            HandOutcome::Lose | HandOutcome::Bust => 0.0,
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            HandOutcome::Blackjack => "Blackjack! A triumph of socialist planning",
+            HandOutcome::Win => "Victory for the worker",
+            HandOutcome::Push => "Push - equality preserved",
+            HandOutcome::Lose => "The house prevails",
+            HandOutcome::Bust => "Bust - overproduction",
+        }
+    }
+}
+
+pub struct BlackjackGame {
+    deck: Deck,
+    pub dealer: Hand,
+    pub hands: Vec<PlayerHand>,
+    pub active: usize,
+    pub finished: bool,
+}
+
+impl BlackjackGame {
+    pub fn new(deck: Deck, bet: f64) -> Self {
+        let mut game = Self {
+            deck,
+            dealer: Hand::default(),
+            hands: vec![PlayerHand::new(bet, false)],
+            active: 0,
+            finished: false,
+        };
+
+        // Deal alternately, player first
+        for _ in 0..2 {
+            let card = game.deck.draw();
+            game.hands[0].hand.cards.push(card);
+            let card = game.deck.draw();
+            game.dealer.cards.push(card);
+        }
+
+        // Naturals on either side end the round immediately
+        if game.hands[0].hand.is_natural() || game.dealer.is_natural() {
+            game.hands[0].done = true;
+            game.finished = true;
+        }
+
+        game
+    }
+
+    pub fn active_hand(&self) -> Option<&PlayerHand> {
+        if self.finished {
+            None
+        } else {
+            self.hands.get(self.active)
+        }
+    }
+
+    pub fn can_double(&self) -> bool {
+        self.active_hand().is_some_and(|h| h.hand.cards.len() == 2)
+    }
+
+    pub fn can_split(&self) -> bool {
+        self.hands.len() < MAX_HANDS && self.active_hand().is_some_and(|h| h.hand.is_pair())
+    }
+
+    pub fn hit(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let card = self.deck.draw();
+        let hand = &mut self.hands[self.active];
+        hand.hand.cards.push(card);
+
+        if hand.hand.total() >= 21 {
+            self.advance();
+        }
+    }
+
+    pub fn stand(&mut self) {
+        if !self.finished {
+            self.advance();
+        }
+    }
+
+    // Double the active hand's bet and take exactly one more card.
+    // The caller is responsible for collecting the extra stake.
+    pub fn double(&mut self) -> bool {
+        if !self.can_double() {
+            return false;
+        }
+
+        let card = self.deck.draw();
+        let hand = &mut self.hands[self.active];
+        hand.bet *= 2.0;
+        hand.doubled = true;
+        hand.hand.cards.push(card);
+        self.advance();
+        true
+    }
+
+    // Split the active pair into two hands with equal bets.
+    // The caller is responsible for collecting the extra stake.
+    pub fn split(&mut self) -> bool {
+        if !self.can_split() {
+            return false;
+        }
+
+        let bet = self.hands[self.active].bet;
+        let moved = self.hands[self.active].hand.cards.pop().expect("a pair has two cards");
+        let split_aces = moved.rank == 1;
+
+        let mut new_hand = PlayerHand::new(bet, true);
+        new_hand.hand.cards.push(moved);
+        self.hands[self.active].from_split = true;
+        self.hands.insert(self.active + 1, new_hand);
+
+        // Each half receives a second card
+        for i in [self.active, self.active + 1] {
+            let card = self.deck.draw();
+            self.hands[i].hand.cards.push(card);
+        }
+
+        // Split aces get one card each and no more
+        if split_aces {
+            self.hands[self.active].done = true;
+            self.hands[self.active + 1].done = true;
+            self.advance();
+        } else if self.hands[self.active].hand.total() == 21 {
+            self.advance();
+        }
+
+        true
+    }
+
+    // Stand on every remaining hand, e.g. when the player walks away
+    pub fn stand_all(&mut self) {
+        while !self.finished {
+            self.advance();
+        }
+    }
+
+    fn advance(&mut self) {
+        self.hands[self.active].done = true;
+
+        match self.hands.iter().position(|h| !h.done) {
+            Some(next) => {
+                self.active = next;
+                // A hand dealt to 21 after a split plays itself
+                if self.hands[next].hand.total() == 21 {
+                    self.advance();
+                }
+            }
+            None => self.play_dealer(),
+        }
+    }
+
+    fn play_dealer(&mut self) {
+        self.finished = true;
+
+        // The dealer only draws if some hand is still alive
+        if self.hands.iter().all(|h| h.hand.is_bust()) {
+            return;
+        }
+
+        // Dealer stands on all 17s
+        while self.dealer.total() < 17 {
+            let card = self.deck.draw();
+            self.dealer.cards.push(card);
+        }
+    }
+
+    pub fn outcome(&self, hand: &PlayerHand) -> HandOutcome {
+        let player = hand.hand.total();
+        let dealer = self.dealer.total();
+
+        if hand.hand.is_bust() {
+            return HandOutcome::Bust;
+        }
+
+        match (hand.is_blackjack(), self.dealer.is_natural()) {
+            (true, true) => return HandOutcome::Push,
+            (true, false) => return HandOutcome::Blackjack,
+            (false, true) => return HandOutcome::Lose,
+            (false, false) => {}
+        }
+
+        if self.dealer.is_bust() || player > dealer {
+            HandOutcome::Win
+        } else if player == dealer {
+            HandOutcome::Push
+        } else {
+            HandOutcome::Lose
+        }
+    }
+
+    pub fn total_bet(&self) -> f64 {
+        self.hands.iter().map(|h| h.bet).sum()
+    }
+
+    pub fn total_payout(&self) -> f64 {
+        self.hands.iter().map(|h| self.outcome(h).payout(h.bet)).sum()
+    }
+
+    fn render(&self) -> String {
+        let mut text = String::from("**☭ State-Sanctioned Resource Allocation ☭**\n\n");
+
+        if self.finished {
+            text.push_str(&format!("**Dealer** ({}): {}\n\n", self.dealer.total(), self.dealer.render()));
+        } else {
+            // Only the upcard is visible until the round is over
+            let upcard = self.dealer.cards[0];
+            let shown = if upcard.rank == 1 { 11 } else { upcard.value() };
+            text.push_str(&format!("**Dealer** ({}): {} 🂠\n\n", shown, upcard));
+        }
+
+        for (i, hand) in self.hands.iter().enumerate() {
+            let label = if self.hands.len() > 1 {
+                format!("Hand {}", i + 1)
+            } else {
+                "Your hand".to_string()
+            };
+
+            let marker = if !self.finished && i == self.active { " ◀" } else { "" };
+
+            text.push_str(&format!(
+                "**{}** ({}, bet **{:.2}**{}): {}{}\n",
+                label,
+                hand.hand.total(),
+                hand.bet,
+                if hand.doubled { ", doubled" } else { "" },
+                hand.hand.render(),
+                marker
+            ));
+
+            if self.finished {
+                text.push_str(&format!("↳ {}\n", self.outcome(hand).describe()));
+            }
+        }
+
+        text
+    }
+}
+
+fn create_action_buttons<'a>(
+    c: &'a mut serenity::CreateComponents,
+    game: &BlackjackGame,
+    balance: f64,
+) -> &'a mut serenity::CreateComponents {
+    if game.finished {
+        return c;
+    }
+
+    // Doubling and splitting both cost another stake equal to the active hand's bet
+    let extra_stake = game.active_hand().map(|h| h.bet).unwrap_or(0.0);
+    let can_afford = balance >= extra_stake;
+
+    c.create_action_row(|row| {
+        row.create_button(|b| {
+            b.custom_id("bj_hit")
+             .label("Hit")
+             .style(serenity::ButtonStyle::Primary)
+        })
+        .create_button(|b| {
+            b.custom_id("bj_stand")
+             .label("Stand")
+             .style(serenity::ButtonStyle::Secondary)
+        })
+        .create_button(|b| {
+            b.custom_id("bj_double")
+             .label("Double")
+             .style(serenity::ButtonStyle::Success)
+             .disabled(!game.can_double() || !can_afford)
+        })
+        .create_button(|b| {
+            b.custom_id("bj_split")
+             .label("Split")
+             .style(serenity::ButtonStyle::Success)
+             .disabled(!game.can_split() || !can_afford)
+        })
+    })
+}
+
+/// Wager boops in a game of blackjack against the state
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn blackjack(
+    ctx: crate::Context<'_>,
+    #[description = "Boops to wager (1-1000)"]
+    bet: f64,
+) -> Result<(), CommandError> {
+    let user_id = ctx.author().id.to_string();
+    let username = ctx.author().name.clone();
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    if !(MIN_BET..=MAX_BET).contains(&bet) {
+        ctx.say(format!("Bets must be between {:.0} and {:.0} boops, comrade.", MIN_BET, MAX_BET)).await?;
+        return Ok(());
+    }
+    let bet = (bet * 100.0).round() / 100.0;
+
+    let db = &ctx.data().db;
+    db.ensure_user_exists(&user_id, &server_id, &username).await?;
+
+    // Hold the stake in escrow until the hand is settled, so it is returned if the
+    // hand breaks or the bot restarts before then
+    let stakes = [(user_id.clone(), bet)];
+    let escrow_id = match db.open_escrow(&server_id, "blackjack", &stakes).await? {
+        Some(escrow_id) => escrow_id,
+        None => {
+            let balance = db.get_user_boops(&user_id).await?;
+            ctx.say(format!(
+                "You cannot afford a bet of **{:.2}** boops, comrade. You only have **{:.2}**.",
+                bet, balance
+            )).await?;
+            return Ok(());
+        }
+    };
+
+    let played = play_hand(ctx, escrow_id, &user_id, bet).await;
+    if played.is_err() {
+        // Never keep the stake of a hand that broke; a settled escrow stays settled
+        db.settle_escrow(escrow_id, &EscrowOutcome::Refund).await?;
+    }
+    played
+}
+
+// Deal and play a hand whose stake is held in `escrow_id`, then settle it
+async fn play_hand(
+    ctx: crate::Context<'_>,
+    escrow_id: i64,
+    user_id: &str,
+    bet: f64,
+) -> Result<(), CommandError> {
+    let db = &ctx.data().db;
+    let mut game = BlackjackGame::new(Deck::new_shoe(SHOE_DECKS), bet);
+    let mut balance = db.get_user_boops(user_id).await?;
+    let mut timed_out = false;
+
+    let reply = ctx.send(|m| {
+        m.content(game.render())
+         .components(|c| create_action_buttons(c, &game, balance))
+    }).await?;
+    let message_id = reply.message().await?.id;
+    let author_id = ctx.author().id;
+
+    while !game.finished {
+        let press = serenity::CollectComponentInteraction::new(ctx)
+            .message_id(message_id)
+            .filter(move |press| press.user.id == author_id)
+            .timeout(Duration::from_secs(120))
+            .await;
+
+        let press = match press {
+            Some(press) => press,
+            None => {
+                // Stand on everything so the stake is still settled
+                game.stand_all();
+                timed_out = true;
+                break;
+            }
+        };
+
+        press.defer(ctx).await?;
+
+        match press.data.custom_id.as_str() {
+            "bj_hit" => game.hit(),
+            "bj_stand" => game.stand(),
+            "bj_double" | "bj_split" => {
+                let is_double = press.data.custom_id == "bj_double";
+                let allowed = if is_double { game.can_double() } else { game.can_split() };
+                let extra_stake = game.active_hand().map(|h| h.bet).unwrap_or(0.0);
+
+                if allowed {
+                    // The extra stake joins the first in escrow
+                    if db.raise_escrow_stake(escrow_id, user_id, extra_stake).await? {
+                        if is_double {
+                            game.double();
+                        } else {
+                            game.split();
+                        }
+                    } else {
+                        press.create_followup_message(ctx, |f| {
+                            f.content("You cannot afford to raise your stake, comrade.")
+                             .ephemeral(true)
+                        }).await?;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        balance = db.get_user_boops(user_id).await?;
+
+        if !game.finished {
+            reply.edit(ctx, |m| {
+                m.content(game.render())
+                 .components(|c| create_action_buttons(c, &game, balance))
+            }).await?;
+        }
+    }
+
+    // Pay out winnings; whatever the player lost goes to the communal treasury
+    let total_bet = game.total_bet();
+    let payout = game.total_payout();
+    db.settle_escrow(escrow_id, &EscrowOutcome::Payout { user_id: user_id.to_string(), amount: payout }).await?;
+
+    let net = payout - total_bet;
+    let summary = if net > 0.0 {
+        format!("You won **{:.2}** boops, comrade. Spend them wisely.", net)
+    } else if net < 0.0 {
+        format!("**{:.2}** boops have been contributed to the communal treasury.", -net)
+    } else {
+        "Your stake has been returned. The state thanks you for participating.".to_string()
+    };
+
+    let notice = if timed_out { "\n*You walked away from the table. Your remaining hands stood.*" } else { "" };
+
+    reply.edit(ctx, |m| {
+        m.content(format!("{}\n{}{}", game.render(), summary, notice))
+         .components(|c| c)
+    }).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(rank: u8) -> Card {
+        Card::new(rank, Suit::Spades)
+    }
+
+    fn hand(ranks: &[u8]) -> Hand {
+        Hand { cards: ranks.iter().map(|&r| card(r)).collect() }
+    }
+
+    // Deal order is player, dealer, player, dealer, then hits
+    fn game(ranks: &[u8]) -> BlackjackGame {
+        BlackjackGame::new(Deck::from_cards(ranks.iter().map(|&r| card(r)).collect()), 10.0)
+    }
+
+    #[test]
+    fn shoe_contains_every_card() {
+        let mut deck = Deck::new_shoe(1);
+        assert_eq!(deck.remaining(), 52);
+
+        let mut seen = Vec::new();
+        for _ in 0..52 {
+            let c = deck.draw();
+            assert!(!seen.contains(&c));
+            seen.push(c);
+        }
+        assert_eq!(deck.remaining(), 0);
+    }
+
+    #[test]
+    fn hand_values() {
+        assert_eq!(hand(&[1, 13]).value(), (21, true));
+        assert!(hand(&[1, 13]).is_natural());
+        assert_eq!(hand(&[1, 1, 9]).value(), (21, true));
+        assert_eq!(hand(&[1, 13, 5]).value(), (16, false));
+        assert_eq!(hand(&[1, 1]).value(), (12, true));
+        assert_eq!(hand(&[12, 11]).total(), 20);
+        assert!(hand(&[10, 6, 9]).is_bust());
+        assert!(hand(&[10, 13]).is_pair());
+        assert!(!hand(&[10, 9]).is_pair());
+    }
+
+    #[test]
+    fn player_blackjack_pays_three_to_two() {
+        let game = game(&[1, 9, 13, 8]);
+        assert!(game.finished);
+        assert_eq!(game.outcome(&game.hands[0]), HandOutcome::Blackjack);
+        assert_eq!(game.total_payout(), 25.0);
+    }
+
+    #[test]
+    fn both_naturals_push() {
+        let game = game(&[1, 1, 13, 12]);
+        assert!(game.finished);
+        assert_eq!(game.outcome(&game.hands[0]), HandOutcome::Push);
+        assert_eq!(game.total_payout(), 10.0);
+    }
+
+    #[test]
+    fn dealer_natural_wins() {
+        let game = game(&[10, 1, 9, 13]);
+        assert!(game.finished);
+        assert_eq!(game.outcome(&game.hands[0]), HandOutcome::Lose);
+        assert_eq!(game.total_payout(), 0.0);
+    }
+
+    #[test]
+    fn bust_ends_round_without_dealer_drawing() {
+        let mut game = game(&[10, 10, 6, 6, 9]);
+        game.hit();
+        assert!(game.finished);
+        assert_eq!(game.dealer.cards.len(), 2);
+        assert_eq!(game.outcome(&game.hands[0]), HandOutcome::Bust);
+    }
+
+    #[test]
+    fn dealer_draws_to_seventeen() {
+        // Player stands on 18, dealer 12 draws 4 then 3 to reach 19
+        let mut game = game(&[10, 10, 8, 2, 4, 3, 5]);
+        game.stand();
+        assert!(game.finished);
+        assert_eq!(game.dealer.total(), 19);
+        assert_eq!(game.outcome(&game.hands[0]), HandOutcome::Lose);
+    }
+
+    #[test]
+    fn dealer_bust_pays_even_money() {
+        let mut game = game(&[10, 10, 2, 6, 13]);
+        game.stand();
+        assert_eq!(game.dealer.total(), 26);
+        assert_eq!(game.outcome(&game.hands[0]), HandOutcome::Win);
+        assert_eq!(game.total_payout(), 20.0);
+    }
+
+    #[test]
+    fn double_takes_one_card_and_doubles_bet() {
+        let mut game = game(&[5, 10, 6, 7, 10]);
+        assert!(game.can_double());
+        assert!(game.double());
+        assert!(game.finished);
+        assert_eq!(game.hands[0].hand.total(), 21);
+        assert_eq!(game.total_bet(), 20.0);
+        assert_eq!(game.outcome(&game.hands[0]), HandOutcome::Win);
+        assert_eq!(game.total_payout(), 40.0);
+    }
+
+    #[test]
+    fn cannot_double_after_hitting() {
+        let mut game = game(&[2, 10, 3, 7, 4]);
+        game.hit();
+        assert!(!game.finished);
+        assert!(!game.can_double());
+        assert!(!game.double());
+    }
+
+    #[test]
+    fn split_plays_each_hand() {
+        // Player 8 8 vs dealer 10 7; hands receive 3 and 10
+        let mut game = game(&[8, 10, 8, 7, 3, 10, 10]);
+        assert!(game.can_split());
+        assert!(game.split());
+        assert_eq!(game.hands.len(), 2);
+        assert_eq!(game.total_bet(), 20.0);
+        assert_eq!(game.hands[0].hand.total(), 11);
+        assert_eq!(game.hands[1].hand.total(), 18);
+
+        // First hand hits to 21 and moves on automatically
+        game.hit();
+        assert_eq!(game.active, 1);
+        game.stand();
+
+        assert!(game.finished);
+        assert_eq!(game.outcome(&game.hands[0]), HandOutcome::Win);
+        assert_eq!(game.outcome(&game.hands[1]), HandOutcome::Win);
+        assert_eq!(game.total_payout(), 40.0);
+    }
+
+    #[test]
+    fn split_aces_get_one_card_and_no_blackjack() {
+        let mut game = game(&[1, 10, 1, 7, 13, 12]);
+        assert!(game.split());
+        assert!(game.finished);
+        assert_eq!(game.hands[0].hand.total(), 21);
+        assert_eq!(game.hands[1].hand.total(), 21);
+        assert_eq!(game.outcome(&game.hands[0]), HandOutcome::Win);
+        assert_eq!(game.total_payout(), 40.0);
+    }
+
+    #[test]
+    fn split_is_limited() {
+        let mut game = game(&[8, 10, 8, 7, 8, 8, 8, 8, 8, 8]);
+        assert!(game.split());
+        assert!(game.split());
+        assert!(game.split());
+        assert_eq!(game.hands.len(), MAX_HANDS);
+        assert!(!game.can_split());
+    }
+
+    #[test]
+    fn stand_all_finishes_the_round() {
+        let mut game = game(&[8, 10, 8, 7, 3, 10]);
+        game.split();
+        game.stand_all();
+        assert!(game.finished);
+        assert!(game.hands.iter().all(|h| h.done));
+    }
+}
@@ -0,0 +1,803 @@
+use crate::CommandError;
+use crate::db::ActiveGame;
+use super::active_games::Resumed;
+use super::checkers_engine::{self, Board, CheckersMove, Man, Side};
+use super::chess_engine::{Color, Move, Position};
+use super::game_engine::{resume_press, resume_typed_move, run_game, Game, Outcome, Turn};
+use poise::serenity_prelude as serenity;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// How long a board waits for a move before the game carries on by correspondence
+const LIVE_MOVE_TIME: Duration = Duration::from_secs(600);
+
+// Chess's fifty-move rule, in plies without a capture or a pawn move
+const FIFTY_MOVE_PLIES: u32 = 100;
+
+// Checkers' forty-move rule, in plies without a capture or a man's move
+const FORTY_MOVE_PLIES: u32 = 80;
+
+// Times a position must come up for the game to be drawn by repetition
+const REPETITIONS: usize = 3;
+
+// Longest line of moves in a PGN or PDN record
+const RECORD_LINE_WIDTH: usize = 80;
+
+const CHECKERS_RANKS: [&str; 8] = ["8️⃣", "7️⃣", "6️⃣", "5️⃣", "4️⃣", "3️⃣", "2️⃣", "1️⃣"];
+// Zero-width spaces keep neighbouring letters from turning into flags
+const CHECKERS_FILES: &str = "⬛🇦\u{200b}🇧\u{200b}🇨\u{200b}🇩\u{200b}🇪\u{200b}🇫\u{200b}🇬\u{200b}🇭";
+
+// How a game at the table ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Ending {
+    Won { side: usize, by: String },
+    Drawn { by: String },
+}
+
+// What a comrade may do at the table besides moving
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TableAction {
+    Draw,
+    Resign,
+}
+
+// What chess and checkers share besides a board: who sits where, a draw offer
+// waiting for an answer, and how the game ended
+#[derive(Clone, Serialize, Deserialize)]
+struct Table {
+    seats: Vec<serenity::UserId>,
+    // The side whose offer stands until the other accepts it or a move is made
+    draw_offer: Option<usize>,
+    ending: Option<Ending>,
+    // When the game began, for its record
+    date: String,
+}
+
+impl Table {
+    fn new(players: &[serenity::User]) -> Self {
+        Table {
+            seats: players.iter().map(|player| player.id).collect(),
+            draw_offer: None,
+            ending: None,
+            date: chrono::Utc::now().format("%Y.%m.%d").to_string(),
+        }
+    }
+
+    fn side_of(&self, user: serenity::UserId) -> Option<usize> {
+        self.seats.iter().position(|&id| id == user)
+    }
+
+    fn action(custom_id: &str) -> Option<TableAction> {
+        match custom_id {
+            "table_draw" => Some(TableAction::Draw),
+            "table_resign" => Some(TableAction::Resign),
+            _ => None,
+        }
+    }
+
+    // Offer a draw, take back an offer, accept the other side's offer or resign.
+    // Either side may do so at any time, whoever's move it is.
+    fn act(&mut self, side: usize, action: TableAction) -> bool {
+        if self.ending.is_some() {
+            return false;
+        }
+
+        match (action, self.draw_offer) {
+            (TableAction::Draw, None) => self.draw_offer = Some(side),
+            (TableAction::Draw, Some(offered_by)) if offered_by == side => self.draw_offer = None,
+            (TableAction::Draw, Some(_)) => self.ending = Some(Ending::Drawn { by: "agreement".to_string() }),
+            (TableAction::Resign, _) => self.ending = Some(Ending::Won { side: 1 - side, by: "resignation".to_string() }),
+        }
+        true
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        match self.ending.as_ref()? {
+            Ending::Won { side, .. } => Some(Outcome::Win(*side)),
+            Ending::Drawn { .. } => Some(Outcome::Draw),
+        }
+    }
+
+    // The result as a record writes it, from the point of view of `first`
+    fn result(&self, first: usize) -> &'static str {
+        match &self.ending {
+            Some(Ending::Won { side, .. }) if *side == first => "1-0",
+            Some(Ending::Won { .. }) => "0-1",
+            Some(Ending::Drawn { .. }) => "1/2-1/2",
+            None => "*",
+        }
+    }
+
+    // How the game ended, naming each side as given
+    fn describe_ending(&self, names: [&str; 2]) -> Option<String> {
+        match self.ending.as_ref()? {
+            Ending::Won { side, by } => Some(format!("{} wins by {}", names[*side], by)),
+            Ending::Drawn { by } => Some(format!("Drawn by {}", by)),
+        }
+    }
+
+    // Whose move it is, or how the game ended, and any draw on offer
+    fn render_status(&self, players: &[serenity::User], to_move: usize, colors: [&str; 2], check: bool) -> String {
+        let names = [0, 1].map(|side| format!("**{}**", player_name(players, side)));
+
+        if let Some(ending) = self.describe_ending([&names[0], &names[1]]) {
+            return match self.ending {
+                Some(Ending::Won { .. }) => format!("{}! Glory to the victor of the people's struggle!", ending),
+                _ => format!("{}. Neither side yields an inch of ground.", ending),
+            };
+        }
+
+        let mut status = format!("It's {}'s move ({}).", names[to_move], colors[to_move]);
+        if check {
+            status.push_str(" **Check!**");
+        }
+        if let Some(side) = self.draw_offer {
+            status.push_str(&format!("\n🤝 {} offers a draw.", names[side]));
+        }
+        status
+    }
+}
+
+fn player_name(players: &[serenity::User], side: usize) -> &str {
+    players.get(side).map(|player| player.name.as_str()).unwrap_or("Unknown comrade")
+}
+
+// A move as the table sees it: a move on the board, or something said across it
+#[derive(Debug, Clone, PartialEq)]
+enum TableMove<M> {
+    Play { side: usize, mv: M },
+    Table { side: usize, action: TableAction },
+}
+
+// Read a press or a typed move: what follows `move_id` and a colon is passed to `parse`
+fn parse_table_move<M>(
+    table: &Table,
+    custom_id: &str,
+    presser: &serenity::User,
+    move_id: &str,
+    parse: impl Fn(&str) -> Option<M>,
+) -> Option<TableMove<M>> {
+    let side = table.side_of(presser.id)?;
+
+    match custom_id.strip_prefix(move_id).and_then(|rest| rest.strip_prefix(':')) {
+        Some(text) => parse(text).map(|mv| TableMove::Play { side, mv }),
+        None => Table::action(custom_id).map(|action| TableMove::Table { side, action }),
+    }
+}
+
+// Numbered movetext with the result at the end, wrapped as PGN and PDN expect
+fn record_movetext(moves: &[String], comment: Option<String>, result: &str) -> String {
+    let mut tokens = Vec::new();
+    for (ply, mv) in moves.iter().enumerate() {
+        if ply.is_multiple_of(2) {
+            tokens.push(format!("{}.", ply / 2 + 1));
+        }
+        tokens.push(mv.clone());
+    }
+    tokens.extend(comment.map(|comment| format!("{{{}}}", comment)));
+    tokens.push(result.to_string());
+
+    let mut text = String::new();
+    let mut line_width = 0;
+    for token in tokens {
+        if line_width > 0 && line_width + 1 + token.chars().count() > RECORD_LINE_WIDTH {
+            text.push('\n');
+            line_width = 0;
+        } else if line_width > 0 {
+            text.push(' ');
+            line_width += 1;
+        }
+        line_width += token.chars().count();
+        text.push_str(&token);
+    }
+    text.push('\n');
+    text
+}
+
+fn record_tags(tags: &[(&str, &str)]) -> String {
+    tags.iter()
+        .map(|(tag, value)| format!("[{} \"{}\"]\n", tag, value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect()
+}
+
+// The button that asks for a typed move, then the draw and resignation buttons.
+// Empty once the game is over.
+fn create_table_controls<'a>(
+    c: &'a mut serenity::CreateComponents,
+    table: &Table,
+    move_id: &str,
+) -> &'a mut serenity::CreateComponents {
+    if table.ending.is_some() {
+        return c;
+    }
+
+    c.create_action_row(|row| {
+        row.create_button(|b| {
+            b.custom_id(move_id)
+             .label("Make Move")
+             .style(serenity::ButtonStyle::Primary)
+             .emoji('✍')
+        });
+        row.create_button(|b| {
+            b.custom_id("table_draw")
+             .label(if table.draw_offer.is_some() { "Accept Draw" } else { "Offer Draw" })
+             .style(serenity::ButtonStyle::Secondary)
+             .emoji('🤝')
+        });
+        row.create_button(|b| {
+            b.custom_id("table_resign")
+             .label("Resign")
+             .style(serenity::ButtonStyle::Danger)
+             .emoji('🏳')
+        })
+    })
+}
+
+// Chess, with White in the first seat
+#[derive(Clone, Serialize, Deserialize)]
+struct Chess {
+    table: Table,
+    position: Position,
+    // Every move so far in SAN, for the record
+    moves: Vec<String>,
+    // Every position so far, for the repetition rule
+    seen: Vec<String>,
+}
+
+impl Chess {
+    fn new(players: &[serenity::User]) -> Self {
+        let position = Position::start();
+        Chess {
+            table: Table::new(players),
+            seen: vec![position.placement_key()],
+            position,
+            moves: Vec::new(),
+        }
+    }
+
+    fn side_to_move(&self) -> usize {
+        match self.position.turn {
+            Color::White => 0,
+            Color::Black => 1,
+        }
+    }
+
+    // End the game if the move just made left nothing to play for
+    fn check_ending(&mut self) {
+        let by = if self.position.legal_moves().is_empty() {
+            if self.position.in_check() {
+                let winner = 1 - self.side_to_move();
+                self.table.ending = Some(Ending::Won { side: winner, by: "checkmate".to_string() });
+                return;
+            }
+            "stalemate"
+        } else if self.position.insufficient_material() {
+            "insufficient material"
+        } else if self.position.halfmove_clock >= FIFTY_MOVE_PLIES {
+            "the fifty-move rule"
+        } else if self.seen.iter().filter(|&key| *key == self.position.placement_key()).count() >= REPETITIONS {
+            "threefold repetition"
+        } else {
+            return;
+        };
+
+        self.table.ending = Some(Ending::Drawn { by: by.to_string() });
+    }
+
+    fn render_board(&self) -> String {
+        let mut board = "```\n  a b c d e f g h\n".to_string();
+        for rank in (0..8u8).rev() {
+            board.push_str(&format!("{} ", rank + 1));
+            for file in 0..8u8 {
+                board.push(self.position.piece_at(rank * 8 + file).map(|piece| piece.symbol()).unwrap_or('·'));
+                board.push(' ');
+            }
+            board.push_str(&format!("{}\n", rank + 1));
+        }
+        board.push_str("  a b c d e f g h\n```");
+        board
+    }
+}
+
+impl Game for Chess {
+    type Move = TableMove<Move>;
+
+    const KEY: &'static str = "chess";
+    const NAME: &'static str = "People's Chess";
+    const CORRESPONDENCE: bool = true;
+
+    // Either player may offer a draw or resign at any time, so presses are open to
+    // both and the game checks whose move it is
+    fn turn(&self) -> Turn {
+        match self.table.ending {
+            Some(_) => Turn::Over,
+            None => Turn::Open,
+        }
+    }
+
+    fn legal_moves(&self) -> Vec<TableMove<Move>> {
+        if self.table.ending.is_some() {
+            return Vec::new();
+        }
+
+        let side = self.side_to_move();
+        self.position.legal_moves().into_iter().map(|mv| TableMove::Play { side, mv }).collect()
+    }
+
+    fn apply_move(&mut self, mv: TableMove<Move>) -> bool {
+        match mv {
+            TableMove::Table { side, action } => self.table.act(side, action),
+            TableMove::Play { side, mv } => {
+                let legal = self.table.ending.is_none()
+                    && side == self.side_to_move()
+                    && self.position.legal_moves().contains(&mv);
+                if !legal {
+                    return false;
+                }
+
+                self.moves.push(self.position.san(mv));
+                self.position = self.position.play(mv);
+                self.seen.push(self.position.placement_key());
+                // Moving declines any draw on offer
+                self.table.draw_offer = None;
+                self.check_ending();
+                true
+            },
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.table.ending.is_some()
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        self.table.outcome()
+    }
+
+    fn parse_move(&self, custom_id: &str, presser: &serenity::User, _think_time: Duration) -> Option<TableMove<Move>> {
+        parse_table_move(&self.table, custom_id, presser, "chess_move", |text| self.position.parse_move(text))
+    }
+
+    fn render(&self, players: &[serenity::User]) -> String {
+        let mut content = format!(
+            "**☭ People's Chess ☭**\n♔ White: **{}** · ♚ Black: **{}**\n\n{}\n",
+            player_name(players, 0), player_name(players, 1), self.render_board()
+        );
+
+        if let Some(last) = self.moves.last() {
+            let ply = self.moves.len() - 1;
+            let dots = if ply.is_multiple_of(2) { "." } else { "..." };
+            content.push_str(&format!("Last move: {}{} {}\n", ply / 2 + 1, dots, last));
+        }
+
+        let check = self.position.in_check() && self.table.ending.is_none();
+        content.push_str(&self.table.render_status(players, self.side_to_move(), [Color::White.name(), Color::Black.name()], check));
+        content
+    }
+
+    fn render_abandoned(&self, _players: &[serenity::User]) -> String {
+        "**☭ People's Chess ☭**\n\nGame abandoned! The pieces return to the box unfinished.".to_string()
+    }
+
+    fn components<'a>(&self, c: &'a mut serenity::CreateComponents) -> &'a mut serenity::CreateComponents {
+        create_table_controls(c, &self.table, "chess_move")
+    }
+
+    fn move_prompt(&self, custom_id: &str) -> Option<(&'static str, &'static str)> {
+        (custom_id == "chess_move").then_some(("Your Move", "Move in SAN, e.g. Nf3, exd5, O-O or e8=Q"))
+    }
+
+    fn move_timeout(&self) -> Duration {
+        LIVE_MOVE_TIME
+    }
+
+    // The game in PGN
+    fn transcript(&self, players: &[serenity::User]) -> Option<(String, String)> {
+        let result = self.table.result(0);
+        let mut pgn = record_tags(&[
+            ("Event", Self::NAME),
+            ("Site", "Discord"),
+            ("Date", &self.table.date),
+            ("Round", "-"),
+            ("White", player_name(players, 0)),
+            ("Black", player_name(players, 1)),
+            ("Result", result),
+        ]);
+        pgn.push('\n');
+        pgn.push_str(&record_movetext(&self.moves, self.table.describe_ending(["White", "Black"]), result));
+
+        Some(("chess.pgn".to_string(), pgn))
+    }
+}
+
+// English checkers, with Black in the first seat and moving first
+#[derive(Clone, Serialize, Deserialize)]
+struct Checkers {
+    table: Table,
+    board: Board,
+    // Every move so far in PDN, for the record
+    moves: Vec<String>,
+    // Every position so far, for the repetition rule
+    seen: Vec<String>,
+    // Plies since the last capture or man's move, for the forty-move rule
+    quiet_plies: u32,
+}
+
+impl Checkers {
+    fn new(players: &[serenity::User]) -> Self {
+        let board = Board::start();
+        Checkers {
+            table: Table::new(players),
+            seen: vec![board.key()],
+            board,
+            moves: Vec::new(),
+            quiet_plies: 0,
+        }
+    }
+
+    fn side_to_move(&self) -> usize {
+        match self.board.turn {
+            Side::Black => 0,
+            Side::White => 1,
+        }
+    }
+
+    // End the game if the move just made left nothing to play for. A side that
+    // can't move, whether it has pieces left or not, has lost.
+    fn check_ending(&mut self) {
+        let mover = 1 - self.side_to_move();
+        let by = if self.board.legal_moves().is_empty() {
+            let wiped_out = self.board.squares.iter().flatten().all(|man| man.side != self.board.turn);
+            let by = if wiped_out { "taking every piece" } else { "leaving no moves" };
+            self.table.ending = Some(Ending::Won { side: mover, by: by.to_string() });
+            return;
+        } else if self.quiet_plies >= FORTY_MOVE_PLIES {
+            "the forty-move rule"
+        } else if self.seen.iter().filter(|&key| *key == self.board.key()).count() >= REPETITIONS {
+            "threefold repetition"
+        } else {
+            return;
+        };
+
+        self.table.ending = Some(Ending::Drawn { by: by.to_string() });
+    }
+
+    fn render_board(&self) -> String {
+        let mut board = String::new();
+        for (row, rank) in CHECKERS_RANKS.iter().enumerate() {
+            board.push_str(rank);
+            for col in 0..8 {
+                let square = checkers_engine::square_at(row as i8, col);
+                board.push_str(match square.and_then(|square| self.board.man_at(square)) {
+                    _ if square.is_none() => "⬜",
+                    None => "🟫",
+                    Some(Man { side: Side::Black, king: false }) => "⚫",
+                    Some(Man { side: Side::Black, king: true }) => "🖤",
+                    Some(Man { side: Side::White, king: false }) => "⚪",
+                    Some(Man { side: Side::White, king: true }) => "🤍",
+                });
+            }
+            board.push('\n');
+        }
+        board.push_str(CHECKERS_FILES);
+        board
+    }
+}
+
+impl Game for Checkers {
+    type Move = TableMove<CheckersMove>;
+
+    const KEY: &'static str = "checkers";
+    const NAME: &'static str = "Proletarian Checkers";
+    const CORRESPONDENCE: bool = true;
+
+    // As in chess, either player may offer a draw or resign at any time
+    fn turn(&self) -> Turn {
+        match self.table.ending {
+            Some(_) => Turn::Over,
+            None => Turn::Open,
+        }
+    }
+
+    fn legal_moves(&self) -> Vec<TableMove<CheckersMove>> {
+        if self.table.ending.is_some() {
+            return Vec::new();
+        }
+
+        let side = self.side_to_move();
+        self.board.legal_moves().into_iter().map(|mv| TableMove::Play { side, mv }).collect()
+    }
+
+    fn apply_move(&mut self, mv: TableMove<CheckersMove>) -> bool {
+        match mv {
+            TableMove::Table { side, action } => self.table.act(side, action),
+            TableMove::Play { side, mv } => {
+                let legal = self.table.ending.is_none()
+                    && side == self.side_to_move()
+                    && self.board.legal_moves().contains(&mv);
+                if !legal {
+                    return false;
+                }
+
+                let king_moved = mv.path.first().and_then(|&from| self.board.man_at(from)).is_some_and(|man| man.king);
+                self.quiet_plies = if king_moved && mv.captured.is_empty() { self.quiet_plies + 1 } else { 0 };
+
+                self.moves.push(mv.notation());
+                self.board = self.board.play(&mv);
+                self.seen.push(self.board.key());
+                // Moving declines any draw on offer
+                self.table.draw_offer = None;
+                self.check_ending();
+                true
+            },
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.table.ending.is_some()
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        self.table.outcome()
+    }
+
+    fn parse_move(&self, custom_id: &str, presser: &serenity::User, _think_time: Duration) -> Option<TableMove<CheckersMove>> {
+        parse_table_move(&self.table, custom_id, presser, "checkers_move", |text| self.board.parse_move(text))
+    }
+
+    fn render(&self, players: &[serenity::User]) -> String {
+        let mut content = format!(
+            "**☭ Proletarian Checkers ☭**\n⚫ Black: **{}** · ⚪ White: **{}**\n\n{}\n*Squares are numbered 1-32 from the top left, or named a1-h8.*\n",
+            player_name(players, 0), player_name(players, 1), self.render_board()
+        );
+
+        if let Some(last) = self.moves.last() {
+            content.push_str(&format!("Last move: {}\n", last));
+        }
+
+        content.push_str(&self.table.render_status(players, self.side_to_move(), [Side::Black.name(), Side::White.name()], false));
+        content
+    }
+
+    fn render_abandoned(&self, _players: &[serenity::User]) -> String {
+        "**☭ Proletarian Checkers ☭**\n\nGame abandoned! The pieces return to the box unfinished.".to_string()
+    }
+
+    fn components<'a>(&self, c: &'a mut serenity::CreateComponents) -> &'a mut serenity::CreateComponents {
+        create_table_controls(c, &self.table, "checkers_move")
+    }
+
+    fn move_prompt(&self, custom_id: &str) -> Option<(&'static str, &'static str)> {
+        (custom_id == "checkers_move").then_some(("Your Move", "Move, e.g. 11-15, 22x15 or c3-d4"))
+    }
+
+    fn move_timeout(&self) -> Duration {
+        LIVE_MOVE_TIME
+    }
+
+    // The game in PDN, with the result from Black's side as the first to move
+    fn transcript(&self, players: &[serenity::User]) -> Option<(String, String)> {
+        let result = self.table.result(0);
+        let mut pdn = record_tags(&[
+            ("Event", Self::NAME),
+            ("Site", "Discord"),
+            ("Date", &self.table.date),
+            ("Black", player_name(players, 0)),
+            ("White", player_name(players, 1)),
+            ("Result", result),
+            ("GameType", "21"),
+        ]);
+        pdn.push('\n');
+        pdn.push_str(&record_movetext(&self.moves, self.table.describe_ending(["Black", "White"]), result));
+
+        Some(("checkers.pdn".to_string(), pdn))
+    }
+}
+
+// The saved game types played by correspondence, as the games themselves declare
+pub fn correspondence_games() -> Vec<&'static str> {
+    [(Chess::KEY, Chess::CORRESPONDENCE), (Checkers::KEY, Checkers::CORRESPONDENCE)]
+        .into_iter()
+        .filter(|(_, correspondence)| *correspondence)
+        .map(|(key, _)| key)
+        .collect()
+}
+
+// Apply a press to a saved game of chess or checkers whose command is no longer running
+pub(crate) async fn resume_board_press(
+    ctx: &serenity::Context,
+    data: &crate::Data,
+    record: &ActiveGame,
+    press: &serenity::MessageComponentInteraction,
+) -> Result<Resumed, CommandError> {
+    match record.game_type.as_str() {
+        Chess::KEY => resume_press::<Chess>(ctx, data, record, press).await,
+        _ => resume_press::<Checkers>(ctx, data, record, press).await,
+    }
+}
+
+// Apply a typed move to a saved game of chess or checkers, as for presses
+pub(crate) async fn resume_board_move(
+    ctx: &serenity::Context,
+    data: &crate::Data,
+    record: &ActiveGame,
+    submit: &serenity::ModalSubmitInteraction,
+) -> Result<Resumed, CommandError> {
+    match record.game_type.as_str() {
+        Chess::KEY => resume_typed_move::<Chess>(ctx, data, record, submit).await,
+        _ => resume_typed_move::<Checkers>(ctx, data, record, submit).await,
+    }
+}
+
+// Both board games need a human opponent
+async fn check_opponent(ctx: crate::Context<'_>, opponent: &serenity::User) -> Result<bool, CommandError> {
+    if opponent.id == ctx.author().id {
+        ctx.say("You can't play against yourself, comrade! Even Lenin needed a sparring partner.").await?;
+        return Ok(false);
+    }
+
+    if opponent.bot {
+        ctx.say("You can't play against a bot! Challenge a human comrade.").await?;
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// People's Chess: challenge a comrade, with moves typed in algebraic notation
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn chess(
+    ctx: crate::Context<'_>,
+    #[description = "The comrade to play against"]
+    opponent: serenity::User,
+    #[description = "Boops each player stakes on the outcome (1-500)"]
+    wager: Option<f64>,
+) -> Result<(), CommandError> {
+    if !check_opponent(ctx, &opponent).await? {
+        return Ok(());
+    }
+
+    // The first seat plays White
+    run_game(ctx, Some(opponent), wager, Chess::new).await
+}
+
+/// Proletarian Checkers: challenge a comrade, where every capture is compulsory
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn checkers(
+    ctx: crate::Context<'_>,
+    #[description = "The comrade to play against"]
+    opponent: serenity::User,
+    #[description = "Boops each player stakes on the outcome (1-500)"]
+    wager: Option<f64>,
+) -> Result<(), CommandError> {
+    if !check_opponent(ctx, &opponent).await? {
+        return Ok(());
+    }
+
+    // The first seat plays Black, which moves first
+    run_game(ctx, Some(opponent), wager, Checkers::new).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comrade(id: u64, name: &str) -> serenity::User {
+        let mut user = serenity::User::default();
+        user.id = serenity::UserId(id);
+        user.name = name.to_string();
+        user
+    }
+
+    fn players() -> Vec<serenity::User> {
+        vec![comrade(1, "Lenin"), comrade(2, "Trotsky")]
+    }
+
+    // Type each move in turn, as whichever seated comrade it belongs to would
+    fn play<G: Game>(game: &mut G, players: &[serenity::User], move_id: &str, moves: &[&str]) {
+        for text in moves {
+            let played = players.iter().any(|player| {
+                let mv = game.parse_move(&format!("{}:{}", move_id, text), player, Duration::ZERO);
+                mv.is_some_and(|mv| game.apply_move(mv))
+            });
+            assert!(played, "{} should be legal", text);
+        }
+    }
+
+    #[test]
+    fn chess_ends_in_checkmate_with_a_pgn_record() {
+        let players = players();
+        let mut game = Chess::new(&players);
+        play(&mut game, &players, "chess_move", &["f3", "e5", "g4", "Qh4"]);
+
+        assert_eq!(game.outcome(), Some(Outcome::Win(1)));
+        assert_eq!(game.turn(), Turn::Over);
+
+        let (filename, pgn) = game.transcript(&players).unwrap();
+        assert_eq!(filename, "chess.pgn");
+        assert!(pgn.contains("[White \"Lenin\"]\n[Black \"Trotsky\"]\n[Result \"0-1\"]"));
+        assert!(pgn.ends_with("\n\n1. f3 e5 2. g4 Qh4# {Black wins by checkmate} 0-1\n"));
+    }
+
+    #[test]
+    fn only_the_side_to_move_may_move() {
+        let players = players();
+        let mut game = Chess::new(&players);
+
+        let black_first = game.parse_move("chess_move:e5", &players[1], Duration::ZERO);
+        assert!(black_first.is_none(), "e5 isn't legal for White");
+        let black_first = game.parse_move("chess_move:e4", &players[1], Duration::ZERO).unwrap();
+        assert!(!game.apply_move(black_first));
+        assert!(game.parse_move("chess_move:e4", &comrade(3, "Kamenev"), Duration::ZERO).is_none());
+        assert!(game.parse_move("chess_move:e5e4", &players[0], Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn draws_are_offered_and_accepted_across_the_table() {
+        let players = players();
+        let mut game = Chess::new(&players);
+        let offer = |game: &Chess, side: usize| game.parse_move("table_draw", &players[side], Duration::ZERO).unwrap();
+
+        assert!(game.apply_move(offer(&game, 0)));
+        assert_eq!(game.table.draw_offer, Some(0));
+
+        // A move declines the offer
+        play(&mut game, &players, "chess_move", &["e4"]);
+        assert_eq!(game.table.draw_offer, None);
+
+        assert!(game.apply_move(offer(&game, 1)));
+        assert!(game.apply_move(offer(&game, 0)));
+        assert_eq!(game.outcome(), Some(Outcome::Draw));
+        assert!(game.transcript(&players).unwrap().1.ends_with("1. e4 {Drawn by agreement} 1/2-1/2\n"));
+        assert!(!game.apply_move(offer(&game, 1)), "the game is over");
+    }
+
+    #[test]
+    fn resigning_hands_the_game_to_the_other_side() {
+        let players = players();
+        let mut game = Checkers::new(&players);
+        play(&mut game, &players, "checkers_move", &["11-15", "23-19"]);
+
+        let resign = game.parse_move("table_resign", &players[1], Duration::ZERO).unwrap();
+        assert!(game.apply_move(resign));
+        assert_eq!(game.outcome(), Some(Outcome::Win(0)));
+
+        let (_, pdn) = game.transcript(&players).unwrap();
+        assert!(pdn.contains("[Black \"Lenin\"]\n[White \"Trotsky\"]\n[Result \"1-0\"]"));
+        assert!(pdn.ends_with("1. 11-15 23-19 {Black wins by resignation} 1-0\n"));
+    }
+
+    #[test]
+    fn checkers_captures_are_forced() {
+        let players = players();
+        let mut game = Checkers::new(&players);
+        play(&mut game, &players, "checkers_move", &["11-15", "22-18"]);
+
+        // Black must take 18 rather than move quietly, and White must take back
+        assert!(game.parse_move("checkers_move:12-16", &players[0], Duration::ZERO).is_none());
+        play(&mut game, &players, "checkers_move", &["15x22"]);
+        assert!(game.parse_move("checkers_move:24-20", &players[1], Duration::ZERO).is_none());
+        play(&mut game, &players, "checkers_move", &["25x18"]);
+        assert_eq!(game.quiet_plies, 0);
+    }
+
+    #[test]
+    fn a_repeated_position_is_drawn() {
+        let players = players();
+        let mut game = Chess::new(&players);
+        play(&mut game, &players, "chess_move", &["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1"]);
+        assert!(game.outcome().is_none());
+
+        play(&mut game, &players, "chess_move", &["Ng8"]);
+        assert_eq!(game.outcome(), Some(Outcome::Draw));
+        assert!(game.render(&players).contains("Drawn by threefold repetition"));
+    }
+
+    #[test]
+    fn long_records_are_wrapped() {
+        let moves: Vec<String> = (0..60).map(|_| "Nf3".to_string()).collect();
+        let movetext = record_movetext(&moves, None, "*");
+        assert!(movetext.lines().count() > 1);
+        assert!(movetext.lines().all(|line| line.chars().count() <= RECORD_LINE_WIDTH));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::CommandError;
+use poise::serenity_prelude as serenity;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// How long a challenge or rematch offer stays open
+const INVITE_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Tracks which comrades are currently in a game (or have a challenge pending),
+// which game messages are being driven by a running command, and which channels
+// have saved games that take their moves from chat
+#[derive(Default)]
+pub struct GameRegistry {
+    players: Mutex<HashSet<serenity::UserId>>,
+    live_messages: Mutex<HashSet<serenity::MessageId>>,
+    chat_channels: Mutex<HashSet<serenity::ChannelId>>,
+    resume_lock: tokio::sync::Mutex<()>,
+}
+
+impl GameRegistry {
+    // Reserve every player for a game. Fails with the first player who is already busy.
+    pub(crate) fn try_reserve(self: &Arc<Self>, players: &[serenity::UserId]) -> Result<PlayerReservation, serenity::UserId> {
+        let mut active = self.players.lock().unwrap();
+
+        if let Some(busy) = players.iter().find(|p| active.contains(p)) {
+            return Err(*busy);
+        }
+
+        active.extend(players.iter().copied());
+
+        Ok(PlayerReservation {
+            registry: Arc::clone(self),
+            players: players.to_vec(),
+        })
+    }
+    
+    // Mark a game message as handled by a running command, so the interaction
+    // router leaves its presses to the command's own collector
+    pub(crate) fn track_live(self: &Arc<Self>, message_id: serenity::MessageId) -> LiveGame {
+        self.live_messages.lock().unwrap().insert(message_id);
+        
+        LiveGame {
+            registry: Arc::clone(self),
+            message_id,
+        }
+    }
+    
+    pub(crate) fn is_live(&self, message_id: serenity::MessageId) -> bool {
+        self.live_messages.lock().unwrap().contains(&message_id)
+    }
+    
+    // Note channels with saved games that take their moves from chat, so the message
+    // router only looks for games under messages sent there
+    pub(crate) fn watch_channels(&self, channels: impl IntoIterator<Item = serenity::ChannelId>) {
+        self.chat_channels.lock().unwrap().extend(channels);
+    }
+    
+    pub(crate) fn is_watched(&self, channel_id: serenity::ChannelId) -> bool {
+        self.chat_channels.lock().unwrap().contains(&channel_id)
+    }
+    
+    pub(crate) fn unwatch_channel(&self, channel_id: serenity::ChannelId) {
+        self.chat_channels.lock().unwrap().remove(&channel_id);
+    }
+    
+    // Held while a saved game is loaded, updated and stored again
+    pub(crate) async fn lock_resumes(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.resume_lock.lock().await
+    }
+}
+
+// Releases its players when dropped, however the game ends
+pub(crate) struct PlayerReservation {
+    registry: Arc<GameRegistry>,
+    players: Vec<serenity::UserId>,
+}
+
+impl Drop for PlayerReservation {
+    fn drop(&mut self) {
+        let mut active = self.registry.players.lock().unwrap();
+        for player in &self.players {
+            active.remove(player);
+        }
+    }
+}
+
+// Hands a game message over to the router when dropped
+pub(crate) struct LiveGame {
+    registry: Arc<GameRegistry>,
+    message_id: serenity::MessageId,
+}
+
+impl Drop for LiveGame {
+    fn drop(&mut self) {
+        self.registry.live_messages.lock().unwrap().remove(&self.message_id);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChallengeResponse {
+    Accepted,
+    Declined,
+    Withdrawn,
+    Expired,
+}
+
+// Invite an opponent to a game and wait for them to accept or decline.
+// The challenger may withdraw the invite while it is pending.
+pub(crate) async fn send_challenge(
+    ctx: crate::Context<'_>,
+    challenger: &serenity::User,
+    opponent: &serenity::User,
+    game_name: &str,
+    wager: Option<f64>,
+) -> Result<ChallengeResponse, CommandError> {
+    let stakes = match wager {
+        Some(wager) => format!(" for **{:.2}** boops each", wager),
+        None => String::new(),
+    };
+
+    let reply = ctx.send(|m| {
+        m.content(format!(
+            "<@{}>, comrade **{}** challenges you to {}{}! Do you accept?\n*This invitation expires <t:{}:R>.*",
+            opponent.id,
+            challenger.name,
+            game_name,
+            stakes,
+            chrono::Utc::now().timestamp() + INVITE_TIMEOUT.as_secs() as i64
+        ))
+        .components(|c| {
+            c.create_action_row(|row| {
+                row.create_button(|b| {
+                    b.custom_id("challenge_accept")
+                     .label("Accept")
+                     .style(serenity::ButtonStyle::Success)
+                })
+                .create_button(|b| {
+                    b.custom_id("challenge_decline")
+                     .label("Decline")
+                     .style(serenity::ButtonStyle::Danger)
+                })
+            })
+        })
+    }).await?;
+    let message_id = reply.message().await?.id;
+
+    let challenger_id = challenger.id;
+    let opponent_id = opponent.id;
+    let deadline = tokio::time::Instant::now() + INVITE_TIMEOUT;
+
+    let response = loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+
+        let press = serenity::CollectComponentInteraction::new(ctx)
+            .message_id(message_id)
+            .filter(move |press| press.user.id == opponent_id || press.user.id == challenger_id)
+            .timeout(remaining)
+            .await;
+
+        let press = match press {
+            Some(press) => press,
+            None => break ChallengeResponse::Expired,
+        };
+
+        press.defer(ctx).await?;
+
+        match (press.user.id == opponent_id, press.data.custom_id.as_str()) {
+            (true, "challenge_accept") => break ChallengeResponse::Accepted,
+            (true, "challenge_decline") => break ChallengeResponse::Declined,
+            (false, "challenge_decline") => break ChallengeResponse::Withdrawn,
+            // The challenger can't accept on their opponent's behalf
+            _ => continue,
+        }
+    };
+
+    let outcome = match response {
+        ChallengeResponse::Accepted => "The challenge has been accepted! To your positions, comrades.",
+        ChallengeResponse::Declined => "The challenge was declined.",
+        ChallengeResponse::Withdrawn => "The challenge was withdrawn.",
+        ChallengeResponse::Expired => "The challenge expired without an answer.",
+    };
+
+    reply.edit(ctx, |m| {
+        m.content(format!(
+            "**{}** vs **{}** in {}{}. {}",
+            challenger.name, opponent.name, game_name, stakes, outcome
+        ))
+        .components(|c| c)
+    }).await?;
+
+    Ok(response)
+}
+
+// Offer a rematch to everyone who just played. Returns true once every player
+// has pressed the button before the offer expires.
+pub(crate) async fn offer_rematch(
+    ctx: crate::Context<'_>,
+    players: &[&serenity::User],
+    game_name: &str,
+) -> Result<bool, CommandError> {
+    let player_ids: Vec<serenity::UserId> = players.iter().map(|p| p.id).collect();
+    let mut ready: Vec<serenity::UserId> = Vec::new();
+
+    let reply = ctx.send(|m| {
+        m.content(format!("Another round of {}, comrades?", game_name))
+         .components(|c| create_rematch_button(c, ready.len(), player_ids.len()))
+    }).await?;
+    let message_id = reply.message().await?.id;
+    let deadline = tokio::time::Instant::now() + INVITE_TIMEOUT;
+
+    while ready.len() < player_ids.len() {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        let allowed = player_ids.clone();
+
+        let press = serenity::CollectComponentInteraction::new(ctx)
+            .message_id(message_id)
+            .filter(move |press| allowed.contains(&press.user.id))
+            .timeout(remaining)
+            .await;
+
+        let press = match press {
+            Some(press) => press,
+            None => break,
+        };
+
+        press.defer(ctx).await?;
+
+        if !ready.contains(&press.user.id) {
+            ready.push(press.user.id);
+        }
+
+        if ready.len() < player_ids.len() {
+            reply.edit(ctx, |m| {
+                m.components(|c| create_rematch_button(c, ready.len(), player_ids.len()))
+            }).await?;
+        }
+    }
+
+    let agreed = ready.len() == player_ids.len();
+
+    reply.edit(ctx, |m| {
+        m.content(if agreed {
+            format!("Rematch of {} agreed! The struggle continues.", game_name)
+        } else {
+            format!("The {} rematch offer has expired.", game_name)
+        })
+        .components(|c| c)
+    }).await?;
+
+    Ok(agreed)
+}
+
+fn create_rematch_button(
+    c: &mut serenity::CreateComponents,
+    ready: usize,
+    total: usize,
+) -> &mut serenity::CreateComponents {
+    c.create_action_row(|row| {
+        row.create_button(|b| {
+            let label = if total > 1 {
+                format!("Rematch ({}/{})", ready, total)
+            } else {
+                "Rematch".to_string()
+            };
+            b.custom_id("rematch")
+             .label(label)
+             .style(serenity::ButtonStyle::Primary)
+        })
+    })
+}
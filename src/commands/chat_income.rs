@@ -0,0 +1,79 @@
+use crate::CommandError;
+use super::admin::check_if_admin;
+use poise::serenity_prelude as serenity;
+
+/// Configure boops earned from chat participation (Admin only)
+#[poise::command(slash_command, prefix_command, track_edits, check = "check_if_admin")]
+pub async fn chat_income(
+    ctx: crate::Context<'_>,
+    #[description = "Enable or disable chat income"]
+    enabled: Option<bool>,
+    #[description = "Boops earned per qualifying message (0.01-10)"]
+    reward: Option<f64>,
+    #[description = "Minutes a comrade must wait between rewarded messages (0-1440)"]
+    cooldown_minutes: Option<i64>,
+    #[description = "Minimum message length in characters (0-500)"]
+    min_length: Option<i64>,
+    #[description = "Maximum boops a comrade can earn from chat per day (0-1000)"]
+    daily_cap: Option<f64>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let db = &ctx.data().db;
+    let mut settings = db.get_chat_income_settings(&server_id).await?;
+
+    let changed = enabled.is_some()
+        || reward.is_some()
+        || cooldown_minutes.is_some()
+        || min_length.is_some()
+        || daily_cap.is_some();
+
+    // Apply any provided changes, keeping values within sane bounds
+    if let Some(enabled) = enabled {
+        settings.enabled = enabled;
+    }
+    if let Some(reward) = reward {
+        settings.reward = reward.clamp(0.01, 10.0);
+    }
+    if let Some(minutes) = cooldown_minutes {
+        settings.cooldown_secs = minutes.clamp(0, 1440) * 60;
+    }
+    if let Some(min_length) = min_length {
+        settings.min_length = min_length.clamp(0, 500);
+    }
+    if let Some(daily_cap) = daily_cap {
+        settings.daily_cap = daily_cap.clamp(0.0, 1000.0);
+    }
+
+    if changed {
+        db.set_chat_income_settings(&server_id, &settings).await?;
+    }
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title("☭ Ministry of Communal Discourse ☭")
+             .description(if changed {
+                 "The chat income decree has been amended."
+             } else {
+                 "Current chat income decree for this collective."
+             })
+             .color(if settings.enabled { serenity::Color::RED } else { serenity::Color::DARK_GREY })
+             .field("Status", if settings.enabled { "Active" } else { "Suspended" }, true)
+             .field("Reward", format!("**{:.2}** boops per message", settings.reward), true)
+             .field("Cooldown", format!("**{}** minutes", settings.cooldown_secs / 60), true)
+             .field("Minimum Length", format!("**{}** characters", settings.min_length), true)
+             .field("Daily Cap", format!("**{:.2}** boops", settings.daily_cap), true)
+             .field(
+                "Distribution",
+                "90% to the communal treasury, 10% to the speaker. Repeated messages earn nothing.",
+                false
+             )
+             .footer(|f| f.text("Every word spoken for the collective strengthens the collective."))
+        })
+    }).await?;
+
+    Ok(())
+}
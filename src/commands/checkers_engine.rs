@@ -0,0 +1,335 @@
+// English draughts on the 32 dark squares, numbered 1-32 as in PDN: square 1 is at the
+// top left of Black's side, four to a row. Black starts on 1-12 and moves first.
+use serde::{Deserialize, Serialize};
+
+pub(crate) const SQUARES: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Side {
+    Black,
+    White,
+}
+
+impl Side {
+    pub(crate) fn other(self) -> Side {
+        match self {
+            Side::Black => Side::White,
+            Side::White => Side::Black,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Side::Black => "Black",
+            Side::White => "White",
+        }
+    }
+
+    // Which way its men move, in rows from the top
+    fn forward(self) -> i8 {
+        match self {
+            Side::Black => 1,
+            Side::White => -1,
+        }
+    }
+
+    // The row where its men are crowned
+    fn crowning_row(self) -> u8 {
+        match self {
+            Side::Black => 7,
+            Side::White => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Man {
+    pub(crate) side: Side,
+    pub(crate) king: bool,
+}
+
+// Squares count from 0 here; the notation adds one
+pub(crate) fn row_of(square: u8) -> u8 {
+    square / 4
+}
+
+pub(crate) fn col_of(square: u8) -> u8 {
+    let row = row_of(square);
+    (square % 4) * 2 + if row.is_multiple_of(2) { 1 } else { 0 }
+}
+
+// The dark square at a row and column, if it is one
+pub(crate) fn square_at(row: i8, col: i8) -> Option<u8> {
+    let on_board = (0..8).contains(&row) && (0..8).contains(&col);
+    (on_board && (row + col) % 2 == 1).then(|| (row * 4 + col / 2) as u8)
+}
+
+fn neighbour(square: u8, (rows, cols): (i8, i8)) -> Option<u8> {
+    square_at(row_of(square) as i8 + rows, col_of(square) as i8 + cols)
+}
+
+// A square by number, 1-32, or by name, a1-h8 with a1 at White's bottom left
+fn parse_square(token: &str) -> Option<u8> {
+    if let Ok(number) = token.parse::<usize>() {
+        return (1..=SQUARES).contains(&number).then(|| number as u8 - 1);
+    }
+
+    let mut chars = token.chars();
+    let file = chars.next()?.to_ascii_lowercase();
+    let rank = chars.next()?.to_digit(10)? as i8;
+    if chars.next().is_some() || !('a'..='h').contains(&file) || !(1..=8).contains(&rank) {
+        return None;
+    }
+    square_at(8 - rank, file as i8 - 'a' as i8)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CheckersMove {
+    // Every square the piece stops on, from first to last
+    pub(crate) path: Vec<u8>,
+    pub(crate) captured: Vec<u8>,
+}
+
+impl CheckersMove {
+    // PDN notation: 11-15, or every landing square of a jump, as in 22x15x6
+    pub(crate) fn notation(&self) -> String {
+        let separator = if self.captured.is_empty() { "-" } else { "x" };
+        self.path.iter().map(|square| (square + 1).to_string()).collect::<Vec<_>>().join(separator)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Board {
+    pub(crate) squares: [Option<Man>; SQUARES],
+    pub(crate) turn: Side,
+}
+
+impl Board {
+    pub(crate) fn start() -> Self {
+        let mut squares = [None; SQUARES];
+        for square in 0..12 {
+            squares[square] = Some(Man { side: Side::Black, king: false });
+            squares[SQUARES - 1 - square] = Some(Man { side: Side::White, king: false });
+        }
+        Board { squares, turn: Side::Black }
+    }
+
+    pub(crate) fn man_at(&self, square: u8) -> Option<Man> {
+        self.squares[square as usize]
+    }
+
+    // Men step forward only; kings either way
+    fn directions(man: Man) -> Vec<(i8, i8)> {
+        let forward = man.side.forward();
+        let mut directions = vec![(forward, -1), (forward, 1)];
+        if man.king {
+            directions.extend([(-forward, -1), (-forward, 1)]);
+        }
+        directions
+    }
+
+    // Every legal move. Taking is compulsory, and a jump must go on for as long as it can.
+    pub(crate) fn legal_moves(&self) -> Vec<CheckersMove> {
+        let mut jumps = Vec::new();
+        for square in 0..SQUARES as u8 {
+            if let Some(man) = self.man_at(square).filter(|man| man.side == self.turn) {
+                self.extend_jump(man, &mut vec![square], &mut Vec::new(), &mut jumps);
+            }
+        }
+        if !jumps.is_empty() {
+            return jumps;
+        }
+
+        let mut moves = Vec::new();
+        for square in 0..SQUARES as u8 {
+            if let Some(man) = self.man_at(square).filter(|man| man.side == self.turn) {
+                for direction in Board::directions(man) {
+                    if let Some(to) = neighbour(square, direction).filter(|&to| self.man_at(to).is_none()) {
+                        moves.push(CheckersMove { path: vec![square, to], captured: Vec::new() });
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    fn extend_jump(&self, man: Man, path: &mut Vec<u8>, captured: &mut Vec<u8>, jumps: &mut Vec<CheckersMove>) {
+        let from = *path.last().unwrap_or(&0);
+        let mut extended = false;
+
+        for direction in Board::directions(man) {
+            let over = match neighbour(from, direction) {
+                Some(over) => over,
+                None => continue,
+            };
+            let land = match neighbour(over, direction) {
+                Some(land) => land,
+                None => continue,
+            };
+
+            // The jumping piece has left its first square, and nothing is taken twice
+            let enemy = self.man_at(over).is_some_and(|other| other.side != man.side) && !captured.contains(&over);
+            let empty = self.man_at(land).is_none() || land == path[0];
+            if !enemy || !empty {
+                continue;
+            }
+
+            extended = true;
+            path.push(land);
+            captured.push(over);
+
+            // A man that reaches the far row is crowned, which ends its move
+            if !man.king && row_of(land) == man.side.crowning_row() {
+                jumps.push(CheckersMove { path: path.clone(), captured: captured.clone() });
+            } else {
+                self.extend_jump(man, path, captured, jumps);
+            }
+
+            path.pop();
+            captured.pop();
+        }
+
+        if !extended && !captured.is_empty() {
+            jumps.push(CheckersMove { path: path.clone(), captured: captured.clone() });
+        }
+    }
+
+    // The board after a move, which is trusted to be legal
+    pub(crate) fn play(&self, mv: &CheckersMove) -> Board {
+        let mut next = self.clone();
+        let (from, to) = match (mv.path.first(), mv.path.last()) {
+            (Some(&from), Some(&to)) => (from, to),
+            _ => return next,
+        };
+
+        let mut man = match next.squares[from as usize].take() {
+            Some(man) => man,
+            None => return next,
+        };
+        for &square in &mv.captured {
+            next.squares[square as usize] = None;
+        }
+        if row_of(to) == man.side.crowning_row() {
+            man.king = true;
+        }
+        next.squares[to as usize] = Some(man);
+        next.turn = self.turn.other();
+
+        next
+    }
+
+    // Read a move as its squares joined by - or x, by number or by name. Two squares
+    // are enough for a jump of several steps, as long as only one jump joins them.
+    pub(crate) fn parse_move(&self, text: &str) -> Option<CheckersMove> {
+        let squares: Option<Vec<u8>> = text
+            .split(|c: char| c == '-' || c == 'x' || c == 'X' || c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .map(parse_square)
+            .collect();
+        let squares = squares?;
+        if squares.len() < 2 {
+            return None;
+        }
+
+        let moves = self.legal_moves();
+        if let Some(mv) = moves.iter().find(|mv| mv.path == squares) {
+            return Some(mv.clone());
+        }
+
+        let ends: Vec<&CheckersMove> = moves
+            .iter()
+            .filter(|mv| squares.len() == 2 && mv.path.first() == squares.first() && mv.path.last() == squares.last())
+            .collect();
+        match ends.as_slice() {
+            [mv] => Some((*mv).clone()),
+            _ => None,
+        }
+    }
+
+    // The position for the repetition rule
+    pub(crate) fn key(&self) -> String {
+        let mut key: String = self.squares
+            .iter()
+            .map(|man| match man {
+                None => '.',
+                Some(Man { side: Side::Black, king: false }) => 'b',
+                Some(Man { side: Side::Black, king: true }) => 'B',
+                Some(Man { side: Side::White, king: false }) => 'w',
+                Some(Man { side: Side::White, king: true }) => 'W',
+            })
+            .collect();
+        key.push(if self.turn == Side::Black { 'b' } else { 'w' });
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(black: &[u8], white: &[u8], kings: &[u8], turn: Side) -> Board {
+        let mut squares = [None; SQUARES];
+        for (&number, side) in black.iter().map(|n| (n, Side::Black)).chain(white.iter().map(|n| (n, Side::White))) {
+            squares[number as usize - 1] = Some(Man { side, king: kings.contains(&number) });
+        }
+        Board { squares, turn }
+    }
+
+    #[test]
+    fn squares_are_numbered_as_in_pdn() {
+        assert_eq!(parse_square("1"), Some(0));
+        assert_eq!(parse_square("b8"), Some(0));
+        assert_eq!(parse_square("a1"), Some(28));
+        assert_eq!(parse_square("g1"), Some(31));
+        assert_eq!(parse_square("h2"), Some(27));
+        assert_eq!(parse_square("a2"), None, "a light square");
+        assert_eq!(parse_square("33"), None);
+    }
+
+    #[test]
+    fn the_opening_position_has_seven_moves() {
+        let start = Board::start();
+        assert_eq!(start.legal_moves().len(), 7);
+
+        let mv = start.parse_move("11-15").unwrap();
+        assert_eq!(mv.notation(), "11-15");
+        assert_eq!(start.parse_move("f6-e5"), Some(mv));
+        assert!(start.parse_move("11-16").is_some());
+        assert!(start.parse_move("22-18").is_none(), "White doesn't move first");
+    }
+
+    #[test]
+    fn captures_are_compulsory_and_chain() {
+        // Black on 9 may step elsewhere, but must take 14 and then 23
+        let position = board(&[9, 1], &[14, 23], &[], Side::Black);
+        let moves = position.legal_moves();
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].notation(), "9x18x27");
+        assert!(position.parse_move("1-5").is_none());
+        assert_eq!(position.parse_move("9x27"), Some(moves[0].clone()));
+
+        let after = position.play(&moves[0]);
+        assert!(after.man_at(13).is_none() && after.man_at(22).is_none());
+        assert_eq!(after.turn, Side::White);
+    }
+
+    #[test]
+    fn men_crown_on_the_far_row_and_stop() {
+        // White's man jumps onto Black's back row and may not go on to take 6 as a king
+        let position = board(&[6, 7], &[11], &[], Side::White);
+        let moves = position.legal_moves();
+        assert_eq!(moves.iter().map(CheckersMove::notation).collect::<Vec<_>>(), ["11x2"]);
+
+        let after = position.play(&moves[0]);
+        assert_eq!(after.man_at(1), Some(Man { side: Side::White, king: true }));
+        assert!(after.man_at(5).is_some());
+    }
+
+    #[test]
+    fn kings_move_backwards() {
+        let position = board(&[18], &[32], &[18], Side::Black);
+        let targets: Vec<String> = position.legal_moves().iter().map(CheckersMove::notation).collect();
+        assert_eq!(targets.len(), 4);
+        assert!(targets.contains(&"18-14".to_string()) && targets.contains(&"18-23".to_string()));
+    }
+}
@@ -0,0 +1,759 @@
+// Chess rules on a plain 64-square board. Squares count from a1 = 0 along each rank,
+// so h1 = 7 and h8 = 63. Positions are saved as FEN.
+use serde::{Deserialize, Serialize};
+
+pub(crate) const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+const KNIGHT_STEPS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_STEPS: [(i8, i8); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+const ROOK_LINES: [(i8, i8); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const BISHOP_LINES: [(i8, i8); 4] = [(1, 1), (-1, 1), (-1, -1), (1, -1)];
+
+// Castling rights, as bits
+const WHITE_KINGSIDE: u8 = 1;
+const WHITE_QUEENSIDE: u8 = 2;
+const BLACK_KINGSIDE: u8 = 4;
+const BLACK_QUEENSIDE: u8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    pub(crate) fn other(self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Color::White => "White",
+            Color::Black => "Black",
+        }
+    }
+
+    // Which way its pawns move, in ranks
+    fn forward(self) -> i8 {
+        match self {
+            Color::White => 1,
+            Color::Black => -1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+impl Kind {
+    // The letter used in SAN and FEN, upper case; pawns have none in SAN
+    fn letter(self) -> char {
+        match self {
+            Kind::Pawn => 'P',
+            Kind::Knight => 'N',
+            Kind::Bishop => 'B',
+            Kind::Rook => 'R',
+            Kind::Queen => 'Q',
+            Kind::King => 'K',
+        }
+    }
+
+    fn from_letter(letter: char) -> Option<Kind> {
+        match letter.to_ascii_uppercase() {
+            'P' => Some(Kind::Pawn),
+            'N' => Some(Kind::Knight),
+            'B' => Some(Kind::Bishop),
+            'R' => Some(Kind::Rook),
+            'Q' => Some(Kind::Queen),
+            'K' => Some(Kind::King),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Piece {
+    pub(crate) color: Color,
+    pub(crate) kind: Kind,
+}
+
+impl Piece {
+    pub(crate) fn symbol(self) -> char {
+        match (self.color, self.kind) {
+            (Color::White, Kind::King) => '♔',
+            (Color::White, Kind::Queen) => '♕',
+            (Color::White, Kind::Rook) => '♖',
+            (Color::White, Kind::Bishop) => '♗',
+            (Color::White, Kind::Knight) => '♘',
+            (Color::White, Kind::Pawn) => '♙',
+            (Color::Black, Kind::King) => '♚',
+            (Color::Black, Kind::Queen) => '♛',
+            (Color::Black, Kind::Rook) => '♜',
+            (Color::Black, Kind::Bishop) => '♝',
+            (Color::Black, Kind::Knight) => '♞',
+            (Color::Black, Kind::Pawn) => '♟',
+        }
+    }
+
+    fn fen_letter(self) -> char {
+        match self.color {
+            Color::White => self.kind.letter(),
+            Color::Black => self.kind.letter().to_ascii_lowercase(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Move {
+    pub(crate) from: u8,
+    pub(crate) to: u8,
+    pub(crate) promotion: Option<Kind>,
+}
+
+impl Move {
+    // Coordinate notation, e.g. e2e4 or e7e8q
+    pub(crate) fn uci(&self) -> String {
+        let promotion = self.promotion.map(|kind| kind.letter().to_ascii_lowercase().to_string()).unwrap_or_default();
+        format!("{}{}{}", square_name(self.from), square_name(self.to), promotion)
+    }
+}
+
+pub(crate) fn file_of(square: u8) -> u8 {
+    square % 8
+}
+
+pub(crate) fn rank_of(square: u8) -> u8 {
+    square / 8
+}
+
+pub(crate) fn square_name(square: u8) -> String {
+    format!("{}{}", (b'a' + file_of(square)) as char, rank_of(square) + 1)
+}
+
+fn parse_square(name: &str) -> Option<u8> {
+    let mut chars = name.chars();
+    let file = chars.next()?;
+    let rank = chars.next()?;
+    if chars.next().is_some() || !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+        return None;
+    }
+    Some((rank as u8 - b'1') * 8 + (file as u8 - b'a'))
+}
+
+// The square a step away, if it's on the board
+fn step(square: u8, (files, ranks): (i8, i8)) -> Option<u8> {
+    let file = file_of(square) as i8 + files;
+    let rank = rank_of(square) as i8 + ranks;
+    ((0..8).contains(&file) && (0..8).contains(&rank)).then(|| (rank * 8 + file) as u8)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub(crate) struct Position {
+    board: [Option<Piece>; 64],
+    pub(crate) turn: Color,
+    castling: u8,
+    // The square a pawn skipped over last move, where it may be taken en passant
+    en_passant: Option<u8>,
+    // Moves since the last capture or pawn move, for the fifty-move rule
+    pub(crate) halfmove_clock: u32,
+    pub(crate) fullmove: u32,
+}
+
+impl From<Position> for String {
+    fn from(position: Position) -> String {
+        position.fen()
+    }
+}
+
+impl TryFrom<String> for Position {
+    type Error = String;
+
+    fn try_from(fen: String) -> Result<Self, String> {
+        Position::from_fen(&fen).ok_or_else(|| format!("invalid FEN: {}", fen))
+    }
+}
+
+impl Position {
+    pub(crate) fn start() -> Self {
+        Position::from_fen(START_FEN).expect("the starting position is valid")
+    }
+
+    pub(crate) fn from_fen(fen: &str) -> Option<Self> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() != 6 {
+            return None;
+        }
+
+        let mut board = [None; 64];
+        let ranks: Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return None;
+        }
+        for (i, rank) in ranks.iter().enumerate() {
+            let rank_index = 7 - i as u8;
+            let mut file = 0u8;
+            for c in rank.chars() {
+                if let Some(empty) = c.to_digit(10) {
+                    file += empty as u8;
+                } else {
+                    let kind = Kind::from_letter(c)?;
+                    let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+                    if file >= 8 {
+                        return None;
+                    }
+                    board[(rank_index * 8 + file) as usize] = Some(Piece { color, kind });
+                    file += 1;
+                }
+            }
+            if file != 8 {
+                return None;
+            }
+        }
+
+        let turn = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return None,
+        };
+
+        let mut castling = 0;
+        for c in fields[2].chars() {
+            castling |= match c {
+                'K' => WHITE_KINGSIDE,
+                'Q' => WHITE_QUEENSIDE,
+                'k' => BLACK_KINGSIDE,
+                'q' => BLACK_QUEENSIDE,
+                '-' => 0,
+                _ => return None,
+            };
+        }
+
+        let en_passant = match fields[3] {
+            "-" => None,
+            square => Some(parse_square(square)?),
+        };
+
+        Some(Position {
+            board,
+            turn,
+            castling,
+            en_passant,
+            halfmove_clock: fields[4].parse().ok()?,
+            fullmove: fields[5].parse().ok()?,
+        })
+    }
+
+    pub(crate) fn fen(&self) -> String {
+        format!("{} {}", self.fen_fields(self.en_passant), self.clocks())
+    }
+
+    fn clocks(&self) -> String {
+        format!("{} {}", self.halfmove_clock, self.fullmove)
+    }
+
+    // The FEN without its move counters: equal for repeated positions. The en passant
+    // square only counts when a capture onto it is actually legal.
+    pub(crate) fn placement_key(&self) -> String {
+        let en_passant = self
+            .en_passant
+            .filter(|_| self.legal_moves().into_iter().any(|mv| self.is_en_passant(mv)));
+        self.fen_fields(en_passant)
+    }
+
+    fn fen_fields(&self, en_passant: Option<u8>) -> String {
+        let mut placement = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.board[rank * 8 + file] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            placement.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        placement.push(piece.fen_letter());
+                    },
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                placement.push('/');
+            }
+        }
+
+        let castling: String = [(WHITE_KINGSIDE, 'K'), (WHITE_QUEENSIDE, 'Q'), (BLACK_KINGSIDE, 'k'), (BLACK_QUEENSIDE, 'q')]
+            .iter()
+            .filter(|(right, _)| self.castling & right != 0)
+            .map(|(_, letter)| *letter)
+            .collect();
+        let castling = if castling.is_empty() { "-".to_string() } else { castling };
+        let en_passant = en_passant.map(square_name).unwrap_or_else(|| "-".to_string());
+        let turn = if self.turn == Color::White { "w" } else { "b" };
+
+        format!("{} {} {} {}", placement, turn, castling, en_passant)
+    }
+
+    pub(crate) fn piece_at(&self, square: u8) -> Option<Piece> {
+        self.board[square as usize]
+    }
+
+    fn king_square(&self, color: Color) -> Option<u8> {
+        (0..64).find(|&square| self.piece_at(square) == Some(Piece { color, kind: Kind::King }))
+    }
+
+    // Whether any piece of `by` attacks a square
+    pub(crate) fn is_attacked(&self, square: u8, by: Color) -> bool {
+        let holds = |square: Option<u8>, kinds: &[Kind]| {
+            square
+                .and_then(|square| self.piece_at(square))
+                .is_some_and(|piece| piece.color == by && kinds.contains(&piece.kind))
+        };
+
+        // Pawns attack from one rank behind, as seen from the attacked square
+        let back = -by.forward();
+        if holds(step(square, (-1, back)), &[Kind::Pawn]) || holds(step(square, (1, back)), &[Kind::Pawn]) {
+            return true;
+        }
+        if KNIGHT_STEPS.iter().any(|&s| holds(step(square, s), &[Kind::Knight])) {
+            return true;
+        }
+        if KING_STEPS.iter().any(|&s| holds(step(square, s), &[Kind::King])) {
+            return true;
+        }
+
+        for (lines, kinds) in [(ROOK_LINES, [Kind::Rook, Kind::Queen]), (BISHOP_LINES, [Kind::Bishop, Kind::Queen])] {
+            for line in lines {
+                let mut current = square;
+                while let Some(next) = step(current, line) {
+                    match self.piece_at(next) {
+                        Some(piece) => {
+                            if piece.color == by && kinds.contains(&piece.kind) {
+                                return true;
+                            }
+                            break;
+                        },
+                        None => current = next,
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    pub(crate) fn in_check(&self) -> bool {
+        self.king_square(self.turn).is_some_and(|king| self.is_attacked(king, self.turn.other()))
+    }
+
+    // Moves that follow how the pieces move, whether or not they leave the king in check
+    fn pseudo_legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let us = self.turn;
+
+        for from in 0..64u8 {
+            let piece = match self.piece_at(from) {
+                Some(piece) if piece.color == us => piece,
+                _ => continue,
+            };
+            let mut add = |to: u8| moves.push(Move { from, to, promotion: None });
+
+            match piece.kind {
+                Kind::Pawn => self.pawn_moves(from, &mut moves),
+                Kind::Knight | Kind::King => {
+                    let steps = if piece.kind == Kind::Knight { KNIGHT_STEPS } else { KING_STEPS };
+                    for to in steps.iter().filter_map(|&s| step(from, s)) {
+                        if self.piece_at(to).is_none_or(|other| other.color != us) {
+                            add(to);
+                        }
+                    }
+                    if piece.kind == Kind::King {
+                        self.castling_moves(from, &mut moves);
+                    }
+                },
+                Kind::Bishop | Kind::Rook | Kind::Queen => {
+                    let lines: Vec<(i8, i8)> = match piece.kind {
+                        Kind::Bishop => BISHOP_LINES.to_vec(),
+                        Kind::Rook => ROOK_LINES.to_vec(),
+                        _ => BISHOP_LINES.iter().chain(ROOK_LINES.iter()).copied().collect(),
+                    };
+                    for line in lines {
+                        let mut current = from;
+                        while let Some(to) = step(current, line) {
+                            match self.piece_at(to) {
+                                None => add(to),
+                                Some(other) => {
+                                    if other.color != us {
+                                        add(to);
+                                    }
+                                    break;
+                                },
+                            }
+                            current = to;
+                        }
+                    }
+                },
+            }
+        }
+
+        moves
+    }
+
+    fn pawn_moves(&self, from: u8, moves: &mut Vec<Move>) {
+        let us = self.turn;
+        let forward = us.forward();
+        let (start_rank, last_rank) = if us == Color::White { (1, 7) } else { (6, 0) };
+
+        let mut push = |to: u8| {
+            if rank_of(to) == last_rank {
+                for kind in [Kind::Queen, Kind::Rook, Kind::Bishop, Kind::Knight] {
+                    moves.push(Move { from, to, promotion: Some(kind) });
+                }
+            } else {
+                moves.push(Move { from, to, promotion: None });
+            }
+        };
+
+        if let Some(one) = step(from, (0, forward)).filter(|&to| self.piece_at(to).is_none()) {
+            push(one);
+            if rank_of(from) == start_rank {
+                if let Some(two) = step(one, (0, forward)).filter(|&to| self.piece_at(to).is_none()) {
+                    push(two);
+                }
+            }
+        }
+
+        for side in [-1, 1] {
+            if let Some(to) = step(from, (side, forward)) {
+                let takes = self.piece_at(to).is_some_and(|other| other.color != us);
+                if takes || self.en_passant == Some(to) {
+                    push(to);
+                }
+            }
+        }
+    }
+
+    // The king may not castle out of, through or into check
+    fn castling_moves(&self, from: u8, moves: &mut Vec<Move>) {
+        let us = self.turn;
+        let (home, kingside, queenside) = match us {
+            Color::White => (4, WHITE_KINGSIDE, WHITE_QUEENSIDE),
+            Color::Black => (60, BLACK_KINGSIDE, BLACK_QUEENSIDE),
+        };
+        if from != home || self.is_attacked(home, us.other()) {
+            return;
+        }
+
+        let rook = Some(Piece { color: us, kind: Kind::Rook });
+        let empty = |squares: &[u8]| squares.iter().all(|&square| self.piece_at(square).is_none());
+        let safe = |squares: &[u8]| squares.iter().all(|&square| !self.is_attacked(square, us.other()));
+
+        if self.castling & kingside != 0 && self.piece_at(home + 3) == rook && empty(&[home + 1, home + 2]) && safe(&[home + 1, home + 2]) {
+            moves.push(Move { from, to: home + 2, promotion: None });
+        }
+        if self.castling & queenside != 0
+            && self.piece_at(home - 4) == rook
+            && empty(&[home - 1, home - 2, home - 3])
+            && safe(&[home - 1, home - 2])
+        {
+            moves.push(Move { from, to: home - 2, promotion: None });
+        }
+    }
+
+    pub(crate) fn legal_moves(&self) -> Vec<Move> {
+        let us = self.turn;
+        self.pseudo_legal_moves()
+            .into_iter()
+            .filter(|&mv| {
+                let after = self.play(mv);
+                after.king_square(us).is_some_and(|king| !after.is_attacked(king, us.other()))
+            })
+            .collect()
+    }
+
+    fn is_capture(&self, mv: Move) -> bool {
+        self.piece_at(mv.to).is_some() || self.is_en_passant(mv)
+    }
+
+    fn is_en_passant(&self, mv: Move) -> bool {
+        self.en_passant == Some(mv.to) && self.piece_at(mv.from).is_some_and(|piece| piece.kind == Kind::Pawn)
+    }
+
+    // The position after a move, which is trusted to be legal
+    pub(crate) fn play(&self, mv: Move) -> Position {
+        let mut next = self.clone();
+        let piece = match self.piece_at(mv.from) {
+            Some(piece) => piece,
+            None => return next,
+        };
+
+        if self.is_en_passant(mv) {
+            let taken = (mv.to as i8 - 8 * piece.color.forward()) as u8;
+            next.board[taken as usize] = None;
+        }
+
+        // Castling moves the rook too
+        if piece.kind == Kind::King && file_of(mv.from).abs_diff(file_of(mv.to)) == 2 {
+            let (rook_from, rook_to) = if mv.to > mv.from { (mv.from + 3, mv.from + 1) } else { (mv.from - 4, mv.from - 1) };
+            next.board[rook_to as usize] = next.board[rook_from as usize].take();
+        }
+
+        next.board[mv.from as usize] = None;
+        next.board[mv.to as usize] = Some(Piece { color: piece.color, kind: mv.promotion.unwrap_or(piece.kind) });
+
+        // Rights are lost when the king or a rook moves, or a rook is taken at home
+        for square in [mv.from, mv.to] {
+            next.castling &= !match square {
+                4 => WHITE_KINGSIDE | WHITE_QUEENSIDE,
+                0 => WHITE_QUEENSIDE,
+                7 => WHITE_KINGSIDE,
+                60 => BLACK_KINGSIDE | BLACK_QUEENSIDE,
+                56 => BLACK_QUEENSIDE,
+                63 => BLACK_KINGSIDE,
+                _ => 0,
+            };
+        }
+
+        next.en_passant = (piece.kind == Kind::Pawn && rank_of(mv.from).abs_diff(rank_of(mv.to)) == 2)
+            .then(|| (mv.from + mv.to) / 2);
+        next.halfmove_clock = if piece.kind == Kind::Pawn || self.is_capture(mv) { 0 } else { self.halfmove_clock + 1 };
+        if self.turn == Color::Black {
+            next.fullmove += 1;
+        }
+        next.turn = self.turn.other();
+
+        next
+    }
+
+    // Standard algebraic notation, e.g. Nbd7, exd6, O-O or e8=Q+
+    pub(crate) fn san(&self, mv: Move) -> String {
+        let piece = match self.piece_at(mv.from) {
+            Some(piece) => piece,
+            None => return mv.uci(),
+        };
+
+        let mut san = if piece.kind == Kind::King && file_of(mv.from).abs_diff(file_of(mv.to)) == 2 {
+            if mv.to > mv.from { "O-O".to_string() } else { "O-O-O".to_string() }
+        } else {
+            let mut san = String::new();
+            if piece.kind == Kind::Pawn {
+                if self.is_capture(mv) {
+                    san.push((b'a' + file_of(mv.from)) as char);
+                }
+            } else {
+                san.push(piece.kind.letter());
+
+                // Name the file, rank or both when another such piece could go there too
+                let rivals: Vec<u8> = self.legal_moves()
+                    .into_iter()
+                    .filter(|other| other.to == mv.to && other.from != mv.from && self.piece_at(other.from) == Some(piece))
+                    .map(|other| other.from)
+                    .collect();
+                if !rivals.is_empty() {
+                    let file_unique = rivals.iter().all(|&from| file_of(from) != file_of(mv.from));
+                    let rank_unique = rivals.iter().all(|&from| rank_of(from) != rank_of(mv.from));
+                    if file_unique {
+                        san.push((b'a' + file_of(mv.from)) as char);
+                    } else if rank_unique {
+                        san.push((b'1' + rank_of(mv.from)) as char);
+                    } else {
+                        san.push_str(&square_name(mv.from));
+                    }
+                }
+            }
+
+            if self.is_capture(mv) {
+                san.push('x');
+            }
+            san.push_str(&square_name(mv.to));
+            if let Some(kind) = mv.promotion {
+                san.push('=');
+                san.push(kind.letter());
+            }
+            san
+        };
+
+        let after = self.play(mv);
+        if after.in_check() {
+            san.push(if after.legal_moves().is_empty() { '#' } else { '+' });
+        }
+
+        san
+    }
+
+    // Read a move typed in SAN, more or less, or in coordinates like e2e4. Check marks,
+    // capture marks and annotations are optional, and zeros may stand in for castling's Os.
+    // Returns None if it isn't exactly one legal move.
+    pub(crate) fn parse_move(&self, text: &str) -> Option<Move> {
+        let normalize = |text: &str| -> String {
+            text.trim()
+                .replace("e.p.", "")
+                .replace('0', "O")
+                .chars()
+                .filter(|c| !matches!(c, '+' | '#' | '!' | '?' | 'x' | ':' | '=' | '-' | ' '))
+                .collect()
+        };
+
+        let wanted = normalize(text);
+        if wanted.is_empty() {
+            return None;
+        }
+
+        let moves = self.legal_moves();
+        let find = |matches: &dyn Fn(&str) -> bool| -> Vec<Move> {
+            moves.iter().copied().filter(|&mv| matches(&normalize(&self.san(mv))) || matches(&mv.uci())).collect()
+        };
+
+        // Exactly as typed first, since "b" may be a file or a bishop
+        let exact = find(&|candidate| candidate == wanted);
+        if exact.len() == 1 {
+            return exact.first().copied();
+        }
+
+        let loose = find(&|candidate| candidate.eq_ignore_ascii_case(&wanted));
+        (loose.len() == 1).then(|| loose[0])
+    }
+
+    // Neither side can ever mate: bare kings, or a king and a single minor piece against a king
+    pub(crate) fn insufficient_material(&self) -> bool {
+        let pieces: Vec<Kind> = self.board
+            .iter()
+            .flatten()
+            .map(|piece| piece.kind)
+            .filter(|&kind| kind != Kind::King)
+            .collect();
+        matches!(pieces.as_slice(), [] | [Kind::Knight] | [Kind::Bishop])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play_all(position: &Position, moves: &[&str]) -> Position {
+        moves.iter().fold(position.clone(), |position, text| {
+            let mv = position.parse_move(text).unwrap_or_else(|| panic!("{} should be legal", text));
+            position.play(mv)
+        })
+    }
+
+    // Positions reachable in a few plies, counted by exhaustive search
+    fn perft(position: &Position, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        position.legal_moves().iter().map(|&mv| perft(&position.play(mv), depth - 1)).sum()
+    }
+
+    #[test]
+    fn move_generation_matches_known_counts() {
+        let start = Position::start();
+        assert_eq!(perft(&start, 1), 20);
+        assert_eq!(perft(&start, 2), 400);
+        assert_eq!(perft(&start, 3), 8902);
+
+        // "Kiwipete", full of castling, en passant and promotion tricks
+        let kiwipete = Position::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(perft(&kiwipete, 1), 48);
+        assert_eq!(perft(&kiwipete, 2), 2039);
+    }
+
+    #[test]
+    fn fen_round_trips() {
+        for fen in [START_FEN, "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", "8/8/8/3k4/8/8/8/4K3 b - - 12 40"] {
+            assert_eq!(Position::from_fen(fen).unwrap().fen(), fen);
+        }
+        let after = play_all(&Position::start(), &["e4"]);
+        assert_eq!(after.fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    }
+
+    #[test]
+    fn castling_en_passant_and_promotion() {
+        // Castling both ways, written either way
+        let position = Position::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let castled = play_all(&position, &["O-O", "0-0-0"]);
+        assert_eq!(castled.placement_key(), "2kr3r/8/8/8/8/8/8/R4RK1 w - -");
+
+        // Not through an attacked square
+        let position = Position::from_fen("r3k2r/8/8/8/8/8/5r2/R3K2R w KQkq - 0 1").unwrap();
+        assert!(position.parse_move("O-O").is_none());
+        assert!(position.parse_move("O-O-O").is_some());
+
+        // En passant, only straight after the double step
+        let position = play_all(&Position::start(), &["e4", "a6", "e5", "d5"]);
+        let mv = position.parse_move("exd6").unwrap();
+        assert_eq!(position.san(mv), "exd6");
+        assert!(position.play(mv).piece_at(parse_square("d5").unwrap()).is_none());
+        let later = play_all(&position, &["a3", "a5"]);
+        assert!(later.parse_move("exd6").is_none());
+
+        // Promotion to any piece, with check marks worked out
+        let position = Position::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let mv = position.parse_move("e8=Q").unwrap();
+        assert_eq!(position.san(mv), "e8=Q+");
+        assert_eq!(position.parse_move("e8N").unwrap().promotion, Some(Kind::Knight));
+        assert_eq!(position.parse_move("e7e8r").unwrap().promotion, Some(Kind::Rook));
+    }
+
+    #[test]
+    fn repetition_key_ignores_en_passant_nobody_can_take() {
+        // After 1. e4 no black pawn can capture on e3, so the square doesn't count
+        let double_step = play_all(&Position::start(), &["e4"]);
+        assert!(double_step.fen().contains(" e3 "));
+        assert_eq!(double_step.placement_key(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq -");
+
+        // The same position reached by knight moves repeats it
+        let shuffled = play_all(&double_step, &["Nf6", "Nf3", "Ng8", "Ng1"]);
+        assert_eq!(shuffled.placement_key(), double_step.placement_key());
+
+        // When the capture is there, the square is part of the position
+        let capturable = play_all(&Position::start(), &["e4", "a6", "e5", "d5"]);
+        assert!(capturable.placement_key().ends_with(" d6"));
+
+        // But not when the capturing pawn is pinned to its king
+        let pinned = Position::from_fen("4k3/8/8/r2pP2K/8/8/8/8 w - d6 0 1").unwrap();
+        assert!(pinned.parse_move("exd6").is_none());
+        assert!(pinned.placement_key().ends_with(" -"));
+    }
+
+    #[test]
+    fn san_disambiguates_and_marks_mate() {
+        let position = Position::from_fen("4k3/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+        assert_eq!(position.san(position.parse_move("Rb1").unwrap()), "Rb1");
+
+        let position = Position::from_fen("4k3/R7/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert!(position.parse_move("Ra4").is_none(), "ambiguous");
+        assert_eq!(position.san(position.parse_move("R1a4").unwrap()), "R1a4");
+
+        let position = Position::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+        assert!(position.parse_move("Nd2").is_none(), "ambiguous");
+        assert_eq!(position.san(position.parse_move("Nbd2").unwrap()), "Nbd2");
+        assert_eq!(position.san(position.parse_move("Nfd2").unwrap()), "Nfd2");
+
+        let mated = play_all(&Position::start(), &["f3", "e5", "g4"]);
+        let mv = mated.parse_move("Qh4").unwrap();
+        assert_eq!(mated.san(mv), "Qh4#");
+        let after = mated.play(mv);
+        assert!(after.in_check() && after.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn bare_kings_cannot_mate() {
+        assert!(Position::from_fen("8/8/8/3k4/8/8/8/4K3 w - - 0 1").unwrap().insufficient_material());
+        assert!(Position::from_fen("8/8/8/3k4/8/8/8/4KB2 w - - 0 1").unwrap().insufficient_material());
+        assert!(!Position::from_fen("8/8/8/3k4/8/8/8/4KR2 w - - 0 1").unwrap().insufficient_material());
+    }
+}
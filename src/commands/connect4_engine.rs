@@ -0,0 +1,479 @@
+// Connect 4 search on bitboards. Each column takes seven bits, bottom row first;
+// the seventh bit is always empty so shifts never spill into the next column.
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+pub(crate) const WIDTH: usize = 7;
+pub(crate) const HEIGHT: usize = 6;
+const COLUMN_BITS: usize = HEIGHT + 1;
+const CELLS: u32 = (WIDTH * HEIGHT) as u32;
+
+const fn bottom_row() -> u64 {
+    let mut row = 0;
+    let mut col = 0;
+    while col < WIDTH {
+        row |= 1 << (col * COLUMN_BITS);
+        col += 1;
+    }
+    row
+}
+
+const BOTTOM_ROW: u64 = bottom_row();
+const BOARD: u64 = BOTTOM_ROW * ((1 << HEIGHT) - 1);
+
+// Columns from the centre out, where good moves usually are
+pub(crate) const CENTRE_FIRST: [usize; WIDTH] = [3, 2, 4, 1, 5, 0, 6];
+
+// Score of a win on the next move; quicker wins score higher
+const WIN: i32 = 1_000_000;
+const FORCED: i32 = WIN - 100;
+
+// Transposition table slots, indexed by the low bits of the position's key
+const TABLE_SIZE: usize = 1 << 18;
+
+// How often the search looks at the clock
+const NODES_PER_CLOCK_CHECK: u64 = 4096;
+
+// The bit for a cell, counting rows from the bottom
+pub(crate) fn cell_bit(row: usize, col: usize) -> u64 {
+    1 << (col * COLUMN_BITS + row)
+}
+
+pub(crate) fn column_mask(col: usize) -> u64 {
+    ((1 << HEIGHT) - 1) << (col * COLUMN_BITS)
+}
+
+// Whether a set of stones contains four in a row
+pub(crate) fn has_four(stones: u64) -> bool {
+    [1, COLUMN_BITS, COLUMN_BITS - 1, COLUMN_BITS + 1].iter().any(|&shift| {
+        let pairs = stones & (stones >> shift);
+        pairs & (pairs >> (2 * shift)) != 0
+    })
+}
+
+// Empty cells that would give `stones` four in a row
+fn winning_cells(stones: u64, mask: u64) -> u64 {
+    // Vertical: only ever above three stones
+    let mut cells = (stones << 1) & (stones << 2) & (stones << 3);
+
+    for shift in [COLUMN_BITS, COLUMN_BITS - 1, COLUMN_BITS + 1] {
+        let pair = (stones << shift) & (stones << (2 * shift));
+        cells |= pair & (stones << (3 * shift));
+        cells |= pair & (stones >> shift);
+
+        let pair = (stones >> shift) & (stones >> (2 * shift));
+        cells |= pair & (stones << shift);
+        cells |= pair & (stones >> (3 * shift));
+    }
+
+    cells & (BOARD ^ mask)
+}
+
+// Random keys for each (player, cell), fixed so keys are stable between runs
+fn zobrist_keys() -> &'static [[u64; 64]; 2] {
+    static KEYS: OnceLock<[[u64; 64]; 2]> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let mut rng = StdRng::seed_from_u64(0x1917_1991);
+        let mut keys = [[0; 64]; 2];
+        for player in keys.iter_mut() {
+            for key in player.iter_mut() {
+                *key = rng.gen();
+            }
+        }
+        keys
+    })
+}
+
+// A position as the search sees it, from the side to move's point of view
+#[derive(Clone, Copy)]
+pub(crate) struct Position {
+    // Stones of the side to move, and of both sides
+    current: u64,
+    mask: u64,
+    moves: u32,
+    // Zobrist key, updated with each move
+    key: u64,
+}
+
+impl Position {
+    // `first` holds the stones of whoever moved first; the side to move follows from the count
+    pub(crate) fn new(first: u64, second: u64) -> Self {
+        let mask = first | second;
+        let moves = mask.count_ones();
+        let keys = zobrist_keys();
+
+        let mut key = 0;
+        for (bit, (first_key, second_key)) in keys[0].iter().zip(&keys[1]).enumerate() {
+            if first & (1 << bit) != 0 {
+                key ^= first_key;
+            }
+            if second & (1 << bit) != 0 {
+                key ^= second_key;
+            }
+        }
+
+        Position {
+            current: if moves.is_multiple_of(2) { first } else { second },
+            mask,
+            moves,
+            key,
+        }
+    }
+
+    fn opponent(&self) -> u64 {
+        self.current ^ self.mask
+    }
+
+    // The lowest empty cell of every column that isn't full
+    fn possible(&self) -> u64 {
+        (self.mask + BOTTOM_ROW) & BOARD
+    }
+
+    pub(crate) fn can_play(&self, col: usize) -> bool {
+        self.possible() & column_mask(col) != 0
+    }
+
+    pub(crate) fn play(&self, col: usize) -> Position {
+        let bit = self.possible() & column_mask(col);
+
+        Position {
+            current: self.opponent(),
+            mask: self.mask | bit,
+            moves: self.moves + 1,
+            key: self.key ^ zobrist_keys()[(self.moves % 2) as usize][bit.trailing_zeros() as usize],
+        }
+    }
+
+    // Columns that win on the spot for the side to move
+    pub(crate) fn winning_columns(&self) -> Vec<usize> {
+        columns_of(winning_cells(self.current, self.mask) & self.possible())
+    }
+
+    // Columns the opponent would win in if left alone
+    pub(crate) fn threatened_columns(&self) -> Vec<usize> {
+        columns_of(winning_cells(self.opponent(), self.mask) & self.possible())
+    }
+
+    // Columns that leave the side to move two wins at once, which can't both be stopped
+    pub(crate) fn double_threat_columns(&self) -> Vec<usize> {
+        CENTRE_FIRST
+            .into_iter()
+            .filter(|&col| self.can_play(col))
+            .filter(|&col| {
+                let next = self.play(col);
+                let wins = winning_cells(next.opponent(), next.mask) & next.possible();
+                wins.count_ones() >= 2 && winning_cells(next.current, next.mask) & next.possible() == 0
+            })
+            .collect()
+    }
+
+    // Moves that don't hand the opponent an immediate win. Empty if every move loses.
+    fn non_losing_moves(&self) -> u64 {
+        let possible = self.possible();
+        let opponent_wins = winning_cells(self.opponent(), self.mask);
+        let forced = possible & opponent_wins;
+
+        if forced != 0 {
+            // Two threats can't both be blocked
+            if forced & (forced - 1) != 0 {
+                return 0;
+            }
+            return forced & !(opponent_wins >> 1);
+        }
+
+        // Never play right under a cell the opponent wants
+        possible & !(opponent_wins >> 1)
+    }
+
+    // Static score for the side to move: open threats count most, then central stones
+    fn evaluate(&self) -> i32 {
+        let count = |stones: u64| winning_cells(stones, self.mask).count_ones() as i32;
+        let central = |stones: u64| {
+            3 * (stones & column_mask(3)).count_ones() as i32
+                + ((stones & (column_mask(2) | column_mask(4))).count_ones() as i32)
+        };
+
+        16 * (count(self.current) - count(self.opponent())) + central(self.current) - central(self.opponent())
+    }
+}
+
+fn columns_of(cells: u64) -> Vec<usize> {
+    (0..WIDTH).filter(|&col| cells & column_mask(col) != 0).collect()
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Bound {
+    #[default]
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Entry {
+    key: u64,
+    score: i32,
+    depth: u8,
+    bound: Bound,
+    best: u8,
+}
+
+// One move's search: a transposition table and an optional time limit
+pub(crate) struct Search {
+    table: Vec<Entry>,
+    nodes: u64,
+    deadline: Option<Instant>,
+    stopped: bool,
+    // Whether the search notices moves that hand the opponent a win
+    careful: bool,
+}
+
+impl Search {
+    pub(crate) fn new(careful: bool) -> Self {
+        Search {
+            table: vec![Entry::default(); TABLE_SIZE],
+            nodes: 0,
+            deadline: None,
+            stopped: false,
+            careful,
+        }
+    }
+
+    // Best column for the side to move. Every depth up to `depth` is searched in full;
+    // with a deadline the search keeps deepening until the time runs out.
+    pub(crate) fn best_move(&mut self, position: &Position, depth: u32, deadline: Option<Instant>) -> Option<usize> {
+        let remaining = CELLS - position.moves;
+        let mut best = None;
+
+        for current_depth in 1..=remaining {
+            if current_depth > depth {
+                match deadline {
+                    Some(deadline) if Instant::now() < deadline => self.deadline = Some(deadline),
+                    _ => break,
+                }
+            }
+
+            match self.search_root(position, current_depth) {
+                Some((col, score)) => {
+                    best = Some(col);
+                    // A forced result won't change with more depth
+                    if score.abs() >= FORCED {
+                        break;
+                    }
+                },
+                None => break,
+            }
+        }
+
+        best
+    }
+
+    fn search_root(&mut self, position: &Position, depth: u32) -> Option<(usize, i32)> {
+        if let Some(&col) = position.winning_columns().first() {
+            return Some((col, WIN));
+        }
+
+        let mut candidates = if self.careful { position.non_losing_moves() } else { 0 };
+        // Lost anyway, or not looking: any legal move will do
+        if candidates == 0 {
+            candidates = position.possible();
+        }
+
+        let entry = self.probe(position);
+        let mut best = None;
+        let mut alpha = -2 * WIN;
+
+        for col in self.order_moves(position, candidates, entry.map(|e| e.best as usize)) {
+            let score = -self.negamax(&position.play(col), depth - 1, -2 * WIN, -alpha, 1);
+            if self.stopped {
+                return None;
+            }
+
+            if best.is_none() || score > alpha {
+                alpha = score;
+                best = Some((col, score));
+            }
+        }
+
+        best
+    }
+
+    fn negamax(&mut self, position: &Position, depth: u32, mut alpha: i32, mut beta: i32, ply: i32) -> i32 {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(NODES_PER_CLOCK_CHECK) {
+            if let Some(deadline) = self.deadline {
+                self.stopped = Instant::now() >= deadline;
+            }
+        }
+        if self.stopped {
+            return 0;
+        }
+
+        if position.moves == CELLS {
+            return 0;
+        }
+        if winning_cells(position.current, position.mask) & position.possible() != 0 {
+            return WIN - ply;
+        }
+        if depth == 0 {
+            return position.evaluate();
+        }
+
+        let candidates = if self.careful { position.non_losing_moves() } else { position.possible() };
+        if candidates == 0 {
+            return -(WIN - ply - 1);
+        }
+        if position.moves >= CELLS - 2 {
+            return 0;
+        }
+
+        let original_alpha = alpha;
+        let entry = self.probe(position);
+
+        if let Some(entry) = entry.filter(|e| u32::from(e.depth) >= depth) {
+            let score = score_from_table(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return score,
+                Bound::Lower => alpha = alpha.max(score),
+                Bound::Upper => beta = beta.min(score),
+            }
+            if alpha >= beta {
+                return score;
+            }
+        }
+
+        let mut best_score = -2 * WIN;
+        let mut best_col = 0;
+
+        for col in self.order_moves(position, candidates, entry.map(|e| e.best as usize)) {
+            let score = -self.negamax(&position.play(col), depth - 1, -beta, -alpha, ply + 1);
+            if self.stopped {
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_col = col;
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+
+        self.table[slot(position.key)] = Entry {
+            key: position.key,
+            score: score_to_table(best_score, ply),
+            depth: depth.min(u8::MAX as u32) as u8,
+            bound,
+            best: best_col as u8,
+        };
+
+        best_score
+    }
+
+    fn probe(&self, position: &Position) -> Option<Entry> {
+        let entry = self.table[slot(position.key)];
+        (entry.key == position.key && entry.key != 0).then_some(entry)
+    }
+
+    // The table's best move first, then moves that open the most threats, then the centre
+    fn order_moves(&self, position: &Position, candidates: u64, table_move: Option<usize>) -> Vec<usize> {
+        let mut moves: Vec<(i32, usize)> = CENTRE_FIRST
+            .iter()
+            .enumerate()
+            .filter(|(_, &col)| candidates & column_mask(col) != 0)
+            .map(|(order, &col)| {
+                let bit = candidates & column_mask(col);
+                let threats = winning_cells(position.current | bit, position.mask | bit).count_ones() as i32;
+                let priority = if Some(col) == table_move { i32::MAX } else { threats * 16 - order as i32 };
+                (priority, col)
+            })
+            .collect();
+
+        moves.sort_by_key(|&(priority, _)| std::cmp::Reverse(priority));
+        moves.into_iter().map(|(_, col)| col).collect()
+    }
+}
+
+fn slot(key: u64) -> usize {
+    key as usize & (TABLE_SIZE - 1)
+}
+
+// Forced results are stored relative to the node so they stay valid at any ply
+fn score_to_table(score: i32, ply: i32) -> i32 {
+    if score >= FORCED {
+        score + ply
+    } else if score <= -FORCED {
+        score - ply
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: i32) -> i32 {
+    if score >= FORCED {
+        score - ply
+    } else if score <= -FORCED {
+        score + ply
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(moves: &[usize]) -> Position {
+        let empty = Position::new(0, 0);
+        moves.iter().fold(empty, |position, &col| position.play(col))
+    }
+
+    #[test]
+    fn lines_of_four_are_found() {
+        assert!(has_four(cell_bit(0, 0) | cell_bit(1, 0) | cell_bit(2, 0) | cell_bit(3, 0)));
+        assert!(has_four(cell_bit(0, 2) | cell_bit(0, 3) | cell_bit(0, 4) | cell_bit(0, 5)));
+        assert!(has_four(cell_bit(0, 0) | cell_bit(1, 1) | cell_bit(2, 2) | cell_bit(3, 3)));
+        assert!(has_four(cell_bit(3, 0) | cell_bit(2, 1) | cell_bit(1, 2) | cell_bit(0, 3)));
+
+        // Lines must not wrap from the top of one column to the bottom of the next
+        assert!(!has_four(cell_bit(4, 0) | cell_bit(5, 0) | cell_bit(0, 1) | cell_bit(1, 1)));
+    }
+
+    #[test]
+    fn keys_follow_the_position_not_the_move_order() {
+        assert_eq!(position(&[3, 2, 4]).key, position(&[4, 2, 3]).key);
+        assert_ne!(position(&[3, 2]).key, position(&[2, 3]).key);
+        assert_eq!(position(&[3, 2, 4]).key, Position::new(cell_bit(0, 3) | cell_bit(0, 4), cell_bit(0, 2)).key);
+    }
+
+    #[test]
+    fn threats_are_seen_for_both_sides() {
+        let open_three = position(&[1, 1, 2, 2, 3]);
+        assert_eq!(open_three.threatened_columns(), vec![0, 4]);
+        assert!(open_three.winning_columns().is_empty());
+        assert_eq!(open_three.play(6).winning_columns(), vec![0, 4]);
+    }
+
+    #[test]
+    fn search_blocks_and_wins() {
+        // Red threatens the bottom row; Yellow must block
+        let threatened = position(&[0, 6, 1, 6, 2]);
+        assert_eq!(Search::new(true).best_move(&threatened, 4, None), Some(3));
+
+        // Red to move sets up the open three and then wins
+        let attack = position(&[2, 6, 3, 6]);
+        let mut search = Search::new(true);
+        assert!([1, 4].contains(&search.best_move(&attack, 5, None).unwrap()));
+    }
+}
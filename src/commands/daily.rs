@@ -0,0 +1,170 @@
+use crate::CommandError;
+use crate::db::DailyAttempt;
+use super::active_games;
+use super::kremlin_secrets::{daily_word_difficulty, play_daily_kremlin};
+use super::soviet_hangman::play_daily_hangman;
+use super::word_banks::daily_word;
+use poise::serenity_prelude as serenity;
+
+// Daily games, as (key stored in the database, display name)
+const DAILY_GAMES: [(&str, &str); 2] = [
+    (active_games::HANGMAN, "Soviet Hangman"),
+    (active_games::KREMLIN_SECRETS, "Kremlin Secrets"),
+];
+
+// Comrades shown on each daily leaderboard
+const LEADERBOARD_SIZE: usize = 10;
+
+// Puzzles change at midnight UTC, the same moment for every server
+fn today() -> String {
+    chrono::Utc::now().format("%Y-%m-%d").to_string()
+}
+
+fn daily_game_type(name: &str) -> Option<&'static str> {
+    match name.to_lowercase().replace([' ', '-', '_'], "").as_str() {
+        "hangman" | "soviethangman" => Some(active_games::HANGMAN),
+        "kremlin" | "kremlinsecrets" => Some(active_games::KREMLIN_SECRETS),
+        _ => None,
+    }
+}
+
+// Claim the author's attempt at today's puzzle. If it's already been had, tells them
+// how it went and returns false.
+async fn claim_attempt(ctx: crate::Context<'_>, server_id: &str, game_type: &str, date: &str) -> Result<bool, CommandError> {
+    let db = &ctx.data().db;
+    let user_id = ctx.author().id.to_string();
+
+    if db.start_daily_attempt(server_id, game_type, date, &user_id, &ctx.author().name).await? {
+        return Ok(true);
+    }
+
+    let reply = match db.get_daily_attempt(server_id, game_type, date, &user_id).await? {
+        Some(DailyAttempt { grid: Some(grid), .. }) => {
+            format!("You have already had today's attempt, comrade. Your result:\n\n{}", grid)
+        },
+        _ => "Your attempt at today's puzzle is already underway, comrade.".to_string(),
+    };
+    ctx.send(|m| m.content(reply).ephemeral(true)).await?;
+
+    Ok(false)
+}
+
+// The note a finished attempt leaves in the channel. The share grid gives nothing
+// away, unlike the game itself, which only its player could see.
+pub(crate) fn share_note(player: serenity::UserId, grid: &str) -> String {
+    format!("<@{}> has played today's puzzle.\n{}", player, grid)
+}
+
+/// Today's puzzles, the same for every comrade in the server
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("daily_hangman", "daily_kremlin", "daily_leaderboard")
+)]
+pub async fn daily(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    ctx.say("Use `/daily hangman`, `/daily kremlin` or `/daily leaderboard`, comrade.").await?;
+    Ok(())
+}
+
+// The daily games are slash commands only, so their messages can be ones only the
+// player sees. A prefix command's reply would show the word to the whole channel.
+
+/// Play today's hangman word. One attempt per comrade.
+#[poise::command(slash_command, rename = "hangman")]
+pub async fn daily_hangman(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let date = today();
+    let entry = daily_word(&ctx.data().db, &server_id, active_games::HANGMAN, &date, None).await?;
+
+    if !claim_attempt(ctx, &server_id, active_games::HANGMAN, &date).await? {
+        return Ok(());
+    }
+
+    play_daily_hangman(ctx, &server_id, &date, entry).await
+}
+
+/// Uncover today's Kremlin secret. One attempt per comrade.
+#[poise::command(slash_command, rename = "kremlin")]
+pub async fn daily_kremlin(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let date = today();
+    let entry = daily_word(
+        &ctx.data().db,
+        &server_id,
+        active_games::KREMLIN_SECRETS,
+        &date,
+        Some(daily_word_difficulty()),
+    ).await?;
+
+    if !claim_attempt(ctx, &server_id, active_games::KREMLIN_SECRETS, &date).await? {
+        return Ok(());
+    }
+
+    play_daily_kremlin(ctx, &server_id, &date, entry).await
+}
+
+/// Today's fewest-guesses leaderboard
+#[poise::command(slash_command, prefix_command, rename = "leaderboard")]
+pub async fn daily_leaderboard(
+    ctx: crate::Context<'_>,
+    #[description = "Game to show: hangman or kremlin (default: both)"]
+    game: Option<String>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let games: Vec<(&str, &str)> = match game.as_deref() {
+        Some(name) => {
+            let game_type = daily_game_type(name).ok_or("Choose `hangman` or `kremlin`, comrade.")?;
+            DAILY_GAMES.iter().copied().filter(|(key, _)| *key == game_type).collect()
+        },
+        None => DAILY_GAMES.to_vec(),
+    };
+
+    let date = today();
+    let db = &ctx.data().db;
+    let mut boards = Vec::new();
+    for (game_type, name) in games {
+        let attempts = db.get_daily_leaderboard(&server_id, game_type, &date, LEADERBOARD_SIZE).await?;
+
+        let lines: Vec<String> = attempts
+            .iter()
+            .enumerate()
+            .map(|(i, attempt)| {
+                let guesses = attempt.guesses.unwrap_or(0);
+                match attempt.won {
+                    Some(true) => format!("**{}.** {} - {} guesses", i + 1, attempt.username, guesses),
+                    _ => format!("**{}.** {} - failed after {} guesses", i + 1, attempt.username, guesses),
+                }
+            })
+            .collect();
+
+        let board = if lines.is_empty() {
+            "Nobody has finished today's puzzle yet. Be the first!".to_string()
+        } else {
+            lines.join("\n")
+        };
+        boards.push((name, board));
+    }
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title(format!("☭ Daily Leaderboard · {} ☭", date))
+             .color(serenity::Color::RED)
+             .fields(boards.iter().map(|(name, board)| (*name, board.as_str(), false)))
+             .footer(|f| f.text("Fewest guesses wins. Ties go to the quickest comrade."))
+        })
+    }).await?;
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use crate::CommandError;
+use crate::db::DossierEntry;
+use poise::serenity_prelude as serenity;
+
+// Longest remark shown in the dossier embed
+const REMARK_PREVIEW_CHARS: usize = 80;
+
+// How many remarks the embed lists
+const REMARKS_SHOWN: i64 = 10;
+
+// Discord rejects embed fields longer than this, in characters
+const EMBED_FIELD_LIMIT: usize = 1024;
+
+// One line per remark, newest first, leaving off any that would overflow the field
+fn render_remarks(entries: &[DossierEntry]) -> String {
+    let mut remarks = String::new();
+    let mut length = 0;
+    
+    for entry in entries {
+        let mut preview: String = entry.remark.chars().take(REMARK_PREVIEW_CHARS).collect();
+        if entry.remark.chars().count() > REMARK_PREVIEW_CHARS {
+            preview.push('…');
+        }
+        
+        let line = format!("<t:{}:R> — \"{}\"\n", entry.recorded_at, preview.replace('"', "'"));
+        let line_length = line.chars().count();
+        if length + line_length > EMBED_FIELD_LIMIT {
+            break;
+        }
+        
+        remarks.push_str(&line);
+        length += line_length;
+    }
+    
+    remarks
+}
+
+/// View the KGB's file on a comrade
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn dossier(
+    ctx: crate::Context<'_>,
+    #[description = "The comrade whose file to open (default: yourself)"]
+    user: Option<serenity::User>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+    
+    let target = user.as_ref().unwrap_or_else(|| ctx.author());
+    let db = &ctx.data().db;
+    let entries = db.get_dossier(&target.id.to_string(), &server_id, REMARKS_SHOWN).await?;
+    
+    if entries.is_empty() {
+        ctx.say(format!(
+            "The KGB has no file on **{}**. A model citizen... or a careful one.",
+            target.name
+        )).await?;
+        return Ok(());
+    }
+    
+    // Collect the distinct topics the comrade has been flagged for
+    let mut flagged: Vec<&str> = Vec::new();
+    for entry in &entries {
+        if let Some(word) = entry.trigger_word.as_deref() {
+            if !flagged.contains(&word) {
+                flagged.push(word);
+            }
+        }
+    }
+    
+    let flagged_text = if flagged.is_empty() {
+        "None. Suspiciously clean.".to_string()
+    } else {
+        flagged.iter().map(|w| format!("`{}`", w)).collect::<Vec<_>>().join(", ")
+    };
+    
+    let threat_level = match flagged.len() {
+        0 => "Low",
+        1 => "Elevated",
+        2 | 3 => "High",
+        _ => "Enemy of the State",
+    };
+    
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title(format!("☭ KGB Dossier: {} ☭", target.name))
+             .description("**TOP SECRET** — property of the Committee for State Security.")
+             .color(serenity::Color::RED)
+             .thumbnail(target.face())
+             .field("Threat Level", threat_level, true)
+             .field("Flagged Topics", flagged_text, true)
+             .field("Overheard Remarks", render_remarks(&entries), false)
+             .footer(|f| f.text("Comrades may request their file be burned with /kgb forget."))
+        })
+    }).await?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_remarks_fit_in_one_embed_field() {
+        let entries: Vec<DossierEntry> = (0..REMARKS_SHOWN)
+            .map(|i| DossierEntry {
+                remark: "The five year plan will be finished in four, comrades, or else. ".repeat(3),
+                trigger_word: Some("plan".to_string()),
+                recorded_at: 1_760_000_000 + i,
+            })
+            .collect();
+        
+        let remarks = render_remarks(&entries);
+        assert!(remarks.chars().count() <= EMBED_FIELD_LIMIT);
+        assert!(remarks.lines().count() >= 9, "only the overflowing remark is left off");
+    }
+
+    #[test]
+    fn short_remarks_are_all_shown() {
+        let entries: Vec<DossierEntry> = (0..3)
+            .map(|i| DossierEntry {
+                remark: "Long live the \"Party\"".to_string(),
+                trigger_word: None,
+                recorded_at: 1_760_000_000 + i,
+            })
+            .collect();
+        
+        assert_eq!(render_remarks(&entries).lines().count(), 3);
+        assert!(render_remarks(&entries).contains("'Party'"));
+    }
+}
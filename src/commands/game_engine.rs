@@ -0,0 +1,886 @@
+use crate::CommandError;
+use crate::db::{ActiveGame, Database, EscrowOutcome};
+use super::active_games::{save_game, forget_game, Resumed};
+use super::challenge::{send_challenge, offer_rematch, ChallengeResponse};
+use super::ratings::record_rated_match;
+use poise::serenity_prelude as serenity;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Who a game is waiting on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Turn {
+    // The player in this seat (0 is whoever started the game)
+    Player(usize),
+    // Any comrade may press; the game decides whether the press counts
+    Open,
+    Computer,
+    Over,
+}
+
+// How a finished game ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Outcome {
+    Win(usize),
+    ComputerWin,
+    Draw,
+    // Solo games are scored instead; lower is better unless the game says otherwise
+    Score(f64),
+    // Several comrades raced; their scores come from `Game::race_scores`
+    Raced,
+}
+
+// A game played through message buttons. Implementations hold only game logic and
+// rendering; the driver below deals with Discord, turn order, timeouts and results.
+pub(crate) trait Game: Clone + Serialize + DeserializeOwned + Send + Sync + 'static {
+    type Move: Clone + Send;
+
+    // Key used for ratings, scores, escrows and saved games
+    const KEY: &'static str;
+    // Name used in challenges and rematch offers
+    const NAME: &'static str;
+    // Whether the game can be picked up again after a restart
+    const RESUMABLE: bool = true;
+    // Whether higher scores beat lower ones, as with points rather than times
+    const HIGHER_SCORES_WIN: bool = false;
+    // Whether a round nobody moves in before the timeout stays on its board for moves
+    // at any later time, rather than being abandoned
+    const CORRESPONDENCE: bool = false;
+
+    fn turn(&self) -> Turn;
+    fn legal_moves(&self) -> Vec<Self::Move>;
+    // Returns false (and changes nothing) if the move isn't legal
+    fn apply_move(&mut self, mv: Self::Move) -> bool;
+    fn is_terminal(&self) -> bool;
+    // None until the game is over
+    fn outcome(&self) -> Option<Outcome>;
+
+    // Share of the reward for beating the computer that a win earns at this game's
+    // level, so the levels anyone can beat pay little or nothing
+    fn computer_win_reward_share(&self) -> f64 {
+        1.0
+    }
+
+    // The computer's choice when it is to move. This runs on the blocking pool,
+    // so it may take its time.
+    fn computer_move(&self) -> Option<Self::Move> {
+        None
+    }
+
+    // Turn a button press into a move. A select menu's choice follows its id after a colon.
+    // `think_time` is how long the board had been shown.
+    fn parse_move(&self, custom_id: &str, presser: &serenity::User, think_time: Duration) -> Option<Self::Move>;
+
+    // `players` holds the seated comrades in seat order; the computer has no entry
+    fn render(&self, players: &[serenity::User]) -> String;
+    fn render_abandoned(&self, players: &[serenity::User]) -> String;
+    fn components<'a>(&self, c: &'a mut serenity::CreateComponents) -> &'a mut serenity::CreateComponents;
+
+    // What only `viewer` may see, such as their own fleet, for games with hidden
+    // information. Shown to them alone when they press and kept up to date as the game goes on.
+    fn private_view(&self, _viewer: &serenity::User, _players: &[serenity::User]) -> Option<String> {
+        None
+    }
+
+    // A button that asks for a typed move instead of making one, as the title and label of
+    // the pop-up it opens. What's typed reaches `parse_move` after the button's id and a colon.
+    fn move_prompt(&self, _custom_id: &str) -> Option<(&'static str, &'static str)> {
+        None
+    }
+
+    // How long the current player has to move, counted from when the board was shown
+    fn move_timeout(&self) -> Duration;
+
+    // Called when the move timeout runs out. Timed games move on to their next round
+    // and return true; the rest leave it to the driver to abandon the game.
+    fn time_up(&mut self) -> bool {
+        false
+    }
+
+    // How a score is shown on the leaderboard
+    fn format_score(score: f64) -> String {
+        format!("{:.2}", score)
+    }
+
+    // How race results are ranked, to show under the race leaderboard
+    fn race_ranking() -> Option<&'static str> {
+        None
+    }
+
+    // The leaderboard this game's scores count towards, for games with several modes
+    fn score_key(&self) -> &'static str {
+        Self::KEY
+    }
+
+    // Each racer's score once a race is over, better as for `Outcome::Score`
+    fn race_scores(&self) -> Vec<(serenity::User, f64)> {
+        Vec::new()
+    }
+
+    // A record of the finished game to post after it, as (file name, contents)
+    fn transcript(&self, _players: &[serenity::User]) -> Option<(String, String)> {
+        None
+    }
+}
+
+// A round as saved between moves, so it can be resumed after a restart
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SavedRound<G> {
+    pub(crate) game: G,
+    pub(crate) players: Vec<serenity::User>,
+    pub(crate) wager: Option<f64>,
+}
+
+impl<G: Game> SavedRound<G> {
+    // The comrade whose move it is, if it's anyone's
+    fn current_player(&self) -> Option<&serenity::User> {
+        match self.game.turn() {
+            Turn::Player(seat) => self.players.get(seat),
+            _ => None,
+        }
+    }
+
+    // Let the computer play for as long as it is its turn, falling back to
+    // any legal move if it has no preference. Searching can be slow, so it
+    // happens off the async runtime.
+    async fn play_computer_moves(&mut self) -> Result<(), CommandError> {
+        if self.game.turn() != Turn::Computer {
+            return Ok(());
+        }
+
+        let mut game = self.game.clone();
+        self.game = tokio::task::spawn_blocking(move || {
+            while game.turn() == Turn::Computer {
+                let mv = game.computer_move().or_else(|| game.legal_moves().into_iter().next());
+                if !mv.is_some_and(|mv| game.apply_move(mv)) {
+                    break;
+                }
+            }
+            game
+        }).await?;
+
+        Ok(())
+    }
+}
+
+// The id a game parses for a press, with a select menu's choice appended
+fn press_id(press: &serenity::MessageComponentInteraction) -> String {
+    match press.data.values.first() {
+        Some(value) => format!("{}:{}", press.data.custom_id, value),
+        None => press.data.custom_id.clone(),
+    }
+}
+
+// The id a game parses for a typed move: the prompting button's id, then what was typed
+fn typed_id(submit: &serenity::ModalSubmitInteraction) -> String {
+    format!("{}:{}", submit.data.custom_id, typed_text(submit))
+}
+
+fn typed_text(submit: &serenity::ModalSubmitInteraction) -> &str {
+    submit.data.components
+        .iter()
+        .flat_map(|row| &row.components)
+        .find_map(|component| match component {
+            serenity::ActionRowComponent::InputText(input) => Some(input.value.trim()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+// A move as it reaches a live round
+enum Input {
+    Press(std::sync::Arc<serenity::MessageComponentInteraction>),
+    Typed(std::sync::Arc<serenity::ModalSubmitInteraction>),
+}
+
+// Longest move that can be typed into a game's pop-up
+const MAX_TYPED_MOVE: u64 = 40;
+
+// Open the pop-up a game's button asks for
+async fn open_prompt(
+    ctx: &serenity::Context,
+    press: &serenity::MessageComponentInteraction,
+    title: &str,
+    label: &str,
+) -> Result<(), CommandError> {
+    press.create_interaction_response(ctx, |r| {
+        r.kind(serenity::InteractionResponseType::Modal)
+         .interaction_response_data(|d| {
+             d.custom_id(&press.data.custom_id)
+              .title(title)
+              .components(|c| {
+                  c.create_action_row(|row| {
+                      row.create_input_text(|t| {
+                          t.custom_id("move")
+                           .label(label)
+                           .style(serenity::InputTextStyle::Short)
+                           .min_length(1)
+                           .max_length(MAX_TYPED_MOVE)
+                           .required(true)
+                      })
+                  })
+              })
+         })
+    }).await?;
+
+    Ok(())
+}
+
+// Tell a comrade, and only them, that the move they typed didn't count
+async fn reject_typed(ctx: &serenity::Context, submit: &serenity::ModalSubmitInteraction) -> Result<(), CommandError> {
+    submit.create_interaction_response(ctx, |r| {
+        r.kind(serenity::InteractionResponseType::ChannelMessageWithSource)
+         .interaction_response_data(|d| {
+             d.content(format!("`{}` isn't a move you can make right now, comrade.", typed_text(submit)))
+              .ephemeral(true)
+         })
+    }).await?;
+
+    Ok(())
+}
+
+// Each comrade's private view of a live round, as an ephemeral message edited in place.
+// Discord only allows those edits for 15 minutes, after which the comrade gets a new
+// message the next time they press.
+#[derive(Default)]
+struct PrivatePanels(HashMap<serenity::UserId, (serenity::MessageComponentInteraction, serenity::MessageId)>);
+
+impl PrivatePanels {
+    async fn open(
+        &mut self,
+        ctx: crate::Context<'_>,
+        press: &serenity::MessageComponentInteraction,
+        view: String,
+    ) -> Result<(), CommandError> {
+        let message = press.create_followup_message(ctx, |f| f.content(view).ephemeral(true)).await?;
+        self.0.insert(press.user.id, (press.clone(), message.id));
+        Ok(())
+    }
+
+    // Bring every open panel up to date, and show the presser (if any) the result of their move
+    async fn refresh<G: Game>(
+        &mut self,
+        ctx: crate::Context<'_>,
+        round: &SavedRound<G>,
+        press: Option<&serenity::MessageComponentInteraction>,
+    ) -> Result<(), CommandError> {
+        let mut expired = Vec::new();
+        for (user_id, (interaction, message_id)) in &self.0 {
+            let view = round.players
+                .iter()
+                .find(|player| player.id == *user_id)
+                .and_then(|viewer| round.game.private_view(viewer, &round.players));
+            if let Some(view) = view {
+                if interaction.edit_followup_message(ctx, *message_id, |m| m.content(view)).await.is_err() {
+                    expired.push(*user_id);
+                }
+            }
+        }
+        for user_id in expired {
+            self.0.remove(&user_id);
+        }
+
+        if let Some(press) = press.filter(|press| !self.0.contains_key(&press.user.id)) {
+            if let Some(view) = round.game.private_view(&press.user, &round.players) {
+                self.open(ctx, press, view).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Wager limits in boops for two-player games
+pub(crate) const MIN_WAGER: f64 = 1.0;
+pub(crate) const MAX_WAGER: f64 = 500.0;
+
+// Share of a won pot that goes to the communal treasury
+const WAGER_COMMUNAL_CUT: f64 = 0.1;
+
+// Reward for beating the computer at its strongest, wagered or not, and how many
+// wins a day earn it. This is the only pay for a win over the computer beyond the stake.
+const AI_WIN_REWARD: f64 = 5.0;
+const AI_WIN_REWARDS_PER_DAY: i64 = 10;
+
+// Pause before the computer moves, so it looks like it is thinking
+const COMPUTER_THINKING: Duration = Duration::from_millis(1500);
+
+// Run a game from challenge to rematch: reserve the players, get the opponent's
+// consent, escrow any wager, then play, settle and record rounds until nobody
+// wants another. `new_game` sets up each round for the seated players.
+pub(crate) async fn run_game<G: Game>(
+    ctx: crate::Context<'_>,
+    opponent: Option<serenity::User>,
+    wager: Option<f64>,
+    new_game: impl Fn(&[serenity::User]) -> G + Send + Sync,
+) -> Result<(), CommandError> {
+    let author = ctx.author().clone();
+
+    let wager = match wager {
+        Some(wager) if !(MIN_WAGER..=MAX_WAGER).contains(&wager) => {
+            ctx.say(format!("Wagers must be between {:.0} and {:.0} boops, comrade.", MIN_WAGER, MAX_WAGER)).await?;
+            return Ok(());
+        },
+        Some(wager) => Some((wager * 100.0).round() / 100.0),
+        None => None,
+    };
+
+    // Nobody may be in two games at once, including while a challenge is pending
+    let mut players = vec![author.clone()];
+    players.extend(opponent.clone());
+    let player_ids: Vec<serenity::UserId> = players.iter().map(|p| p.id).collect();
+
+    let _reservation = match ctx.data().games.try_reserve(&player_ids) {
+        Ok(reservation) => reservation,
+        Err(busy) => {
+            ctx.say(format!("<@{}> is already in a game, comrade. Finish one struggle before starting another.", busy)).await?;
+            return Ok(());
+        }
+    };
+
+    if let Some(opponent) = &opponent {
+        if send_challenge(ctx, &author, opponent, G::NAME, wager).await? != ChallengeResponse::Accepted {
+            return Ok(());
+        }
+    }
+
+    loop {
+        // Collect stakes before the first move
+        let escrow_id = match wager {
+            Some(wager) => match open_wager(ctx, G::KEY, &players, wager).await? {
+                Some(escrow_id) => Some(escrow_id),
+                None => return Ok(()),
+            },
+            None => None,
+        };
+
+        let round = SavedRound {
+            game: new_game(&players),
+            players: players.clone(),
+            wager,
+        };
+
+        let round = match play_round(ctx, round, escrow_id).await {
+            Ok(Some(round)) => round,
+            // The stakes stay in escrow until the round is finished
+            Ok(None) => {
+                ctx.say(format!(
+                    "♟️ This game of {} continues by correspondence, comrades. Make your moves on the board above whenever you're ready.",
+                    G::NAME
+                )).await?;
+                return Ok(());
+            },
+            Err(e) => {
+                // Never keep stakes for a game that broke
+                if let Some(escrow_id) = escrow_id {
+                    ctx.data().db.settle_escrow(escrow_id, &EscrowOutcome::Refund).await?;
+                }
+                return Err(e);
+            }
+        };
+
+        let server_id = ctx.guild_id().map(|id| id.to_string());
+        for summary in record_round(&ctx.data().db, server_id.as_deref(), &round, escrow_id).await? {
+            ctx.say(summary).await?;
+        }
+        post_transcript(ctx.serenity_context(), ctx.channel_id(), &round).await?;
+
+        let rematch_players: Vec<&serenity::User> = players.iter().collect();
+        if !offer_rematch(ctx, &rematch_players, G::NAME).await? {
+            break;
+        }
+
+        // Swap seats for a human rematch so the other comrade moves first
+        players.rotate_left(1);
+    }
+
+    Ok(())
+}
+
+// Play one round on a new message until it ends or a player runs out of time.
+// Returns the round as it stood at the end, or None if it was left to be finished
+// by correspondence.
+async fn play_round<G: Game>(
+    ctx: crate::Context<'_>,
+    mut round: SavedRound<G>,
+    escrow_id: Option<i64>,
+) -> Result<Option<SavedRound<G>>, CommandError> {
+    let msg = ctx.send(|m| {
+        m.content(round.game.render(&round.players))
+         .components(|c| round.game.components(c))
+    }).await?;
+
+    // Only listen to this game's board, so concurrent games don't interfere
+    let message_id = msg.message().await?.id;
+    let _live = ctx.data().games.track_live(message_id);
+
+    // Whether the round was left on its board for later moves
+    let played: Result<bool, CommandError> = async {
+        let mut shown_at = Instant::now();
+        let mut panels = PrivatePanels::default();
+
+        loop {
+            if G::RESUMABLE && !round.game.is_terminal() {
+                save_game(ctx, message_id, G::KEY, &round, escrow_id).await?;
+            }
+
+            if round.game.turn() == Turn::Computer {
+                // The pause covers the search, unless the search takes longer
+                let (_, moved) = tokio::join!(tokio::time::sleep(COMPUTER_THINKING), round.play_computer_moves());
+                moved?;
+
+                msg.edit(ctx, |m| {
+                    m.content(round.game.render(&round.players))
+                     .components(|c| round.game.components(c))
+                }).await?;
+                shown_at = Instant::now();
+                panels.refresh(ctx, &round, None).await?;
+                continue;
+            }
+
+            // Only the current player may move, unless the floor is open to everyone
+            let player_id = match (round.game.turn(), round.current_player()) {
+                (Turn::Open, _) => None,
+                (_, Some(player)) => Some(player.id),
+                (_, None) => break,
+            };
+
+            // Moves come from button presses, or typed into a pop-up a button opened.
+            // Presses that don't count don't restart the clock.
+            let timeout = round.game.move_timeout().saturating_sub(shown_at.elapsed());
+            let press = serenity::CollectComponentInteraction::new(ctx)
+                .message_id(message_id)
+                .filter(move |press| player_id.is_none_or(|id| press.user.id == id))
+                .timeout(timeout);
+            let submit = serenity::CollectModalInteraction::new(ctx)
+                .message_id(message_id)
+                .filter(move |submit| player_id.is_none_or(|id| submit.user.id == id))
+                .timeout(timeout);
+
+            let input = tokio::select! {
+                press = press => press.map(Input::Press),
+                submit = submit => submit.map(Input::Typed),
+            };
+
+            let input = match input {
+                Some(input) => input,
+                None if round.game.time_up() => {
+                    msg.edit(ctx, |m| {
+                        m.content(round.game.render(&round.players))
+                         .components(|c| round.game.components(c))
+                    }).await?;
+                    shown_at = Instant::now();
+                    continue;
+                },
+                // The board stays up and its saved round takes later moves
+                None if G::CORRESPONDENCE => return Ok(true),
+                None => {
+                    msg.edit(ctx, |m| {
+                        m.content(round.game.render_abandoned(&round.players))
+                         .components(|c| c)
+                    }).await?;
+                    break;
+                }
+            };
+
+            let applied = match &input {
+                Input::Press(press) => {
+                    if let Some((title, label)) = round.game.move_prompt(&press.data.custom_id) {
+                        open_prompt(ctx.serenity_context(), press, title, label).await?;
+                        continue;
+                    }
+
+                    // Acknowledge the button press
+                    press.defer(ctx).await?;
+
+                    let mv = round.game.parse_move(&press_id(press), &press.user, shown_at.elapsed());
+                    let applied = mv.is_some_and(|mv| round.game.apply_move(mv));
+
+                    // A press that moves nothing may still ask to see the comrade's own side,
+                    // in a new message in case they dismissed the last one
+                    if !applied {
+                        if let Some(view) = round.game.private_view(&press.user, &round.players) {
+                            panels.open(ctx, press, view).await?;
+                        }
+                    }
+                    applied
+                },
+                Input::Typed(submit) => {
+                    let mv = round.game.parse_move(&typed_id(submit), &submit.user, shown_at.elapsed());
+                    let applied = mv.is_some_and(|mv| round.game.apply_move(mv));
+
+                    if applied {
+                        submit.defer(ctx).await?;
+                    } else {
+                        reject_typed(ctx.serenity_context(), submit).await?;
+                    }
+                    applied
+                },
+            };
+            if !applied {
+                continue;
+            }
+
+            msg.edit(ctx, |m| {
+                m.content(round.game.render(&round.players))
+                 .components(|c| round.game.components(c))
+            }).await?;
+            shown_at = Instant::now();
+
+            let press = match &input {
+                Input::Press(press) => Some(press.as_ref()),
+                Input::Typed(_) => None,
+            };
+            panels.refresh(ctx, &round, press).await?;
+        }
+
+        Ok(false)
+    }.await;
+
+    // Finished, abandoned or broken, the round no longer needs resuming
+    let handed_off = matches!(played, Ok(true));
+    if G::RESUMABLE && !handed_off {
+        forget_game(ctx, message_id).await?;
+    }
+    played?;
+
+    Ok((!handed_off).then_some(round))
+}
+
+// Apply a button press to a saved round whose command is no longer running.
+// The computer answers at once, since the interaction has to be acknowledged quickly.
+pub(crate) async fn resume_press<G: Game>(
+    ctx: &serenity::Context,
+    data: &crate::Data,
+    record: &ActiveGame,
+    press: &serenity::MessageComponentInteraction,
+) -> Result<Resumed, CommandError> {
+    let mut round: SavedRound<G> = serde_json::from_str(&record.state)?;
+
+    let may_press = match round.game.turn() {
+        Turn::Open => true,
+        _ => round.current_player().map(|p| p.id) == Some(press.user.id),
+    };
+    if !may_press {
+        return Ok(Resumed::Ignored);
+    }
+
+    if let Some((title, label)) = round.game.move_prompt(&press.data.custom_id) {
+        open_prompt(ctx, press, title, label).await?;
+        return Ok(Resumed::Answered);
+    }
+
+    let think_time = Duration::from_secs((chrono::Utc::now().timestamp() - record.updated_at).max(0) as u64);
+    let mv = round.game.parse_move(&press_id(press), &press.user, think_time);
+    if !mv.is_some_and(|mv| round.game.apply_move(mv)) {
+        // Nothing changes, but the comrade may have asked to see their own side
+        let view = match round.game.private_view(&press.user, &round.players) {
+            Some(view) => view,
+            None => return Ok(Resumed::Ignored),
+        };
+        press.create_interaction_response(ctx, |r| {
+            r.kind(serenity::InteractionResponseType::ChannelMessageWithSource)
+             .interaction_response_data(|d| d.content(view).ephemeral(true))
+        }).await?;
+        return Ok(Resumed::Answered);
+    }
+
+    press.defer(ctx).await?;
+    round.play_computer_moves().await?;
+
+    press.edit_original_interaction_response(ctx, |r| {
+        r.content(round.game.render(&round.players))
+         .components(|c| round.game.components(c))
+    }).await?;
+
+    if let Some(view) = round.game.private_view(&press.user, &round.players) {
+        press.create_followup_message(ctx, |f| f.content(view).ephemeral(true)).await?;
+    }
+
+    finish_resumed(ctx, data, record, &round, press.channel_id, press.guild_id).await
+}
+
+// Apply a move typed into a pop-up to a saved round whose command is no longer running
+pub(crate) async fn resume_typed_move<G: Game>(
+    ctx: &serenity::Context,
+    data: &crate::Data,
+    record: &ActiveGame,
+    submit: &serenity::ModalSubmitInteraction,
+) -> Result<Resumed, CommandError> {
+    let mut round: SavedRound<G> = serde_json::from_str(&record.state)?;
+
+    let may_move = match round.game.turn() {
+        Turn::Open => true,
+        _ => round.current_player().map(|p| p.id) == Some(submit.user.id),
+    };
+    if !may_move {
+        return Ok(Resumed::Ignored);
+    }
+
+    let think_time = Duration::from_secs((chrono::Utc::now().timestamp() - record.updated_at).max(0) as u64);
+    let mv = round.game.parse_move(&typed_id(submit), &submit.user, think_time);
+    if !mv.is_some_and(|mv| round.game.apply_move(mv)) {
+        reject_typed(ctx, submit).await?;
+        return Ok(Resumed::Answered);
+    }
+
+    submit.defer(ctx).await?;
+    round.play_computer_moves().await?;
+
+    submit.edit_original_interaction_response(ctx, |r| {
+        r.content(round.game.render(&round.players))
+         .components(|c| round.game.components(c))
+    }).await?;
+
+    finish_resumed(ctx, data, record, &round, submit.channel_id, submit.guild_id).await
+}
+
+// Save a resumed round after a move, or record it if that move ended it. There is no
+// command left to offer a rematch.
+async fn finish_resumed<G: Game>(
+    ctx: &serenity::Context,
+    data: &crate::Data,
+    record: &ActiveGame,
+    round: &SavedRound<G>,
+    channel_id: serenity::ChannelId,
+    guild_id: Option<serenity::GuildId>,
+) -> Result<Resumed, CommandError> {
+    if !round.game.is_terminal() {
+        return Ok(Resumed::Continue(serde_json::to_string(round)?));
+    }
+
+    let server_id = guild_id.map(|id| id.to_string());
+    for summary in record_round(&data.db, server_id.as_deref(), round, record.escrow_id).await? {
+        channel_id.say(ctx, summary).await?;
+    }
+    post_transcript(ctx, channel_id, round).await?;
+
+    Ok(Resumed::Finished)
+}
+
+// Post a finished round's transcript, for games that keep one
+async fn post_transcript<G: Game>(
+    ctx: &serenity::Context,
+    channel_id: serenity::ChannelId,
+    round: &SavedRound<G>,
+) -> Result<(), CommandError> {
+    let (filename, contents) = match round.game.transcript(&round.players) {
+        Some(transcript) if round.game.is_terminal() => transcript,
+        _ => return Ok(()),
+    };
+
+    channel_id.send_message(ctx, |m| {
+        m.content(format!("📜 The record of this game of {}, for the archives:", G::NAME))
+         .add_file(serenity::AttachmentType::Bytes { data: contents.into_bytes().into(), filename })
+    }).await?;
+
+    Ok(())
+}
+
+// Settle a round's wager, update ratings or save its score, and return the
+// announcements to post. An abandoned round is refunded like a draw.
+async fn record_round<G: Game>(
+    db: &Database,
+    server_id: Option<&str>,
+    round: &SavedRound<G>,
+    escrow_id: Option<i64>,
+) -> Result<Vec<String>, CommandError> {
+    let mut summaries = Vec::new();
+    let outcome = round.game.outcome();
+    let vs_computer = round.players.len() == 1;
+
+    if let (Some(escrow_id), Some(wager)) = (escrow_id, round.wager) {
+        let winner = match outcome {
+            Some(Outcome::Win(seat)) => round.players.get(seat),
+            _ => None,
+        };
+        let ai_won = outcome == Some(Outcome::ComputerWin);
+
+        summaries.push(settle_wager(db, escrow_id, wager, winner, ai_won, vs_computer).await?);
+    }
+
+    // Beating the computer pays a fixed reward, scaled to how hard it played
+    let reward = AI_WIN_REWARD * round.game.computer_win_reward_share();
+    if let (true, Some(Outcome::Win(_)), Some(server_id), [player]) = (vs_computer, outcome, server_id, round.players.as_slice()) {
+        if reward > 0.0 {
+            summaries.push(reward_computer_win(db, server_id, player, reward).await?);
+        }
+    }
+
+    match (outcome, round.players.as_slice()) {
+        // Only finished games between two comrades in a server are rated
+        (Some(Outcome::Win(_)) | Some(Outcome::Draw), [first, second]) => {
+            let score = match outcome {
+                Some(Outcome::Win(0)) => 1.0,
+                Some(Outcome::Win(_)) => 0.0,
+                _ => 0.5,
+            };
+
+            if let Some(server_id) = server_id {
+                summaries.push(record_rated_match(db, server_id, G::KEY, first, second, score).await?);
+            }
+        },
+        (Some(Outcome::Score(score)), [player]) => {
+            summaries.push(record_score::<G>(db, server_id.unwrap_or("DM"), round.game.score_key(), player, score).await?);
+        },
+        (Some(Outcome::Raced), _) => {
+            summaries.push(record_race(db, server_id.unwrap_or("DM"), &round.game).await?);
+        },
+        _ => {},
+    }
+
+    Ok(summaries)
+}
+
+// Pay a comrade for defeating the machine, up to the day's limit
+async fn reward_computer_win(db: &Database, server_id: &str, player: &serenity::User, reward: f64) -> Result<String, CommandError> {
+    let user_id = player.id.to_string();
+    db.ensure_user_exists(&user_id, server_id, &player.name).await?;
+
+    if db.claim_daily_reward(&user_id, server_id, "computer_win", reward, AI_WIN_REWARDS_PER_DAY).await? {
+        Ok(format!("☭ **{}** earns **{:.2}** boops for defeating the machine!", player.name, reward))
+    } else {
+        Ok(format!(
+            "☭ The machine is defeated, but the Party only rewards {} such victories a day, comrade.",
+            AI_WIN_REWARDS_PER_DAY
+        ))
+    }
+}
+
+// Save a solo game's score and compare it with the comrade's best and the server's top three
+async fn record_score<G: Game>(
+    db: &Database,
+    server_id: &str,
+    key: &str,
+    player: &serenity::User,
+    score: f64,
+) -> Result<String, CommandError> {
+    let user_id = player.id.to_string();
+    db.save_game_score(&user_id, server_id, &player.name, key, score).await?;
+
+    let best_score = db.get_user_best_score(&user_id, server_id, key, G::HIGHER_SCORES_WIN).await?;
+    let leaderboard = db.get_server_leaderboard(server_id, key, 3, G::HIGHER_SCORES_WIN).await?;
+
+    let best_score_text = match best_score {
+        Some(best) if (score - best).abs() < 0.001 => "This is a new personal best!".to_string(),
+        Some(best) => format!("Your best score: {}", G::format_score(best)),
+        None => "This is your first score!".to_string(),
+    };
+
+    let mut text = format!("Comrade {}, your score: **{}**\n{}\n\n**Top Scores:**\n", player.name, G::format_score(score), best_score_text);
+    for (i, (username, score)) in leaderboard.iter().enumerate() {
+        text.push_str(&format!("{}. **{}**: {}\n", i + 1, username, G::format_score(*score)));
+    }
+
+    Ok(text)
+}
+
+// Save every racer's score and show the server's top three for the race
+async fn record_race<G: Game>(
+    db: &Database,
+    server_id: &str,
+    game: &G,
+) -> Result<String, CommandError> {
+    let key = game.score_key();
+    for (racer, score) in game.race_scores() {
+        db.save_game_score(&racer.id.to_string(), server_id, &racer.name, key, score).await?;
+    }
+
+    let leaderboard = db.get_server_leaderboard(server_id, key, 3, G::HIGHER_SCORES_WIN).await?;
+    let mut text = match G::race_ranking() {
+        Some(ranking) => format!("**Top Race Scores** ({}):\n", ranking.to_lowercase()),
+        None => "**Top Race Scores:**\n".to_string(),
+    };
+    for (i, (username, score)) in leaderboard.iter().enumerate() {
+        text.push_str(&format!("{}. **{}**: {}\n", i + 1, username, G::format_score(*score)));
+    }
+
+    Ok(text)
+}
+
+// Collect the stakes for a wagered game into escrow. Returns None (after telling
+// the players why) if the stakes could not be collected.
+async fn open_wager(
+    ctx: crate::Context<'_>,
+    game_type: &str,
+    players: &[serenity::User],
+    wager: f64,
+) -> Result<Option<i64>, CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => {
+            ctx.say("Wagers can only be placed in a server, comrade.").await?;
+            return Ok(None);
+        }
+    };
+
+    let db = &ctx.data().db;
+    let mut stakes = Vec::new();
+
+    for user in players {
+        db.ensure_user_exists(&user.id.to_string(), &server_id, &user.name).await?;
+        stakes.push((user.id.to_string(), wager));
+    }
+
+    match db.open_escrow(&server_id, game_type, &stakes).await? {
+        Some(escrow_id) => Ok(Some(escrow_id)),
+        None => {
+            ctx.say(format!(
+                "The wager of **{:.2}** boops could not be collected - not every comrade can afford it.",
+                wager
+            )).await?;
+            Ok(None)
+        }
+    }
+}
+
+// Release a game's escrow and describe what happened to the stakes.
+// `winner` is None for a draw or an abandoned game.
+async fn settle_wager(
+    db: &Database,
+    escrow_id: i64,
+    wager: f64,
+    winner: Option<&serenity::User>,
+    ai_won: bool,
+    ai_mode: bool,
+) -> Result<String, CommandError> {
+    let (outcome, message) = if ai_won {
+        (
+            EscrowOutcome::Forfeit,
+            format!("The computer has claimed your **{:.2}** boops for the communal treasury.", wager),
+        )
+    } else if let Some(winner) = winner {
+        // Against the computer the pot is the player's own stake, so it comes back whole
+        let communal_cut = if ai_mode { 0.0 } else { WAGER_COMMUNAL_CUT };
+
+        (
+            EscrowOutcome::Winner { user_id: winner.id.to_string(), communal_cut },
+            String::new(),
+        )
+    } else {
+        (
+            EscrowOutcome::Refund,
+            "All stakes have been returned to their owners.".to_string(),
+        )
+    };
+
+    let paid = db.settle_escrow(escrow_id, &outcome).await?;
+
+    let message = match (&outcome, winner) {
+        (EscrowOutcome::Winner { communal_cut, .. }, Some(winner)) => {
+            if ai_mode {
+                format!("**{}** takes back **{:.2}** boops from the machine.", winner.name, paid)
+            } else {
+                format!(
+                    "**{}** collects **{:.2}** boops. {:.0}% of the pot was contributed to the communal treasury.",
+                    winner.name, paid, communal_cut * 100.0
+                )
+            }
+        },
+        _ => message,
+    };
+
+    Ok(format!("☭ **Wager settled:** {}", message))
+}
@@ -1,14 +1,16 @@
 use crate::CommandError;
+use crate::db::ActiveGame;
+use super::active_games::Resumed;
+use super::connect4_engine::{Position, Search, cell_bit, column_mask, has_four, HEIGHT, WIDTH};
+use super::game_engine::{Game, Outcome, Turn, run_game, resume_press};
 use poise::serenity_prelude as serenity;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::time::{Duration, Instant};
 use rand::{Rng, seq::SliceRandom};
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
-use chrono;
 
 // Tic-Tac-Toe Game
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum Cell {
     Empty,
     X,
@@ -25,25 +27,78 @@ impl fmt::Display for Cell {
     }
 }
 
+// How hard the computer plays tic-tac-toe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum TicTacToeDifficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl TicTacToeDifficulty {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "easy" => Some(TicTacToeDifficulty::Easy),
+            "medium" | "normal" => Some(TicTacToeDifficulty::Medium),
+            "hard" | "unbeatable" => Some(TicTacToeDifficulty::Hard),
+            _ => None,
+        }
+    }
+    
+    fn name(&self) -> &'static str {
+        match self {
+            TicTacToeDifficulty::Easy => "Easy",
+            TicTacToeDifficulty::Medium => "Medium",
+            TicTacToeDifficulty::Hard => "Hard",
+        }
+    }
+    
+    // Share of the reward for beating the computer a win at this level earns
+    fn reward_share(&self) -> f64 {
+        match self {
+            TicTacToeDifficulty::Easy => 0.0,
+            TicTacToeDifficulty::Medium => 0.5,
+            TicTacToeDifficulty::Hard => 1.0,
+        }
+    }
+    
+    // Chance of playing a random move instead of the best one
+    fn mistake_chance(&self) -> f64 {
+        match self {
+            TicTacToeDifficulty::Easy => 0.5,
+            TicTacToeDifficulty::Medium => 0.2,
+            TicTacToeDifficulty::Hard => 0.0,
+        }
+    }
+}
+
+// Games saved before difficulty levels, and games started without one, can be won
+fn default_tictactoe_difficulty() -> TicTacToeDifficulty {
+    TicTacToeDifficulty::Medium
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 struct TicTacToe {
     board: [Cell; 9],
     current_player: Cell,
-    opponent: Option<serenity::UserId>,
     ai_mode: bool,
+    #[serde(default = "default_tictactoe_difficulty")]
+    difficulty: TicTacToeDifficulty,
     game_over: bool,
     winner: Option<Cell>,
+    last_move: Option<usize>,
 }
 
 impl TicTacToe {
-    fn new(_starter: serenity::UserId, opponent: Option<serenity::UserId>) -> Self {
-        let ai_mode = opponent.is_none();
+    fn new(ai_mode: bool, difficulty: TicTacToeDifficulty) -> Self {
         TicTacToe {
             board: [Cell::Empty; 9],
             current_player: Cell::X, // X always starts
-            opponent,
             ai_mode,
+            difficulty,
             game_over: false,
             winner: None,
+            last_move: None,
         }
     }
 
@@ -57,6 +112,7 @@ impl TicTacToe {
         }
         
         self.board[position] = self.current_player;
+        self.last_move = Some(position);
         
         // Check for win or draw
         if self.check_winner() {
@@ -75,76 +131,57 @@ impl TicTacToe {
         true
     }
     
-    fn ai_move(&mut self) -> Option<usize> {
+    fn choose_ai_move(&self) -> Option<usize> {
+        self.choose_ai_move_with(self.difficulty, &mut rand::thread_rng())
+    }
+    
+    // The move for whoever is to play. Every level plays perfectly apart from its
+    // mistakes, which are random moves; ties between equally good moves are broken
+    // at random so games don't all go the same way.
+    fn choose_ai_move_with(&self, difficulty: TicTacToeDifficulty, rng: &mut impl Rng) -> Option<usize> {
         if self.game_over {
             return None;
         }
         
-        // Check if AI can win in the next move
-        for i in 0..9 {
-            if self.is_valid_move(i) {
-                self.board[i] = self.current_player;
-                let win = self.check_winner();
-                self.board[i] = Cell::Empty; // Undo move
-                
-                if win {
-                    self.make_move(i);
-                    return Some(i);
-                }
-            }
-        }
-        
-        // Check if player can win in the next move and block
-        let opponent = if self.current_player == Cell::X { Cell::O } else { Cell::X };
-        for i in 0..9 {
-            if self.is_valid_move(i) {
-                self.board[i] = opponent;
-                let win = self.check_winner();
-                self.board[i] = Cell::Empty; // Undo move
-                
-                if win {
-                    self.make_move(i);
-                    return Some(i);
-                }
-            }
-        }
-        
-        // Take center if available
-        if self.is_valid_move(4) {
-            self.make_move(4);
-            return Some(4);
+        if rng.gen_bool(difficulty.mistake_chance()) {
+            let legal: Vec<usize> = (0..9).filter(|&i| self.is_valid_move(i)).collect();
+            return legal.choose(rng).copied();
         }
         
-        // Take a corner if available
-        let corners = [0, 2, 6, 8];
-        let available_corners: Vec<usize> = corners.iter()
-            .filter(|&&i| self.is_valid_move(i))
-            .cloned()
-            .collect();
-            
-        if !available_corners.is_empty() {
-            let idx = rand::thread_rng().gen_range(0..available_corners.len());
-            let corner = available_corners[idx];
-            self.make_move(corner);
-            return Some(corner);
-        }
-        
-        // Take any available side
-        let sides = [1, 3, 5, 7];
-        let available_sides: Vec<usize> = sides.iter()
-            .filter(|&&i| self.is_valid_move(i))
-            .cloned()
+        self.best_moves().choose(rng).copied()
+    }
+    
+    // Every move that does as well as possible for the player to move
+    fn best_moves(&self) -> Vec<usize> {
+        let scores: Vec<(usize, i32)> = (0..9)
+            .filter(|&i| self.is_valid_move(i))
+            .map(|i| (i, self.score_move(i)))
             .collect();
-            
-        if !available_sides.is_empty() {
-            let idx = rand::thread_rng().gen_range(0..available_sides.len());
-            let side = available_sides[idx];
-            self.make_move(side);
-            return Some(side);
-        }
+        let best = scores.iter().map(|&(_, score)| score).max();
         
-        // No move found (should never happen unless board is full)
-        None
+        scores.into_iter()
+            .filter(|&(_, score)| Some(score) == best)
+            .map(|(i, _)| i)
+            .collect()
+    }
+    
+    // Minimax value of a move for the player making it, assuming perfect play
+    // afterwards: positive wins (sooner scores higher), zero draws, negative loses
+    fn score_move(&self, position: usize) -> i32 {
+        let mut next = self.clone();
+        next.make_move(position);
+        
+        if next.winner.is_some() {
+            1 + next.board.iter().filter(|&&cell| cell == Cell::Empty).count() as i32
+        } else if next.game_over {
+            0
+        } else {
+            -(0..9)
+                .filter(|&i| next.is_valid_move(i))
+                .map(|i| next.score_move(i))
+                .max()
+                .unwrap_or(0)
+        }
     }
     
     fn check_winner(&self) -> bool {
@@ -233,7 +270,7 @@ impl TicTacToe {
 }
 
 // Connect 4 Game - People's Revolution Edition
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 enum Connect4Cell {
     Empty,
     Red,    // Player 1
@@ -250,41 +287,157 @@ impl std::fmt::Display for Connect4Cell {
     }
 }
 
-#[derive(Clone)]
+// How hard the computer plays Connect 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Connect4Difficulty {
+    Conscript,
+    Soldier,
+    General,
+    Marshal,
+}
+
+// What the computer may use at a difficulty level
+struct Connect4AiSettings {
+    // Plies searched on every move
+    depth: u32,
+    // Keep searching deeper until the thinking time runs out
+    deepen: bool,
+    // Chance of playing a random column instead of thinking
+    blunder_chance: f64,
+    opening_book: bool,
+    // Block the opponent's wins, and never set one up for them
+    block_threats: bool,
+    // Play a double threat as soon as one appears
+    forced_wins: bool,
+}
+
+impl Connect4Difficulty {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "conscript" | "easy" => Some(Connect4Difficulty::Conscript),
+            "soldier" | "medium" => Some(Connect4Difficulty::Soldier),
+            "general" | "hard" => Some(Connect4Difficulty::General),
+            "marshal" | "expert" => Some(Connect4Difficulty::Marshal),
+            _ => None,
+        }
+    }
+    
+    fn name(&self) -> &'static str {
+        match self {
+            Connect4Difficulty::Conscript => "Conscript",
+            Connect4Difficulty::Soldier => "Soldier",
+            Connect4Difficulty::General => "General",
+            Connect4Difficulty::Marshal => "Marshal",
+        }
+    }
+    
+    // Share of the reward for beating the computer a win at this level earns.
+    // The Conscript never guards against a win, so beating it earns nothing.
+    fn reward_share(&self) -> f64 {
+        match self {
+            Connect4Difficulty::Conscript => 0.0,
+            Connect4Difficulty::Soldier => 0.5,
+            Connect4Difficulty::General | Connect4Difficulty::Marshal => 1.0,
+        }
+    }
+    
+    fn settings(&self) -> Connect4AiSettings {
+        match self {
+            // Greedy and careless: takes a win it can see but never guards against one
+            Connect4Difficulty::Conscript => Connect4AiSettings {
+                depth: 1,
+                deepen: false,
+                blunder_chance: 0.3,
+                opening_book: false,
+                block_threats: false,
+                forced_wins: false,
+            },
+            Connect4Difficulty::Soldier => Connect4AiSettings {
+                depth: 3,
+                deepen: false,
+                blunder_chance: 0.1,
+                opening_book: false,
+                block_threats: true,
+                forced_wins: false,
+            },
+            Connect4Difficulty::General => Connect4AiSettings {
+                depth: 6,
+                deepen: false,
+                blunder_chance: 0.03,
+                opening_book: true,
+                block_threats: true,
+                forced_wins: true,
+            },
+            // Searches as deep as its thinking time allows
+            Connect4Difficulty::Marshal => Connect4AiSettings {
+                depth: 10,
+                deepen: true,
+                blunder_chance: 0.0,
+                opening_book: true,
+                block_threats: true,
+                forced_wins: true,
+            },
+        }
+    }
+}
+
+// Saved games from before difficulty levels played at full strength
+fn default_connect4_difficulty() -> Connect4Difficulty {
+    Connect4Difficulty::Marshal
+}
+
+// How long the computer may keep deepening its search, at levels that do
+const CONNECT4_THINK_TIME: Duration = Duration::from_millis(1200);
+
+#[derive(Clone, Serialize, Deserialize)]
 struct Connect4 {
-    // Standard Connect 4 has 7 columns and 6 rows
-    board: [[Connect4Cell; 7]; 6],
+    // Standard Connect 4 has 7 columns and 6 rows, stored as one bitboard
+    // per colour (see connect4_engine for the layout)
+    red: u64,
+    yellow: u64,
     current_player: Connect4Cell,
-    opponent: Option<serenity::UserId>,
     ai_mode: bool,
+    #[serde(default = "default_connect4_difficulty")]
+    difficulty: Connect4Difficulty,
     game_over: bool,
     winner: Option<Connect4Cell>,
-    last_move: Option<(usize, usize)>, // (row, col) of last move
-    transposition_table: Arc<Mutex<HashMap<u64, (i32, i32)>>>, // (board_hash) -> (score, depth)
+    last_move: Option<(usize, usize)>, // (row, col) of last move, row 0 at the top
 }
 
 impl Connect4 {
-    fn new(_starter: serenity::UserId, opponent: Option<serenity::UserId>) -> Self {
-        let ai_mode = opponent.is_none();
+    fn new(ai_mode: bool, difficulty: Connect4Difficulty) -> Self {
         Connect4 {
-            board: [[Connect4Cell::Empty; 7]; 6],
+            red: 0,
+            yellow: 0,
             current_player: Connect4Cell::Red, // Red always starts
-            opponent,
             ai_mode,
+            difficulty,
             game_over: false,
             winner: None,
             last_move: None,
-            transposition_table: Arc::new(Mutex::new(HashMap::new())),
         }
     }
+    
+    // The piece in a cell, with row 0 at the top as the board is drawn
+    fn cell(&self, row: usize, col: usize) -> Connect4Cell {
+        let bit = cell_bit(HEIGHT - 1 - row, col);
+        
+        if self.red & bit != 0 {
+            Connect4Cell::Red
+        } else if self.yellow & bit != 0 {
+            Connect4Cell::Yellow
+        } else {
+            Connect4Cell::Empty
+        }
+    }
+    
+    // Number of pieces in a column
+    fn height(&self, column: usize) -> usize {
+        ((self.red | self.yellow) & column_mask(column)).count_ones() as usize
+    }
 
     fn is_valid_move(&self, column: usize) -> bool {
-        if column >= 7 {
-            return false;
-        }
-        
-        // Check if the top cell in the column is empty
-        self.board[0][column] == Connect4Cell::Empty
+        column < WIDTH && self.height(column) < HEIGHT
     }
 
     fn make_move(&mut self, column: usize) -> bool {
@@ -292,23 +445,17 @@ impl Connect4 {
             return false;
         }
         
-        // Find the lowest empty cell in the selected column
-        let mut row = 5;
-        while row > 0 && self.board[row][column] != Connect4Cell::Empty {
-            row -= 1;
-        }
-        
-        // If the whole column is full, this shouldn't happen due to is_valid_move check
-        if self.board[row][column] != Connect4Cell::Empty {
-            return false;
-        }
-        
-        // Place the piece
-        self.board[row][column] = self.current_player;
-        self.last_move = Some((row, column));
+        // The piece lands on top of the column
+        let row = self.height(column);
+        let pieces = match self.current_player {
+            Connect4Cell::Red => &mut self.red,
+            _ => &mut self.yellow,
+        };
+        *pieces |= cell_bit(row, column);
+        self.last_move = Some((HEIGHT - 1 - row, column));
         
         // Check for win or draw
-        if self.check_winner() {
+        if has_four(*pieces) {
             self.game_over = true;
             self.winner = Some(self.current_player);
             return true;
@@ -329,1205 +476,865 @@ impl Connect4 {
         true
     }
     
-    fn ai_move(&mut self) -> Option<usize> {
+    fn choose_ai_move(&self) -> Option<usize> {
+        self.choose_ai_move_with(self.difficulty, &mut rand::thread_rng(), CONNECT4_THINK_TIME)
+    }
+    
+    // The move for whoever is to play, at a difficulty. Weaker levels search less deeply,
+    // skip heuristics and sometimes blunder on purpose. This can take `think_time`,
+    // so call it off the async runtime.
+    fn choose_ai_move_with(
+        &self,
+        difficulty: Connect4Difficulty,
+        rng: &mut impl Rng,
+        think_time: Duration,
+    ) -> Option<usize> {
         if self.game_over {
             return None;
         }
-
-        // Check opening book
-        if let Some(opening_move) = self.get_opening_move() {
-            if self.is_valid_move(opening_move) {
-                self.make_move(opening_move);
-                return Some(opening_move);
-            }
-        }
-
-        // First, check for immediate winning move (depth 1)
-        if let Some(winning_col) = self.find_winning_move(Connect4Cell::Yellow) {
-            self.make_move(winning_col);
-            return Some(winning_col);
-        }
-        
-        // Then, check if player has a winning move and block it (depth 1)
-        if let Some(blocking_col) = self.find_winning_move(Connect4Cell::Red) {
-            self.make_move(blocking_col);
-            return Some(blocking_col);
-        }
         
-        // Check for forced win in 2 moves (double threat)
-        if let Some(forced_win_col) = self.find_forced_win_in_two() {
-            self.make_move(forced_win_col);
-            return Some(forced_win_col);
-        }
+        let settings = difficulty.settings();
+        let position = Position::new(self.red, self.yellow);
+        let legal: Vec<usize> = (0..WIDTH).filter(|&col| self.is_valid_move(col)).collect();
         
-        // Check for trap setups (creating double threats)
-        if let Some(trap_col) = self.find_trap_setup() {
-            self.make_move(trap_col);
-            return Some(trap_col);
+        if rng.gen_bool(settings.blunder_chance) {
+            return legal.choose(rng).copied();
         }
-
-        // Use minimax with deeper search when fewer pieces are on the board
-        // and shallower search for endgame to maintain performance
-        let piece_count = self.count_pieces();
-        let max_depth = if piece_count < 10 {
-            7 // Deep search early game
-        } else if piece_count < 20 {
-            6 // Medium depth mid-game
-        } else {
-            5 // Shallower for endgame
-        };
-        
-        // Use iterative deepening for more responsive AI
-        let mut best_col = 3; // Default to center column
-        let mut best_score = i32::MIN;
         
-        // Start with depth 1 and increase gradually
-        for depth in 1..=max_depth {
-            let (col, score) = self.minimax(depth, true, i32::MIN, i32::MAX);
-            
-            // If we find a winning move, use it immediately
-            if score > 900000 {
-                best_col = col;
-                break;
-            }
-            
-            // Otherwise update our best move
-            if col >= 0 && score > best_score {
-                best_col = col;
-                best_score = score;
+        if settings.opening_book {
+            if let Some(opening_move) = self.get_opening_move() {
+                return Some(opening_move);
             }
         }
         
-        if best_col >= 0 && best_col < 7 && self.is_valid_move(best_col as usize) {
-            self.make_move(best_col as usize);
-            return Some(best_col as usize);
+        // Never miss a win
+        if let Some(&winning_col) = position.winning_columns().first() {
+            return Some(winning_col);
         }
         
-        // Fallback to simple strategy if minimax fails (shouldn't happen)
-        if self.is_valid_move(3) {
-            self.make_move(3);
-            return Some(3);
+        if settings.block_threats {
+            if let Some(&blocking_col) = position.threatened_columns().first() {
+                return Some(blocking_col);
+            }
         }
         
-        // Otherwise prefer columns closer to center
-        let column_preference = [3, 2, 4, 1, 5, 0, 6];
-        for &col in &column_preference {
-            if self.is_valid_move(col) {
-                self.make_move(col);
-                return Some(col);
+        if settings.forced_wins {
+            if let Some(&forced_win_col) = position.double_threat_columns().first() {
+                return Some(forced_win_col);
             }
         }
         
-        None
+        let deadline = settings.deepen.then(|| Instant::now() + think_time);
+        Search::new(settings.block_threats)
+            .best_move(&position, settings.depth, deadline)
+            .or_else(|| legal.first().copied())
     }
     
     // Count the total number of pieces on the board
     fn count_pieces(&self) -> usize {
-        let mut count = 0;
-        for row in 0..6 {
-            for col in 0..7 {
-                if self.board[row][col] != Connect4Cell::Empty {
-                    count += 1;
-                }
-            }
-        }
-        count
-    }
-    
-    // Find an immediate winning move for the specified player
-    fn find_winning_move(&self, player: Connect4Cell) -> Option<usize> {
-        for col in 0..7 {
-            if !self.is_valid_move(col) {
-                continue;
-            }
-            
-            // Create a board copy and make the move
-            let mut board_copy = self.clone();
-            
-            // Find the row where the piece will land
-            let mut row = 5;
-            while row > 0 && board_copy.board[row][col] != Connect4Cell::Empty {
-                row -= 1;
-            }
-            
-            // Place piece and check for win
-            board_copy.board[row][col] = player;
-            board_copy.last_move = Some((row, col));
-            
-            if board_copy.check_winner_minimax() {
-                return Some(col);
-            }
-        }
-        None
+        (self.red | self.yellow).count_ones() as usize
     }
     
-    // Find double threat situations - where we can create two winning threats in one move
-    // This is a forced win in 2 moves
-    fn find_forced_win_in_two(&self) -> Option<usize> {
-        for col in 0..7 {
-            if !self.is_valid_move(col) {
-                continue;
-            }
-            
-            // Create a board copy and make the move
-            let mut board_copy = self.clone();
-            
-            // Find the row where the piece will land
-            let mut row = 5;
-            while row > 0 && board_copy.board[row][col] != Connect4Cell::Empty {
-                row -= 1;
-            }
-            
-            // Place AI piece
-            board_copy.board[row][col] = Connect4Cell::Yellow;
-            
-            // Count how many winning moves AI would have after this move
-            let mut winning_moves = 0;
-            for next_col in 0..7 {
-                if !board_copy.is_valid_move(next_col) {
-                    continue;
-                }
-                
-                // Create another copy for checking the next move
-                let mut next_board = board_copy.clone();
-                
-                // Find the row for the next move
-                let mut next_row = 5;
-                while next_row > 0 && next_board.board[next_row][next_col] != Connect4Cell::Empty {
-                    next_row -= 1;
-                }
-                
-                // Place AI piece for the second move
-                next_board.board[next_row][next_col] = Connect4Cell::Yellow;
-                next_board.last_move = Some((next_row, next_col));
-                
-                if next_board.check_winner_minimax() {
-                    winning_moves += 1;
-                }
-            }
-            
-            // If we found at least two winning moves, this is a forced win
-            if winning_moves >= 2 {
-                return Some(col);
-            }
-        }
-        None
+    fn is_board_full(&self) -> bool {
+        self.count_pieces() == WIDTH * HEIGHT
     }
     
-    // Find trap setups - positions that lead to future winning opportunities
-    fn find_trap_setup(&self) -> Option<usize> {
-        let mut best_col = None;
-        let mut max_threats = 0;
+    fn render_board(&self) -> String {
+        let mut board_str = String::new();
         
-        for col in 0..7 {
-            if !self.is_valid_move(col) {
-                continue;
-            }
-            
-            // Create a board copy and make the move
-            let mut board_copy = self.clone();
-            
-            // Find the row where the piece will land
-            let mut row = 5;
-            while row > 0 && board_copy.board[row][col] != Connect4Cell::Empty {
-                row -= 1;
-            }
-            
-            // Place AI piece
-            board_copy.board[row][col] = Connect4Cell::Yellow;
-            
-            // Simulate opponent's possible responses and count our threats after each
-            let mut min_opponent_threats = i32::MAX;
-            let mut our_max_future_threats = 0;
-            
-            // Check each possible opponent response
-            for opp_col in 0..7 {
-                if !board_copy.is_valid_move(opp_col) {
-                    continue;
-                }
-                
-                // Create another board copy for opponent's move
-                let mut opp_board = board_copy.clone();
-                
-                // Find row for opponent's move
-                let mut opp_row = 5;
-                while opp_row > 0 && opp_board.board[opp_row][opp_col] != Connect4Cell::Empty {
-                    opp_row -= 1;
-                }
-                
-                // Place opponent's piece
-                opp_board.board[opp_row][opp_col] = Connect4Cell::Red;
-                
-                // Count threats for both players after this sequence
-                let our_threats = opp_board.count_threats(Connect4Cell::Yellow);
-                let opponent_threats = opp_board.count_threats(Connect4Cell::Red);
-                
-                // Check if opponent can win after their move
-                if opponent_threats == 0 {
-                    // Update our minimum threat count among all opponent responses
-                    if our_threats > our_max_future_threats {
-                        our_max_future_threats = our_threats;
-                    }
-                    
-                    if opponent_threats < min_opponent_threats {
-                        min_opponent_threats = opponent_threats;
-                    }
-                }
-            }
-            
-            // If we found a position with more potential threats than current best,
-            // and opponent has no immediate winning response, update best move
-            if our_max_future_threats > max_threats && min_opponent_threats == 0 {
-                max_threats = our_max_future_threats;
-                best_col = Some(col);
+        // Board rows
+        for row in 0..HEIGHT {
+            for col in 0..WIDTH {
+                board_str.push_str(&format!("{}", self.cell(row, col)));
             }
+            board_str.push('\n');
         }
         
-        best_col
+        // Column numbers at the bottom
+        board_str.push_str("1️⃣2️⃣3️⃣4️⃣5️⃣6️⃣7️⃣");
+        
+        board_str
     }
     
-    // Count how many potential winning threats a player has
-    fn count_threats(&self, player: Connect4Cell) -> i32 {
-        let mut threats = 0;
+    fn render_status(&self, player1: &serenity::User, player2: Option<&serenity::User>) -> String {
+        let red_player = format!("**{}** (🔴)", player1.name);
+        let yellow_player = if let Some(p2) = player2 {
+            format!("**{}** (🟡)", p2.name)
+        } else {
+            "The computer (🟡)".to_string()
+        };
         
-        // Check horizontal threats
-        for row in 0..6 {
-            for col in 0..4 {
-                let window = [
-                    self.board[row][col],
-                    self.board[row][col+1],
-                    self.board[row][col+2],
-                    self.board[row][col+3]
-                ];
-                
-                let player_count = window.iter().filter(|&&cell| cell == player).count();
-                let empty_count = window.iter().filter(|&&cell| cell == Connect4Cell::Empty).count();
-                
-                // A threat is three pieces in a row with an empty space
-                if player_count == 3 && empty_count == 1 {
-                    threats += 1;
-                }
-            }
-        }
+        let communist_win_messages = [
+            "has seized control of the board! The revolution succeeds!",
+            "has united the workers and achieved victory!",
+            "has spread the revolution across the board!",
+            "has established the dictatorship of the proletariat!",
+            "has proven that the collective will always triumph!",
+        ];
         
-        // Check vertical threats
-        for col in 0..7 {
-            for row in 0..3 {
-                let window = [
-                    self.board[row][col],
-                    self.board[row+1][col],
-                    self.board[row+2][col],
-                    self.board[row+3][col]
-                ];
-                
-                let player_count = window.iter().filter(|&&cell| cell == player).count();
-                let empty_count = window.iter().filter(|&&cell| cell == Connect4Cell::Empty).count();
-                
-                if player_count == 3 && empty_count == 1 {
-                    threats += 1;
-                }
-            }
-        }
+        let capitalist_messages = [
+            "has temporarily gained control of the means of production...",
+            "has shown reactionary tendencies, but the revolution will continue!",
+            "has secured a victory, but the struggle continues!",
+            "has won this battle, but not the class war!",
+            "has succeeded in this game, but history is on our side!",
+        ];
         
-        // Check diagonal threats (positive slope)
-        for row in 0..3 {
-            for col in 0..4 {
-                let window = [
-                    self.board[row][col],
-                    self.board[row+1][col+1],
-                    self.board[row+2][col+2],
-                    self.board[row+3][col+3]
-                ];
-                
-                let player_count = window.iter().filter(|&&cell| cell == player).count();
-                let empty_count = window.iter().filter(|&&cell| cell == Connect4Cell::Empty).count();
-                
-                if player_count == 3 && empty_count == 1 {
-                    threats += 1;
-                }
-            }
-        }
+        let msg_idx = rand::random::<usize>() % communist_win_messages.len();
         
-        // Check diagonal threats (negative slope)
-        for row in 3..6 {
-            for col in 0..4 {
-                let window = [
-                    self.board[row][col],
-                    self.board[row-1][col+1],
-                    self.board[row-2][col+2],
-                    self.board[row-3][col+3]
-                ];
-                
-                let player_count = window.iter().filter(|&&cell| cell == player).count();
-                let empty_count = window.iter().filter(|&&cell| cell == Connect4Cell::Empty).count();
-                
-                if player_count == 3 && empty_count == 1 {
-                    threats += 1;
+        if self.game_over {
+            if let Some(winner) = self.winner {
+                match winner {
+                    Connect4Cell::Red => format!("{} {}", red_player, communist_win_messages[msg_idx]),
+                    Connect4Cell::Yellow => {
+                        if self.ai_mode {
+                            format!("{} {}", yellow_player, capitalist_messages[msg_idx])
+                        } else {
+                            format!("{} {}", yellow_player, communist_win_messages[msg_idx])
+                        }
+                    },
+                    _ => "Unknown winner".to_string()
                 }
+            } else {
+                "The game ended in a draw! Perfect balance, as all things should be!".to_string()
+            }
+        } else {
+            match self.current_player {
+                Connect4Cell::Red => format!("It's {}'s turn", red_player),
+                Connect4Cell::Yellow => format!("It's {}'s turn", yellow_player),
+                _ => "Unknown player's turn".to_string()
             }
         }
-        
-        threats
     }
 
-    // Minimax algorithm with alpha-beta pruning
-    fn minimax(&self, depth: i32, maximizing_player: bool, mut alpha: i32, mut beta: i32) -> (i32, i32) {
-        // Terminal conditions: depth reached or game over
-        if depth == 0 || self.is_board_full() || self.check_winner_minimax() {
-            return (-1, self.evaluate_board());
-        }
-        
-        // Check transposition table
-        let board_hash = self.hash_board();
-        if let Ok(table) = self.transposition_table.lock() {
-            if let Some(&(score, stored_depth)) = table.get(&board_hash) {
-                // Only use stored value if it was computed to at least the current depth
-                if stored_depth >= depth {
-                    return (-1, score);
-                }
-            }
-        }
-        
-        // Column ordering for better alpha-beta pruning (start with center columns)
-        let column_order = [3, 2, 4, 1, 5, 0, 6];
-        let current_player = if maximizing_player { Connect4Cell::Yellow } else { Connect4Cell::Red };
-        
-        // For AI (maximizing) - higher score is better
-        // For player (minimizing) - lower score is better
-        if maximizing_player {
-            let mut best_score = i32::MIN;
-            let mut best_col = -1;
-            
-            // Try each column in order of preference
-            for &col in &column_order {
-                if self.is_valid_move(col) {
-                    // Create a board copy and make the move
-                    let mut board_copy = self.clone();
-                    
-                    // Find the row where the piece will land
-                    let mut row = 5;
-                    while row > 0 && board_copy.board[row][col] != Connect4Cell::Empty {
-                        row -= 1;
-                    }
-                    
-                    board_copy.board[row][col] = current_player;
-                    board_copy.last_move = Some((row, col));
-                    
-                    // Check for immediate win to speed up search
-                    if board_copy.check_winner_minimax() {
-                        // Update transposition table using the mutex
-                        if let Ok(mut table) = self.transposition_table.lock() {
-                            table.insert(board_hash, (1000000, depth));
-                        }
-                        return (col as i32, 1000000);
-                    }
-                    
-                    // Recursive call
-                    let (_, score) = board_copy.minimax(depth - 1, false, alpha, beta);
-                    
-                    if score > best_score {
-                        best_score = score;
-                        best_col = col as i32;
-                    }
-                    
-                    // Alpha-beta pruning
-                    alpha = alpha.max(best_score);
-                    if beta <= alpha {
-                        break; // Beta cutoff
-                    }
-                }
-            }
-            
-            // Store result in transposition table - but only at the root call
-            if depth > 1 {
-                // Safety check to avoid overflow: only insert if we have a valid best_col
-                if best_col >= 0 {
-                    // Update transposition table using the mutex
-                    if let Ok(mut table) = self.transposition_table.lock() {
-                        table.insert(board_hash, (best_score, depth));
-                    }
-                }
-            }
-            
-            (best_col, best_score)
-        } else {
-            // Similar minimizing player code with table updates
-            let mut best_score = i32::MAX;
-            let mut best_col = -1;
-            
-            // Try each column in order of preference
-            for &col in &column_order {
-                if self.is_valid_move(col) {
-                    // Create a board copy and make the move
-                    let mut board_copy = self.clone();
-                    
-                    // Find the row where the piece will land
-                    let mut row = 5;
-                    while row > 0 && board_copy.board[row][col] != Connect4Cell::Empty {
-                        row -= 1;
-                    }
-                    
-                    board_copy.board[row][col] = current_player;
-                    board_copy.last_move = Some((row, col));
-                    
-                    // Check for immediate win to speed up search
-                    if board_copy.check_winner_minimax() {
-                        // Update transposition table using the mutex
-                        if let Ok(mut table) = self.transposition_table.lock() {
-                            table.insert(board_hash, (-1000000, depth));
-                        }
-                        return (col as i32, -1000000);
-                    }
-                    
-                    // Recursive call
-                    let (_, score) = board_copy.minimax(depth - 1, true, alpha, beta);
-                    
-                    if score < best_score {
-                        best_score = score;
-                        best_col = col as i32;
-                    }
-                    
-                    // Alpha-beta pruning
-                    beta = beta.min(best_score);
-                    if beta <= alpha {
-                        break; // Alpha cutoff
-                    }
-                }
-            }
-            
-            // Store result in transposition table - but only at important depths
-            // to avoid table bloat and overflows
-            if depth > 1 {
-                // Safety check to avoid overflow: only insert if we have a valid best_col
-                if best_col >= 0 {
-                    // Update transposition table using the mutex
-                    if let Ok(mut table) = self.transposition_table.lock() {
-                        table.insert(board_hash, (best_score, depth));
-                    }
-                }
-            }
-            
-            (best_col, best_score)
+    // Opening book: take the centre, or sit beside it if it's gone
+    fn get_opening_move(&self) -> Option<usize> {
+        match self.count_pieces() {
+            0 => Some(3),
+            1 if self.cell(HEIGHT - 1, 3) == Connect4Cell::Empty => Some(3),
+            1 => Some(2),
+            _ => None,
         }
     }
-    
-    // Evaluate the board state - higher score favors AI (Yellow)
-    fn evaluate_board(&self) -> i32 {
-        // Check if game is won
-        if self.check_winner_minimax() {
-            if let Some((row, col)) = self.last_move {
-                if self.board[row][col] == Connect4Cell::Yellow {
-                    return 1000000; // AI wins
-                } else {
-                    return -1000000; // Player wins
-                }
-            }
-        }
-        
-        let mut score = 0;
-        
-        // Add score for center column control (center is strategically valuable)
-        for row in 0..6 {
-            if self.board[row][3] == Connect4Cell::Yellow {
-                score += 6; // Increased value for center control
-            } else if self.board[row][3] == Connect4Cell::Red {
-                score -= 6;
-            }
-        }
-        
-        // Score adjacent center columns with slightly less weight
-        for row in 0..6 {
-            for col in [2, 4] {
-                if self.board[row][col] == Connect4Cell::Yellow {
-                    score += 4;
-                } else if self.board[row][col] == Connect4Cell::Red {
-                    score -= 4;
-                }
-            }
-        }
-        
-        // Score horizontal windows
-        for row in 0..6 {
-            for col in 0..4 {
-                score += self.evaluate_window([
-                    self.board[row][col], 
-                    self.board[row][col+1], 
-                    self.board[row][col+2], 
-                    self.board[row][col+3]
-                ], row);
-            }
-        }
-        
-        // Score vertical windows
-        for col in 0..7 {
-            for row in 0..3 {
-                score += self.evaluate_window([
-                    self.board[row][col], 
-                    self.board[row+1][col], 
-                    self.board[row+2][col], 
-                    self.board[row+3][col]
-                ], row);
-            }
-        }
-        
-        // Score diagonal windows (positive slope)
-        for row in 0..3 {
-            for col in 0..4 {
-                score += self.evaluate_window([
-                    self.board[row][col], 
-                    self.board[row+1][col+1], 
-                    self.board[row+2][col+2], 
-                    self.board[row+3][col+3]
-                ], row);
-            }
-        }
-        
-        // Score diagonal windows (negative slope)
-        for row in 3..6 {
-            for col in 0..4 {
-                score += self.evaluate_window([
-                    self.board[row][col], 
-                    self.board[row-1][col+1], 
-                    self.board[row-2][col+2], 
-                    self.board[row-3][col+3]
-                ], row);
-            }
+}
+
+// Comrade Clicker Game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum ClickerMode {
+    // One comrade against the clock
+    Solo,
+    // Everyone who joins clicks the same grid; the first correct click takes the round
+    Race,
+}
+
+impl ClickerMode {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "solo" | "classic" => Some(ClickerMode::Solo),
+            "race" | "multiplayer" => Some(ClickerMode::Race),
+            _ => None,
         }
-        
-        // Add specialized pattern recognition for horizontal threats at bottom
-        for col in 0..4 {
-            let bottom_row = 5;
-            let window = [
-                self.board[bottom_row][col],
-                self.board[bottom_row][col+1],
-                self.board[bottom_row][col+2],
-                self.board[bottom_row][col+3]
-            ];
-            
-            // Special scoring for horizontal patterns on bottom row
-            // This addresses the issue where the AI missed the user's horizontal win
-            let _yellow_count = window.iter().filter(|&&c| c == Connect4Cell::Yellow).count();
-            let red_count = window.iter().filter(|&&c| c == Connect4Cell::Red).count();
-            let empty_count = window.iter().filter(|&&c| c == Connect4Cell::Empty).count();
-            
-            if red_count == 2 && empty_count == 2 {
-                // Dangerous potential bottom row setup by player
-                score -= 50;  // Much higher penalty
-            } else if red_count == 3 && empty_count == 1 {
-                // Critical bottom row threat
-                score -= 500; // Extremely high penalty
-            }
+    }
+}
+
+// Discord allows at most five rows of five buttons
+const CLICKER_GRID_SIZES: std::ops::RangeInclusive<usize> = 2..=5;
+const CLICKER_ROUNDS: std::ops::RangeInclusive<usize> = 1..=20;
+const CLICKER_MAX_DECOYS: usize = 5;
+const CLICKER_MAX_RACERS: usize = 10;
+
+// Time added to a solo round for clicking a decoy
+const DECOY_PENALTY: Duration = Duration::from_secs(1);
+
+// Race scores rank by rounds won, then by average winning time. Both are packed
+// into one lower-is-better number: each round won is worth more than any average.
+const RACE_ROUND_WEIGHT: f64 = 1_000_000.0;
+pub(super) const CLICKER_RACE_RANKING: &str = "Ranked by rounds won, then by fastest average time";
+
+fn race_score(rounds_won: usize, average_ms: f64) -> f64 {
+    average_ms.min(RACE_ROUND_WEIGHT - 1.0) - rounds_won as f64 * RACE_ROUND_WEIGHT
+}
+
+// Shows a race score as its rounds won and average time. Solo scores, and races
+// recorded before rounds were counted, are plain times.
+pub(super) fn format_race_score(score: f64) -> String {
+    if score >= 0.0 {
+        return format!("{:.0}ms", score);
+    }
+    let rounds_won = (-score / RACE_ROUND_WEIGHT).ceil();
+    let average_ms = score + rounds_won * RACE_ROUND_WEIGHT;
+    let plural = if rounds_won == 1.0 { "" } else { "s" };
+    format!("{:.0} round{} won, {:.0}ms average", rounds_won, plural, average_ms)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Racer {
+    user: serenity::User,
+    // Rounds won, less one for every decoy clicked
+    points: i32,
+    // Reaction times of the rounds this comrade won
+    win_times: Vec<Duration>,
+}
+
+// A click that didn't win the round, shown until the next one
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum ClickerSlip {
+    WrongCell,
+    // A decoy, clicked by the racer at this index
+    Decoy(usize),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct ComradeClicker {
+    mode: ClickerMode,
+    grid_size: usize,
+    decoy_count: usize,
+    active_cell: Option<usize>,
+    decoys: Vec<usize>,
+    round: usize,
+    total_rounds: usize,
+    // Time spent on the current round so far, including any wrong clicks
+    round_time: Duration,
+    // Everyone playing, the comrade who started the game first
+    racers: Vec<Racer>,
+    // Index of the racer who won each round
+    round_winners: Vec<usize>,
+    started: bool,
+    game_over: bool,
+    slip: Option<ClickerSlip>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ClickerMove {
+    Start { by: serenity::UserId },
+    // Enter a race before it starts
+    Join(serenity::User),
+    // `reaction` is how long the board was shown before the click
+    Click { by: serenity::UserId, position: usize, reaction: Duration },
+}
+
+impl ComradeClicker {
+    fn new(mode: ClickerMode, host: &serenity::User, grid_size: usize, total_rounds: usize, decoy_count: usize) -> Self {
+        ComradeClicker {
+            mode,
+            grid_size,
+            decoy_count,
+            active_cell: None,
+            decoys: Vec::new(),
+            round: 0,
+            total_rounds,
+            round_time: Duration::ZERO,
+            racers: vec![Racer { user: host.clone(), points: 0, win_times: Vec::with_capacity(total_rounds) }],
+            round_winners: Vec::new(),
+            started: false,
+            game_over: false,
+            slip: None,
         }
-        
-        // Score threats (3-in-a-row with open ends)
-        score += self.count_threats(Connect4Cell::Yellow) * 25;
-        score -= self.count_threats(Connect4Cell::Red) * 30; // Weight opponent threats higher
-        
-        // Add special score for stair-step patterns that lead to forced wins
-        score += self.detect_stairstep_patterns();
-        
-        score
     }
     
-    // Evaluate a window of 4 cells
-    fn evaluate_window(&self, window: [Connect4Cell; 4], row: usize) -> i32 {
-        let mut score = 0;
-        
-        let yellow_count = window.iter().filter(|&&c| c == Connect4Cell::Yellow).count();
-        let red_count = window.iter().filter(|&&c| c == Connect4Cell::Red).count();
-        let empty_count = window.iter().filter(|&&c| c == Connect4Cell::Empty).count();
-        
-        // If both players have pieces in the window, it can't be completed by either
-        if yellow_count > 0 && red_count > 0 {
-            return 0;
-        }
-        
-        // Score AI (Yellow) possibilities with exponential scoring
-        if yellow_count > 0 && red_count == 0 {
-            if yellow_count == 4 {
-                score += 1000; // Should be caught by win check, but just in case
-            } else if yellow_count == 3 && empty_count == 1 {
-                score += 100; // Three in a row is very valuable
-            } else if yellow_count == 2 && empty_count == 2 {
-                score += 10;  // Two in a row with space
-            } else if yellow_count == 1 && empty_count == 3 {
-                score += 1;   // One piece
-            }
-        }
-        
-        // Score opponent (Red) possibilities with exponential scoring
-        if red_count > 0 && yellow_count == 0 {
-            if red_count == 4 {
-                score -= 1000; // Should be caught by win check
-            } else if red_count == 3 && empty_count == 1 {
-                score -= 120; // Block opponent's three in a row aggressively
-            } else if red_count == 2 && empty_count == 2 {
-                score -= 12;  // Preemptively block two in a row
-            } else if red_count == 1 && empty_count == 3 {
-                score -= 1;   // One piece
-            }
-        }
+    // Decoys fit on any grid as long as the real square still has room to move
+    fn max_decoys(grid_size: usize) -> usize {
+        CLICKER_MAX_DECOYS.min(grid_size * grid_size - 2)
+    }
+    
+    fn start_round(&mut self) {
+        let total_cells = self.grid_size * self.grid_size;
+        let mut cells: Vec<usize> = (0..total_cells).collect();
+        cells.shuffle(&mut rand::thread_rng());
         
-        // Apply row-based multiplier to prioritize bottom rows
-        // Bottom row is critical in Connect4
-        if row == 5 {  // Bottom row
-            score *= 3;  // Triple the importance
-        } else if row == 4 {  // Second from bottom
-            score *= 2;  // Double the importance
+        self.active_cell = Some(cells[0]);
+        self.decoys = cells[1..=self.decoy_count].to_vec();
+        self.round_time = Duration::ZERO;
+    }
+    
+    fn join(&mut self, user: serenity::User) -> bool {
+        if self.mode != ClickerMode::Race
+            || self.started
+            || self.racers.len() >= CLICKER_MAX_RACERS
+            || self.racers.iter().any(|r| r.user.id == user.id)
+        {
+            return false;
         }
         
-        score
+        self.racers.push(Racer { user, points: 0, win_times: Vec::with_capacity(self.total_rounds) });
+        true
     }
     
-    // Check for a winner specifically for minimax, without modifying game state
-    fn check_winner_minimax(&self) -> bool {
-        // No need to check if no moves have been made
-        if self.last_move.is_none() {
+    fn handle_click(&mut self, by: serenity::UserId, position: usize, reaction: Duration) -> bool {
+        if self.game_over || self.active_cell.is_none() || position >= self.grid_size * self.grid_size {
             return false;
         }
         
-        let (last_row, last_col) = self.last_move.unwrap();
-        let piece = self.board[last_row][last_col];
+        let racer = match self.racers.iter().position(|r| r.user.id == by) {
+            Some(racer) => racer,
+            None => return false,
+        };
         
-        if piece == Connect4Cell::Empty {
+        // A race ignores plain misses; only decoys cost anything
+        let hit_decoy = self.decoys.contains(&position);
+        if self.mode == ClickerMode::Race && !hit_decoy && Some(position) != self.active_cell {
             return false;
         }
         
-        // Check horizontal
-        let mut count = 0;
-        for col in 0..7 {
-            if self.board[last_row][col] == piece {
-                count += 1;
-                if count >= 4 {
-                    return true;
-                }
-            } else {
-                count = 0;
-            }
-        }
+        // The clock keeps running through wrong clicks
+        self.round_time += reaction;
         
-        // Check vertical
-        count = 0;
-        for row in 0..6 {
-            if self.board[row][last_col] == piece {
-                count += 1;
-                if count >= 4 {
-                    return true;
-                }
-            } else {
-                count = 0;
+        if hit_decoy {
+            match self.mode {
+                ClickerMode::Solo => self.round_time += DECOY_PENALTY,
+                ClickerMode::Race => self.racers[racer].points -= 1,
             }
+            self.slip = Some(ClickerSlip::Decoy(racer));
+            return true;
         }
         
-        // Check diagonal (up-right)
-        count = 0;
-        let mut row = last_row as i32;
-        let mut col = last_col as i32;
-        
-        // Move to bottom-left of the diagonal
-        while row < 5 && col > 0 {
-            row += 1;
-            col -= 1;
-        }
-        
-        // Check the whole diagonal
-        while row >= 0 && col < 7 {
-            if row >= 0 && row < 6 && col >= 0 && col < 7 &&
-               self.board[row as usize][col as usize] == piece {
-                count += 1;
-                if count >= 4 {
-                    return true;
-                }
-            } else {
-                count = 0;
-            }
-            row -= 1;
-            col += 1;
+        if Some(position) != self.active_cell {
+            self.slip = Some(ClickerSlip::WrongCell);
+            return true;
         }
         
-        // Check diagonal (up-left)
-        count = 0;
-        row = last_row as i32;
-        col = last_col as i32;
+        // Correct cell clicked
+        self.racers[racer].points += 1;
+        self.racers[racer].win_times.push(self.round_time);
+        self.round_winners.push(racer);
+        self.slip = None;
+        self.round += 1;
         
-        // Move to bottom-right of the diagonal
-        while row < 5 && col < 6 {
-            row += 1;
-            col += 1;
+        if self.round >= self.total_rounds {
+            self.game_over = true;
+            self.active_cell = None;
+            self.decoys.clear();
+        } else {
+            self.start_round();
         }
         
-        // Check the whole diagonal
-        while row >= 0 && col >= 0 {
-            if row >= 0 && row < 6 && col >= 0 && col < 7 &&
-               self.board[row as usize][col as usize] == piece {
-                count += 1;
-                if count >= 4 {
-                    return true;
-                }
-            } else {
-                count = 0;
-            }
-            row -= 1;
-            col -= 1;
+        true
+    }
+    
+    fn get_average_time(racer: &Racer) -> Option<Duration> {
+        if racer.win_times.is_empty() {
+            return None;
         }
         
-        false
+        let total = racer.win_times.iter().sum::<Duration>();
+        Some(total / racer.win_times.len() as u32)
     }
     
-    fn check_winner(&self) -> bool {
-        // No need to check if no moves have been made
-        if self.last_move.is_none() {
-            return false;
+    fn get_best_time(&self) -> Option<Duration> {
+        self.racers[0].win_times.iter().min().copied()
+    }
+    
+    fn get_score(&self) -> Option<f64> {
+        // Score is the average reaction time in milliseconds
+        Self::get_average_time(&self.racers[0]).map(|duration| {
+            duration.as_secs_f64() * 1000.0
+        })
+    }
+    
+    // Racers by points, then by how quickly they won their rounds
+    fn standings(&self) -> Vec<&Racer> {
+        let mut standings: Vec<&Racer> = self.racers.iter().collect();
+        standings.sort_by_key(|r| (std::cmp::Reverse(r.points), Self::get_average_time(r).unwrap_or(Duration::MAX)));
+        standings
+    }
+    
+    fn render_standings(&self) -> String {
+        self.standings()
+            .iter()
+            .enumerate()
+            .map(|(i, racer)| format!("{}. **{}** - {} points", i + 1, racer.user.name, racer.points))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+    
+    fn render_status(&self) -> String {
+        if self.game_over {
+            if self.mode == ClickerMode::Race {
+                return format!(
+                    "**Race Complete!**\n\
+                    {}\n\n\
+                    **{}** is the most productive worker in the collective!",
+                    self.render_standings(), self.standings()[0].user.name
+                );
+            }
+            
+            let avg_time = Self::get_average_time(&self.racers[0])
+                .map_or_else(|| "N/A".to_string(), |t| format!("{:.2}ms", t.as_secs_f64() * 1000.0));
+            
+            let best_time = self.get_best_time()
+                .map_or_else(|| "N/A".to_string(), |t| format!("{:.2}ms", t.as_secs_f64() * 1000.0));
+            
+            format!(
+                "**Game Complete!**\n\
+                Rounds: {}/{}\n\
+                Average time: {}\n\
+                Best time: {}\n\
+                The Motherland thanks you for your swift labor, comrade!",
+                self.round, self.total_rounds, avg_time, best_time
+            )
+        } else if self.active_cell.is_none() {
+            format!(
+                "**Preparing Round {}**\n\
+                Get ready to click the red square when it appears!",
+                self.round + 1
+            )
+        } else if self.mode == ClickerMode::Race {
+            let last_winner = self.round_winners.last()
+                .map(|&racer| format!("**{}** took round {}!\n", self.racers[racer].user.name, self.round))
+                .unwrap_or_default();
+            
+            format!(
+                "{}**Round {}/{}**\n\
+                Click the red circle before your comrades do!\n\n\
+                {}",
+                last_winner, self.round + 1, self.total_rounds, self.render_standings()
+            )
+        } else {
+            format!(
+                "**Round {}/{}**\n\
+                Click the red square as quickly as possible!",
+                self.round + 1, self.total_rounds
+            )
         }
-        
-        let (last_row, last_col) = self.last_move.unwrap();
-        let piece = self.board[last_row][last_col];
-        
-        if piece == Connect4Cell::Empty {
-            return false;
+    }
+    
+    fn render_slip(&self) -> String {
+        match (self.slip, self.mode) {
+            (Some(ClickerSlip::WrongCell), _) => "That was the wrong cell, comrade! Pay attention!\n\n".to_string(),
+            (Some(ClickerSlip::Decoy(_)), ClickerMode::Solo) => format!(
+                "That was a decoy, comrade! The Party adds {:.1} seconds to your time.\n\n",
+                DECOY_PENALTY.as_secs_f64()
+            ),
+            (Some(ClickerSlip::Decoy(racer)), ClickerMode::Race) => format!(
+                "**{}** fell for a decoy and loses a point!\n\n",
+                self.racers[racer].user.name
+            ),
+            (None, _) => String::new(),
         }
-        
-        // Check horizontal
-        let mut count = 0;
-        for col in 0..7 {
-            if self.board[last_row][col] == piece {
-                count += 1;
-                if count >= 4 {
-                    return true;
-                }
-            } else {
-                count = 0;
-            }
+    }
+}
+
+/// Shows available games
+#[poise::command(slash_command, prefix_command)]
+pub async fn game(
+    ctx: crate::Context<'_>,
+) -> Result<(), CommandError> {
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title("☭ State-Approved Entertainment ☭")
+             .description("The Ministry of Leisure presents approved games for your recreational period.")
+             .color(serenity::Color::RED)
+             .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/a/a9/Soviet_Union_state_emblem.svg/240px-Soviet_Union_state_emblem.svg.png")
+             .field(
+                "Classic Games", 
+                "• `/tictactoe [@user] [wager] [difficulty]` - Tactical Grid Competition\n\
+                 • `/connect4 [@user] [wager] [difficulty]` - People's Revolution Edition\n\
+                 • `/battleship [@user] [wager] [difficulty]` - Naval Command, with fleets deployed in secret\n\
+                 • `/chess @user [wager]` - People's Chess, played live or by correspondence\n\
+                 • `/checkers @user [wager]` - Proletarian Checkers, played live or by correspondence", 
+                false
+             )
+             .field(
+                "Skill Games", 
+                "• `/clicker [mode] [grid_size] [rounds] [decoys]` - Test your reaction time in Comrade Clicker, alone or in a race\n\
+                 • `/kremlin_secrets` - Word Association Challenge", 
+                false
+             )
+             .field(
+                "Language Games", 
+                "• `/soviet_hangman` - Ideological Rehabilitation Challenge\n\
+                 • `/trivia [mode] [pack] [questions]` - Ministry of Education Examination", 
+                false
+             )
+             .field(
+                "Economic Games", 
+                "• `/blackjack <bet>` - State-Sanctioned Resource Allocation Game", 
+                false
+             )
+             .footer(|f| f.text("Recreation increases worker productivity by 27%. Glory to the collective!"))
+        })
+    }).await?;
+    
+    Ok(())
+}
+
+
+impl Game for TicTacToe {
+    type Move = usize;
+    
+    const KEY: &'static str = "tictactoe";
+    const NAME: &'static str = "tic-tac-toe";
+    
+    fn computer_win_reward_share(&self) -> f64 {
+        self.difficulty.reward_share()
+    }
+    
+    fn turn(&self) -> Turn {
+        match self.current_player {
+            _ if self.game_over => Turn::Over,
+            Cell::X => Turn::Player(0),
+            _ if self.ai_mode => Turn::Computer,
+            _ => Turn::Player(1),
         }
-        
-        // Check vertical
-        count = 0;
-        for row in 0..6 {
-            if self.board[row][last_col] == piece {
-                count += 1;
-                if count >= 4 {
-                    return true;
-                }
-            } else {
-                count = 0;
-            }
+    }
+    
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.game_over {
+            return Vec::new();
         }
-        
-        // Check diagonal (up-right)
-        count = 0;
-        let mut row = last_row as i32;
-        let mut col = last_col as i32;
-        
-        // Move to bottom-left of the diagonal
-        while row < 5 && col > 0 {
-            row += 1;
-            col -= 1;
+        (0..9).filter(|&i| self.is_valid_move(i)).collect()
+    }
+    
+    fn apply_move(&mut self, position: usize) -> bool {
+        self.make_move(position)
+    }
+    
+    fn is_terminal(&self) -> bool {
+        self.game_over
+    }
+    
+    fn outcome(&self) -> Option<Outcome> {
+        match self.winner {
+            _ if !self.game_over => None,
+            Some(Cell::X) => Some(Outcome::Win(0)),
+            Some(Cell::O) if self.ai_mode => Some(Outcome::ComputerWin),
+            Some(Cell::O) => Some(Outcome::Win(1)),
+            _ => Some(Outcome::Draw),
         }
+    }
+    
+    fn computer_move(&self) -> Option<usize> {
+        self.choose_ai_move()
+    }
+    
+    fn parse_move(&self, custom_id: &str, _presser: &serenity::User, _think_time: Duration) -> Option<usize> {
+        custom_id.strip_prefix("button_").and_then(|s| s.parse().ok())
+    }
+    
+    fn render(&self, players: &[serenity::User]) -> String {
+        let mut content = format!("**☭ Communist Tic-Tac-Toe Game ☭**\n\n{}\n\n", self.render_board());
         
-        // Check the whole diagonal
-        while row >= 0 && col < 7 {
-            if row >= 0 && row < 6 && col >= 0 && col < 7 &&
-               self.board[row as usize][col as usize] == piece {
-                count += 1;
-                if count >= 4 {
-                    return true;
-                }
-            } else {
-                count = 0;
-            }
-            row -= 1;
-            col += 1;
+        if self.ai_mode {
+            content.push_str(&format!("*Computer difficulty: {}*\n", self.difficulty.name()));
         }
         
-        // Check diagonal (up-left)
-        count = 0;
-        row = last_row as i32;
-        col = last_col as i32;
-        
-        // Move to bottom-right of the diagonal
-        while row < 5 && col < 6 {
-            row += 1;
-            col += 1;
+        // Point out the computer's reply
+        if let Some(position) = self.last_move.filter(|&p| self.ai_mode && self.board[p] == Cell::O) {
+            content.push_str(&format!("Computer played position {}.\n", position + 1));
         }
+        content.push_str(&self.render_status(&players[0], players.get(1)));
         
-        // Check the whole diagonal
-        while row >= 0 && col >= 0 {
-            if row >= 0 && row < 6 && col >= 0 && col < 7 &&
-               self.board[row as usize][col as usize] == piece {
-                count += 1;
-                if count >= 4 {
-                    return true;
-                }
-            } else {
-                count = 0;
-            }
-            row -= 1;
-            col -= 1;
+        if self.game_over {
+            let final_message = match self.winner {
+                Some(Cell::X) => "The glory of the X workers prevails!",
+                Some(Cell::O) => "The triumph of the O collective is complete!",
+                _ => "A fair draw - the means of production have been equally distributed!",
+            };
+            content.push_str(&format!("\n\n{}", final_message));
         }
         
-        false
+        content
     }
     
-    fn is_board_full(&self) -> bool {
-        // Check if the top row is full
-        self.board[0].iter().all(|&cell| cell != Connect4Cell::Empty)
+    fn render_abandoned(&self, _players: &[serenity::User]) -> String {
+        format!(
+            "**☭ Communist Tic-Tac-Toe Game ☭**\n\n{}\n\nGame abandoned due to inactivity!",
+            self.render_board()
+        )
     }
     
-    fn render_board(&self) -> String {
-        let mut board_str = String::new();
-        
-        // Board rows
-        for row in 0..6 {
-            for col in 0..7 {
-                board_str.push_str(&format!("{}", self.board[row][col]));
-            }
-            board_str.push('\n');
+    fn components<'a>(&self, c: &'a mut serenity::CreateComponents) -> &'a mut serenity::CreateComponents {
+        create_tictactoe_buttons(c, self)
+    }
+    
+    fn move_timeout(&self) -> Duration {
+        Duration::from_secs(300)
+    }
+}
+
+impl Game for Connect4 {
+    type Move = usize;
+    
+    const KEY: &'static str = "connect4";
+    const NAME: &'static str = "Connect 4";
+    
+    fn computer_win_reward_share(&self) -> f64 {
+        self.difficulty.reward_share()
+    }
+    
+    fn turn(&self) -> Turn {
+        match self.current_player {
+            _ if self.game_over => Turn::Over,
+            Connect4Cell::Red => Turn::Player(0),
+            _ if self.ai_mode => Turn::Computer,
+            _ => Turn::Player(1),
         }
-        
-        // Column numbers at the bottom
-        board_str.push_str("1️⃣2️⃣3️⃣4️⃣5️⃣6️⃣7️⃣");
-        
-        board_str
     }
     
-    fn render_status(&self, player1: &serenity::User, player2: Option<&serenity::User>) -> String {
-        let red_player = format!("**{}** (🔴)", player1.name);
-        let yellow_player = if let Some(p2) = player2 {
-            format!("**{}** (🟡)", p2.name)
-        } else {
-            "The computer (🟡)".to_string()
-        };
-        
-        let communist_win_messages = [
-            "has seized control of the board! The revolution succeeds!",
-            "has united the workers and achieved victory!",
-            "has spread the revolution across the board!",
-            "has established the dictatorship of the proletariat!",
-            "has proven that the collective will always triumph!",
-        ];
-        
-        let capitalist_messages = [
-            "has temporarily gained control of the means of production...",
-            "has shown reactionary tendencies, but the revolution will continue!",
-            "has secured a victory, but the struggle continues!",
-            "has won this battle, but not the class war!",
-            "has succeeded in this game, but history is on our side!",
-        ];
-        
-        let msg_idx = rand::random::<usize>() % communist_win_messages.len();
-        
+    fn legal_moves(&self) -> Vec<usize> {
         if self.game_over {
-            if let Some(winner) = self.winner {
-                match winner {
-                    Connect4Cell::Red => format!("{} {}", red_player, communist_win_messages[msg_idx]),
-                    Connect4Cell::Yellow => {
-                        if self.ai_mode {
-                            format!("{} {}", yellow_player, capitalist_messages[msg_idx])
-                        } else {
-                            format!("{} {}", yellow_player, communist_win_messages[msg_idx])
-                        }
-                    },
-                    _ => "Unknown winner".to_string()
-                }
-            } else {
-                "The game ended in a draw! Perfect balance, as all things should be!".to_string()
-            }
-        } else {
-            match self.current_player {
-                Connect4Cell::Red => format!("It's {}'s turn", red_player),
-                Connect4Cell::Yellow => format!("It's {}'s turn", yellow_player),
-                _ => "Unknown player's turn".to_string()
-            }
+            return Vec::new();
         }
+        (0..7).filter(|&col| self.is_valid_move(col)).collect()
     }
-
-    // Fix the hash_board function to avoid overflow
-    fn hash_board(&self) -> u64 {
-        let mut hash: u64 = 0;
-        
-        // Use Zobrist hashing approach - XOR operations don't overflow
-        for row in 0..6 {
-            for col in 0..7 {
-                let cell_value = match self.board[row][col] {
-                    Connect4Cell::Empty => 0u64,
-                    Connect4Cell::Red => 1u64,
-                    Connect4Cell::Yellow => 2u64,
-                };
-                
-                if cell_value > 0 {
-                    // Simple hash that won't overflow - use position and value to create unique bits
-                    // (row * 7 + col) gives unique position index (0-41)
-                    // cell_value gives piece type (1-2)
-                    // Shift by position to get unique bit patterns
-                    let position_hash = ((row * 7 + col) as u64) << 1;
-                    let piece_hash = cell_value;
-                    let cell_hash = position_hash ^ piece_hash;
-                    
-                    // XOR doesn't overflow
-                    hash ^= cell_hash;
-                    
-                    // Add a second mixing component based on position to improve hash distribution
-                    hash ^= ((row * 13 + col * 17) as u64) * cell_value;
-                }
-            }
+    
+    fn apply_move(&mut self, column: usize) -> bool {
+        self.make_move(column)
+    }
+    
+    fn is_terminal(&self) -> bool {
+        self.game_over
+    }
+    
+    fn outcome(&self) -> Option<Outcome> {
+        match self.winner {
+            _ if !self.game_over => None,
+            Some(Connect4Cell::Red) => Some(Outcome::Win(0)),
+            Some(Connect4Cell::Yellow) if self.ai_mode => Some(Outcome::ComputerWin),
+            Some(Connect4Cell::Yellow) => Some(Outcome::Win(1)),
+            _ => Some(Outcome::Draw),
         }
-        
-        // Ensure non-zero hash (extremely unlikely but just in case)
-        if hash == 0 {
-            hash = 1;
+    }
+    
+    fn computer_move(&self) -> Option<usize> {
+        self.choose_ai_move()
+    }
+    
+    fn parse_move(&self, custom_id: &str, _presser: &serenity::User, _think_time: Duration) -> Option<usize> {
+        custom_id.strip_prefix("col_").and_then(|s| s.parse().ok())
+    }
+    
+    fn render(&self, players: &[serenity::User]) -> String {
+        let mut content = "**☭ Connect 4: People's Revolution Edition ☭**\n".to_string();
+        if self.ai_mode {
+            content.push_str(&format!("*Computer rank: {}*\n", self.difficulty.name()));
         }
+        content.push_str(&format!("\n{}\n\n", self.render_board()));
         
-        hash
-    }
-
-    // Add new function to detect stair-step patterns
-    fn detect_stairstep_patterns(&self) -> i32 {
-        let mut score = 0;
-        
-        // Check for yellow (AI) stair patterns that lead to forced wins
-        for col in 0..4 {
-            for row in 2..6 {
-                // Check pattern: yellow piece, empty above, yellow diagonally up
-                if row >= 3 && col <= 5 &&
-                   self.board[row][col] == Connect4Cell::Yellow &&
-                   self.board[row-1][col] == Connect4Cell::Empty &&
-                   self.board[row-1][col+1] == Connect4Cell::Yellow {
-                    score += 25;
-                }
-                
-                // Check another common forced win pattern
-                if row >= 3 && col <= 4 &&
-                   self.board[row][col] == Connect4Cell::Yellow &&
-                   self.board[row-1][col+1] == Connect4Cell::Yellow &&
-                   self.board[row-2][col+2] == Connect4Cell::Empty &&
-                   self.board[row-1][col+2] == Connect4Cell::Empty {
-                    score += 40;
-                }
-            }
+        // Point out the computer's reply
+        if let Some((_, column)) = self.last_move.filter(|&(row, col)| self.ai_mode && self.cell(row, col) == Connect4Cell::Yellow) {
+            content.push_str(&format!("Computer played in column {}.\n", column + 1));
         }
+        content.push_str(&self.render_status(&players[0], players.get(1)));
         
-        // Check for and defend against red (player) stair patterns
-        for col in 0..4 {
-            for row in 2..6 {
-                // Check pattern: red piece, empty above, red diagonally up
-                if row >= 3 && col <= 5 &&
-                   self.board[row][col] == Connect4Cell::Red &&
-                   self.board[row-1][col] == Connect4Cell::Empty &&
-                   self.board[row-1][col+1] == Connect4Cell::Red {
-                    score -= 30;
-                }
-                
-                // Check another common forced win pattern for opponent
-                if row >= 3 && col <= 4 &&
-                   self.board[row][col] == Connect4Cell::Red &&
-                   self.board[row-1][col+1] == Connect4Cell::Red &&
-                   self.board[row-2][col+2] == Connect4Cell::Empty &&
-                   self.board[row-1][col+2] == Connect4Cell::Empty {
-                    score -= 50;
-                }
-            }
+        if self.game_over {
+            let final_message = match self.winner {
+                Some(Connect4Cell::Red) => "The Red Revolution has prevailed! The workers control the means of production!",
+                Some(Connect4Cell::Yellow) if self.ai_mode => {
+                    "The capitalist AI has temporarily gained control... but history is on our side!"
+                },
+                Some(Connect4Cell::Yellow) => "The Yellow Faction has seized victory! All hail our new revolutionary leader!",
+                _ => "A perfect draw - cooperation and equality have prevailed!",
+            };
+            content.push_str(&format!("\n\n{}", final_message));
         }
         
-        score
+        content
     }
+    
+    fn render_abandoned(&self, _players: &[serenity::User]) -> String {
+        format!(
+            "**☭ Connect 4: People's Revolution Edition ☭**\n\n{}\n\nGame abandoned due to inactivity! The people demand action!",
+            self.render_board()
+        )
+    }
+    
+    fn components<'a>(&self, c: &'a mut serenity::CreateComponents) -> &'a mut serenity::CreateComponents {
+        create_connect4_buttons(c, self)
+    }
+    
+    fn move_timeout(&self) -> Duration {
+        Duration::from_secs(300)
+    }
+}
 
-    // Add an opening book for the first few moves
-    fn get_opening_move(&self) -> Option<usize> {
-        let piece_count = self.count_pieces();
-        
-        // Only use opening book for the first few moves
-        if piece_count > 4 {
-            return None;
+impl Game for ComradeClicker {
+    type Move = ClickerMove;
+    
+    const KEY: &'static str = "clicker";
+    const NAME: &'static str = "Comrade Clicker";
+    // Reaction times mean nothing across a restart
+    const RESUMABLE: bool = false;
+    
+    fn turn(&self) -> Turn {
+        match self.mode {
+            _ if self.game_over => Turn::Over,
+            ClickerMode::Solo => Turn::Player(0),
+            ClickerMode::Race => Turn::Open,
         }
-        
-        // First move as AI (piece_count = 1, player just made first move)
-        if piece_count == 1 {
-            // If player played in center, play adjacent to center
-            if self.board[5][3] == Connect4Cell::Red {
-                return Some(2); // Play column 2 (adjacent to center)
-            }
-            
-            // If player played on sides, play center
-            return Some(3); // Play center column
-        }
-        
-        // Second move as AI (piece_count = 3, player made 2 moves, AI made 1)
-        if piece_count == 3 {
-            // If we control center, develop adjacent positions
-            if self.board[5][3] == Connect4Cell::Yellow {
-                // Check where player's pieces are
-                if self.board[5][0] == Connect4Cell::Red || self.board[5][1] == Connect4Cell::Red {
-                    return Some(2); // Play defensive on left side
-                } else if self.board[5][5] == Connect4Cell::Red || self.board[5][6] == Connect4Cell::Red {
-                    return Some(4); // Play defensive on right side
-                }
-                return Some(4); // Default to right-center development
-            }
-            
-            // If we don't control center, try to create dual threats
-            if self.board[5][2] == Connect4Cell::Yellow {
-                if self.is_valid_move(4) {
-                    return Some(4);
-                }
-            } else if self.board[5][4] == Connect4Cell::Yellow {
-                if self.is_valid_move(2) {
-                    return Some(2);
-                }
-            }
+    }
+    
+    fn legal_moves(&self) -> Vec<ClickerMove> {
+        let by = self.racers[0].user.id;
+        if !self.started {
+            return vec![ClickerMove::Start { by }];
         }
-        
-        None
+        if self.game_over {
+            return Vec::new();
+        }
+        (0..self.grid_size * self.grid_size)
+            .map(|position| ClickerMove::Click { by, position, reaction: Duration::ZERO })
+            .collect()
     }
-}
-
-// Comrade Clicker Game
-struct ComradeClicker {
-    grid_size: usize,
-    active_cell: Option<usize>,
-    round: usize,
-    total_rounds: usize,
-    start_time: Instant,
-    click_times: Vec<Duration>,
-    game_over: bool,
-}
-
-impl ComradeClicker {
-    fn new(grid_size: usize, total_rounds: usize) -> Self {
-        ComradeClicker {
-            grid_size,
-            active_cell: None,
-            round: 0,
-            total_rounds,
-            start_time: Instant::now(),
-            click_times: Vec::with_capacity(total_rounds),
-            game_over: false,
+    
+    fn apply_move(&mut self, mv: ClickerMove) -> bool {
+        match mv {
+            // Only the comrade who called the game may start it
+            ClickerMove::Start { by } if !self.started && by == self.racers[0].user.id => {
+                self.started = true;
+                self.start_round();
+                true
+            },
+            ClickerMove::Start { .. } => false,
+            ClickerMove::Join(user) => self.join(user),
+            ClickerMove::Click { by, position, reaction } => self.handle_click(by, position, reaction),
         }
     }
     
-    fn start_round(&mut self) {
-        let total_cells = self.grid_size * self.grid_size;
-        let mut rng = rand::thread_rng();
-        self.active_cell = Some(rng.gen_range(0..total_cells));
-        self.start_time = Instant::now();
+    fn is_terminal(&self) -> bool {
+        self.game_over
     }
     
-    fn handle_click(&mut self, position: usize) -> bool {
-        if self.game_over || self.active_cell.is_none() {
-            return false;
+    fn outcome(&self) -> Option<Outcome> {
+        match self.mode {
+            _ if !self.game_over => None,
+            ClickerMode::Solo => self.get_score().map(Outcome::Score),
+            ClickerMode::Race => Some(Outcome::Raced),
         }
-        
-        if Some(position) == self.active_cell {
-            // Correct cell clicked
-            let click_time = self.start_time.elapsed();
-            self.click_times.push(click_time);
-            self.round += 1;
-            
-            if self.round >= self.total_rounds {
-                self.game_over = true;
-                self.active_cell = None;
-            } else {
-                self.start_round();
-            }
-            
-            return true;
+    }
+    
+    fn parse_move(&self, custom_id: &str, presser: &serenity::User, think_time: Duration) -> Option<ClickerMove> {
+        match custom_id {
+            "start_game" => return Some(ClickerMove::Start { by: presser.id }),
+            "join_race" => return Some(ClickerMove::Join(presser.clone())),
+            _ => {},
         }
         
-        // Wrong cell clicked
-        false
+        custom_id
+            .strip_prefix("cell_")
+            .and_then(|s| s.parse().ok())
+            .map(|position| ClickerMove::Click { by: presser.id, position, reaction: think_time })
     }
     
-    fn get_average_time(&self) -> Option<Duration> {
-        if self.click_times.is_empty() {
-            return None;
+    fn render(&self, players: &[serenity::User]) -> String {
+        let decoy_rule = if self.decoy_count > 0 {
+            "• Beware the orange decoys (🟠) - clicking one will cost you\n"
+        } else {
+            ""
+        };
+        
+        if !self.started && self.mode == ClickerMode::Race {
+            let racers: Vec<&str> = self.racers.iter().map(|r| r.user.name.as_str()).collect();
+            return format!(
+                "**☭ Comrade Clicker: Race ☭**\n\n\
+                Comrade {} calls the workers to compete! Press Join to enter, then {} starts the race.\n\n\
+                Rules:\n\
+                • The first comrade to click the red circle (🔴) wins the round\n\
+                {}\
+                • Most rounds won after {} rounds takes the glory\n\n\
+                Racers ({}/{}): {}",
+                players[0].name, players[0].name, decoy_rule, self.total_rounds,
+                racers.len(), CLICKER_MAX_RACERS, racers.join(", ")
+            );
+        }
+        
+        if !self.started {
+            return format!(
+                "**☭ Comrade Clicker ☭**\n\n\
+                Welcome, Comrade {}! Your labor efficiency will be tested.\n\n\
+                Rules:\n\
+                • Click on the red square (🟥) as quickly as possible\n\
+                {}\
+                • Complete {} rounds to get your score\n\
+                • Your score is your average reaction time (lower is better)\n\n\
+                {}",
+                players[0].name, decoy_rule, self.total_rounds, self.render_status()
+            );
+        }
+        
+        format!("**☭ Comrade Clicker ☭**\n\n{}{}", self.render_slip(), self.render_status())
+    }
+    
+    fn render_abandoned(&self, _players: &[serenity::User]) -> String {
+        if !self.started {
+            return "**☭ Comrade Clicker ☭**\n\n\
+                Game abandoned. The Party notes your lack of enthusiasm, comrade.".to_string();
+        }
+        
+        if self.mode == ClickerMode::Race {
+            return format!(
+                "**☭ Comrade Clicker ☭**\n\n\
+                The workers have left the factory floor. Race abandoned after {} of {} rounds.",
+                self.round, self.total_rounds
+            );
         }
         
-        let total = self.click_times.iter().sum::<Duration>();
-        Some(total / self.click_times.len() as u32)
+        format!(
+            "**☭ Comrade Clicker ☭**\n\n\
+            You were too slow, comrade! The Party is disappointed in your lack of commitment.\n\n\
+            Game abandoned after {} of {} rounds.",
+            self.round, self.total_rounds
+        )
     }
     
-    fn get_best_time(&self) -> Option<Duration> {
-        self.click_times.iter().min().copied()
+    fn components<'a>(&self, c: &'a mut serenity::CreateComponents) -> &'a mut serenity::CreateComponents {
+        create_clicker_buttons(c, self)
     }
     
-    fn get_score(&self) -> Option<f64> {
-        // Score is the average reaction time in milliseconds
-        self.get_average_time().map(|duration| {
-            duration.as_secs_f64() * 1000.0
-        })
+    fn move_timeout(&self) -> Duration {
+        // Longer to get started than to find each square
+        if self.started { Duration::from_secs(10) } else { Duration::from_secs(60) }
     }
     
-    fn render_status(&self) -> String {
-        if self.game_over {
-            let avg_time = self.get_average_time()
-                .map_or_else(|| "N/A".to_string(), |t| format!("{:.2}ms", t.as_secs_f64() * 1000.0));
-            
-            let best_time = self.get_best_time()
-                .map_or_else(|| "N/A".to_string(), |t| format!("{:.2}ms", t.as_secs_f64() * 1000.0));
-            
-            format!(
-                "**Game Complete!**\n\
-                Rounds: {}/{}\n\
-                Average time: {}\n\
-                Best time: {}\n\
-                The Motherland thanks you for your swift labor, comrade!",
-                self.round, self.total_rounds, avg_time, best_time
-            )
+    fn format_score(score: f64) -> String {
+        if score < 0.0 {
+            format_race_score(score)
         } else {
-            if self.active_cell.is_none() {
-                format!(
-                    "**Preparing Round {}**\n\
-                    Get ready to click the red square when it appears!",
-                    self.round + 1
-                )
-            } else {
-                format!(
-                    "**Round {}/{}**\n\
-                    Click the red square as quickly as possible!",
-                    self.round + 1, self.total_rounds
-                )
-            }
+            format!("{:.2}ms", score)
+        }
+    }
+    
+    fn race_ranking() -> Option<&'static str> {
+        Some(CLICKER_RACE_RANKING)
+    }
+    
+    // Races and solo runs have their own leaderboards
+    fn score_key(&self) -> &'static str {
+        match self.mode {
+            ClickerMode::Solo => Self::KEY,
+            ClickerMode::Race => "clicker_race",
         }
     }
+    
+    // Racers are scored on how many rounds they won, then how quickly; those who won none aren't recorded
+    fn race_scores(&self) -> Vec<(serenity::User, f64)> {
+        if self.mode != ClickerMode::Race {
+            return Vec::new();
+        }
+        
+        self.racers
+            .iter()
+            .filter_map(|racer| {
+                Self::get_average_time(racer).map(|time| {
+                    (racer.user.clone(), race_score(racer.win_times.len(), time.as_secs_f64() * 1000.0))
+                })
+            })
+            .collect()
+    }
 }
 
-/// Shows available games
-#[poise::command(slash_command, prefix_command)]
-pub async fn game(
-    ctx: crate::Context<'_>,
-) -> Result<(), CommandError> {
-    ctx.send(|m| {
-        m.embed(|e| {
-            e.title("☭ State-Approved Entertainment ☭")
-             .description("The Ministry of Leisure presents approved games for your recreational period.")
-             .color(serenity::Color::RED)
-             .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/a/a9/Soviet_Union_state_emblem.svg/240px-Soviet_Union_state_emblem.svg.png")
-             .field(
-                "Classic Games", 
-                "• `/tictactoe [@user]` - Tactical Grid Competition\n\
-                 • `/connect4 [@user]` - People's Revolution Edition", 
-                false
-             )
-             .field(
-                "Skill Games", 
-                "• `/clicker` - Test your reaction time in Comrade Clicker\n\
-                 • `/kremlin_secrets` - Word Association Challenge", 
-                false
-             )
-             .field(
-                "Language Games", 
-                "• `/soviet_hangman` - Ideological Rehabilitation Challenge", 
-                false
-             )
-             .field(
-                "Economic Games", 
-                "• `/blackjack` - State-Sanctioned Resource Allocation Game", 
-                false
-             )
-             .footer(|f| f.text("Recreation increases worker productivity by 27%. Glory to the collective!"))
-        })
-    }).await?;
-    
-    Ok(())
+// Apply a button press to a saved game of one of these kinds whose command is no longer running
+pub(crate) async fn resume_game_press(
+    ctx: &serenity::Context,
+    data: &crate::Data,
+    record: &ActiveGame,
+    press: &serenity::MessageComponentInteraction,
+) -> Result<Resumed, CommandError> {
+    match record.game_type.as_str() {
+        TicTacToe::KEY => resume_press::<TicTacToe>(ctx, data, record, press).await,
+        Connect4::KEY => resume_press::<Connect4>(ctx, data, record, press).await,
+        _ => Ok(Resumed::Ignored),
+    }
 }
 
 /// tic-tac-toe
@@ -1536,7 +1343,24 @@ pub async fn tictactoe(
     ctx: crate::Context<'_>,
     #[description = "The user to play against (leave empty to play against the computer)"] 
     opponent: Option<serenity::User>,
+    #[description = "Boops each player stakes on the outcome (1-500)"]
+    wager: Option<f64>,
+    #[description = "Computer difficulty: easy, medium or hard (default: medium)"]
+    difficulty: Option<String>,
 ) -> Result<(), CommandError> {
+    let difficulty = match difficulty.as_deref().map(TicTacToeDifficulty::from_name) {
+        None => default_tictactoe_difficulty(),
+        Some(Some(_)) if opponent.is_some() => {
+            ctx.say("Difficulty only applies when playing against the computer, comrade.").await?;
+            return Ok(());
+        },
+        Some(Some(difficulty)) => difficulty,
+        Some(None) => {
+            ctx.say("Unknown difficulty, comrade. Choose `easy`, `medium` or `hard`.").await?;
+            return Ok(());
+        }
+    };
+    
     // Check if the opponent is the same as the player
     if let Some(ref user) = opponent {
         if user.id == ctx.author().id {
@@ -1550,656 +1374,142 @@ pub async fn tictactoe(
         }
     }
     
-    let author = ctx.author().clone();
-    let mut game = TicTacToe::new(author.id, opponent.as_ref().map(|u| u.id));
-    
-    // Create initial message
-    let msg = ctx.send(|m| {
-        m.content(format!(
-            "**☭ Communist Tic-Tac-Toe Game ☭**\n\n{}\n\n{}",
-            game.render_board(),
-            game.render_status(&author, opponent.as_ref())
-        ))
-        .components(|c| {
-            c.create_action_row(|row| {
-                for i in 0..3 {
-                    row.create_button(|b| {
-                        b.custom_id(format!("button_{}", i))
-                        .label(format!("{}", i + 1))
-                        .style(serenity::ButtonStyle::Secondary)
-                    });
-                }
-                row
-            })
-            .create_action_row(|row| {
-                for i in 3..6 {
-                    row.create_button(|b| {
-                        b.custom_id(format!("button_{}", i))
-                        .label(format!("{}", i + 1))
-                        .style(serenity::ButtonStyle::Secondary)
-                    });
-                }
-                row
-            })
-            .create_action_row(|row| {
-                for i in 6..9 {
-                    row.create_button(|b| {
-                        b.custom_id(format!("button_{}", i))
-                        .label(format!("{}", i + 1))
-                        .style(serenity::ButtonStyle::Secondary)
-                    });
-                }
-                row
-            })
-        })
-    }).await?;
-    
-    // Computer moves first if it's an AI game and computer starts as X
-    if game.ai_mode && game.current_player == Cell::O {
-        // Wait a moment to make it look like the computer is thinking
-        tokio::time::sleep(Duration::from_millis(1500)).await;
-        
-        if let Some(ai_pos) = game.ai_move() {
-            // Update message with the computer's move
-            msg.edit(ctx, |m| {
-                m.content(format!(
-                    "**☭ Communist Tic-Tac-Toe Game ☭**\n\n{}\n\nComputer played position {}.\n{}",
-                    game.render_board(), 
-                    ai_pos + 1,
-                    game.render_status(&author, opponent.as_ref())
-                ))
-                .components(|c| {
-                    if game.game_over {
-                        c // No components if game is over
-                    } else {
-                        for row in 0..3 {
-                            c.create_action_row(|r| {
-                                for col in 0..3 {
-                                    let i = row * 3 + col;
-                                    r.create_button(|b| {
-                                        b.custom_id(format!("button_{}", i))
-                                        .label(format!("{}", i + 1))
-                                        .style(serenity::ButtonStyle::Secondary)
-                                        .disabled(game.board[i] != Cell::Empty)
-                                    });
-                                }
-                                r
-                            });
-                        }
-                        c
-                    }
-                })
-            }).await?;
-        }
-    }
-    
-    // Handle button interactions
-    while !game.game_over {
-        // Wait for someone to click a button
-        if let Some(press) = serenity::CollectComponentInteraction::new(ctx)
-            .filter(move |press| {
-                // Only allow the current player to make a move
-                let user_id = press.user.id;
-                
-                if game.current_player == Cell::X {
-                    user_id == author.id // Only author can play as X
-                } else {
-                    // O's turn
-                    if game.ai_mode {
-                        false // Computer's turn, no user interaction allowed
-                    } else {
-                        // Check if the interaction is from the opponent
-                        if let Some(opponent_id) = game.opponent {
-                            user_id == opponent_id
-                        } else {
-                            false
-                        }
-                    }
-                }
-            })
-            .timeout(Duration::from_secs(300)) // 5 minutes timeout
-            .await
-        {
-            // Acknowledge the button press
-            press.defer(ctx).await?;
-            
-            // Extract position from the button custom_id (format: "button_X")
-            let position = press.data.custom_id
-                .strip_prefix("button_")
-                .and_then(|s| s.parse::<usize>().ok())
-                .unwrap_or(0);
-                
-            // Make the move
-            if game.make_move(position) {
-                // Update message
-                msg.edit(ctx, |m| {
-                    m.content(format!(
-                        "**☭ Communist Tic-Tac-Toe Game ☭**\n\n{}\n\n{}",
-                        game.render_board(),
-                        game.render_status(&author, opponent.as_ref())
-                    ))
-                    .components(|c| {
-                        if game.game_over {
-                            c // No components if game is over
-                        } else {
-                            for row in 0..3 {
-                                c.create_action_row(|r| {
-                                    for col in 0..3 {
-                                        let i = row * 3 + col;
-                                        r.create_button(|b| {
-                                            b.custom_id(format!("button_{}", i))
-                                            .label(format!("{}", i + 1))
-                                            .style(serenity::ButtonStyle::Secondary)
-                                            .disabled(game.board[i] != Cell::Empty)
-                                        });
-                                    }
-                                    r
-                                });
-                            }
-                            c
-                        }
-                    })
-                }).await?;
-                
-                // If game is against computer and it's the computer's turn
-                if game.ai_mode && !game.game_over && game.current_player == Cell::O {
-                    // Wait a moment to make it look like the computer is thinking
-                    tokio::time::sleep(Duration::from_millis(1500)).await;
-                    
-                    if let Some(ai_pos) = game.ai_move() {
-                        // Update message with the computer's move
-                        msg.edit(ctx, |m| {
-                            m.content(format!(
-                                "**☭ Communist Tic-Tac-Toe Game ☭**\n\n{}\n\nComputer played position {}.\n{}",
-                                game.render_board(), 
-                                ai_pos + 1,
-                                game.render_status(&author, opponent.as_ref())
-                            ))
-                            .components(|c| {
-                                if game.game_over {
-                                    c // No components if game is over
-                                } else {
-                                    for row in 0..3 {
-                                        c.create_action_row(|r| {
-                                            for col in 0..3 {
-                                                let i = row * 3 + col;
-                                                r.create_button(|b| {
-                                                    b.custom_id(format!("button_{}", i))
-                                                    .label(format!("{}", i + 1))
-                                                    .style(serenity::ButtonStyle::Secondary)
-                                                    .disabled(game.board[i] != Cell::Empty)
-                                                });
-                                            }
-                                            r
-                                        });
-                                    }
-                                    c
-                                }
-                            })
-                        }).await?;
-                    }
-                }
-            }
-        } else {
-            // Timeout or error - end the game
-            msg.edit(ctx, |m| {
-                m.content(format!(
-                    "**☭ Communist Tic-Tac-Toe Game ☭**\n\n{}\n\nGame abandoned due to inactivity!",
-                    game.render_board()
-                ))
-                .components(|c| c)
-            }).await?;
-            break;
-        }
-    }
-    
-    // Game is over, final message
+    // The first seat plays X and moves first
+    run_game(ctx, opponent, wager, |players| TicTacToe::new(players.len() == 1, difficulty)).await
+}
+
+// The 3x3 grid of move buttons, with taken cells disabled. Empty once the game is over.
+fn create_tictactoe_buttons<'a>(
+    c: &'a mut serenity::CreateComponents,
+    game: &TicTacToe,
+) -> &'a mut serenity::CreateComponents {
     if game.game_over {
-        let final_message = if let Some(winner) = game.winner {
-            match winner {
-                Cell::X => "The glory of the X workers prevails!",
-                Cell::O => "The triumph of the O collective is complete!",
-                _ => "The game ended without a clear outcome."
-            }
-        } else {
-            "A fair draw - the means of production have been equally distributed!"
-        };
-        
-        msg.edit(ctx, |m| {
-            m.content(format!(
-                "**☭ Communist Tic-Tac-Toe Game ☭**\n\n{}\n\n{}\n\n{}", 
-                game.render_board(),
-                game.render_status(&author, opponent.as_ref()),
-                final_message
-            ))
-            .components(|c| c) // Clear components
-        }).await?;
+        return c;
     }
     
-    Ok(())
+    for row in 0..3 {
+        c.create_action_row(|r| {
+            for col in 0..3 {
+                let i = row * 3 + col;
+                r.create_button(|b| {
+                    b.custom_id(format!("button_{}", i))
+                    .label(format!("{}", i + 1))
+                    .style(serenity::ButtonStyle::Secondary)
+                    .disabled(game.board[i] != Cell::Empty)
+                });
+            }
+            r
+        });
+    }
+    c
 }
 
 /// comrade clicker
 #[poise::command(slash_command, prefix_command, track_edits)]
 pub async fn clicker(
     ctx: crate::Context<'_>,
+    #[description = "solo, or race to let everyone in the channel compete (default: solo)"]
+    mode: Option<String>,
+    #[description = "Width of the square grid, 2-5 (default: 5)"]
+    grid_size: Option<usize>,
+    #[description = "Number of rounds, 1-20 (default: 5)"]
+    rounds: Option<usize>,
+    #[description = "Decoy squares that punish careless clicks (default: 0)"]
+    decoys: Option<usize>,
 ) -> Result<(), CommandError> {
-    let author = ctx.author().clone();
-    let server_id = match ctx.guild_id() {
-        Some(id) => id.to_string(),
-        None => "DM".to_string(),
+    let mode = match mode.as_deref().map(ClickerMode::from_name) {
+        None => ClickerMode::Solo,
+        Some(Some(mode)) => mode,
+        Some(None) => {
+            ctx.say("Unknown mode, comrade. Choose `solo` or `race`.").await?;
+            return Ok(());
+        }
     };
     
-    // Create a new clicker game (5x5 grid, 5 rounds)
-    let grid_size = 5;
-    let total_rounds = 5;
-    let mut game = ComradeClicker::new(grid_size, total_rounds);
+    // A 5x5 grid over 5 rounds unless asked otherwise
+    let grid_size = grid_size.unwrap_or(5);
+    let rounds = rounds.unwrap_or(5);
+    let decoys = decoys.unwrap_or(0);
     
-    // Create initial message
-    let msg = ctx.send(|m| {
-        m.content(format!(
-            "**☭ Comrade Clicker ☭**\n\n\
-            Welcome, Comrade {}! Your labor efficiency will be tested.\n\n\
-            Rules:\n\
-            • Click on the red square (🟥) as quickly as possible\n\
-            • Complete {} rounds to get your score\n\
-            • Your score is your average reaction time (lower is better)\n\n\
-            {}",
-            author.name, total_rounds, game.render_status()
-        ))
-        .components(|c| {
-            // Add a start button
-            c.create_action_row(|row| {
+    if !CLICKER_GRID_SIZES.contains(&grid_size) {
+        ctx.say(format!(
+            "The grid must be between {} and {} squares wide, comrade.",
+            CLICKER_GRID_SIZES.start(), CLICKER_GRID_SIZES.end()
+        )).await?;
+        return Ok(());
+    }
+    
+    if !CLICKER_ROUNDS.contains(&rounds) {
+        ctx.say(format!(
+            "A game lasts between {} and {} rounds, comrade.",
+            CLICKER_ROUNDS.start(), CLICKER_ROUNDS.end()
+        )).await?;
+        return Ok(());
+    }
+    
+    if decoys > ComradeClicker::max_decoys(grid_size) {
+        ctx.say(format!(
+            "A {}x{} grid has room for at most {} decoys, comrade.",
+            grid_size, grid_size, ComradeClicker::max_decoys(grid_size)
+        )).await?;
+        return Ok(());
+    }
+    
+    run_game(ctx, None, None, |players| ComradeClicker::new(mode, &players[0], grid_size, rounds, decoys)).await
+}
+
+// A start button before the first round (and a join button for races), then the
+// grid with the active cell in red and any decoys in orange. Empty once the game is over.
+fn create_clicker_buttons<'a>(
+    c: &'a mut serenity::CreateComponents,
+    game: &ComradeClicker,
+) -> &'a mut serenity::CreateComponents {
+    if !game.started {
+        return c.create_action_row(|row| {
+            if game.mode == ClickerMode::Race {
                 row.create_button(|b| {
-                    b.custom_id("start_game")
-                    .label("Start Game")
-                    .style(serenity::ButtonStyle::Success)
-                    .emoji('✅')
-                })
+                    b.custom_id("join_race")
+                    .label("Join Race")
+                    .style(serenity::ButtonStyle::Primary)
+                    .emoji('✋')
+                    .disabled(game.racers.len() >= CLICKER_MAX_RACERS)
+                });
+            }
+            row.create_button(|b| {
+                b.custom_id("start_game")
+                .label("Start Game")
+                .style(serenity::ButtonStyle::Success)
+                .emoji('✅')
             })
-        })
-    }).await?;
+        });
+    }
     
-    // Wait for the start button to be pressed
-    if let Some(press) = serenity::CollectComponentInteraction::new(ctx)
-        .filter(move |press| press.user.id == author.id)
-        .timeout(Duration::from_secs(60))
-        .await
-    {
-        press.defer(ctx).await?;
-        
-        // Start the first round
-        game.start_round();
-        
-        // Main game loop
-        while !game.game_over {
-            // Update the message with the current board
-            let active_cell = game.active_cell;
-            
-            msg.edit(ctx, |m| {
-                m.content(format!(
-                    "**☭ Comrade Clicker ☭**\n\n{}",
-                    game.render_status()
-                ))
-                .components(|c| {
-                    // Create grid of buttons
-                    for row in 0..grid_size {
-                        c.create_action_row(|action_row| {
-                            for col in 0..grid_size {
-                                let position = row * grid_size + col;
-                                let is_active = Some(position) == active_cell;
-                                
-                                action_row.create_button(|b| {
-                                    b.custom_id(format!("cell_{}", position))
-                                    .label(" ")
-                                    .style(if is_active {
-                                        serenity::ButtonStyle::Danger
-                                    } else {
-                                        serenity::ButtonStyle::Secondary
-                                    })
-                                    .emoji(if is_active { '🔴' } else { '⬛' })
-                                });
-                            }
-                            action_row
-                        });
-                    }
-                    c
-                })
-            }).await?;
-            
-            // Wait for a button press
-            if let Some(press) = serenity::CollectComponentInteraction::new(ctx)
-                .filter(move |press| press.user.id == author.id)
-                .timeout(Duration::from_secs(10))
-                .await
-            {
-                press.defer(ctx).await?;
-                
-                // Extract position from the button custom_id (format: "cell_X")
-                let position = press.data.custom_id
-                    .strip_prefix("cell_")
-                    .and_then(|s| s.parse::<usize>().ok())
-                    .unwrap_or(0);
-                
-                // Handle the click
-                let correct = game.handle_click(position);
-                
-                if !correct {
-                    // User clicked the wrong cell - flash a message
-                    msg.edit(ctx, |m| {
-                        m.content(format!(
-                            "**☭ Comrade Clicker ☭**\n\n\
-                            That was the wrong cell, comrade! Pay attention!\n\n\
-                            {}",
-                            game.render_status()
-                        ))
-                    }).await?;
-                    
-                    // Pause briefly to show error
-                    tokio::time::sleep(Duration::from_millis(800)).await;
-                }
-            } else {
-                // Timeout - end the game
-                game.game_over = true;
-                
-                msg.edit(ctx, |m| {
-                    m.content(format!(
-                        "**☭ Comrade Clicker ☭**\n\n\
-                        You were too slow, comrade! The Party is disappointed in your lack of commitment.\n\n\
-                        Game abandoned after {} of {} rounds.",
-                        game.round, game.total_rounds
-                    ))
-                    .components(|c| c)
-                }).await?;
-                
-                return Ok(());
-            }
-        }
-        
-        // Game is over, show final score
-        let score = game.get_score();
-        
-        // Save score to database if available
-        if let Some(score_ms) = score {
-            let db = &ctx.data().db;
-            db.save_game_score(
-                &author.id.to_string(),
-                &server_id,
-                &author.name,
-                "clicker",
-                score_ms,
-            ).await?;
-            
-            // Get the user's best score
-            let best_score = db.get_user_best_score(&author.id.to_string(), &server_id, "clicker").await?;
-            
-            // Get server leaderboard (top 3)
-            let leaderboard = db.get_server_leaderboard(&server_id, "clicker", 3).await?;
-            
-            // Prepare leaderboard text
-            let leaderboard_text = if leaderboard.is_empty() {
-                "No other scores recorded yet".to_string()
-            } else {
-                let mut text = "**Top Reaction Times:**\n".to_string();
-                for (i, (username, score)) in leaderboard.iter().enumerate() {
-                    text.push_str(&format!("{}. **{}**: {:.2}ms\n", i+1, username, score));
-                }
-                text
-            };
-            
-            let best_score_text = if let Some(best) = best_score {
-                if (score_ms - best).abs() < 0.001 {
-                    "This is a new personal best!".to_string()
-                } else {
-                    format!("Your best score: {:.2}ms", best)
-                }
-            } else {
-                "This is your first score!".to_string()
-            };
-            
-            msg.edit(ctx, |m| {
-                m.content(format!(
-                    "**☭ Comrade Clicker ☭**\n\n\
-                    **Game Complete!**\n\
-                    Comrade {}, your average reaction time was: **{:.2}ms**\n\
-                    {}\n\n\
-                    {}\n\n\
-                    The Motherland thanks you for your swift labor!",
-                    author.name, score_ms, best_score_text, leaderboard_text
-                ))
-                .components(|c| {
-                    c.create_action_row(|row| {
-                        row.create_button(|b| {
-                            b.custom_id("play_again")
-                            .label("Play Again")
-                            .style(serenity::ButtonStyle::Primary)
-                        })
-                    })
-                })
-            }).await?;
-            
-            // Wait for "Play Again" button press
-            if let Some(press) = serenity::CollectComponentInteraction::new(ctx)
-                .filter(move |press| {
-                    press.user.id == author.id && press.data.custom_id == "play_again"
-                })
-                .timeout(Duration::from_secs(60))
-                .await
-            {
-                press.defer(ctx).await?;
-                
-                // Instead of recursive call, just create a new message
-                ctx.say("Starting a new Comrade Clicker game...").await?;
-                
-                // Start a new game directly using current context
-                let mut new_game = ComradeClicker::new(grid_size, total_rounds);
-                new_game.start_round();
-                
-                // Create a new message for the new game
-                let new_msg = ctx.send(|m| {
-                    m.content(format!(
-                        "**☭ Comrade Clicker ☭**\n\n\
-                        Welcome, Comrade {}! Your labor efficiency will be tested.\n\n\
-                        Rules:\n\
-                        • Click on the red square (🟥) as quickly as possible\n\
-                        • Complete {} rounds to get your score\n\
-                        • Your score is your average reaction time (lower is better)\n\n\
-                        {}",
-                        author.name, total_rounds, new_game.render_status()
-                    ))
-                    .components(|c| {
-                        // Create grid of buttons for the new game
-                        for row in 0..grid_size {
-                            c.create_action_row(|action_row| {
-                                for col in 0..grid_size {
-                                    let position = row * grid_size + col;
-                                    let is_active = Some(position) == new_game.active_cell;
-                                    
-                                    action_row.create_button(|b| {
-                                        b.custom_id(format!("cell_{}", position))
-                                        .label(" ")
-                                        .style(if is_active {
-                                            serenity::ButtonStyle::Danger
-                                        } else {
-                                            serenity::ButtonStyle::Secondary
-                                        })
-                                        .emoji(if is_active { '🔴' } else { '⬛' })
-                                    });
-                                }
-                                action_row
-                            });
-                        }
-                        c
-                    })
-                }).await?;
+    if game.game_over {
+        return c;
+    }
+    
+    for row in 0..game.grid_size {
+        c.create_action_row(|action_row| {
+            for col in 0..game.grid_size {
+                let position = row * game.grid_size + col;
+                let is_active = Some(position) == game.active_cell;
+                let is_decoy = game.decoys.contains(&position);
                 
-                // Start game loop for the new game
-                let mut game_loop = true;
-                while game_loop && !new_game.game_over {
-                    if let Some(press) = serenity::CollectComponentInteraction::new(ctx)
-                        .filter(move |press| press.user.id == author.id)
-                        .timeout(Duration::from_secs(10))
-                        .await
-                    {
-                        press.defer(ctx).await?;
-                        
-                        let position = press.data.custom_id
-                            .strip_prefix("cell_")
-                            .and_then(|s| s.parse::<usize>().ok())
-                            .unwrap_or(0);
-                        
-                        let correct = new_game.handle_click(position);
-                        
-                        if !correct {
-                            new_msg.edit(ctx, |m| {
-                                m.content(format!(
-                                    "**☭ Comrade Clicker ☭**\n\n\
-                                    That was the wrong cell, comrade! Pay attention!\n\n\
-                                    {}",
-                                    new_game.render_status()
-                                ))
-                            }).await?;
-                            
-                            tokio::time::sleep(Duration::from_millis(800)).await;
-                        }
-                        
-                        // Update board
-                        let active_cell = new_game.active_cell;
-                        
-                        new_msg.edit(ctx, |m| {
-                            m.content(format!(
-                                "**☭ Comrade Clicker ☭**\n\n{}",
-                                new_game.render_status()
-                            ))
-                            .components(|c| {
-                                if new_game.game_over {
-                                    c
-                                } else {
-                                    for row in 0..grid_size {
-                                        c.create_action_row(|action_row| {
-                                            for col in 0..grid_size {
-                                                let position = row * grid_size + col;
-                                                let is_active = Some(position) == active_cell;
-                                                
-                                                action_row.create_button(|b| {
-                                                    b.custom_id(format!("cell_{}", position))
-                                                    .label(" ")
-                                                    .style(if is_active {
-                                                        serenity::ButtonStyle::Danger
-                                                    } else {
-                                                        serenity::ButtonStyle::Secondary
-                                                    })
-                                                    .emoji(if is_active { '🔴' } else { '⬛' })
-                                                });
-                                            }
-                                            action_row
-                                        });
-                                    }
-                                    c
-                                }
-                            })
-                        }).await?;
+                action_row.create_button(|b| {
+                    b.custom_id(format!("cell_{}", position))
+                    .label(" ")
+                    .style(if is_active || is_decoy {
+                        serenity::ButtonStyle::Danger
                     } else {
-                        // Timeout - end the game
-                        new_game.game_over = true;
-                        
-                        new_msg.edit(ctx, |m| {
-                            m.content(format!(
-                                "**☭ Comrade Clicker ☭**\n\n\
-                                You were too slow, comrade! The Party is disappointed in your lack of commitment.\n\n\
-                                Game abandoned after {} of {} rounds.",
-                                new_game.round, new_game.total_rounds
-                            ))
-                            .components(|c| c)
-                        }).await?;
-                        
-                        game_loop = false;
-                    }
-                }
-                
-                // Display final score
-                if new_game.game_over {
-                    if let Some(score_ms) = new_game.get_score() {
-                        let db = &ctx.data().db;
-                        db.save_game_score(
-                            &author.id.to_string(),
-                            &server_id,
-                            &author.name,
-                            "clicker",
-                            score_ms,
-                        ).await?;
-                        
-                        let best_score = db.get_user_best_score(&author.id.to_string(), &server_id, "clicker").await?;
-                        let leaderboard = db.get_server_leaderboard(&server_id, "clicker", 3).await?;
-                        
-                        let leaderboard_text = if leaderboard.is_empty() {
-                            "No other scores recorded yet".to_string()
-                        } else {
-                            let mut text = "**Top Reaction Times:**\n".to_string();
-                            for (i, (username, score)) in leaderboard.iter().enumerate() {
-                                text.push_str(&format!("{}. **{}**: {:.2}ms\n", i+1, username, score));
-                            }
-                            text
-                        };
-                        
-                        let best_score_text = if let Some(best) = best_score {
-                            if (score_ms - best).abs() < 0.001 {
-                                "This is a new personal best!".to_string()
-                            } else {
-                                format!("Your best score: {:.2}ms", best)
-                            }
-                        } else {
-                            "This is your first score!".to_string()
-                        };
-                        
-                        new_msg.edit(ctx, |m| {
-                            m.content(format!(
-                                "**☭ Comrade Clicker ☭**\n\n\
-                                **Game Complete!**\n\
-                                Comrade {}, your average reaction time was: **{:.2}ms**\n\
-                                {}\n\n\
-                                {}\n\n\
-                                The Motherland thanks you for your swift labor!",
-                                author.name, score_ms, best_score_text, leaderboard_text
-                            ))
-                            .components(|c| {
-                                c.create_action_row(|row| {
-                                    row.create_button(|b| {
-                                        b.custom_id("play_again")
-                                        .label("Play Again")
-                                        .style(serenity::ButtonStyle::Primary)
-                                    })
-                                })
-                            })
-                        }).await?;
-                    }
-                }
-                
-                // Return early to avoid executing the rest of the function
-                return Ok(());
+                        serenity::ButtonStyle::Secondary
+                    })
+                    .emoji(if is_active { '🔴' } else if is_decoy { '🟠' } else { '⬛' })
+                });
             }
-        } else {
-            // User didn't press start button
-            msg.edit(ctx, |m| {
-                m.content(format!(
-                    "**☭ Comrade Clicker ☭**\n\n\
-                    Game abandoned. The Party notes your lack of enthusiasm, comrade."
-                ))
-                .components(|c| c)
-            }).await?;
-        }
-    } else {
-        // User didn't press start button
-        msg.edit(ctx, |m| {
-            m.content(format!(
-                "**☭ Comrade Clicker ☭**\n\n\
-                Game abandoned. The Party notes your lack of enthusiasm, comrade."
-            ))
-            .components(|c| c)
-        }).await?;
+            action_row
+        });
     }
-    
-    Ok(())
+    c
 }
 
 /// connect 4
@@ -2208,7 +1518,24 @@ pub async fn connect4(
     ctx: crate::Context<'_>,
     #[description = "The user to play against (leave empty to play against the computer)"] 
     opponent: Option<serenity::User>,
+    #[description = "Boops each player stakes on the outcome (1-500)"]
+    wager: Option<f64>,
+    #[description = "Computer difficulty: conscript, soldier, general or marshal (default: marshal)"]
+    difficulty: Option<String>,
 ) -> Result<(), CommandError> {
+    let difficulty = match difficulty.as_deref().map(Connect4Difficulty::from_name) {
+        None => default_connect4_difficulty(),
+        Some(Some(_)) if opponent.is_some() => {
+            ctx.say("Difficulty only applies when playing against the computer, comrade.").await?;
+            return Ok(());
+        },
+        Some(Some(difficulty)) => difficulty,
+        Some(None) => {
+            ctx.say("Unknown difficulty, comrade. Choose `conscript`, `soldier`, `general` or `marshal`.").await?;
+            return Ok(());
+        }
+    };
+    
     // Check if the opponent is the same as the player
     if let Some(ref user) = opponent {
         if user.id == ctx.author().id {
@@ -2222,251 +1549,392 @@ pub async fn connect4(
         }
     }
     
-    let author = ctx.author().clone();
-    let mut game = Connect4::new(author.id, opponent.as_ref().map(|u| u.id));
-    
-    // Create initial message
-    let msg = ctx.send(|m| {
-        m.content(format!(
-            "**☭ Connect 4: People's Revolution Edition ☭**\n\n{}\n\n{}",
-            game.render_board(),
-            game.render_status(&author, opponent.as_ref())
-        ))
-        .components(|c| {
-            // Create 7 buttons for the 7 columns, split into two rows
-            c.create_action_row(|row| {
-                for i in 0..4 {
-                    row.create_button(|b| {
-                        b.custom_id(format!("col_{}", i))
-                        .label(format!("{}", i + 1))
-                        .style(serenity::ButtonStyle::Secondary)
-                        .disabled(false)
-                    });
+    // The first seat plays Red and moves first
+    run_game(ctx, opponent, wager, |players| Connect4::new(players.len() == 1, difficulty)).await
+}
+
+// One button per column, split over two rows, with full columns disabled.
+// Empty once the game is over.
+fn create_connect4_buttons<'a>(
+    c: &'a mut serenity::CreateComponents,
+    game: &Connect4,
+) -> &'a mut serenity::CreateComponents {
+    if game.game_over {
+        return c;
+    }
+    
+    for columns in [0..4, 4..7] {
+        c.create_action_row(|row| {
+            for i in columns {
+                row.create_button(|b| {
+                    b.custom_id(format!("col_{}", i))
+                    .label(format!("{}", i + 1))
+                    .style(serenity::ButtonStyle::Secondary)
+                    .disabled(!game.is_valid_move(i))
+                });
+            }
+            row
+        });
+    }
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::game_engine::SavedRound;
+    use rand::{SeedableRng, rngs::StdRng};
+    
+    // Play a sequence of moves, asserting each one is accepted
+    fn play<G: Game>(game: &mut G, moves: &[G::Move]) {
+        for mv in moves {
+            assert!(game.apply_move(mv.clone()));
+        }
+    }
+    
+    #[test]
+    fn tictactoe_players_alternate() {
+        let mut game = TicTacToe::new(false, TicTacToeDifficulty::Hard);
+        assert_eq!(game.turn(), Turn::Player(0));
+        play(&mut game, &[4]);
+        assert_eq!(game.turn(), Turn::Player(1));
+        
+        // Taken and out-of-range cells are refused without passing the turn
+        assert!(!game.apply_move(4));
+        assert!(!game.apply_move(9));
+        assert_eq!(game.turn(), Turn::Player(1));
+        assert_eq!(game.legal_moves().len(), 8);
+    }
+    
+    #[test]
+    fn tictactoe_detects_lines() {
+        for (first, second) in [
+            ([0, 1, 2], [3, 4]),
+            ([0, 3, 6], [1, 2]),
+            ([0, 4, 8], [1, 2]),
+            ([2, 4, 6], [0, 1]),
+        ] {
+            let mut game = TicTacToe::new(false, TicTacToeDifficulty::Hard);
+            play(&mut game, &[first[0], second[0], first[1], second[1], first[2]]);
+            assert!(game.is_terminal());
+            assert_eq!(game.outcome(), Some(Outcome::Win(0)));
+            assert!(game.legal_moves().is_empty());
+        }
+    }
+    
+    #[test]
+    fn tictactoe_full_board_is_a_draw() {
+        let mut game = TicTacToe::new(false, TicTacToeDifficulty::Hard);
+        play(&mut game, &[0, 1, 2, 4, 3, 5, 7, 6, 8]);
+        assert_eq!(game.turn(), Turn::Over);
+        assert_eq!(game.outcome(), Some(Outcome::Draw));
+    }
+    
+    #[test]
+    fn tictactoe_computer_wins_or_blocks() {
+        let mut game = TicTacToe::new(true, TicTacToeDifficulty::Hard);
+        play(&mut game, &[0, 4, 1]);
+        assert_eq!(game.turn(), Turn::Computer);
+        assert_eq!(game.computer_move(), Some(2));
+        
+        // With a win on offer, the computer takes it instead of blocking
+        let mut game = TicTacToe::new(true, TicTacToeDifficulty::Hard);
+        play(&mut game, &[0, 3, 1, 4, 8]);
+        assert_eq!(game.computer_move(), Some(5));
+        play(&mut game, &[5]);
+        assert_eq!(game.outcome(), Some(Outcome::ComputerWin));
+    }
+    
+    // Every game the hard computer can reach as O, against every reply from X
+    fn assert_never_loses(game: &TicTacToe) {
+        match game.turn() {
+            Turn::Over => assert_ne!(game.winner, Some(Cell::X), "hard computer lost: {:?}", game.board),
+            Turn::Computer => {
+                for position in game.best_moves() {
+                    let mut next = game.clone();
+                    play(&mut next, &[position]);
+                    assert_never_loses(&next);
                 }
-                row
-            })
-            .create_action_row(|row| {
-                for i in 4..7 {
-                    row.create_button(|b| {
-                        b.custom_id(format!("col_{}", i))
-                        .label(format!("{}", i + 1))
-                        .style(serenity::ButtonStyle::Secondary)
-                        .disabled(false)
-                    });
+            },
+            Turn::Player(_) | Turn::Open => {
+                for position in game.legal_moves() {
+                    let mut next = game.clone();
+                    play(&mut next, &[position]);
+                    assert_never_loses(&next);
                 }
-                row
-            })
-        })
-    }).await?;
+            },
+        }
+    }
     
-    // Computer moves first if it's an AI game and computer starts as Yellow (which it shouldn't since Red goes first)
-    if game.ai_mode && game.current_player == Connect4Cell::Yellow {
-        // Wait a moment to make it look like the computer is thinking
-        tokio::time::sleep(Duration::from_millis(1500)).await;
-        
-        if let Some(ai_col) = game.ai_move() {
-            // Update message with the computer's move
-            msg.edit(ctx, |m| {
-                m.content(format!(
-                    "**☭ Connect 4: People's Revolution Edition ☭**\n\n{}\n\nComputer played in column {}.\n{}",
-                    game.render_board(), 
-                    ai_col + 1,
-                    game.render_status(&author, opponent.as_ref())
-                ))
-                .components(|c| {
-                    if game.game_over {
-                        c // No components if game is over
-                    } else {
-                        c.create_action_row(|row| {
-                            for i in 0..4 {
-                                row.create_button(|b| {
-                                    b.custom_id(format!("col_{}", i))
-                                    .label(format!("{}", i + 1))
-                                    .style(serenity::ButtonStyle::Secondary)
-                                    .disabled(!game.is_valid_move(i))
-                                });
-                            }
-                            row
-                        })
-                        .create_action_row(|row| {
-                            for i in 4..7 {
-                                row.create_button(|b| {
-                                    b.custom_id(format!("col_{}", i))
-                                    .label(format!("{}", i + 1))
-                                    .style(serenity::ButtonStyle::Secondary)
-                                    .disabled(!game.is_valid_move(i))
-                                });
-                            }
-                            row
-                        })
-                    }
-                })
-            }).await?;
+    #[test]
+    fn tictactoe_hard_computer_never_loses() {
+        assert_never_loses(&TicTacToe::new(true, TicTacToeDifficulty::Hard));
+    }
+    
+    #[test]
+    fn tictactoe_easier_levels_make_mistakes() {
+        // X threatens the top row; a perfect O always blocks
+        let mut game = TicTacToe::new(true, TicTacToeDifficulty::Easy);
+        play(&mut game, &[0, 4, 1]);
+        
+        let mut rng = StdRng::seed_from_u64(1917);
+        let blocks = |difficulty, rng: &mut StdRng| {
+            (0..200).filter(|_| game.choose_ai_move_with(difficulty, rng) == Some(2)).count()
+        };
+        assert_eq!(blocks(TicTacToeDifficulty::Hard, &mut rng), 200);
+        
+        let medium = blocks(TicTacToeDifficulty::Medium, &mut rng);
+        let easy = blocks(TicTacToeDifficulty::Easy, &mut rng);
+        assert!(easy < medium && medium < 200, "easy blocked {}, medium {}", easy, medium);
+    }
+    
+    #[test]
+    fn tictactoe_difficulty_names() {
+        assert_eq!(TicTacToeDifficulty::from_name(" Hard "), Some(TicTacToeDifficulty::Hard));
+        assert_eq!(TicTacToeDifficulty::from_name("unbeatable"), Some(TicTacToeDifficulty::Hard));
+        assert_eq!(TicTacToeDifficulty::from_name("marshal"), None);
+    }
+    
+    #[test]
+    fn connect4_pieces_stack_and_columns_fill() {
+        let mut game = Connect4::new(false, Connect4Difficulty::Marshal);
+        play(&mut game, &[0, 0, 0, 0, 0, 0]);
+        assert_eq!(game.cell(0, 0), Connect4Cell::Yellow);
+        assert_eq!(game.cell(5, 0), Connect4Cell::Red);
+        assert!(!game.apply_move(0));
+        assert!(!game.apply_move(7));
+        assert_eq!(game.legal_moves(), vec![1, 2, 3, 4, 5, 6]);
+    }
+    
+    #[test]
+    fn connect4_detects_lines() {
+        // Vertical, horizontal, and both diagonals for the first player
+        let games: [&[usize]; 4] = [
+            &[0, 1, 0, 1, 0, 1, 0],
+            &[0, 0, 1, 1, 2, 2, 3],
+            &[0, 1, 1, 2, 2, 3, 2, 3, 3, 6, 3],
+            &[6, 5, 5, 4, 4, 3, 4, 3, 3, 0, 3],
+        ];
+        
+        for moves in games {
+            let mut game = Connect4::new(false, Connect4Difficulty::Marshal);
+            play(&mut game, moves);
+            assert!(game.is_terminal(), "no win after {:?}", moves);
+            assert_eq!(game.outcome(), Some(Outcome::Win(0)));
         }
     }
     
-    // Handle button interactions
-    while !game.game_over {
-        // Wait for someone to click a button
-        if let Some(press) = serenity::CollectComponentInteraction::new(ctx)
-            .filter(move |press| {
-                // Only allow the current player to make a move
-                let user_id = press.user.id;
-                
-                if game.current_player == Connect4Cell::Red {
-                    user_id == author.id // Only author can play as Red
-                } else {
-                    // Yellow's turn
-                    if game.ai_mode {
-                        false // Computer's turn, no user interaction allowed
-                    } else {
-                        // Check if the interaction is from the opponent
-                        if let Some(opponent_id) = game.opponent {
-                            user_id == opponent_id
-                        } else {
-                            false
-                        }
-                    }
-                }
-            })
-            .timeout(Duration::from_secs(300)) // 5 minutes timeout
-            .await
-        {
-            // Acknowledge the button press
-            press.defer(ctx).await?;
+    #[test]
+    fn connect4_second_player_can_win() {
+        let mut game = Connect4::new(false, Connect4Difficulty::Marshal);
+        play(&mut game, &[6, 0, 6, 0, 5, 0, 5, 0]);
+        assert_eq!(game.outcome(), Some(Outcome::Win(1)));
+        
+        let mut game = Connect4::new(true, Connect4Difficulty::Marshal);
+        play(&mut game, &[6, 0, 6, 0, 5, 0, 5, 0]);
+        assert_eq!(game.outcome(), Some(Outcome::ComputerWin));
+    }
+    
+    #[test]
+    fn connect4_computer_wins_or_blocks() {
+        // Red threatens the bottom row; Yellow must block
+        let mut game = Connect4::new(true, Connect4Difficulty::Marshal);
+        play(&mut game, &[1, 6, 2, 6, 3]);
+        assert_eq!(game.turn(), Turn::Computer);
+        let block = game.computer_move().unwrap();
+        assert!(block == 0 || block == 4);
+        
+        // Yellow has three stacked; it should complete the column
+        let mut game = Connect4::new(true, Connect4Difficulty::Marshal);
+        play(&mut game, &[0, 6, 1, 6, 0, 6, 5]);
+        assert_eq!(game.computer_move(), Some(6));
+    }
+    
+    // Play one game between two computer levels from an opening, with `red` moving first
+    fn play_levels(opening: &[usize], red: Connect4Difficulty, yellow: Connect4Difficulty, rng: &mut StdRng) -> Outcome {
+        let mut game = Connect4::new(true, yellow);
+        play(&mut game, opening);
+        
+        // No thinking time, so the result depends only on the seed
+        while !game.is_terminal() {
+            let level = match game.current_player {
+                Connect4Cell::Red => red,
+                _ => yellow,
+            };
+            let column = game.choose_ai_move_with(level, rng, Duration::ZERO);
+            play(&mut game, &[column.unwrap()]);
+        }
+        
+        game.outcome().unwrap()
+    }
+    
+    #[test]
+    fn only_a_computer_that_can_defend_pays_for_beating_it() {
+        assert_eq!(TicTacToe::new(true, TicTacToeDifficulty::Easy).computer_win_reward_share(), 0.0);
+        assert_eq!(Connect4::new(true, Connect4Difficulty::Conscript).computer_win_reward_share(), 0.0);
+        assert!(TicTacToe::new(true, TicTacToeDifficulty::Medium).computer_win_reward_share() < 1.0);
+        assert_eq!(Connect4::new(true, Connect4Difficulty::Marshal).computer_win_reward_share(), 1.0);
+    }
+    
+    #[test]
+    fn connect4_levels_are_ordered_by_strength() {
+        use Connect4Difficulty::*;
+        let mut rng = StdRng::seed_from_u64(1917);
+        
+        // Each pairing plays random two-move openings from both sides, since
+        // moving first matters more than anything; the stronger level must score more
+        for (weaker, stronger) in [(Conscript, Soldier), (Soldier, General), (General, Marshal)] {
+            let mut points = 0.0;
+            let mut games = 0;
             
-            // Extract column from the button custom_id (format: "col_X")
-            let column = press.data.custom_id
-                .strip_prefix("col_")
-                .and_then(|s| s.parse::<usize>().ok())
-                .unwrap_or(0);
+            for _ in 0..6 {
+                let opening = [rng.gen_range(0..7), rng.gen_range(0..7)];
                 
-            // Make the move
-            if game.make_move(column) {
-                // Update message
-                msg.edit(ctx, |m| {
-                    m.content(format!(
-                        "**☭ Connect 4: People's Revolution Edition ☭**\n\n{}\n\n{}",
-                        game.render_board(),
-                        game.render_status(&author, opponent.as_ref())
-                    ))
-                    .components(|c| {
-                        if game.game_over {
-                            c // No components if game is over
-                        } else {
-                            c.create_action_row(|row| {
-                                for i in 0..4 {
-                                    row.create_button(|b| {
-                                        b.custom_id(format!("col_{}", i))
-                                        .label(format!("{}", i + 1))
-                                        .style(serenity::ButtonStyle::Secondary)
-                                        .disabled(!game.is_valid_move(i))
-                                    });
-                                }
-                                row
-                            })
-                            .create_action_row(|row| {
-                                for i in 4..7 {
-                                    row.create_button(|b| {
-                                        b.custom_id(format!("col_{}", i))
-                                        .label(format!("{}", i + 1))
-                                        .style(serenity::ButtonStyle::Secondary)
-                                        .disabled(!game.is_valid_move(i))
-                                    });
-                                }
-                                row
-                            })
-                        }
-                    })
-                }).await?;
-                
-                // If game is against computer and it's the computer's turn
-                if game.ai_mode && !game.game_over && game.current_player == Connect4Cell::Yellow {
-                    // Wait a moment to make it look like the computer is thinking
-                    tokio::time::sleep(Duration::from_millis(1500)).await;
+                for stronger_is_red in [true, false] {
+                    let (red, yellow) = if stronger_is_red { (stronger, weaker) } else { (weaker, stronger) };
                     
-                    if let Some(ai_col) = game.ai_move() {
-                        // Update message with the computer's move
-                        msg.edit(ctx, |m| {
-                            m.content(format!(
-                                "**☭ Connect 4: People's Revolution Edition ☭**\n\n{}\n\nComputer played in column {}.\n{}",
-                                game.render_board(), 
-                                ai_col + 1,
-                                game.render_status(&author, opponent.as_ref())
-                            ))
-                            .components(|c| {
-                                if game.game_over {
-                                    c // No components if game is over
-                                } else {
-                                    c.create_action_row(|row| {
-                                        for i in 0..4 {
-                                            row.create_button(|b| {
-                                                b.custom_id(format!("col_{}", i))
-                                                .label(format!("{}", i + 1))
-                                                .style(serenity::ButtonStyle::Secondary)
-                                                .disabled(!game.is_valid_move(i))
-                                            });
-                                        }
-                                        row
-                                    });
-                                    c.create_action_row(|row| {
-                                        for i in 4..7 {
-                                            row.create_button(|b| {
-                                                b.custom_id(format!("col_{}", i))
-                                                .label(format!("{}", i + 1))
-                                                .style(serenity::ButtonStyle::Secondary)
-                                                .disabled(!game.is_valid_move(i))
-                                            });
-                                        }
-                                        row
-                                    })
-                                }
-                            })
-                        }).await?;
-                    }
+                    points += match play_levels(&opening, red, yellow, &mut rng) {
+                        Outcome::Draw => 0.5,
+                        Outcome::Win(0) if stronger_is_red => 1.0,
+                        Outcome::ComputerWin if !stronger_is_red => 1.0,
+                        _ => 0.0,
+                    };
+                    games += 1;
                 }
             }
-        } else {
-            // Timeout or error - end the game
-            msg.edit(ctx, |m| {
-                m.content(format!(
-                    "**☭ Connect 4: People's Revolution Edition ☭**\n\n{}\n\nGame abandoned due to inactivity! The people demand action!",
-                    game.render_board()
-                ))
-                .components(|c| c)
-            }).await?;
-            break;
+            
+            assert!(
+                points > games as f64 / 2.0,
+                "{:?} only scored {} of {} against {:?}", stronger, points, games, weaker
+            );
         }
     }
     
-    // Game is over, final message
-    if game.game_over {
-        let final_message = if let Some(winner) = game.winner {
-            match winner {
-                Connect4Cell::Red => "The Red Revolution has prevailed! The workers control the means of production!",
-                Connect4Cell::Yellow => {
-                    if game.ai_mode {
-                        "The capitalist AI has temporarily gained control... but history is on our side!"
-                    } else {
-                        "The Yellow Faction has seized victory! All hail our new revolutionary leader!"
-                    }
-                },
-                _ => "The game ended without a clear outcome."
-            }
-        } else {
-            "A perfect draw - cooperation and equality have prevailed!"
-        };
-        
-        msg.edit(ctx, |m| {
-            m.content(format!(
-                "**☭ Connect 4: People's Revolution Edition ☭**\n\n{}\n\n{}\n\n{}", 
-                game.render_board(),
-                game.render_status(&author, opponent.as_ref()),
-                final_message
-            ))
-            .components(|c| c) // Clear components
-        }).await?;
+    #[test]
+    fn connect4_difficulty_names() {
+        assert_eq!(Connect4Difficulty::from_name(" General "), Some(Connect4Difficulty::General));
+        assert_eq!(Connect4Difficulty::from_name("easy"), Some(Connect4Difficulty::Conscript));
+        assert_eq!(Connect4Difficulty::from_name("tsar"), None);
     }
     
-    Ok(())
-} 
\ No newline at end of file
+    fn comrade(id: u64, name: &str) -> serenity::User {
+        let mut user = serenity::User::default();
+        user.id = serenity::UserId(id);
+        user.name = name.to_string();
+        user.bot = false;
+        user
+    }
+    
+    fn click(by: &serenity::User, position: usize, millis: u64) -> ClickerMove {
+        ClickerMove::Click { by: by.id, position, reaction: Duration::from_millis(millis) }
+    }
+    
+    #[test]
+    fn clicker_scores_correct_clicks() {
+        let host = comrade(1, "Lenin");
+        let mut game = ComradeClicker::new(ClickerMode::Solo, &host, 3, 2, 0);
+        assert_eq!(game.legal_moves(), vec![ClickerMove::Start { by: host.id }]);
+        
+        // Clicks before the start don't count
+        assert!(!game.apply_move(click(&host, 0, 0)));
+        play(&mut game, &[ClickerMove::Start { by: host.id }]);
+        assert!(!game.apply_move(ClickerMove::Start { by: host.id }));
+        
+        // A wrong click adds to the round's time instead of ending it
+        let active = game.active_cell.unwrap();
+        let wrong = (active + 1) % 9;
+        play(&mut game, &[click(&host, wrong, 100), click(&host, active, 200)]);
+        assert_eq!(game.round, 1);
+        assert!(game.outcome().is_none());
+        
+        let active = game.active_cell.unwrap();
+        play(&mut game, &[click(&host, active, 100)]);
+        assert_eq!(game.turn(), Turn::Over);
+        assert_eq!(game.outcome(), Some(Outcome::Score(200.0)));
+        assert_eq!(game.score_key(), "clicker");
+    }
+    
+    #[test]
+    fn clicker_decoys_cost_time() {
+        let host = comrade(1, "Lenin");
+        let mut game = ComradeClicker::new(ClickerMode::Solo, &host, 3, 1, 2);
+        play(&mut game, &[ClickerMove::Start { by: host.id }]);
+        
+        let active = game.active_cell.unwrap();
+        assert_eq!(game.decoys.len(), 2);
+        assert!(!game.decoys.contains(&active));
+        
+        let decoy = game.decoys[0];
+        play(&mut game, &[click(&host, decoy, 100), click(&host, active, 100)]);
+        let expected = (Duration::from_millis(200) + DECOY_PENALTY).as_secs_f64() * 1000.0;
+        assert_eq!(game.outcome(), Some(Outcome::Score(expected)));
+    }
+    
+    #[test]
+    fn clicker_race_goes_to_the_first_correct_click() {
+        let (host, rival, latecomer) = (comrade(1, "Lenin"), comrade(2, "Trotsky"), comrade(3, "Kamenev"));
+        let mut game = ComradeClicker::new(ClickerMode::Race, &host, 3, 2, 1);
+        assert_eq!(game.turn(), Turn::Open);
+        
+        // Anyone may join before the start, once, but only the host starts
+        play(&mut game, &[ClickerMove::Join(rival.clone())]);
+        assert!(!game.apply_move(ClickerMove::Join(rival.clone())));
+        assert!(!game.apply_move(ClickerMove::Start { by: rival.id }));
+        play(&mut game, &[ClickerMove::Start { by: host.id }]);
+        assert!(!game.apply_move(ClickerMove::Join(latecomer.clone())));
+        
+        // Strangers and plain misses are ignored; a decoy costs a point
+        let active = game.active_cell.unwrap();
+        let decoy = game.decoys[0];
+        let blank = (0..9).find(|&p| p != active && p != decoy).unwrap();
+        assert!(!game.apply_move(click(&latecomer, active, 50)));
+        assert!(!game.apply_move(click(&host, blank, 50)));
+        play(&mut game, &[click(&host, decoy, 50), click(&rival, active, 150)]);
+        assert_eq!((game.racers[0].points, game.racers[1].points), (-1, 1));
+        
+        let active = game.active_cell.unwrap();
+        play(&mut game, &[click(&rival, active, 300)]);
+        assert_eq!(game.outcome(), Some(Outcome::Raced));
+        assert_eq!(game.standings()[0].user.id, rival.id);
+        
+        // Only round winners are scored, on their own leaderboard
+        assert_eq!(game.score_key(), "clicker_race");
+        assert_eq!(game.race_scores(), vec![(rival, race_score(2, 250.0))]);
+        assert_eq!(ComradeClicker::format_score(race_score(2, 250.0)), "2 rounds won, 250ms average");
+    }
+    
+    #[test]
+    fn clicker_race_ranks_rounds_won_before_speed() {
+        // One lucky fast round doesn't beat nine slower ones; equal rounds go to the faster
+        let steady = race_score(9, 400.0);
+        let lucky = race_score(1, 90.0);
+        let quicker = race_score(9, 350.0);
+        assert!(quicker < steady && steady < lucky);
+        
+        assert_eq!(format_race_score(lucky), "1 round won, 90ms average");
+        assert_eq!(format_race_score(race_score(20, 5_000_000.0)), "20 rounds won, 999999ms average");
+        assert_eq!(format_race_score(250.0), "250ms");
+    }
+    
+    #[test]
+    fn clicker_parses_buttons() {
+        let host = comrade(1, "Lenin");
+        let game = ComradeClicker::new(ClickerMode::Race, &host, 5, 5, 0);
+        let reaction = Duration::from_millis(321);
+        assert_eq!(game.parse_move("start_game", &host, reaction), Some(ClickerMove::Start { by: host.id }));
+        assert_eq!(game.parse_move("join_race", &host, reaction), Some(ClickerMove::Join(host.clone())));
+        assert_eq!(game.parse_move("cell_7", &host, reaction), Some(ClickerMove::Click { by: host.id, position: 7, reaction }));
+        assert_eq!(game.parse_move("col_1", &host, reaction), None);
+    }
+    
+    #[test]
+    fn saved_rounds_survive_serialization() {
+        let mut round = SavedRound { game: Connect4::new(true, Connect4Difficulty::Marshal), players: Vec::new(), wager: Some(25.0) };
+        play(&mut round.game, &[3, 3, 2]);
+        
+        let restored: SavedRound<Connect4> = serde_json::from_str(&serde_json::to_string(&round).unwrap()).unwrap();
+        assert_eq!((restored.game.red, restored.game.yellow), (round.game.red, round.game.yellow));
+        assert_eq!(restored.game.turn(), Turn::Computer);
+        assert_eq!(restored.wager, Some(25.0));
+    }
+}
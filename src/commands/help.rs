@@ -19,19 +19,38 @@ pub async fn help(ctx: Context<'_>) -> Result<(), CommandError> {
 **/prosper** - Level up your job (33% success)", false)
                 .field("Games", "
 **/game** - Shows available games
-**/tictactoe [@user]** - Play tic-tac-toe
-**/connect4 [@user]** - Play Connect 4
-**/clicker** - Test your reaction time
+**/tictactoe [@user] [wager] [difficulty]** - Play tic-tac-toe
+**/connect4 [@user] [wager] [difficulty]** - Play Connect 4
+**/battleship [@user] [wager] [difficulty]** - Naval Command: sink the enemy fleet
+**/chess @user [wager]** - People's Chess, with moves typed in algebraic notation
+**/checkers @user [wager]** - Proletarian Checkers, where every capture is compulsory
+**/blackjack [bet]** - Wager boops at blackjack
+**/rank [user]** - Show ranked game ratings
+**/leaderboard [game]** - Top players of a game
+**/stats [user]** - Wins, losses and best scores across every game
+**/clicker [mode] [grid_size] [rounds] [decoys]** - Test your reaction time, alone or racing the channel
 **/kremlin_secrets [difficulty]** - Word challenge
-**/soviet_hangman** - Word guessing game", false)
+**/soviet_hangman [difficulty] [mode]** - Word guessing game, solo or with the channel
+**/trivia [mode] [pack] [questions]** - Timed Soviet trivia, alone or racing the channel
+**/daily hangman|kremlin** - Today's puzzle, one attempt each
+**/daily leaderboard [game]** - Today's fewest guesses", false)
                 .field("Admin Commands", "
 **/distribute** - Distribute all communal boops
 **/sync_users** - Sync server members to database
+**/chat_income** - Configure boops earned from chatting
+**/autoreact** - Manage automatic emoji reactions
+**/kgb settings** - Configure the KGB listener
+**/wordbank** - Manage the words used by the word games
+**/hangman_settings** - Choose buttons or menus for hangman
+**/trivia_pack** - Import and manage trivia question packs
 **/reset_cooldowns** - Clear your cooldowns (owner)
 **/list_users** - List all users in database (owner)
 **/reset_server** - Reset server data (owner)
 **/redistribute [percentage]** - Redistribute wealth", false)
                 .field("Utilities", "
+**/kgb optout** - Stop the KGB from listening to you
+**/dossier [user]** - Open a comrade's KGB file
+**/kgb forget** - Have your KGB file burned
 **/about** - Bot information
 **/help** - Show this message", false)
                 .color(0xE74C3C) // Red color for Soviet theme
@@ -0,0 +1,155 @@
+use crate::CommandError;
+use super::admin::check_if_admin;
+use super::autoreact::parse_channel_ids;
+use poise::serenity_prelude as serenity;
+
+/// Manage the KGB listener
+#[poise::command(slash_command, prefix_command, subcommands("kgb_settings", "kgb_optout", "kgb_optin", "kgb_forget"))]
+pub async fn kgb(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    ctx.say("Use `/kgb optout`, `/kgb optin`, `/kgb forget` or `/kgb settings`, comrade.").await?;
+    Ok(())
+}
+
+/// Configure when the KGB listener overhears conversations (Admin only)
+#[poise::command(slash_command, prefix_command, rename = "settings", check = "check_if_admin")]
+pub async fn kgb_settings(
+    ctx: crate::Context<'_>,
+    #[description = "Enable or disable the KGB listener"]
+    enabled: Option<bool>,
+    #[description = "Chance to overhear an eligible message, in percent (0-100, default: 0.5)"]
+    probability: Option<f64>,
+    #[description = "Channels to listen in, as mentions or IDs (\"all\" to listen everywhere)"]
+    channels: Option<String>,
+    #[description = "Minutes between any two KGB remarks in this server (0-1440)"]
+    global_cooldown_minutes: Option<i64>,
+    #[description = "Minutes before the same comrade can be overheard again (0-10080)"]
+    user_cooldown_minutes: Option<i64>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+    
+    let db = &ctx.data().db;
+    let mut settings = db.get_kgb_settings(&server_id).await?;
+    
+    let changed = enabled.is_some()
+        || probability.is_some()
+        || channels.is_some()
+        || global_cooldown_minutes.is_some()
+        || user_cooldown_minutes.is_some();
+    
+    // Apply any provided changes, keeping values within sane bounds
+    if let Some(enabled) = enabled {
+        settings.enabled = enabled;
+    }
+    if let Some(probability) = probability {
+        settings.probability = probability.clamp(0.0, 100.0) / 100.0;
+    }
+    if let Some(channels) = channels {
+        settings.channels = if channels.trim().eq_ignore_ascii_case("all") {
+            Vec::new()
+        } else {
+            parse_channel_ids(&channels)
+        };
+    }
+    if let Some(minutes) = global_cooldown_minutes {
+        settings.global_cooldown_secs = minutes.clamp(0, 1440) * 60;
+    }
+    if let Some(minutes) = user_cooldown_minutes {
+        settings.user_cooldown_secs = minutes.clamp(0, 10080) * 60;
+    }
+    
+    if changed {
+        db.set_kgb_settings(&server_id, &settings).await?;
+    }
+    
+    let channels_text = if settings.channels.is_empty() {
+        "All channels".to_string()
+    } else {
+        settings.channels.iter().map(|c| format!("<#{}>", c)).collect::<Vec<_>>().join(", ")
+    };
+    
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title("☭ Committee for State Security ☭")
+             .description(if changed {
+                 "Surveillance directives have been updated."
+             } else {
+                 "Current surveillance directives for this collective."
+             })
+             .color(if settings.enabled { serenity::Color::RED } else { serenity::Color::DARK_GREY })
+             .field("Status", if settings.enabled { "Listening" } else { "Suspended" }, true)
+             .field("Probability", format!("**{:.2}%** per message", settings.probability * 100.0), true)
+             .field("Channels", channels_text, false)
+             .field("Server Cooldown", format!("**{}** minutes", settings.global_cooldown_secs / 60), true)
+             .field("Comrade Cooldown", format!("**{}** minutes", settings.user_cooldown_secs / 60), true)
+             .footer(|f| f.text("Comrades may exempt themselves with /kgb optout."))
+        })
+    }).await?;
+    
+    Ok(())
+}
+
+/// Ask the KGB to never listen to your conversations
+#[poise::command(slash_command, prefix_command, rename = "optout")]
+pub async fn kgb_optout(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+    
+    let db = &ctx.data().db;
+    db.set_kgb_optout(&ctx.author().id.to_string(), &server_id, true).await?;
+    
+    ctx.send(|m| {
+        m.content("Your file has been sealed, comrade. The KGB will no longer listen to your conversations. Use `/kgb optin` to resume surveillance.")
+         .ephemeral(true)
+    }).await?;
+    
+    Ok(())
+}
+
+/// Allow the KGB to listen to your conversations again
+#[poise::command(slash_command, prefix_command, rename = "optin")]
+pub async fn kgb_optin(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+    
+    let db = &ctx.data().db;
+    db.set_kgb_optout(&ctx.author().id.to_string(), &server_id, false).await?;
+    
+    ctx.send(|m| {
+        m.content("Your loyalty is noted, comrade. The KGB may once again overhear your conversations.")
+         .ephemeral(true)
+    }).await?;
+    
+    Ok(())
+}
+
+/// Request that the KGB burn your dossier
+#[poise::command(slash_command, prefix_command, rename = "forget")]
+pub async fn kgb_forget(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+    
+    let db = &ctx.data().db;
+    let removed = db.delete_dossier(&ctx.author().id.to_string(), &server_id).await?;
+    
+    let content = if removed > 0 {
+        format!("Your dossier has been fed to the furnace, comrade. {} remarks are now ash. Use `/kgb optout` to stop new ones being filed.", removed)
+    } else {
+        "The KGB has no file on you to burn, comrade.".to_string()
+    };
+    
+    ctx.send(|m| {
+        m.content(content)
+         .ephemeral(true)
+    }).await?;
+    
+    Ok(())
+}
@@ -1,10 +1,40 @@
-use crate::CommandError;
+use crate::{CommandError, Data};
+use crate::db::{ActiveGame, GameResult};
+use super::active_games::{self, save_game, forget_game, Resumed};
+use poise::futures_util::StreamExt;
 use poise::serenity_prelude as serenity;
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use super::similarity::SimilarityEngine;
 use rand::seq::SliceRandom;
-use std::collections::HashSet;
+use super::word_banks::{pick_word, WordDifficulty, WordEntry, WORD_REWARDS_PER_DAY};
+use super::daily::share_note;
+use super::soviet_hangman::modal_text;
+
+const THUMBNAIL_URL: &str = "https://upload.wikimedia.org/wikipedia/commons/thumb/5/58/Coat_of_arms_of_the_KGB.svg/240px-Coat_of_arms_of_the_KGB.svg.png";
+
+const INSTRUCTIONS: &str = "I'm thinking of a word related to Soviet history and ideology.\n\
+                            Guess the word, and I'll tell you how close you are.\n\
+                            The temperature indicates your proximity to the secret term.";
+
+// How long a comrade has to uncover the secret
+const TIME_LIMIT: Duration = Duration::from_secs(180);
+
+// How long the Kremlin deliberates over each guess before answering
+const THINKING_TIME: Duration = Duration::from_millis(1200);
+
+// Longest guess the pop-up accepts
+const MAX_GUESS_LENGTH: u64 = 40;
+
+// Marks per line of a shared result
+const MARKS_PER_LINE: usize = 6;
+
+// Guesses sent closer together than this are turned away, which also keeps
+// edits to the game message well inside Discord's rate limits
+const GUESS_COOLDOWN: Duration = Duration::from_secs(2);
 
 // Kremlin Secrets Game - Word Association Challenge
+#[derive(Serialize, Deserialize)]
 struct KremlinSecrets {
     target_word: String,
     category: String,
@@ -13,16 +43,19 @@ struct KremlinSecrets {
     max_guesses: usize,
     game_over: bool,
     won: bool,
+    // The last guess if it had already been made, so it cost nothing
+    #[serde(default)]
+    repeated_guess: Option<String>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 enum KremlinDifficulty {
     Citizen,    // Easy
     Comrade,    // Medium
     Commissar,  // Hard
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 enum TemperatureLevel {
     Freezing,
     Cold,
@@ -41,6 +74,15 @@ impl KremlinDifficulty {
         }
     }
     
+    // Harder ranks are given harder words as well as fewer guesses
+    fn word_difficulty(&self) -> WordDifficulty {
+        match self {
+            KremlinDifficulty::Citizen => WordDifficulty::Easy,
+            KremlinDifficulty::Comrade => WordDifficulty::Medium,
+            KremlinDifficulty::Commissar => WordDifficulty::Hard,
+        }
+    }
+    
     fn display_name(&self) -> &str {
         match self {
             KremlinDifficulty::Citizen => "Citizen",
@@ -102,66 +144,40 @@ impl TemperatureLevel {
 }
 
 impl KremlinSecrets {
-    fn new(difficulty: KremlinDifficulty) -> Self {
-        // Pick a random category and word
-        let (category, word) = Self::random_word_and_category();
-        
+    fn new(difficulty: KremlinDifficulty, entry: WordEntry) -> Self {
         KremlinSecrets {
-            target_word: word,
-            category,
+            target_word: entry.word,
+            category: entry.category,
             difficulty,
             guesses: Vec::new(),
             max_guesses: difficulty.max_guesses(),
             game_over: false,
             won: false,
+            repeated_guess: None,
         }
     }
     
-    fn random_word_and_category() -> (String, String) {
-        let categories = [
-            ("Soviet Leaders", vec![
-                "Lenin", "Stalin", "Khrushchev", "Brezhnev", "Gorbachev", 
-                "Andropov", "Chernenko", "Malenkov", "Bulganin", "Kosygin"
-            ]),
-            ("Revolutionary Concepts", vec![
-                "Communism", "Socialism", "Proletariat", "Bourgeoisie", "Revolution",
-                "Collectivization", "Vanguard", "Dialectic", "Materialism", "Classless"
-            ]),
-            ("Soviet Geography", vec![
-                "Moscow", "Leningrad", "Kiev", "Stalingrad", "Minsk",
-                "Vladivostok", "Siberia", "Ural", "Crimea", "Volga"
-            ]),
-            ("Cold War Terms", vec![
-                "Detente", "Containment", "Iron Curtain", "Berlin Wall", "Glasnost",
-                "Perestroika", "Domino Theory", "Missiles", "Espionage", "Defector"
-            ]),
-            ("Soviet Achievements", vec![
-                "Sputnik", "Gagarin", "Vostok", "Collective", "Industrialization",
-                "Literacy", "Electrification", "Space Station", "Olympic", "Science"
-            ])
-        ];
-        
-        let mut rng = rand::thread_rng();
-        let (category, words) = categories.choose(&mut rng).unwrap();
-        let word = words.choose(&mut rng).unwrap();
-        
-        (category.to_string(), word.to_string())
-    }
-    
-    fn make_guess(&mut self, guess: &str) -> TemperatureLevel {
+    // Score a guess. Returns None for a word that was already guessed, which uses up no attempt.
+    fn make_guess(&mut self, guess: &str) -> Option<TemperatureLevel> {
         // Convert guess to lowercase
         let guess = guess.trim().to_lowercase();
         let target = self.target_word.to_lowercase();
         
+        if self.guesses.iter().any(|(previous, _)| *previous == guess) {
+            self.repeated_guess = Some(guess);
+            return None;
+        }
+        self.repeated_guess = None;
+        
         // Check if the guess is correct
         if guess == target {
             self.game_over = true;
             self.won = true;
-            return TemperatureLevel::Burning;
+            return Some(TemperatureLevel::Burning);
         }
         
-        // Calculate similarity score (simple version)
-        let similarity = self.calculate_similarity(&guess, &target);
+        // How close the guess is in meaning, or in spelling for words we don't know
+        let similarity = SimilarityEngine::standard().score(&guess, &target);
         let temperature = TemperatureLevel::from_similarity(similarity);
         
         // Store the guess and temperature
@@ -172,63 +188,7 @@ impl KremlinSecrets {
             self.game_over = true;
         }
         
-        temperature
-    }
-    
-    // A simple method to estimate word similarity without external libraries
-    fn calculate_similarity(&self, word1: &str, word2: &str) -> f64 {
-        // If either word is empty, return 0
-        if word1.is_empty() || word2.is_empty() {
-            return 0.0;
-        }
-        
-        // If words are identical, return 1.0
-        if word1 == word2 {
-            return 1.0;
-        }
-        
-        // Check for exact prefixes
-        let min_len = word1.len().min(word2.len());
-        
-        // Compare character by character from the start
-        let mut prefix_match = 0;
-        for (c1, c2) in word1.chars().zip(word2.chars()) {
-            if c1 == c2 {
-                prefix_match += 1;
-            } else {
-                break;
-            }
-        }
-        
-        let prefix_sim = prefix_match as f64 / min_len as f64;
-        
-        // Character set similarity
-        let chars1: HashSet<char> = word1.chars().collect();
-        let chars2: HashSet<char> = word2.chars().collect();
-        
-        let common_chars = chars1.intersection(&chars2).count();
-        let total_chars = chars1.union(&chars2).count();
-        
-        let set_sim = common_chars as f64 / total_chars as f64;
-        
-        // Length similarity
-        let length_ratio = if word1.len() > word2.len() {
-            word2.len() as f64 / word1.len() as f64
-        } else {
-            word1.len() as f64 / word2.len() as f64
-        };
-        
-        // Calculate final similarity score (weighted)
-        let similarity = (prefix_sim * 0.5) + (set_sim * 0.3) + (length_ratio * 0.2);
-        
-        // Apply semantic hints based on the category
-        let semantic_boost = if word1.contains(word2) || word2.contains(word1) {
-            0.2 // Boost if one is substring of the other
-        } else {
-            0.0
-        };
-        
-        (similarity + semantic_boost).min(0.95) // Cap at 0.95 to require exact match for 1.0
+        Some(temperature)
     }
     
     fn render_status(&self) -> String {
@@ -241,6 +201,12 @@ impl KremlinSecrets {
             } else {
                 format!("**GAME OVER.** The state secret was **{}**.", self.target_word)
             }
+        } else if let Some(repeated) = &self.repeated_guess {
+            format!(
+                "**Attempts remaining:** {} out of {}\n\
+                You already guessed `{}`, comrade. The KGB does not charge you twice.",
+                guesses_left, self.max_guesses, repeated
+            )
         } else {
             format!("**Attempts remaining:** {} out of {}", guesses_left, self.max_guesses)
         };
@@ -255,6 +221,23 @@ impl KremlinSecrets {
         )
     }
     
+    // A spoiler-free summary of the game to paste in chat: each guess's temperature
+    fn share_grid(&self, date: &str) -> String {
+        let mut marks: Vec<&str> = self.guesses.iter().map(|(_, temp)| temp.to_emoji()).collect();
+        if self.won {
+            marks.push(TemperatureLevel::Burning.to_emoji());
+        }
+        
+        let lines: Vec<String> = marks.chunks(MARKS_PER_LINE).map(|line| line.concat()).collect();
+        let outcome = if self.won {
+            format!("Uncovered in {}/{} guesses", marks.len(), self.max_guesses)
+        } else {
+            format!("Secret kept after {} guesses", marks.len())
+        };
+        
+        format!("☭ Kremlin Secrets · Daily {}\n{}\n{}", date, lines.join("\n"), outcome)
+    }
+    
     fn render_guesses(&self) -> String {
         if self.guesses.is_empty() {
             return String::from("No guesses yet. Enter your first guess...");
@@ -290,6 +273,443 @@ impl KremlinSecrets {
     }
 }
 
+// A game, its player and the time they have left as of the last save.
+// Saved after every guess so the game can be resumed after a restart.
+#[derive(Serialize, Deserialize)]
+struct KremlinSession {
+    player: serenity::UserId,
+    game: KremlinSecrets,
+    seconds_left: u64,
+    // When the last counted guess was sent, for the cooldown
+    #[serde(default)]
+    last_guess_ms: Option<u64>,
+    // The date of the server's daily puzzle, if this is the player's daily attempt
+    #[serde(default)]
+    daily: Option<String>,
+}
+
+// When Discord created a message or interaction, in milliseconds since its epoch. Read
+// from the id, so it doesn't depend on our clock or on when the event reached us.
+fn sent_at_ms(id: u64) -> u64 {
+    id >> 22
+}
+
+// What became of a guess, shown once the game has been updated
+pub(crate) enum Ruling {
+    // It came too soon after the last one and was turned away
+    TooSoon,
+    // It was counted: the committee deliberates, then rules. A finished daily attempt
+    // also leaves a note with its share grid in the channel.
+    Counted {
+        thinking: serenity::CreateEmbed,
+        outcome: serenity::CreateEmbed,
+        finished: bool,
+        share: Option<String>,
+    },
+}
+
+impl Ruling {
+    // Show the ruling on a game message in the channel, tidying the guess away
+    pub(crate) async fn show_in_channel(
+        self,
+        ctx: &serenity::Context,
+        game_message: serenity::MessageId,
+        guess: &serenity::Message,
+    ) -> Result<(), CommandError> {
+        let (thinking, outcome) = match self {
+            Ruling::TooSoon => {
+                let _ = guess.react(&ctx.http, '⏳').await;
+                return Ok(());
+            },
+            Ruling::Counted { thinking, outcome, .. } => (thinking, outcome),
+        };
+        
+        // Try to delete the user's message to keep the channel clean
+        let _ = guess.delete(&ctx.http).await;
+        
+        guess.channel_id.edit_message(&ctx.http, game_message, |m| m.set_embed(thinking)).await?;
+        tokio::time::sleep(THINKING_TIME).await;
+        guess.channel_id.edit_message(&ctx.http, game_message, |m| m.set_embed(outcome)).await?;
+        
+        Ok(())
+    }
+    
+    // Show the ruling on a daily attempt's private message, in answer to the pop-up
+    // the guess was typed into
+    pub(crate) async fn show_in_reply(
+        self,
+        ctx: &serenity::Context,
+        submit: &serenity::ModalSubmitInteraction,
+    ) -> Result<(), CommandError> {
+        let (thinking, outcome, finished, share) = match self {
+            Ruling::TooSoon => {
+                submit.create_interaction_response(ctx, |r| {
+                    r.kind(serenity::InteractionResponseType::ChannelMessageWithSource)
+                     .interaction_response_data(|d| {
+                         d.content("The committee is still ruling on your last guess, comrade.").ephemeral(true)
+                     })
+                }).await?;
+                return Ok(());
+            },
+            Ruling::Counted { thinking, outcome, finished, share } => (thinking, outcome, finished, share),
+        };
+        
+        submit.create_interaction_response(ctx, |r| {
+            r.kind(serenity::InteractionResponseType::UpdateMessage)
+             .interaction_response_data(|d| d.set_embed(thinking))
+        }).await?;
+        tokio::time::sleep(THINKING_TIME).await;
+        submit.edit_original_interaction_response(ctx, |r| {
+            r.set_embed(outcome);
+            if finished {
+                r.components(|c| c);
+            }
+            r
+        }).await?;
+        
+        if let Some(note) = share {
+            submit.channel_id.say(ctx, note).await?;
+        }
+        
+        Ok(())
+    }
+}
+
+impl KremlinSession {
+    // Record an ended game, won, lost or expired, and pay for a win.
+    // Returns the boops paid, if any.
+    async fn settle(&self, data: &Data, server_id: &str, username: &str) -> Result<Option<f64>, CommandError> {
+        let user_id = self.player.to_string();
+        let guesses = self.game.guesses.len() + self.game.won as usize;
+        
+        let result = GameResult {
+            user_id: user_id.clone(),
+            server_id: server_id.to_string(),
+            username: username.to_string(),
+            game_type: active_games::KREMLIN_SECRETS.to_string(),
+            score: guesses as f64,
+            won: self.game.won,
+            guesses: guesses as i64,
+            duration_secs: Some(TIME_LIMIT.as_secs().saturating_sub(self.seconds_left) as f64),
+        };
+        data.db.save_game_result(&result).await?;
+        
+        if let Some(date) = &self.daily {
+            data.db.finish_daily_attempt(date, &result, &self.game.share_grid(date)).await?;
+        }
+        
+        // There is no treasury to pay from in DMs
+        if !self.game.won || server_id == "DM" {
+            return Ok(None);
+        }
+        
+        // Only so many wins a day are paid for
+        let reward = self.game.difficulty.word_difficulty().reward();
+        data.db.ensure_user_exists(&user_id, server_id, username).await?;
+        let paid = data.db.claim_daily_reward(
+            &user_id,
+            server_id,
+            active_games::KREMLIN_SECRETS,
+            reward,
+            WORD_REWARDS_PER_DAY,
+        ).await?;
+        
+        Ok(paid.then_some(reward))
+    }
+    
+    // How the player makes their next guess
+    fn guess_prompt(&self) -> &'static str {
+        match self.daily {
+            Some(_) => "Press Submit a Guess. Only you can see this game.",
+            None => "Type your next guess in chat.",
+        }
+    }
+    
+    // The note a daily attempt leaves in the channel once it has ended
+    fn share(&self) -> Option<String> {
+        self.daily.as_ref().map(|date| share_note(self.player, &self.game.share_grid(date)))
+    }
+    
+    // Whether a guess sent at `sent_at` comes after the Kremlin has finished thinking about the last
+    fn accepts_guess_at(&self, sent_at: u64) -> bool {
+        self.last_guess_ms.is_none_or(|last| sent_at >= last + GUESS_COOLDOWN.as_millis() as u64)
+    }
+    
+    // Score a guess sent at `sent_at` and settle the game if it ended. Nothing is shown
+    // here; the ruling says what to show.
+    async fn apply_guess(
+        &mut self,
+        data: &Data,
+        server_id: &str,
+        username: &str,
+        words: &str,
+        sent_at: u64,
+    ) -> Result<Ruling, CommandError> {
+        if !self.accepts_guess_at(sent_at) {
+            return Ok(Ruling::TooSoon);
+        }
+        self.last_guess_ms = Some(sent_at);
+        
+        let mut thinking = serenity::CreateEmbed::default();
+        render_thinking(&mut thinking, &self.game, words);
+        
+        // A repeated guess keeps the colour of the last new one
+        let color = match self.game.make_guess(words) {
+            Some(temperature) => temperature.to_color(),
+            None => self.game.guesses.last().map_or(serenity::Color::RED, |(_, t)| t.to_color()),
+        };
+        
+        let reward = if self.game.game_over {
+            self.settle(data, server_id, username).await?
+        } else {
+            None
+        };
+        
+        let mut outcome = serenity::CreateEmbed::default();
+        render_embed(&mut outcome, self, color, reward);
+        
+        Ok(Ruling::Counted {
+            thinking,
+            outcome,
+            finished: self.game.game_over,
+            share: self.share().filter(|_| self.game.game_over),
+        })
+    }
+    
+    // Settle a game whose time has run out. Returns its final embed.
+    async fn expire(&mut self, data: &Data, server_id: &str, username: &str) -> Result<serenity::CreateEmbed, CommandError> {
+        self.seconds_left = 0;
+        self.settle(data, server_id, username).await?;
+        
+        let mut expired = serenity::CreateEmbed::default();
+        render_expired(&mut expired, self);
+        Ok(expired)
+    }
+    
+    // Take the time since a saved game was last updated off its clock
+    fn catch_up(&mut self, record: &ActiveGame) {
+        let elapsed = (chrono::Utc::now().timestamp() - record.updated_at).max(0) as u64;
+        self.seconds_left = self.seconds_left.saturating_sub(elapsed);
+    }
+}
+
+fn render_embed<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    session: &KremlinSession,
+    color: serenity::Color,
+    reward: Option<f64>,
+) -> &'a mut serenity::CreateEmbed {
+    let game = &session.game;
+    let seconds_left = session.seconds_left;
+    
+    e.title("☭ Kremlin Secrets ☭")
+     .description("State Security Word Association Test")
+     .color(color)
+     .thumbnail(THUMBNAIL_URL)
+     .field("Instructions", INSTRUCTIONS, false)
+     .field("Status", game.render_status(), false)
+     .field("Guesses", game.render_guesses(), false);
+    
+    if !game.game_over {
+        e.field("Hint", game.get_hint(), false);
+    } else if game.won {
+        e.field("Result", 
+               format!("Correct! The word was **{}**.\nYou've proven yourself a worthy member of the Party!", 
+                      game.target_word), false);
+        if let Some(reward) = reward {
+            e.field("Reward", format!("**{:.2}** boops for a {} word", reward, game.difficulty.word_difficulty().name()), false);
+        }
+    } else {
+        e.field("Result", 
+               format!("The state secret was **{}**.\nBetter luck next time, comrade.", 
+                      game.target_word), false);
+    }
+    
+    if let (true, Some(date)) = (game.game_over, &session.daily) {
+        e.field("Share", game.share_grid(date), false);
+    }
+    
+    e.footer(|f| {
+        if game.game_over && session.daily.is_some() {
+            f.text("A new daily secret is issued tomorrow. See /daily leaderboard.")
+        } else if game.game_over {
+            f.text("Game over. Type /kremlin_secrets to play again.")
+        } else {
+            f.text(format!("{} Time remaining: {}m {}s", session.guess_prompt(), seconds_left / 60, seconds_left % 60))
+        }
+    })
+}
+
+// Shown while a guess is being considered
+fn render_thinking<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    game: &KremlinSecrets,
+    guess: &str,
+) -> &'a mut serenity::CreateEmbed {
+    e.title("☭ Kremlin Secrets ☭")
+     .description("State Security Word Association Test")
+     .color(serenity::Color::GOLD)
+     .thumbnail(THUMBNAIL_URL)
+     .field("Status", game.render_status(), false)
+     .field("Guesses", game.render_guesses(), false)
+     .field("Under Review", format!("The Politburo is deliberating on `{}`...", guess), false)
+     .footer(|f| f.text("Further guesses must wait until the committee has ruled."))
+}
+
+fn render_expired<'a>(e: &'a mut serenity::CreateEmbed, session: &KremlinSession) -> &'a mut serenity::CreateEmbed {
+    let game = &session.game;
+    if let Some(date) = &session.daily {
+        e.field("Share", game.share_grid(date), false);
+    }
+    
+    e.title("☭ Kremlin Secrets ☭")
+     .description("Operation Terminated")
+     .color(serenity::Color::DARK_GREY)
+     .thumbnail(THUMBNAIL_URL)
+     .field("Status", 
+            format!("Time expired. The secret word was **{}**.\nThe KGB has noted your lack of participation.", 
+                   game.target_word), false)
+     .footer(|f| f.text("Game abandoned. Type /kremlin_secrets to try again."))
+}
+
+// The button under a daily attempt, which opens the pop-up guesses are typed into
+fn create_guess_button(c: &mut serenity::CreateComponents) -> &mut serenity::CreateComponents {
+    c.create_action_row(|row| {
+        row.create_button(|b| {
+            b.custom_id("kremlin_secrets_guess")
+             .label("Submit a Guess")
+             .style(serenity::ButtonStyle::Danger)
+        })
+    })
+}
+
+fn open_guess_modal<'a, 'b>(
+    r: &'a mut serenity::CreateInteractionResponse<'b>,
+) -> &'a mut serenity::CreateInteractionResponse<'b> {
+    r.kind(serenity::InteractionResponseType::Modal)
+     .interaction_response_data(|d| {
+         d.custom_id("kremlin_secrets_guess")
+          .title("Submit a Guess")
+          .components(|c| {
+              c.create_action_row(|row| {
+                  row.create_input_text(|t| {
+                      t.custom_id("word")
+                       .label("Your guess")
+                       .style(serenity::InputTextStyle::Short)
+                       .min_length(1)
+                       .max_length(MAX_GUESS_LENGTH)
+                       .required(true)
+                  })
+              })
+          })
+     })
+}
+
+// Apply a chat message to a saved game whose command is no longer running. The ruling
+// on a counted guess is returned to be shown once the game has been stored again.
+pub(crate) async fn resume_kremlin_guess(
+    ctx: &serenity::Context,
+    data: &Data,
+    record: &ActiveGame,
+    message: &serenity::Message,
+) -> Result<(Resumed, Option<Ruling>), CommandError> {
+    let mut session: KremlinSession = serde_json::from_str(&record.state)?;
+    
+    // A daily attempt takes its guesses privately, never from chat
+    let words = message.content.trim();
+    if message.author.id != session.player || session.daily.is_some() || words.is_empty() {
+        return Ok((Resumed::Ignored, None));
+    }
+    
+    session.catch_up(record);
+    let game_message = serenity::MessageId(record.message_id.parse()?);
+    
+    if session.seconds_left == 0 {
+        let expired = session.expire(data, &record.server_id, &message.author.name).await?;
+        message.channel_id.edit_message(&ctx.http, game_message, |m| m.set_embed(expired)).await?;
+        return Ok((Resumed::Finished, None));
+    }
+    
+    let ruling = session.apply_guess(data, &record.server_id, &message.author.name, words, sent_at_ms(message.id.0)).await?;
+    
+    if session.game.game_over {
+        Ok((Resumed::Finished, Some(ruling)))
+    } else {
+        Ok((Resumed::Continue(serde_json::to_string(&session)?), Some(ruling)))
+    }
+}
+
+// Open the guess pop-up for a saved daily attempt whose command is no longer running
+pub(crate) async fn resume_kremlin_press(
+    ctx: &serenity::Context,
+    data: &Data,
+    record: &ActiveGame,
+    press: &serenity::MessageComponentInteraction,
+) -> Result<Resumed, CommandError> {
+    let mut session: KremlinSession = serde_json::from_str(&record.state)?;
+    
+    if press.user.id != session.player {
+        return Ok(Resumed::Ignored);
+    }
+    
+    session.catch_up(record);
+    if session.seconds_left == 0 {
+        let expired = session.expire(data, &record.server_id, &press.user.name).await?;
+        press.create_interaction_response(ctx, |r| {
+            r.kind(serenity::InteractionResponseType::UpdateMessage)
+             .interaction_response_data(|d| d.set_embed(expired).components(|c| c))
+        }).await?;
+        if let Some(note) = session.share() {
+            press.channel_id.say(ctx, note).await?;
+        }
+        return Ok(Resumed::Finished);
+    }
+    
+    press.create_interaction_response(ctx, open_guess_modal).await?;
+    Ok(Resumed::Answered)
+}
+
+// Apply a guess typed into the pop-up to a saved daily attempt whose command is
+// no longer running. Like a chat guess, its ruling is returned to be shown later.
+pub(crate) async fn resume_kremlin_submit(
+    ctx: &serenity::Context,
+    data: &Data,
+    record: &ActiveGame,
+    submit: &serenity::ModalSubmitInteraction,
+) -> Result<(Resumed, Option<Ruling>), CommandError> {
+    let mut session: KremlinSession = serde_json::from_str(&record.state)?;
+    
+    if submit.user.id != session.player {
+        return Ok((Resumed::Ignored, None));
+    }
+    
+    session.catch_up(record);
+    if session.seconds_left == 0 {
+        let expired = session.expire(data, &record.server_id, &submit.user.name).await?;
+        submit.create_interaction_response(ctx, |r| {
+            r.kind(serenity::InteractionResponseType::UpdateMessage)
+             .interaction_response_data(|d| d.set_embed(expired).components(|c| c))
+        }).await?;
+        if let Some(note) = session.share() {
+            submit.channel_id.say(ctx, note).await?;
+        }
+        return Ok((Resumed::Finished, None));
+    }
+    
+    let words = modal_text(submit, "word").unwrap_or_default();
+    if words.trim().is_empty() {
+        submit.defer(ctx).await?;
+        return Ok((Resumed::Answered, None));
+    }
+    let ruling = session.apply_guess(data, &record.server_id, &submit.user.name, words.trim(), sent_at_ms(submit.id.0)).await?;
+    
+    if session.game.game_over {
+        Ok((Resumed::Finished, Some(ruling)))
+    } else {
+        Ok((Resumed::Continue(serde_json::to_string(&session)?), Some(ruling)))
+    }
+}
+
 /// Word association challenge in Kremlin style
 #[poise::command(slash_command, prefix_command, track_edits)]
 pub async fn kremlin_secrets(
@@ -304,129 +724,225 @@ pub async fn kremlin_secrets(
         _ => KremlinDifficulty::Comrade, // default to medium
     };
     
-    // Create a new game
-    let mut game = KremlinSecrets::new(difficulty_level);
+    let server_id = ctx.guild_id().map(|id| id.to_string()).unwrap_or_else(|| "DM".to_string());
+    let entry = pick_word(
+        &ctx.data().db,
+        &server_id,
+        &ctx.author().id.to_string(),
+        active_games::KREMLIN_SECRETS,
+        Some(difficulty_level.word_difficulty()),
+    ).await?;
+    
+    play_kremlin(ctx, &server_id, difficulty_level, entry, None).await
+}
+
+// Play the server's secret of the day at Comrade level, as the author's one daily attempt
+pub(crate) async fn play_daily_kremlin(
+    ctx: crate::Context<'_>,
+    server_id: &str,
+    date: &str,
+    entry: WordEntry,
+) -> Result<(), CommandError> {
+    play_kremlin(ctx, server_id, KremlinDifficulty::Comrade, entry, Some(date.to_string())).await
+}
+
+// The difficulty of the word every server's daily secret is drawn from
+pub(crate) fn daily_word_difficulty() -> WordDifficulty {
+    KremlinDifficulty::Comrade.word_difficulty()
+}
+
+// Start a game of the author's on a new message and see it through
+async fn play_kremlin(
+    ctx: crate::Context<'_>,
+    server_id: &str,
+    difficulty: KremlinDifficulty,
+    entry: WordEntry,
+    daily: Option<String>,
+) -> Result<(), CommandError> {
+    let mut session = KremlinSession {
+        player: ctx.author().id,
+        game: KremlinSecrets::new(difficulty, entry),
+        seconds_left: TIME_LIMIT.as_secs(),
+        last_guess_ms: None,
+        daily,
+    };
     
-    // Initial message
+    // Initial message. A daily attempt is played where only its player can see it and
+    // takes its guesses in a pop-up, so the secret stays one for everyone else.
+    let is_daily = session.daily.is_some();
     let msg = ctx.send(|m| {
-        m.embed(|e| {
+        m.ephemeral(is_daily)
+         .embed(|e| {
             e.title("☭ Kremlin Secrets ☭")
              .description("State Security Word Association Test")
              .color(serenity::Color::RED)
-             .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/5/58/Coat_of_arms_of_the_KGB.svg/240px-Coat_of_arms_of_the_KGB.svg.png")
-             .field("Instructions", 
-                    "I'm thinking of a word related to Soviet history and ideology.\n\
-                     Guess the word, and I'll tell you how close you are.\n\
-                     The temperature indicates your proximity to the secret term.", false)
-             .field("Status", game.render_status(), false)
+             .thumbnail(THUMBNAIL_URL)
+             .field("Instructions", INSTRUCTIONS, false)
+             .field("Status", session.game.render_status(), false)
              .field("Guesses", "No guesses yet. Enter your first guess...", false)
-             .field("Hint", game.get_hint(), false)
-             .footer(|f| f.text("Type your guess in chat. The KGB is watching your progress."))
-        })
+             .field("Hint", session.game.get_hint(), false)
+             .footer(|f| f.text(format!("{} The KGB is watching your progress.", session.guess_prompt())))
+        });
+        if is_daily {
+            m.components(create_guess_button);
+        }
+        m
     }).await?;
     
-    // Set up message collection
-    let author = ctx.author().id;
-    let channel_id = ctx.channel_id();
-    
-    // Process guesses
-    let mut interval = tokio::time::interval(Duration::from_millis(100));
-    let mut time_left = Duration::from_secs(180); // 3 minute time limit
-    let start_time = tokio::time::Instant::now();
-    
-    while !game.game_over && time_left.as_secs() > 0 {
-        tokio::select! {
-            _ = interval.tick() => {
-                // Get the message ID to search after
-                let message_id = match msg.message().await {
-                    Ok(message) => message.id,
-                    Err(_) => continue,
-                };
-                
-                // Check for new messages that could be guesses
-                if let Ok(messages) = channel_id.messages(&ctx.serenity_context().http, |retriever| {
-                    retriever.after(message_id).limit(10)
-                }).await {
-                    for potential_guess in messages.iter() {
-                        // Only process messages from the command author
-                        if potential_guess.author.id == author {
-                            // Process the guess
-                            let guess = potential_guess.content.clone();
-                            let temperature = game.make_guess(&guess);
-                            
-                            // Try to delete the user's message to keep the channel clean
-                            let _ = potential_guess.delete(&ctx.serenity_context().http).await;
-                            
-                            // Update the message with the new game state
-                            msg.edit(ctx, |m| {
-                                m.embed(|e| {
-                                    e.title("☭ Kremlin Secrets ☭")
-                                     .description("State Security Word Association Test")
-                                     .color(temperature.to_color())
-                                     .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/5/58/Coat_of_arms_of_the_KGB.svg/240px-Coat_of_arms_of_the_KGB.svg.png")
-                                     .field("Instructions", 
-                                            "I'm thinking of a word related to Soviet history and ideology.\n\
-                                             Guess the word, and I'll tell you how close you are.\n\
-                                             The temperature indicates your proximity to the secret term.", false)
-                                     .field("Status", game.render_status(), false)
-                                     .field("Guesses", game.render_guesses(), false);
-                                    
-                                    if !game.game_over {
-                                        e.field("Hint", game.get_hint(), false);
-                                    } else if game.won {
-                                        e.field("Result", 
-                                               format!("Correct! The word was **{}**.\nYou've proven yourself a worthy member of the Party!", 
-                                                      game.target_word), false);
-                                    } else {
-                                        e.field("Result", 
-                                               format!("The state secret was **{}**.\nBetter luck next time, comrade.", 
-                                                      game.target_word), false);
-                                    }
-                                    
-                                    e.footer(|f| {
-                                        if game.game_over {
-                                            f.text("Game over. Type /kremlin_secrets to play again.")
-                                        } else {
-                                            let remaining = time_left.as_secs();
-                                            f.text(format!("Type your next guess in chat. Time remaining: {}m {}s", remaining / 60, remaining % 60))
-                                        }
-                                    })
-                                })
-                            }).await?;
-                            
-                            if game.game_over {
-                                break;
-                            }
-                        }
+    // Guesses arrive as gateway events for the player's messages in this channel,
+    // or for a daily attempt as presses of its button and the pop-ups they open
+    let message_id = msg.message().await?.id;
+    let player = session.player;
+    let mut guesses = serenity::MessageCollectorBuilder::new(ctx)
+        .channel_id(ctx.channel_id())
+        .author_id(player)
+        .filter(move |_| !is_daily)
+        .timeout(TIME_LIMIT)
+        .build();
+    let mut presses = serenity::ComponentInteractionCollectorBuilder::new(ctx)
+        .message_id(message_id)
+        .author_id(player)
+        .timeout(TIME_LIMIT)
+        .build();
+    let mut submits = serenity::ModalInteractionCollectorBuilder::new(ctx)
+        .message_id(message_id)
+        .filter(move |submit| submit.user.id == player)
+        .timeout(TIME_LIMIT)
+        .build();
+    let start_time = Instant::now();
+    
+    let _live = ctx.data().games.track_live(message_id);
+    save_game(ctx, message_id, active_games::KREMLIN_SECRETS, &session, None).await?;
+    
+    // Process guesses until the game ends or the collectors time out
+    let played: Result<(), CommandError> = async {
+        loop {
+            tokio::select! {
+                Some(guess) = guesses.next() => {
+                    let words = guess.content.trim();
+                    if words.is_empty() {
+                        continue;
+                    }
+                    session.seconds_left = TIME_LIMIT.saturating_sub(start_time.elapsed()).as_secs();
+                    let ruling = session.apply_guess(ctx.data(), server_id, &guess.author.name, words, sent_at_ms(guess.id.0)).await?;
+                    ruling.show_in_channel(ctx.serenity_context(), message_id, &guess).await?;
+                },
+                Some(press) = presses.next() => {
+                    press.create_interaction_response(ctx, open_guess_modal).await?;
+                    continue;
+                },
+                Some(submit) = submits.next() => {
+                    let words = modal_text(&submit, "word").unwrap_or_default();
+                    if words.trim().is_empty() {
+                        submit.defer(ctx).await?;
+                        continue;
                     }
-                }
-                
-                // Update time left
-                let elapsed = start_time.elapsed();
-                if elapsed < Duration::from_secs(180) {
-                    time_left = Duration::from_secs(180) - elapsed;
-                } else {
-                    time_left = Duration::from_secs(0);
-                }
+                    session.seconds_left = TIME_LIMIT.saturating_sub(start_time.elapsed()).as_secs();
+                    let ruling = session.apply_guess(ctx.data(), server_id, &submit.user.name, words.trim(), sent_at_ms(submit.id.0)).await?;
+                    ruling.show_in_reply(ctx.serenity_context(), &submit).await?;
+                },
+                else => break,
+            }
+            
+            if session.game.game_over {
+                break;
             }
+            save_game(ctx, message_id, active_games::KREMLIN_SECRETS, &session, None).await?;
         }
-    }
+        
+        Ok(())
+    }.await;
     
-    // Handle case where user didn't respond in time
-    if !game.game_over {
+    // Finished, expired or broken, the game no longer needs resuming
+    forget_game(ctx, message_id).await?;
+    played?;
+    
+    // Handle case where user didn't respond in time, which counts as a loss
+    if !session.game.game_over {
+        let expired = session.expire(ctx.data(), server_id, &ctx.author().name).await?;
         msg.edit(ctx, |m| {
-            m.embed(|e| {
-                e.title("☭ Kremlin Secrets ☭")
-                 .description("Operation Terminated")
-                 .color(serenity::Color::DARK_GREY)
-                 .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/5/58/Coat_of_arms_of_the_KGB.svg/240px-Coat_of_arms_of_the_KGB.svg.png")
-                 .field("Status", 
-                        format!("Time expired. The secret word was **{}**.\nThe KGB has noted your lack of participation.", 
-                               game.target_word), false)
-                 .footer(|f| f.text("Game abandoned. Type /kremlin_secrets to try again."))
-            })
+            m.embeds = vec![expired];
+            m.components(|c| c)
         }).await?;
+        if let Some(note) = session.share() {
+            ctx.channel_id().say(ctx, note).await?;
+        }
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::similarity::RelatednessGraph;
+    use crate::commands::word_banks::WordBank;
+
+    #[test]
+    fn every_bundled_word_is_in_the_relatedness_graph() {
+        let graph = RelatednessGraph::bundled();
+        for entry in WordBank::bundled().entries() {
+            assert!(graph.knows(&entry.word), "{} ({}) is missing from the graph", entry.word, entry.category);
+        }
+    }
+
+    fn bolshevik() -> KremlinSecrets {
+        KremlinSecrets::new(KremlinDifficulty::Commissar, WordEntry {
+            category: "Revolutionary Concepts".to_string(),
+            word: "Bolshevik".to_string(),
+            difficulty: WordDifficulty::Hard,
+        })
+    }
+
+    #[test]
+    fn guesses_inside_the_cooldown_are_turned_away() {
+        // Snowflakes keep their timestamp above the low 22 bits
+        let first = sent_at_ms((1_700_000_000_000 << 22) | 0x3F_FFFF);
+        assert_eq!(first, 1_700_000_000_000);
+
+        let mut session = KremlinSession {
+            player: serenity::UserId(1),
+            game: bolshevik(),
+            seconds_left: TIME_LIMIT.as_secs(),
+            last_guess_ms: None,
+            daily: None,
+        };
+        assert!(session.accepts_guess_at(first));
+
+        session.last_guess_ms = Some(first);
+        let cooldown = GUESS_COOLDOWN.as_millis() as u64;
+        assert!(!session.accepts_guess_at(first));
+        assert!(!session.accepts_guess_at(first + cooldown - 1));
+        assert!(session.accepts_guess_at(first + cooldown));
+    }
+
+    #[test]
+    fn a_repeat_on_the_last_attempt_leaves_it_unspent() {
+        let mut game = bolshevik();
+        for word in ["tractor", "samovar", "borscht", "balalaika", "matryoshka", "sputnik", "kolkhoz"] {
+            assert!(game.make_guess(word).is_some());
+        }
+        assert_eq!(game.guesses.len(), game.max_guesses - 1);
+
+        assert!(game.make_guess("Sputnik").is_none());
+        assert!(!game.game_over);
+        assert!(game.render_status().contains("You already guessed `sputnik`"));
+
+        assert!(game.make_guess("gulag").is_some());
+        assert!(game.game_over && !game.won);
+        assert!(game.repeated_guess.is_none());
+    }
+
+    #[test]
+    fn repeated_guesses_cost_nothing() {
+        let mut game = bolshevik();
+
+        assert!(matches!(game.make_guess("Lenin"), Some(TemperatureLevel::Hot)));
+        assert!(game.make_guess(" lenin ").is_none());
+        assert_eq!(game.guesses.len(), 1);
+        assert_eq!(game.repeated_guess.as_deref(), Some("lenin"));
+
+        assert!(matches!(game.make_guess("bolshevik"), Some(TemperatureLevel::Burning)));
+        assert!(game.won);
+    }
+}
@@ -1,27 +1,58 @@
 // Re-exports for commands
 mod about;
+mod active_games;
 mod admin;
+mod autoreact;
+mod battleship;
+mod blackjack;
+mod board_games;
 mod boops;
+mod challenge;
+mod checkers_engine;
+mod chess_engine;
+mod chat_income;
 mod commit;
+mod dossier;
+mod connect4_engine;
+mod daily;
+mod game_engine;
 mod games;
 mod help;
 mod jobs;
+mod kgb;
 mod kremlin_secrets;
+mod ratings;
 mod redistribution;
+mod similarity;
 mod soviet_hangman;
+mod trivia;
+mod word_banks;
 mod work;
 
 // Re-export command functions for main.rs usage
 pub use about::*;
+pub use active_games::{route_component_interaction, route_message, route_modal_submit, watch_resumable_channels, CORRESPONDENCE_IDLE_SECS};
 pub use admin::*;
+pub use autoreact::*;
+pub use battleship::*;
+pub use blackjack::*;
+pub use board_games::*;
 pub use boops::*;
+pub use challenge::GameRegistry;
+pub use chat_income::*;
 pub use commit::*;
+pub use daily::*;
+pub use dossier::*;
 pub use games::*;
 pub use help::*;
 pub use jobs::*;
+pub use kgb::*;
 pub use kremlin_secrets::*;
+pub use ratings::*;
 pub use redistribution::*;
 pub use soviet_hangman::*;
+pub use trivia::*;
+pub use word_banks::*;
 pub use work::*;
 
 // Re-export command functions with proper SendSync bounds
@@ -0,0 +1,426 @@
+use crate::CommandError;
+use crate::db::{GameStats, Rating};
+use poise::serenity_prelude as serenity;
+
+// Games with Elo ratings, as (key stored in the database, display name)
+pub(crate) const RATED_GAMES: [(&str, &str); 5] = [
+    ("tictactoe", "Tic-Tac-Toe"),
+    ("connect4", "Connect 4"),
+    ("battleship", "Naval Command"),
+    ("chess", "People's Chess"),
+    ("checkers", "Proletarian Checkers"),
+];
+
+// Games that keep scores instead, as (key stored in the database, display name)
+pub(crate) const SCORED_GAMES: [(&str, &str); 5] = [
+    ("clicker", "Comrade Clicker"),
+    ("clicker_race", "Comrade Clicker Race"),
+    ("soviet_hangman", "Soviet Hangman"),
+    ("kremlin_secrets", "Kremlin Secrets"),
+    ("trivia", "Soviet Trivia"),
+];
+
+// Scored games where more is better; the rest score times or guesses
+const POINTS_GAMES: [&str; 1] = ["trivia"];
+
+// Comrades with fewer games than this have provisional, faster-moving ratings
+const PROVISIONAL_GAMES: i64 = 10;
+const PROVISIONAL_K: f64 = 40.0;
+const ESTABLISHED_K: f64 = 20.0;
+
+// Ratings never fall below this floor
+const MIN_RATING: f64 = 100.0;
+
+// Chance of the first player beating the second, according to Elo
+pub(crate) fn expected_score(rating: f64, opponent_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent_rating - rating) / 400.0))
+}
+
+pub(crate) fn k_factor(games: i64) -> f64 {
+    if games < PROVISIONAL_GAMES {
+        PROVISIONAL_K
+    } else {
+        ESTABLISHED_K
+    }
+}
+
+// Apply a match result to both ratings. `score` is from the first player's
+// point of view: 1.0 for a win, 0.5 for a draw and 0.0 for a loss.
+pub(crate) fn apply_result(first: &Rating, second: &Rating, score: f64) -> (Rating, Rating) {
+    let update = |player: &Rating, opponent: &Rating, score: f64| {
+        let expected = expected_score(player.rating, opponent.rating);
+        let rating = (player.rating + k_factor(player.games) * (score - expected)).max(MIN_RATING);
+
+        Rating {
+            rating,
+            games: player.games + 1,
+            wins: player.wins + (score == 1.0) as i64,
+            losses: player.losses + (score == 0.0) as i64,
+            draws: player.draws + (score == 0.5) as i64,
+        }
+    };
+
+    (update(first, second, score), update(second, first, 1.0 - score))
+}
+
+fn game_display_name(key: &str) -> &str {
+    RATED_GAMES
+        .iter()
+        .chain(SCORED_GAMES.iter())
+        .find(|(k, _)| *k == key)
+        .map(|(_, name)| *name)
+        .unwrap_or(key)
+}
+
+// Update both players' ratings after a human-vs-human match and describe the change
+pub(crate) async fn record_rated_match(
+    db: &crate::db::Database,
+    server_id: &str,
+    game_type: &str,
+    first: &serenity::User,
+    second: &serenity::User,
+    score: f64,
+) -> Result<String, CommandError> {
+    let first_id = first.id.to_string();
+    let second_id = second.id.to_string();
+
+    let old_first = db.get_rating(&first_id, server_id, game_type).await?;
+    let old_second = db.get_rating(&second_id, server_id, game_type).await?;
+    let (new_first, new_second) = apply_result(&old_first, &old_second, score);
+
+    db.save_match_ratings(server_id, game_type, [
+        (&first_id, &first.name, &new_first),
+        (&second_id, &second.name, &new_second),
+    ]).await?;
+
+    let describe = |name: &str, old: &Rating, new: &Rating| {
+        format!(
+            "**{}** {:.0} → {:.0} ({:+.0})",
+            name, old.rating, new.rating, new.rating - old.rating
+        )
+    };
+
+    Ok(format!(
+        "📈 **Rating update:** {} · {}",
+        describe(&first.name, &old_first, &new_first),
+        describe(&second.name, &old_second, &new_second)
+    ))
+}
+
+/// Show a comrade's ranked game ratings
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn rank(
+    ctx: crate::Context<'_>,
+    #[description = "The comrade to look up (default: yourself)"]
+    user: Option<serenity::User>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let target = user.as_ref().unwrap_or_else(|| ctx.author());
+    let db = &ctx.data().db;
+    let ratings = db.get_user_ratings(&target.id.to_string(), &server_id).await?;
+
+    if ratings.is_empty() {
+        ctx.say(format!(
+            "**{}** has not played any ranked games yet. Challenge a comrade to `/tictactoe`, `/connect4`, `/battleship`, `/chess` or `/checkers`!",
+            target.name
+        )).await?;
+        return Ok(());
+    }
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title(format!("☭ Service Record: {} ☭", target.name))
+             .description("Ratings earned in honest struggle against fellow comrades.")
+             .color(serenity::Color::RED)
+             .thumbnail(target.face());
+
+            for (game_type, rating, position) in &ratings {
+                let provisional = if rating.games < PROVISIONAL_GAMES { " (provisional)" } else { "" };
+                e.field(
+                    game_display_name(game_type),
+                    format!(
+                        "**{:.0}**{} · Rank **#{}**\n{}W / {}L / {}D",
+                        rating.rating, provisional, position, rating.wins, rating.losses, rating.draws
+                    ),
+                    true,
+                );
+            }
+
+            e.footer(|f| f.text("Ratings only change in games between two comrades."))
+        })
+    }).await?;
+
+    Ok(())
+}
+
+// Share of decided games won, as a whole percentage
+pub(crate) fn win_rate(wins: i64, losses: i64) -> Option<f64> {
+    let decided = wins + losses;
+    (decided > 0).then(|| (wins as f64 * 100.0 / decided as f64).round())
+}
+
+fn higher_is_better(game_type: &str) -> bool {
+    POINTS_GAMES.contains(&game_type)
+}
+
+// A saved score in the game's own unit
+fn format_score(game_type: &str, score: f64) -> String {
+    if game_type == "clicker_race" {
+        super::games::format_race_score(score)
+    } else if game_type.starts_with("clicker") {
+        format!("{:.0}ms", score)
+    } else if higher_is_better(game_type) {
+        format!("{:.0} points", score)
+    } else {
+        format!("{:.0} guesses", score)
+    }
+}
+
+fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as i64;
+    if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+// One game's line in /stats
+fn describe_game_stats(stats: &GameStats) -> String {
+    let mut lines = vec![format!("**{}** played", stats.played)];
+
+    if let Some(rate) = win_rate(stats.wins, stats.losses) {
+        lines.push(format!("{}W / {}L · **{:.0}%** won", stats.wins, stats.losses, rate));
+    }
+
+    let best = if higher_is_better(&stats.game_type) { stats.top_score } else { stats.best_score };
+    if let Some(best) = best {
+        lines.push(format!("Best: {}", format_score(&stats.game_type, best)));
+    }
+
+    match (stats.avg_guesses, stats.avg_duration_secs) {
+        (Some(guesses), Some(secs)) => lines.push(format!("Avg: {:.1} guesses in {}", guesses, format_duration(secs))),
+        (Some(guesses), None) => lines.push(format!("Avg: {:.1} guesses", guesses)),
+        _ => {},
+    }
+
+    lines.join("\n")
+}
+
+/// Show a comrade's record across every game
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn stats(
+    ctx: crate::Context<'_>,
+    #[description = "The comrade to look up (default: yourself)"]
+    user: Option<serenity::User>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let target = user.as_ref().unwrap_or_else(|| ctx.author());
+    let user_id = target.id.to_string();
+    let db = &ctx.data().db;
+    let ratings = db.get_user_ratings(&user_id, &server_id).await?;
+    let scores = db.get_user_game_stats(&user_id, &server_id).await?;
+
+    if ratings.is_empty() && scores.is_empty() {
+        ctx.say(format!("**{}** has no games on record yet, comrade.", target.name)).await?;
+        return Ok(());
+    }
+
+    // Totals over every game with a winner
+    let wins: i64 = ratings.iter().map(|(_, r, _)| r.wins).chain(scores.iter().map(|s| s.wins)).sum();
+    let losses: i64 = ratings.iter().map(|(_, r, _)| r.losses).chain(scores.iter().map(|s| s.losses)).sum();
+    let played: i64 = ratings.iter().map(|(_, r, _)| r.games).chain(scores.iter().map(|s| s.played)).sum();
+    let overall = match win_rate(wins, losses) {
+        Some(rate) => format!("**{}** games played · {}W / {}L · **{:.0}%** won", played, wins, losses, rate),
+        None => format!("**{}** games played", played),
+    };
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title(format!("☭ Personal File: {} ☭", target.name))
+             .description(overall)
+             .color(serenity::Color::RED)
+             .thumbnail(target.face());
+
+            for (game_type, rating, _) in &ratings {
+                let rate = win_rate(rating.wins, rating.losses)
+                    .map(|rate| format!(" · **{:.0}%** won", rate))
+                    .unwrap_or_default();
+                e.field(
+                    game_display_name(game_type),
+                    format!(
+                        "**{}** played\n{}W / {}L / {}D{}\nRating: {:.0}",
+                        rating.games, rating.wins, rating.losses, rating.draws, rate, rating.rating
+                    ),
+                    true,
+                );
+            }
+
+            for game in &scores {
+                e.field(game_display_name(&game.game_type), describe_game_stats(game), true);
+            }
+
+            e.footer(|f| f.text("Every game is entered into your permanent record."))
+        })
+    }).await?;
+
+    Ok(())
+}
+
+/// Show the top players of a game
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn leaderboard(
+    ctx: crate::Context<'_>,
+    #[description = "Game to rank: tictactoe, connect4, battleship, chess, checkers, clicker, clicker_race, hangman, kremlin or trivia"]
+    game: String,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let game_type = match game.to_lowercase().replace([' ', '-'], "").as_str() {
+        "tictactoe" | "ttt" => "tictactoe",
+        "connect4" | "c4" => "connect4",
+        "battleship" | "navalcommand" | "naval" => "battleship",
+        "chess" | "peopleschess" => "chess",
+        "checkers" | "draughts" | "proletariancheckers" => "checkers",
+        "clicker" => "clicker",
+        "clickerrace" | "clicker_race" | "race" => "clicker_race",
+        "hangman" | "soviethangman" | "soviet_hangman" => "soviet_hangman",
+        "kremlin" | "kremlinsecrets" | "kremlin_secrets" => "kremlin_secrets",
+        "trivia" | "quiz" => "trivia",
+        _ => {
+            ctx.say("Unknown game, comrade. Choose `tictactoe`, `connect4`, `battleship`, `chess`, `checkers`, `clicker`, `clicker_race`, `hangman`, `kremlin` or `trivia`.").await?;
+            return Ok(());
+        }
+    };
+
+    let db = &ctx.data().db;
+
+    // Scored games rank their best scores rather than ratings: clicker reaction times,
+    // with races on their own board, the fewest guesses in a won word game and trivia points
+    let (title, lines) = if SCORED_GAMES.iter().any(|(key, _)| *key == game_type) {
+        let scores = db.get_server_leaderboard(&server_id, game_type, 10, higher_is_better(game_type)).await?;
+        let lines: Vec<String> = scores
+            .iter()
+            .enumerate()
+            .map(|(i, (username, score))| format!("**{}.** {} - {}", i + 1, username, format_score(game_type, *score)))
+            .collect();
+        (game_display_name(game_type), lines)
+    } else {
+        let ratings = db.get_rating_leaderboard(&server_id, game_type, 10).await?;
+        let lines: Vec<String> = ratings
+            .iter()
+            .enumerate()
+            .map(|(i, (username, rating))| {
+                format!(
+                    "**{}.** {} - **{:.0}** ({}W / {}L / {}D)",
+                    i + 1, username, rating.rating, rating.wins, rating.losses, rating.draws
+                )
+            })
+            .collect();
+        (game_display_name(game_type), lines)
+    };
+
+    let description = if lines.is_empty() {
+        "No comrades have been ranked yet. Be the first!".to_string()
+    } else {
+        lines.join("\n")
+    };
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title(format!("☭ {} Leaderboard ☭", title))
+             .description(description)
+             .color(serenity::Color::RED)
+             .footer(|f| f.text(match game_type {
+                 "clicker_race" => super::games::CLICKER_RACE_RANKING,
+                 _ => "Glory to the most productive players!",
+             }))
+        })
+    }).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rating(rating: f64, games: i64) -> Rating {
+        Rating { rating, games, ..Rating::default() }
+    }
+
+    #[test]
+    fn expected_scores_are_complementary() {
+        let a = expected_score(1400.0, 1200.0);
+        let b = expected_score(1200.0, 1400.0);
+        assert!((a + b - 1.0).abs() < 1e-9);
+        assert!((expected_score(1200.0, 1200.0) - 0.5).abs() < 1e-9);
+
+        // A 400 point gap means 10:1 odds
+        assert!((expected_score(1600.0, 1200.0) - 10.0 / 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn equal_players_exchange_half_k() {
+        let (winner, loser) = apply_result(&rating(1500.0, 20), &rating(1500.0, 20), 1.0);
+        assert!((winner.rating - 1510.0).abs() < 1e-9);
+        assert!((loser.rating - 1490.0).abs() < 1e-9);
+        assert_eq!((winner.wins, winner.losses, winner.games), (1, 0, 21));
+        assert_eq!((loser.wins, loser.losses, loser.games), (0, 1, 21));
+    }
+
+    #[test]
+    fn draw_between_equals_changes_nothing() {
+        let (a, b) = apply_result(&rating(1300.0, 20), &rating(1300.0, 20), 0.5);
+        assert!((a.rating - 1300.0).abs() < 1e-9);
+        assert!((b.rating - 1300.0).abs() < 1e-9);
+        assert_eq!((a.draws, b.draws), (1, 1));
+    }
+
+    #[test]
+    fn upsets_move_ratings_more() {
+        let (underdog, _) = apply_result(&rating(1200.0, 20), &rating(1600.0, 20), 1.0);
+        let (favourite, _) = apply_result(&rating(1600.0, 20), &rating(1200.0, 20), 1.0);
+        assert!(underdog.rating - 1200.0 > favourite.rating - 1600.0);
+    }
+
+    #[test]
+    fn established_ratings_are_zero_sum() {
+        let (a, b) = apply_result(&rating(1432.0, 50), &rating(1288.0, 30), 0.0);
+        assert!((a.rating + b.rating - (1432.0 + 1288.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn provisional_players_move_faster() {
+        assert_eq!(k_factor(0), PROVISIONAL_K);
+        assert_eq!(k_factor(PROVISIONAL_GAMES), ESTABLISHED_K);
+
+        let (new_player, veteran) = apply_result(&rating(1200.0, 0), &rating(1200.0, 100), 1.0);
+        assert!((new_player.rating - 1220.0).abs() < 1e-9);
+        assert!((veteran.rating - 1190.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn win_rates_ignore_undecided_games() {
+        assert_eq!(win_rate(0, 0), None);
+        assert_eq!(win_rate(2, 1), Some(67.0));
+        assert_eq!(win_rate(0, 4), Some(0.0));
+    }
+
+    #[test]
+    fn ratings_have_a_floor() {
+        let (loser, _) = apply_result(&rating(MIN_RATING, 0), &rating(MIN_RATING, 0), 0.0);
+        assert_eq!(loser.rating, MIN_RATING);
+    }
+}
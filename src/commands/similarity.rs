@@ -0,0 +1,313 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+// The curated graph of Soviet vocabulary shipped with the bot
+const BUNDLED_GRAPH: &str = include_str!("../../assets/kremlin_relatedness.txt");
+
+// Words further apart than this in the graph count as unrelated
+const MAX_HOPS: usize = 3;
+
+// Score for two words the graph knows but can't connect
+const UNRELATED: f64 = 0.05;
+
+// Scores how close a guess is to a secret word, from 0 (unrelated) to 1 (the same word).
+// Words arrive normalized and never equal.
+pub(crate) trait SimilarityBackend: Send + Sync {
+    // None if the backend knows nothing useful about the pair
+    fn similarity(&self, guess: &str, target: &str) -> Option<f64>;
+}
+
+// Asks each backend in turn until one can score the pair
+pub(crate) struct SimilarityEngine {
+    backends: Vec<Box<dyn SimilarityBackend>>,
+}
+
+impl SimilarityEngine {
+    pub(crate) fn new(backends: Vec<Box<dyn SimilarityBackend>>) -> Self {
+        SimilarityEngine { backends }
+    }
+
+    // The bundled relatedness graph, falling back to spelling for words it doesn't know
+    pub(crate) fn standard() -> &'static SimilarityEngine {
+        static ENGINE: OnceLock<SimilarityEngine> = OnceLock::new();
+        ENGINE.get_or_init(|| {
+            SimilarityEngine::new(vec![
+                Box::new(RelatednessGraph::bundled().clone()),
+                Box::new(SpellingHeuristic),
+            ])
+        })
+    }
+
+    pub(crate) fn score(&self, guess: &str, target: &str) -> f64 {
+        let (guess, target) = (normalize(guess), normalize(target));
+
+        if guess.is_empty() || target.is_empty() {
+            return 0.0;
+        }
+        if guess == target {
+            return 1.0;
+        }
+
+        self.backends
+            .iter()
+            .find_map(|backend| backend.similarity(&guess, &target))
+            .unwrap_or(0.0)
+    }
+}
+
+// Lowercase with single spaces, so "Iron-Curtain" and "iron  curtain" match
+fn normalize(word: &str) -> String {
+    word.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Weighted links between related words. Closeness is the product of the weights
+// along the strongest path of up to MAX_HOPS links.
+#[derive(Clone)]
+pub(crate) struct RelatednessGraph {
+    links: HashMap<String, Vec<(String, f64)>>,
+}
+
+impl RelatednessGraph {
+    // Lines look like `word: neighbour 0.75, other neighbour 0.4`; `#` starts a comment
+    pub(crate) fn parse(text: &str) -> Result<Self, String> {
+        let mut links: HashMap<String, HashMap<String, f64>> = HashMap::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (word, neighbours) = line
+                .split_once(':')
+                .ok_or_else(|| format!("line {}: expected `word: neighbour weight, ...`", number + 1))?;
+            let word = normalize(word);
+
+            for entry in neighbours.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (neighbour, weight) = entry
+                    .rsplit_once(' ')
+                    .ok_or_else(|| format!("line {}: `{}` has no weight", number + 1, entry))?;
+                let weight: f64 = weight
+                    .parse()
+                    .map_err(|_| format!("line {}: `{}` is not a weight", number + 1, weight))?;
+
+                if !(0.0..=1.0).contains(&weight) {
+                    return Err(format!("line {}: weight {} is outside 0 to 1", number + 1, weight));
+                }
+
+                // Links work both ways; a pair listed twice keeps its stronger weight
+                let neighbour = normalize(neighbour);
+                for (from, to) in [(&word, &neighbour), (&neighbour, &word)] {
+                    let existing = links.entry(from.clone()).or_default().entry(to.clone()).or_insert(0.0);
+                    *existing = existing.max(weight);
+                }
+            }
+        }
+
+        let links = links
+            .into_iter()
+            .map(|(word, neighbours)| (word, neighbours.into_iter().collect()))
+            .collect();
+
+        Ok(RelatednessGraph { links })
+    }
+
+    pub(crate) fn bundled() -> &'static RelatednessGraph {
+        static GRAPH: OnceLock<RelatednessGraph> = OnceLock::new();
+        GRAPH.get_or_init(|| RelatednessGraph::parse(BUNDLED_GRAPH).expect("bundled relatedness graph is malformed"))
+    }
+
+    // The graph's spelling of a word, forgiving simple plurals
+    fn lookup<'a>(&'a self, word: &str) -> Option<&'a str> {
+        let candidates = [
+            Some(word),
+            word.strip_suffix("es"),
+            word.strip_suffix('s'),
+        ];
+
+        candidates
+            .into_iter()
+            .flatten()
+            .find_map(|candidate| self.links.get_key_value(candidate).map(|(key, _)| key.as_str()))
+    }
+
+    pub(crate) fn knows(&self, word: &str) -> bool {
+        self.lookup(&normalize(word)).is_some()
+    }
+
+    // Strongest path score from one word to another, if there is one within reach
+    fn closeness(&self, from: &str, to: &str) -> Option<f64> {
+        let mut best: HashMap<&str, f64> = HashMap::from([(from, 1.0)]);
+        let mut frontier = vec![(from, 1.0)];
+
+        for _ in 0..MAX_HOPS {
+            let mut next = Vec::new();
+
+            for (word, score) in frontier {
+                for (neighbour, weight) in &self.links[word] {
+                    let through = score * weight;
+                    if through > best.get(neighbour.as_str()).copied().unwrap_or(0.0) {
+                        best.insert(neighbour, through);
+                        next.push((neighbour.as_str(), through));
+                    }
+                }
+            }
+
+            frontier = next;
+        }
+
+        best.get(to).copied()
+    }
+
+    // Every word in the graph
+    #[cfg(test)]
+    pub(crate) fn words(&self) -> HashSet<&str> {
+        self.links.keys().map(String::as_str).collect()
+    }
+}
+
+impl SimilarityBackend for RelatednessGraph {
+    fn similarity(&self, guess: &str, target: &str) -> Option<f64> {
+        let guess = self.lookup(guess)?;
+        let target = self.lookup(target)?;
+
+        // A plural of the secret word is as good as the word itself
+        if guess == target {
+            return Some(0.95);
+        }
+
+        Some(self.closeness(guess, target).unwrap_or(UNRELATED))
+    }
+}
+
+// Compares spelling alone: shared prefix, shared letters and length. Knows every
+// word but nothing of meaning, so it goes last.
+pub(crate) struct SpellingHeuristic;
+
+impl SimilarityBackend for SpellingHeuristic {
+    fn similarity(&self, word1: &str, word2: &str) -> Option<f64> {
+        // Check for exact prefixes
+        let min_len = word1.len().min(word2.len());
+
+        // Compare character by character from the start
+        let prefix_match = word1.chars().zip(word2.chars()).take_while(|(c1, c2)| c1 == c2).count();
+        let prefix_sim = prefix_match as f64 / min_len as f64;
+
+        // Character set similarity
+        let chars1: HashSet<char> = word1.chars().collect();
+        let chars2: HashSet<char> = word2.chars().collect();
+
+        let common_chars = chars1.intersection(&chars2).count();
+        let total_chars = chars1.union(&chars2).count();
+
+        let set_sim = common_chars as f64 / total_chars as f64;
+
+        // Length similarity
+        let length_ratio = if word1.len() > word2.len() {
+            word2.len() as f64 / word1.len() as f64
+        } else {
+            word1.len() as f64 / word2.len() as f64
+        };
+
+        // Calculate final similarity score (weighted)
+        let similarity = (prefix_sim * 0.5) + (set_sim * 0.3) + (length_ratio * 0.2);
+
+        // Boost if one is substring of the other
+        let substring_boost = if word1.contains(word2) || word2.contains(word1) { 0.2 } else { 0.0 };
+
+        // Cap at 0.95 to require exact match for 1.0
+        Some((similarity + substring_boost).min(0.95))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(guess: &str, target: &str) -> f64 {
+        SimilarityEngine::standard().score(guess, target)
+    }
+
+    #[test]
+    fn bundled_graph_links_both_ways() {
+        let graph = RelatednessGraph::bundled();
+        assert!(graph.words().len() > 100);
+
+        for (word, neighbours) in &graph.links {
+            for (neighbour, weight) in neighbours {
+                let back = graph.links[neighbour].iter().find(|(w, _)| w == word).map(|(_, w)| *w);
+                assert_eq!(back, Some(*weight), "{} -> {} is one-way", word, neighbour);
+            }
+        }
+    }
+
+    #[test]
+    fn related_words_are_warm() {
+        let pairs = [
+            ("Lenin", "Bolshevik"),
+            ("Gagarin", "Vostok"),
+            ("glasnost", "Perestroika"),
+            ("spy", "Espionage"),
+            ("Kolkhoz", "Collectivization"),
+            ("Churchill", "Iron Curtain"),
+        ];
+
+        for (guess, target) in pairs {
+            let similarity = score(guess, target);
+            assert!(similarity >= 0.5, "{} / {} only scored {:.2}", guess, target, similarity);
+        }
+
+        // Old names are practically the answer
+        assert!(score("Volgograd", "Stalingrad") >= 0.85);
+    }
+
+    #[test]
+    fn unrelated_words_are_cold() {
+        let pairs = [
+            ("Sputnik", "Bourgeoisie"),
+            ("Crimea", "Literacy"),
+            ("Dialectic", "Vladivostok"),
+            ("Kolkhoz", "Missiles"),
+        ];
+
+        for (guess, target) in pairs {
+            let similarity = score(guess, target);
+            assert!(similarity < 0.15, "{} / {} scored {:.2}", guess, target, similarity);
+        }
+    }
+
+    #[test]
+    fn closer_words_score_higher() {
+        // Direct links beat two steps, which beat three
+        assert!(score("Lenin", "Revolution") > score("Lenin", "Winter Palace"));
+        assert!(score("Trotsky", "Revolution") > score("Exile", "Revolution"));
+    }
+
+    #[test]
+    fn spelling_is_forgiven() {
+        assert_eq!(score("  iron-curtain ", "Iron Curtain"), 1.0);
+        assert!(score("satellites", "Sputnik") >= 0.85);
+        assert!(score("Missile", "Missiles") >= 0.85);
+    }
+
+    #[test]
+    fn unknown_words_fall_back_to_spelling() {
+        assert_eq!(RelatednessGraph::bundled().similarity("stallin", "stalin"), None);
+
+        let typo = score("Stallin", "Stalin");
+        assert_eq!(Some(typo), SpellingHeuristic.similarity("stallin", "stalin"));
+        assert!(typo > 0.7);
+    }
+
+    #[test]
+    fn malformed_graphs_are_rejected() {
+        assert!(RelatednessGraph::parse("lenin bolshevik 0.5").is_err());
+        assert!(RelatednessGraph::parse("lenin: bolshevik").is_err());
+        assert!(RelatednessGraph::parse("lenin: bolshevik 1.5").is_err());
+        assert!(RelatednessGraph::parse("# nothing but comments\n\n").is_ok());
+    }
+}
@@ -1,11 +1,34 @@
-use crate::CommandError;
+use crate::{CommandError, Data};
+use crate::db::{ActiveGame, GameResult};
+use super::active_games::{self, save_game, forget_game, Resumed};
+use super::admin::check_if_admin;
+use super::daily::share_note;
+use super::word_banks::{pick_word, WordDifficulty, WordEntry, WORD_REWARDS_PER_DAY};
 use poise::serenity_prelude as serenity;
 use poise::futures_util::StreamExt;
-use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::time::Duration;
 
+const THUMBNAIL_URL: &str = "https://upload.wikimedia.org/wikipedia/commons/thumb/a/a9/Soviet_Union_state_emblem.svg/240px-Soviet_Union_state_emblem.svg.png";
+
+// How long a game waits for its next guess
+const SESSION_TIMEOUT: Duration = Duration::from_secs(180);
+
+// Longest whole-word guess the pop-up accepts
+const MAX_WORD_GUESS: u64 = 40;
+
+// Marks for each guess in a shared result, which must not give the word away
+const MARK_HIT: char = '🟩';
+const MARK_MISS: char = '⬛';
+const MARK_WORD: char = '🌟';
+const MARK_WRONG_WORD: char = '🟥';
+
+// Marks per line of a shared result
+const MARKS_PER_LINE: usize = 10;
+
 // Soviet Hangman - Word guessing game with button interactions
+#[derive(Serialize, Deserialize)]
 struct SovietHangman {
     target_word: String,
     category: String,
@@ -14,55 +37,37 @@ struct SovietHangman {
     attempts_left: usize,
     game_over: bool,
     won: bool,
+    // Whole-word guesses that were wrong, so repeating one costs nothing
+    #[serde(default)]
+    wrong_words: Vec<String>,
+    // Sets the boops paid for a win
+    #[serde(default)]
+    difficulty: WordDifficulty,
+    // Letters and words tried, not counting repeats
+    #[serde(default)]
+    guesses: usize,
+    // A mark for each of those guesses, in order
+    #[serde(default)]
+    marks: Vec<char>,
 }
 
 impl SovietHangman {
-    fn new() -> Self {
-        // Pick a random category and word
-        let (category, word) = Self::random_word_and_category();
-        
+    fn new(entry: WordEntry) -> Self {
         SovietHangman {
-            target_word: word,
-            category,
+            target_word: entry.word.to_uppercase(),
+            category: entry.category,
             guessed_letters: HashSet::new(),
             max_attempts: 6,
             attempts_left: 6,
             game_over: false,
             won: false,
+            wrong_words: Vec::new(),
+            difficulty: entry.difficulty,
+            guesses: 0,
+            marks: Vec::new(),
         }
     }
     
-    fn random_word_and_category() -> (String, String) {
-        let categories = [
-            ("Soviet Leaders", vec![
-                "LENIN", "STALIN", "KHRUSHCHEV", "BREZHNEV", "GORBACHEV", 
-                "ANDROPOV", "CHERNENKO", "MALENKOV", "BULGANIN", "KOSYGIN"
-            ]),
-            ("Revolutionary Concepts", vec![
-                "COMMUNISM", "SOCIALISM", "PROLETARIAT", "BOURGEOISIE", "REVOLUTION",
-                "COLLECTIVIZATION", "VANGUARD", "DIALECTIC", "MATERIALISM", "CLASSLESS"
-            ]),
-            ("Soviet Geography", vec![
-                "MOSCOW", "LENINGRAD", "STALINGRAD", "VLADIVOSTOK", "MINSK",
-                "SIBERIA", "URAL", "CRIMEA", "VOLGA", "BALTIC"
-            ]),
-            ("Soviet Achievements", vec![
-                "SPUTNIK", "VOSTOK", "INDUSTRIALIZATION", "LITERACY", "EDUCATION",
-                "ELECTRIFICATION", "MIR", "COLLECTIVE", "SUBWAY", "HEALTHCARE"
-            ]),
-            ("Soviet Military", vec![
-                "KALASHNIKOV", "RED ARMY", "NAVY", "MISSILE", "PARTISAN",
-                "DEFENSE", "PARADE", "GUARDS", "VICTORY", "MEDAL"
-            ])
-        ];
-        
-        let mut rng = rand::thread_rng();
-        let (category, words) = categories.choose(&mut rng).unwrap();
-        let word = words.choose(&mut rng).unwrap();
-        
-        (category.to_string(), word.to_string())
-    }
-    
     fn guess_letter(&mut self, letter: char) -> bool {
         let upper_letter = letter.to_ascii_uppercase();
         
@@ -73,9 +78,11 @@ impl SovietHangman {
         
         // Add the letter to guessed letters
         self.guessed_letters.insert(upper_letter);
+        self.guesses += 1;
         
         // Check if the letter is in the word
         let correct = self.target_word.contains(upper_letter);
+        self.marks.push(if correct { MARK_HIT } else { MARK_MISS });
         
         // If incorrect, reduce attempts
         if !correct {
@@ -97,6 +104,44 @@ impl SovietHangman {
         correct
     }
     
+    // Guess the whole word. Right reveals every letter and wrong costs an attempt.
+    // Returns None for a wrong word that was already tried, which costs nothing.
+    fn guess_word(&mut self, guess: &str) -> Option<bool> {
+        let guess = guess.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase();
+        
+        if guess == self.target_word {
+            self.guesses += 1;
+            self.marks.push(MARK_WORD);
+            self.guessed_letters.extend(self.target_word.chars().filter(|&c| c != ' '));
+            self.won = true;
+            self.game_over = true;
+            return Some(true);
+        }
+        
+        if self.wrong_words.contains(&guess) {
+            return None;
+        }
+        self.wrong_words.push(guess);
+        self.guesses += 1;
+        self.marks.push(MARK_WRONG_WORD);
+        
+        self.attempts_left -= 1;
+        if self.attempts_left == 0 {
+            self.game_over = true;
+        }
+        Some(false)
+    }
+    
+    // How many times a letter appears in the word
+    fn occurrences(&self, letter: char) -> usize {
+        self.target_word.chars().filter(|&c| c == letter).count()
+    }
+    
+    // Letters of the word still hidden, counting repeats
+    fn hidden_letters(&self) -> usize {
+        self.target_word.chars().filter(|c| *c != ' ' && !self.guessed_letters.contains(c)).count()
+    }
+    
     fn display_word(&self) -> String {
         self.target_word.chars().map(|c| {
             if c == ' ' {
@@ -109,6 +154,21 @@ impl SovietHangman {
         }).collect()
     }
     
+    // A spoiler-free summary of the game to paste in chat, like Wordle's
+    fn share_grid(&self, date: &str) -> String {
+        let lines: Vec<String> = self.marks
+            .chunks(MARKS_PER_LINE)
+            .map(|line| line.iter().collect())
+            .collect();
+        let outcome = if self.won {
+            format!("Solved in {} guesses, {}/{} lives left", self.guesses, self.attempts_left, self.max_attempts)
+        } else {
+            format!("Hanged after {} guesses", self.guesses)
+        };
+        
+        format!("☭ Soviet Hangman · Daily {}\n{}\n{}", date, lines.join("\n"), outcome)
+    }
+    
     fn display_guessed_letters(&self) -> String {
         let alphabet = ('A'..='Z').collect::<Vec<char>>();
         let mut result = String::new();
@@ -214,154 +274,270 @@ impl SovietHangman {
     }
 }
 
-// Create keyboard with letters A-X and a Quit button
-fn create_keyboard_buttons(game: &SovietHangman) -> Vec<serenity::CreateActionRow> {
-    // Discord allows maximum 5 components per action row and max 5 action rows per message
-    let letters = vec![
-        vec!['A', 'B', 'C', 'D'],       // Row 1 (with Quit button)
-        vec!['E', 'F', 'G', 'H'],       // Row 2
-        vec!['I', 'J', 'K', 'L'],       // Row 3
-        vec!['M', 'N', 'O', 'P'],       // Row 4
-    ];
-    
-    let mut result = Vec::with_capacity(5);
-    
-    // First row with quit button plus letters
-    let mut first_row = serenity::CreateActionRow::default();
-    
-    // Add quit button
-    first_row.create_button(|b| {
-        b.style(serenity::ButtonStyle::Primary)
-         .custom_id("soviet_hangman_quit")
-         .label("Quit")
-    });
-    
-    // Add the letters from the first group
-    for &letter in &letters[0] {
-        let already_guessed = game.guessed_letters.contains(&letter);
-        first_row.create_button(|b| {
-            b.style(if already_guessed {
-                serenity::ButtonStyle::Secondary
-            } else {
-                serenity::ButtonStyle::Success
-            })
-             .custom_id(format!("soviet_hangman_{}", letter))
-             .label(letter.to_string())
-             .disabled(already_guessed)
-        });
+// How a game of hangman is shared
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+enum HangmanMode {
+    // Only the comrade who started the game may guess
+    #[default]
+    Solo,
+    // Anyone in the channel may guess, and the reward is shared evenly
+    Coop,
+    // Anyone may guess, scoring a point for every letter they reveal
+    Versus,
+}
+
+impl HangmanMode {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "solo" | "alone" => Some(HangmanMode::Solo),
+            "coop" | "co-op" | "cooperative" => Some(HangmanMode::Coop),
+            "versus" | "vs" | "competitive" => Some(HangmanMode::Versus),
+            _ => None,
+        }
     }
-    result.push(first_row);
     
-    // Create the other 4 rows
-    for letter_row in letters.iter().skip(1) {
-        let mut action_row = serenity::CreateActionRow::default();
-        for &letter in letter_row {
-            let already_guessed = game.guessed_letters.contains(&letter);
-            action_row.create_button(|b| {
-                b.style(if already_guessed {
-                    serenity::ButtonStyle::Secondary
-                } else {
-                    serenity::ButtonStyle::Success
-                })
-                 .custom_id(format!("soviet_hangman_{}", letter))
-                 .label(letter.to_string())
-                 .disabled(already_guessed)
-            });
+    fn name(&self) -> &'static str {
+        match self {
+            HangmanMode::Solo => "Solo",
+            HangmanMode::Coop => "Cooperative",
+            HangmanMode::Versus => "Competitive",
         }
-        result.push(action_row);
     }
-    
-    result
 }
 
-// Create keyboard with the remaining letters Y-Z and a navigation button
-fn create_second_keyboard(game: &SovietHangman) -> Vec<serenity::CreateActionRow> {
-    // For the last two letters Y and Z
-    let mut result = Vec::with_capacity(1);
-    let mut row = serenity::CreateActionRow::default();
-    
-    // Add page navigation button
-    row.create_button(|b| {
-        b.style(serenity::ButtonStyle::Primary)
-         .custom_id("soviet_hangman_page1")
-         .label("Page 1 (A-X)")
-    });
+// How players pick letters, chosen per server with /hangman_settings
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+enum HangmanInput {
+    // Letter buttons split across two pages
+    #[default]
+    Buttons,
+    // The whole alphabet on one page, as two select menus
+    Menus,
+}
+
+impl HangmanInput {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "buttons" | "button" | "keyboard" => Some(HangmanInput::Buttons),
+            "menus" | "menu" | "select" => Some(HangmanInput::Menus),
+            _ => None,
+        }
+    }
     
-    // Add Y and Z buttons
-    for &letter in &['Y', 'Z'] {
-        let already_guessed = game.guessed_letters.contains(&letter);
-        row.create_button(|b| {
-            b.style(if already_guessed {
-                serenity::ButtonStyle::Secondary
-            } else {
-                serenity::ButtonStyle::Success
-            })
-             .custom_id(format!("soviet_hangman_{}", letter))
-             .label(letter.to_string())
-             .disabled(already_guessed)
-        });
+    fn name(&self) -> &'static str {
+        match self {
+            HangmanInput::Buttons => "buttons",
+            HangmanInput::Menus => "menus",
+        }
     }
     
-    result.push(row);
-    result
+    fn opening_description(&self) -> &'static str {
+        match self {
+            HangmanInput::Buttons => "State-Approved Word Guessing - Page 1 (A-P)",
+            HangmanInput::Menus => "State-Approved Word Guessing",
+        }
+    }
 }
 
-/// Play Soviet-themed Hangman
-#[poise::command(slash_command, prefix_command)]
-pub async fn soviet_hangman(
-    ctx: crate::Context<'_>,
-) -> Result<(), CommandError> {
-    let mut game = SovietHangman::new();
+// A comrade who has guessed in a game and how many letters they revealed
+#[derive(Clone, Serialize, Deserialize)]
+struct HangmanPlayer {
+    user_id: serenity::UserId,
+    name: String,
+    revealed: usize,
+}
+
+// A hangman game, who started it, who has played and the keyboard on show.
+// Saved after every press so the game can be resumed after a restart.
+#[derive(Serialize, Deserialize)]
+struct HangmanSession {
+    player: serenity::UserId,
+    game: SovietHangman,
+    show_page_2: bool,
+    #[serde(default)]
+    mode: HangmanMode,
+    #[serde(default)]
+    players: Vec<HangmanPlayer>,
+    #[serde(default)]
+    input: HangmanInput,
+    // When the game began, in milliseconds, to record how long it took
+    #[serde(default)]
+    started_at: Option<i64>,
+    // The date of the server's daily puzzle, if this is the starter's daily attempt
+    #[serde(default)]
+    daily: Option<String>,
+}
+
+impl HangmanSession {
+    fn may_play(&self, user_id: serenity::UserId) -> bool {
+        self.mode != HangmanMode::Solo || user_id == self.player
+    }
     
-    // Initial message with keyboard (page 1: A-X)
-    let mut show_page_2 = false; // Track which page we're showing
+    // Count a guess towards a player's tally, adding them on their first guess
+    fn credit(&mut self, user: &serenity::User, revealed: usize) {
+        match self.players.iter_mut().find(|p| p.user_id == user.id) {
+            Some(player) => player.revealed += revealed,
+            None => self.players.push(HangmanPlayer {
+                user_id: user.id,
+                name: user.name.clone(),
+                revealed,
+            }),
+        }
+    }
     
-    let msg = ctx.send(|m| {
-        m.embed(|e| {
-            e.title("☭ Comrade Hangman ☭")
-             .description("State-Approved Word Guessing - Page 1 (A-P)")
-             .color(serenity::Color::RED)
-             .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/a/a9/Soviet_Union_state_emblem.svg/240px-Soviet_Union_state_emblem.svg.png")
-             .field("Category", &game.category, true)
-             .field("Attempts", format!("{}/{}", game.attempts_left, game.max_attempts), true)
-             .field("Word", format!("```{}```", game.display_word()), false)
-             .field("Gallows", game.get_gallows(), false)
-             .field("Guessed Letters", game.display_guessed_letters(), false)
-             .footer(|f| f.text("Select a letter on the keyboard. Use page navigation for Q-Z."))
-        })
-        .components(|c| {
-            // Add a page 2 navigation button
-            let mut page_row = serenity::CreateActionRow::default();
-            page_row.create_button(|b| {
-                b.style(serenity::ButtonStyle::Primary)
-                 .custom_id("soviet_hangman_page2")
-                 .label("Page 2 (Y-Z)")
-            });
-            c.add_action_row(page_row);
-            
-            // Add actual letter buttons (just the first 4 rows)
-            for row in create_keyboard_buttons(&game).into_iter().take(4) {
-                c.add_action_row(row);
-            }
-            c
-        })
-    }).await?;
+    fn guess_letter(&mut self, user: &serenity::User, letter: char) -> String {
+        let letter = letter.to_ascii_uppercase();
+        
+        // Two comrades can press the same letter before the keyboard is redrawn
+        if self.game.guessed_letters.contains(&letter) {
+            return format!("{} has already been guessed.", letter);
+        }
+        
+        let correct = self.game.guess_letter(letter);
+        let revealed = if correct { self.game.occurrences(letter) } else { 0 };
+        self.credit(user, revealed);
+        
+        match (self.mode, correct) {
+            (HangmanMode::Solo, true) => "Correct! The letter is present in the word.".to_string(),
+            (HangmanMode::Solo, false) => "Incorrect! You lose an attempt.".to_string(),
+            (_, true) => format!("{} revealed {} × {}!", user.name, revealed, letter),
+            (_, false) => format!("{} guessed {}. Incorrect! The collective loses an attempt.", user.name, letter),
+        }
+    }
     
-    // Button interaction loop
-    let author_id = ctx.author().id;
+    fn guess_word(&mut self, user: &serenity::User, guess: &str) -> String {
+        let hidden = self.game.hidden_letters();
+        let guess = guess.trim().to_uppercase();
+        
+        match (self.game.guess_word(&guess), self.mode) {
+            (Some(true), HangmanMode::Solo) => {
+                self.credit(user, hidden);
+                "Correct! You named the word.".to_string()
+            },
+            (Some(true), _) => {
+                self.credit(user, hidden);
+                format!("{} named the word and revealed the last {} letters!", user.name, hidden)
+            },
+            (Some(false), HangmanMode::Solo) => {
+                self.credit(user, 0);
+                format!("{} is not the word. You lose an attempt.", guess)
+            },
+            (Some(false), _) => {
+                self.credit(user, 0);
+                format!("{} guessed {}. Incorrect! The collective loses an attempt.", user.name, guess)
+            },
+            (None, _) => format!("{} was already tried. No attempt lost.", guess),
+        }
+    }
     
-    // Create a collector for button interactions
-    let mut collector = msg.message().await?.await_component_interactions(ctx)
-        .timeout(Duration::from_secs(180))
-        .filter(move |press| press.user.id == author_id)
-        .build();
+    // Each contributor's cut of a won game's reward: all of it when playing alone,
+    // equal shares in cooperative play and shares by points in competitive play
+    fn reward_shares(&self) -> Vec<(&HangmanPlayer, f64)> {
+        if !self.game.won {
+            return Vec::new();
+        }
+        
+        let contributors: Vec<&HangmanPlayer> = self.players.iter().filter(|p| p.revealed > 0).collect();
+        let total_revealed: usize = contributors.iter().map(|p| p.revealed).sum();
+        let count = contributors.len();
+        
+        contributors
+            .into_iter()
+            .map(|player| {
+                let reward = self.game.difficulty.reward();
+                let share = match self.mode {
+                    HangmanMode::Versus => reward * player.revealed as f64 / total_revealed as f64,
+                    HangmanMode::Solo | HangmanMode::Coop => reward / count as f64,
+                };
+                (player, (share * 100.0).round() / 100.0)
+            })
+            .collect()
+    }
     
-    // Process interactions
-    while let Some(press) = collector.next().await {
-        let custom_id = &press.data.custom_id;
+    // Record an ended game, won, lost or quit, for everyone who played it and pay out
+    // any rewards. A daily attempt also leaves its share grid in the channel.
+    // Returns who earned what, for the final message.
+    async fn settle(
+        &self,
+        ctx: &serenity::Context,
+        data: &Data,
+        server_id: &str,
+        channel_id: serenity::ChannelId,
+    ) -> Result<Option<String>, CommandError> {
+        let duration_secs = self.started_at
+            .map(|started| (chrono::Utc::now().timestamp_millis() - started).max(0) as f64 / 1000.0);
+        
+        for player in &self.players {
+            let result = GameResult {
+                user_id: player.user_id.to_string(),
+                server_id: server_id.to_string(),
+                username: player.name.clone(),
+                game_type: active_games::HANGMAN.to_string(),
+                score: self.game.guesses as f64,
+                won: self.game.won,
+                guesses: self.game.guesses as i64,
+                duration_secs,
+            };
+            data.db.save_game_result(&result).await?;
+            
+            // Daily puzzles are played alone, so this is the starter's attempt
+            if let Some(date) = &self.daily {
+                data.db.finish_daily_attempt(date, &result, &self.game.share_grid(date)).await?;
+            }
+        }
+        
+        if let Some(date) = &self.daily {
+            channel_id.say(ctx, share_note(self.player, &self.game.share_grid(date))).await?;
+        }
+        
+        // There is no treasury to pay from in DMs
+        if server_id == "DM" {
+            return Ok(None);
+        }
+        
+        let shares = self.reward_shares();
+        if shares.is_empty() {
+            return Ok(None);
+        }
+        
+        let mut lines = Vec::new();
+        for (player, amount) in shares {
+            let user_id = player.user_id.to_string();
+            data.db.ensure_user_exists(&user_id, server_id, &player.name).await?;
+            if data.db.claim_daily_reward(&user_id, server_id, active_games::HANGMAN, amount, WORD_REWARDS_PER_DAY).await? {
+                lines.push(format!("<@{}> earned **{:.2}** boops", player.user_id, amount));
+            } else {
+                lines.push(format!("<@{}> has already been paid for {} wins today", player.user_id, WORD_REWARDS_PER_DAY));
+            }
+        }
+        
+        Ok(Some(lines.join("\n")))
+    }
+    
+    // Apply a button press and update the game message. Returns true once the game has ended.
+    async fn handle_press(
+        &mut self,
+        ctx: &serenity::Context,
+        data: &Data,
+        server_id: &str,
+        press: &serenity::MessageComponentInteraction,
+    ) -> Result<bool, CommandError> {
+        let custom_id = press.data.custom_id.as_str();
         
         // Handle quit
         if custom_id == "soviet_hangman_quit" {
+            if press.user.id != self.player {
+                press.create_interaction_response(ctx, |r| {
+                    r.kind(serenity::InteractionResponseType::ChannelMessageWithSource)
+                     .interaction_response_data(|d| {
+                         d.content("Only the comrade who started this game may end it.").ephemeral(true)
+                     })
+                }).await?;
+                return Ok(false);
+            }
+            
+            // Quitting counts as a loss
+            self.settle(ctx, data, server_id, press.channel_id).await?;
             press.create_interaction_response(ctx, |r| {
                 r.kind(serenity::InteractionResponseType::UpdateMessage)
                  .interaction_response_data(|d| {
@@ -369,295 +545,493 @@ pub async fn soviet_hangman(
                          e.title("☭ Comrade Hangman ☭")
                           .description("Game Terminated by User")
                           .color(serenity::Color::DARK_GREY)
-                          .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/a/a9/Soviet_Union_state_emblem.svg/240px-Soviet_Union_state_emblem.svg.png")
-                          .field("Word", &game.target_word, false)
+                          .thumbnail(THUMBNAIL_URL)
+                          .field("Word", &self.game.target_word, false)
                           .footer(|f| f.text("Your defection has been noted in your permanent record."))
                      })
                      .components(|c| c) // Clear components
                  })
             }).await?;
             
-            break;
+            return Ok(true);
         }
         
-        // Handle page switching - to page 1
-        if custom_id == "soviet_hangman_page1" {
-            show_page_2 = false;
+        // Typed guesses go into a pop-up and arrive as a modal submission
+        if custom_id == "soviet_hangman_word" {
             press.create_interaction_response(ctx, |r| {
-                r.kind(serenity::InteractionResponseType::UpdateMessage)
+                r.kind(serenity::InteractionResponseType::Modal)
                  .interaction_response_data(|d| {
-                     d.embed(|e| {
-                         e.title("☭ Comrade Hangman ☭")
-                          .description("State-Approved Word Guessing - Page 1 (A-P)")
-                          .color(serenity::Color::RED)
-                          .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/a/a9/Soviet_Union_state_emblem.svg/240px-Soviet_Union_state_emblem.svg.png")
-                          .field("Category", &game.category, true)
-                          .field("Attempts", format!("{}/{}", game.attempts_left, game.max_attempts), true)
-                          .field("Word", format!("```{}```", game.display_word()), false)
-                          .field("Gallows", game.get_gallows(), false)
-                          .field("Guessed Letters", game.display_guessed_letters(), false)
-                          .footer(|f| f.text("Select a letter on the keyboard. Use page navigation for Q-Z."))
-                     })
-                     .components(|c| {
-                        // Add a page 2 navigation button
-                        let mut page_row = serenity::CreateActionRow::default();
-                        page_row.create_button(|b| {
-                            b.style(serenity::ButtonStyle::Primary)
-                             .custom_id("soviet_hangman_page2")
-                             .label("Page 2 (Y-Z)")
-                        });
-                        c.add_action_row(page_row);
-                        
-                        // Add actual letter buttons (just the first 4 rows)
-                        for row in create_keyboard_buttons(&game).into_iter().take(4) {
-                            c.add_action_row(row);
-                        }
-                        c
-                     })
+                     d.custom_id("soviet_hangman_word")
+                      .title("Make a Guess")
+                      .components(|c| {
+                          c.create_action_row(|row| {
+                              row.create_input_text(|t| {
+                                  t.custom_id("word")
+                                   .label("A letter, or the whole word")
+                                   .placeholder("A wrong word costs an attempt")
+                                   .style(serenity::InputTextStyle::Short)
+                                   .min_length(1)
+                                   .max_length(MAX_WORD_GUESS)
+                                   .required(true)
+                              })
+                          })
+                      })
                  })
             }).await?;
-            continue;
+            
+            return Ok(false);
         }
         
-        // Handle page switching - to page 2
-        if custom_id == "soviet_hangman_page2" {
-            show_page_2 = true;
-            press.create_interaction_response(ctx, |r| {
-                r.kind(serenity::InteractionResponseType::UpdateMessage)
-                 .interaction_response_data(|d| {
-                     d.embed(|e| {
-                         e.title("☭ Comrade Hangman ☭")
-                          .description("State-Approved Word Guessing - Page 2 (Q-Z)")
-                          .color(serenity::Color::RED)
-                          .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/a/a9/Soviet_Union_state_emblem.svg/240px-Soviet_Union_state_emblem.svg.png")
-                          .field("Category", &game.category, true)
-                          .field("Attempts", format!("{}/{}", game.attempts_left, game.max_attempts), true)
-                          .field("Word", format!("```{}```", game.display_word()), false)
-                          .field("Gallows", game.get_gallows(), false)
-                          .field("Guessed Letters", game.display_guessed_letters(), false)
-                          .footer(|f| f.text("Select a letter on the keyboard. Use page navigation to return to A-P."))
-                     })
-                     .components(|c| {
-                         // Page 1 navigation button
-                         let mut page_row = serenity::CreateActionRow::default();
-                         page_row.create_button(|b| {
-                             b.style(serenity::ButtonStyle::Primary)
-                              .custom_id("soviet_hangman_page1")
-                              .label("Page 1 (A-P)")
-                         });
-                         c.add_action_row(page_row);
-                         
-                         // Second half of alphabet (Q-Z)
-                         // Row with Q R S T
-                         let mut row1 = serenity::CreateActionRow::default();
-                         for &letter in &['Q', 'R', 'S', 'T'] {
-                             let already_guessed = game.guessed_letters.contains(&letter);
-                             row1.create_button(|b| {
-                                 b.style(if already_guessed {
-                                     serenity::ButtonStyle::Secondary
-                                 } else {
-                                     serenity::ButtonStyle::Success
-                                 })
-                                  .custom_id(format!("soviet_hangman_{}", letter))
-                                  .label(letter.to_string())
-                                  .disabled(already_guessed)
-                             });
-                         }
-                         c.add_action_row(row1);
-                         
-                         // Row with U V W X
-                         let mut row2 = serenity::CreateActionRow::default();
-                         for &letter in &['U', 'V', 'W', 'X'] {
-                             let already_guessed = game.guessed_letters.contains(&letter);
-                             row2.create_button(|b| {
-                                 b.style(if already_guessed {
-                                     serenity::ButtonStyle::Secondary
-                                 } else {
-                                     serenity::ButtonStyle::Success
-                                 })
-                                  .custom_id(format!("soviet_hangman_{}", letter))
-                                  .label(letter.to_string())
-                                  .disabled(already_guessed)
-                             });
-                         }
-                         c.add_action_row(row2);
-                         
-                         // Row with Y Z
-                         let mut row3 = serenity::CreateActionRow::default();
-                         for &letter in &['Y', 'Z'] {
-                             let already_guessed = game.guessed_letters.contains(&letter);
-                             row3.create_button(|b| {
-                                 b.style(if already_guessed {
-                                     serenity::ButtonStyle::Secondary
-                                 } else {
-                                     serenity::ButtonStyle::Success
-                                 })
-                                  .custom_id(format!("soviet_hangman_{}", letter))
-                                  .label(letter.to_string())
-                                  .disabled(already_guessed)
-                             });
-                         }
-                         c.add_action_row(row3);
-                         
-                         c
-                     })
-                 })
-            }).await?;
-            continue;
+        // Page switching only changes the keyboard
+        let description = match custom_id {
+            "soviet_hangman_page1" => {
+                self.show_page_2 = false;
+                "State-Approved Word Guessing - Page 1 (A-P)".to_string()
+            },
+            "soviet_hangman_page2" => {
+                self.show_page_2 = true;
+                "State-Approved Word Guessing - Page 2 (Q-Z)".to_string()
+            },
+            _ => {
+                // Process a letter guess, from a button or a menu
+                let letter = if custom_id.starts_with("soviet_hangman_menu_") {
+                    press.data.values.first().and_then(|value| value.chars().next())
+                } else {
+                    custom_id.strip_prefix("soviet_hangman_")
+                        .filter(|letter| letter.len() == 1)
+                        .and_then(|letter| letter.chars().next())
+                };
+                
+                match letter {
+                    Some(letter) if letter.is_ascii_alphabetic() => self.guess_letter(&press.user, letter),
+                    _ => return Ok(false),
+                }
+            },
+        };
+        
+        let rewards = if self.game.game_over { self.settle(ctx, data, server_id, press.channel_id).await? } else { None };
+        press.create_interaction_response(ctx, |r| self.update_message(r, &description, rewards.as_deref())).await?;
+        
+        Ok(self.game.game_over)
+    }
+    
+    // Apply a guess typed into the pop-up. Returns true once the game has ended.
+    async fn handle_word_guess(
+        &mut self,
+        ctx: &serenity::Context,
+        data: &Data,
+        server_id: &str,
+        submit: &serenity::ModalSubmitInteraction,
+    ) -> Result<bool, CommandError> {
+        let guess = modal_text(submit, "word").unwrap_or_default();
+        let guess = guess.trim();
+        
+        // A single letter counts as a letter guess, anything longer as the word
+        let description = match guess.chars().collect::<Vec<_>>().as_slice() {
+            [letter] if letter.is_ascii_alphabetic() => self.guess_letter(&submit.user, *letter),
+            _ => self.guess_word(&submit.user, guess),
+        };
+        
+        let rewards = if self.game.game_over { self.settle(ctx, data, server_id, submit.channel_id).await? } else { None };
+        submit.create_interaction_response(ctx, |r| self.update_message(r, &description, rewards.as_deref())).await?;
+        
+        Ok(self.game.game_over)
+    }
+    
+    fn update_message<'a, 'b>(
+        &self,
+        r: &'a mut serenity::CreateInteractionResponse<'b>,
+        description: &str,
+        rewards: Option<&str>,
+    ) -> &'a mut serenity::CreateInteractionResponse<'b> {
+        r.kind(serenity::InteractionResponseType::UpdateMessage)
+         .interaction_response_data(|d| {
+             d.embed(|e| render_embed(e, self, description, rewards))
+              .components(|c| create_keyboard(c, self))
+         })
+    }
+}
+
+// The text typed into one of a modal's inputs
+pub(super) fn modal_text(submit: &serenity::ModalSubmitInteraction, custom_id: &str) -> Option<String> {
+    submit.data.components
+        .iter()
+        .flat_map(|row| &row.components)
+        .find_map(|component| match component {
+            serenity::ActionRowComponent::InputText(input) if input.custom_id == custom_id => Some(input.value.clone()),
+            _ => None,
+        })
+}
+
+fn render_embed<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    session: &HangmanSession,
+    description: &str,
+    rewards: Option<&str>,
+) -> &'a mut serenity::CreateEmbed {
+    let game = &session.game;
+    
+    e.title("☭ Comrade Hangman ☭")
+     .description(description)
+     .color(if game.game_over {
+         if game.won {
+             serenity::Color::DARK_GREEN
+         } else {
+             serenity::Color::DARK_RED
+         }
+     } else {
+         serenity::Color::RED
+     })
+     .thumbnail(THUMBNAIL_URL)
+     .field("Category", &game.category, true)
+     .field("Attempts", format!("{}/{}", game.attempts_left, game.max_attempts), true)
+     .field("Word", format!("```{}```", game.display_word()), false)
+     .field("Gallows", game.get_gallows(), false)
+     .field("Guessed Letters", game.display_guessed_letters(), false);
+    
+    if !game.wrong_words.is_empty() {
+        e.field("Wrong Words", game.wrong_words.join(", "), false);
+    }
+    
+    if session.mode != HangmanMode::Solo {
+        e.field("Mode", session.mode.name(), true);
+        
+        let mut players: Vec<&HangmanPlayer> = session.players.iter().collect();
+        if session.mode == HangmanMode::Versus {
+            players.sort_by_key(|p| std::cmp::Reverse(p.revealed));
         }
         
-        // Process a letter guess
-        if custom_id.starts_with("soviet_hangman_") && custom_id.len() == 16 {
-            let letter = custom_id.chars().last().unwrap();
-            let correct = game.guess_letter(letter);
+        let unit = if session.mode == HangmanMode::Versus { "points" } else { "letters" };
+        let tally = if players.is_empty() {
+            "Nobody has guessed yet.".to_string()
+        } else {
+            players
+                .iter()
+                .map(|p| format!("<@{}> - {} {}", p.user_id, p.revealed, unit))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        e.field("Comrades", tally, false);
+    }
+    
+    if game.game_over {
+        if game.won {
+            e.field("Result", "Victory! The state commends your lexical knowledge!", false);
+        } else {
+            e.field("Result", format!("Failure! The correct word was **{}**", game.target_word), false);
+        }
+        if let Some(rewards) = rewards {
+            e.field("Rewards", rewards, false);
+        }
+        match &session.daily {
+            Some(date) => {
+                e.field("Share", game.share_grid(date), false);
+                e.footer(|f| f.text("A new daily word is issued tomorrow. See /daily leaderboard."))
+            },
+            None => e.footer(|f| f.text("Game over. Use /soviet_hangman to play again.")),
+        }
+    } else if session.mode != HangmanMode::Solo {
+        e.footer(|f| f.text("Any comrade may pick a letter or type a guess."))
+    } else if session.input == HangmanInput::Menus {
+        e.footer(|f| f.text("Pick a letter from the menus, or type a guess."))
+    } else if session.show_page_2 {
+        e.footer(|f| f.text("Select a letter on the keyboard. Use page navigation to return to A-P."))
+    } else {
+        e.footer(|f| f.text("Select a letter on the keyboard. Use page navigation for Q-Z."))
+    }
+}
+
+// The controls under the game, in the input style the game was started with.
+// Empty once the game is over.
+fn create_keyboard<'a>(
+    c: &'a mut serenity::CreateComponents,
+    session: &HangmanSession,
+) -> &'a mut serenity::CreateComponents {
+    if session.game.game_over {
+        return c;
+    }
+    
+    match session.input {
+        HangmanInput::Buttons => create_button_keyboard(c, &session.game, session.show_page_2),
+        HangmanInput::Menus => create_menu_keyboard(c, &session.game),
+    }
+}
+
+// The on-screen keyboard: page 1 has Quit and A-P, page 2 has Q-Z. Both open with
+// the page switch and the typed guess.
+// Discord allows at most 5 buttons per row and 5 rows per message.
+fn create_button_keyboard<'a>(
+    c: &'a mut serenity::CreateComponents,
+    game: &SovietHangman,
+    show_page_2: bool,
+) -> &'a mut serenity::CreateComponents {
+    let (page_button, letter_rows): (_, &[&[char]]) = if show_page_2 {
+        (
+            ("soviet_hangman_page1", "Page 1 (A-P)"),
+            &[&['Q', 'R', 'S', 'T'], &['U', 'V', 'W', 'X'], &['Y', 'Z']],
+        )
+    } else {
+        (
+            ("soviet_hangman_page2", "Page 2 (Q-Z)"),
+            &[&['A', 'B', 'C', 'D'], &['E', 'F', 'G', 'H'], &['I', 'J', 'K', 'L'], &['M', 'N', 'O', 'P']],
+        )
+    };
+    
+    c.create_action_row(|row| {
+        row.create_button(|b| {
+            b.style(serenity::ButtonStyle::Primary)
+             .custom_id(page_button.0)
+             .label(page_button.1)
+        })
+        .create_button(|b| {
+            b.style(serenity::ButtonStyle::Danger)
+             .custom_id("soviet_hangman_word")
+             .label("Type a Guess")
+        })
+    });
+    
+    for (i, letters) in letter_rows.iter().enumerate() {
+        c.create_action_row(|row| {
+            // The quit button sits in front of A-D
+            if i == 0 && !show_page_2 {
+                row.create_button(|b| {
+                    b.style(serenity::ButtonStyle::Primary)
+                     .custom_id("soviet_hangman_quit")
+                     .label("Quit")
+                });
+            }
             
-            // Update the message
-            press.create_interaction_response(ctx, |r| {
-                r.kind(serenity::InteractionResponseType::UpdateMessage)
-                 .interaction_response_data(|d| {
-                     d.embed(|e| {
-                         e.title("☭ Comrade Hangman ☭")
-                          .description(if correct {
-                              "Correct! The letter is present in the word."
-                          } else {
-                              "Incorrect! You lose an attempt."
-                          })
-                          .color(if game.game_over {
-                              if game.won {
-                                  serenity::Color::DARK_GREEN
-                              } else {
-                                  serenity::Color::DARK_RED
-                              }
-                          } else {
-                              serenity::Color::RED
-                          })
-                          .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/a/a9/Soviet_Union_state_emblem.svg/240px-Soviet_Union_state_emblem.svg.png")
-                          .field("Category", &game.category, true)
-                          .field("Attempts", format!("{}/{}", game.attempts_left, game.max_attempts), true)
-                          .field("Word", format!("```{}```", game.display_word()), false)
-                          .field("Gallows", game.get_gallows(), false)
-                          .field("Guessed Letters", game.display_guessed_letters(), false);
-                          
-                         if game.game_over {
-                             if game.won {
-                                 e.field("Result", "Victory! The state commends your lexical knowledge!", false);
-                             } else {
-                                 e.field("Result", format!("Failure! The correct word was **{}**", game.target_word), false);
-                             }
-                             e.footer(|f| f.text("Game over. Use /soviet_hangman to play again."));
-                         } else {
-                             if show_page_2 {
-                                 e.footer(|f| f.text("Select a letter on the keyboard. Use page navigation to return to A-P."));
-                             } else {
-                                 e.footer(|f| f.text("Select a letter on the keyboard. Use page navigation for Q-Z."));
-                             }
-                         }
-                         
-                         e
-                     });
-                     
-                     // Only keep buttons if game is still active
-                     if !game.game_over {
-                         d.components(|c| {
-                             if show_page_2 {
-                                 // Page 1 navigation button
-                                 let mut page_row = serenity::CreateActionRow::default();
-                                 page_row.create_button(|b| {
-                                     b.style(serenity::ButtonStyle::Primary)
-                                      .custom_id("soviet_hangman_page1")
-                                      .label("Page 1 (A-P)")
-                                 });
-                                 c.add_action_row(page_row);
-                                 
-                                 // Second half of alphabet (Q-Z)
-                                 // Row with Q R S T
-                                 let mut row1 = serenity::CreateActionRow::default();
-                                 for &letter in &['Q', 'R', 'S', 'T'] {
-                                     let already_guessed = game.guessed_letters.contains(&letter);
-                                     row1.create_button(|b| {
-                                         b.style(if already_guessed {
-                                             serenity::ButtonStyle::Secondary
-                                         } else {
-                                             serenity::ButtonStyle::Success
-                                         })
-                                          .custom_id(format!("soviet_hangman_{}", letter))
-                                          .label(letter.to_string())
-                                          .disabled(already_guessed)
-                                     });
-                                 }
-                                 c.add_action_row(row1);
-                                 
-                                 // Row with U V W X
-                                 let mut row2 = serenity::CreateActionRow::default();
-                                 for &letter in &['U', 'V', 'W', 'X'] {
-                                     let already_guessed = game.guessed_letters.contains(&letter);
-                                     row2.create_button(|b| {
-                                         b.style(if already_guessed {
-                                             serenity::ButtonStyle::Secondary
-                                         } else {
-                                             serenity::ButtonStyle::Success
-                                         })
-                                          .custom_id(format!("soviet_hangman_{}", letter))
-                                          .label(letter.to_string())
-                                          .disabled(already_guessed)
-                                     });
-                                 }
-                                 c.add_action_row(row2);
-                                 
-                                 // Row with Y Z
-                                 let mut row3 = serenity::CreateActionRow::default();
-                                 for &letter in &['Y', 'Z'] {
-                                     let already_guessed = game.guessed_letters.contains(&letter);
-                                     row3.create_button(|b| {
-                                         b.style(if already_guessed {
-                                             serenity::ButtonStyle::Secondary
-                                         } else {
-                                             serenity::ButtonStyle::Success
-                                         })
-                                          .custom_id(format!("soviet_hangman_{}", letter))
-                                          .label(letter.to_string())
-                                          .disabled(already_guessed)
-                                     });
-                                 }
-                                 c.add_action_row(row3);
-                             } else {
-                                 // Page 2 navigation button
-                                 let mut page_row = serenity::CreateActionRow::default();
-                                 page_row.create_button(|b| {
-                                     b.style(serenity::ButtonStyle::Primary)
-                                      .custom_id("soviet_hangman_page2")
-                                      .label("Page 2 (Q-Z)")
-                                 });
-                                 c.add_action_row(page_row);
-                                 
-                                 // Letter buttons A-P
-                                 for row in create_keyboard_buttons(&game) {
-                                     c.add_action_row(row);
-                                 }
-                             }
-                             c
-                         });
-                     }
-                     
-                     d
-                 })
-            }).await?;
+            for &letter in letters.iter() {
+                let already_guessed = game.guessed_letters.contains(&letter);
+                row.create_button(|b| {
+                    b.style(if already_guessed {
+                        serenity::ButtonStyle::Secondary
+                    } else {
+                        serenity::ButtonStyle::Success
+                    })
+                     .custom_id(format!("soviet_hangman_{}", letter))
+                     .label(letter.to_string())
+                     .disabled(already_guessed)
+                });
+            }
+            row
+        });
+    }
+    c
+}
+
+// The whole alphabet on one page: a select menu for each half, offering the letters
+// not yet guessed, with the typed guess and quit buttons below
+fn create_menu_keyboard<'a>(
+    c: &'a mut serenity::CreateComponents,
+    game: &SovietHangman,
+) -> &'a mut serenity::CreateComponents {
+    for (custom_id, first, last) in [("soviet_hangman_menu_am", 'A', 'M'), ("soviet_hangman_menu_nz", 'N', 'Z')] {
+        let open: Vec<char> = (first..=last).filter(|letter| !game.guessed_letters.contains(letter)).collect();
+        
+        c.create_action_row(|row| {
+            row.create_select_menu(|menu| {
+                menu.custom_id(custom_id)
+                    .placeholder(if open.is_empty() {
+                        format!("Every letter {}-{} has been guessed", first, last)
+                    } else {
+                        format!("Guess a letter ({}-{})", first, last)
+                    })
+                    .disabled(open.is_empty())
+                    .options(|options| {
+                        // A menu needs an option even when there is nothing left to pick
+                        if open.is_empty() {
+                            options.create_option(|o| o.label("-").value("-"));
+                        }
+                        for letter in &open {
+                            options.create_option(|o| o.label(letter.to_string()).value(letter.to_string()));
+                        }
+                        options
+                    })
+            })
+        });
+    }
+    
+    c.create_action_row(|row| {
+        row.create_button(|b| {
+            b.style(serenity::ButtonStyle::Danger)
+             .custom_id("soviet_hangman_word")
+             .label("Type a Guess")
+        })
+        .create_button(|b| {
+            b.style(serenity::ButtonStyle::Primary)
+             .custom_id("soviet_hangman_quit")
+             .label("Quit")
+        })
+    })
+}
+
+// Apply a button press to a saved game whose command is no longer running
+pub(crate) async fn resume_hangman_press(
+    ctx: &serenity::Context,
+    data: &Data,
+    record: &ActiveGame,
+    press: &serenity::MessageComponentInteraction,
+) -> Result<Resumed, CommandError> {
+    let mut session: HangmanSession = serde_json::from_str(&record.state)?;
+    
+    if !session.may_play(press.user.id) {
+        return Ok(Resumed::Ignored);
+    }
+    
+    if session.handle_press(ctx, data, &record.server_id, press).await? {
+        Ok(Resumed::Finished)
+    } else {
+        Ok(Resumed::Continue(serde_json::to_string(&session)?))
+    }
+}
+
+// Apply a typed guess to a saved game whose command is no longer running
+pub(crate) async fn resume_hangman_word(
+    ctx: &serenity::Context,
+    data: &Data,
+    record: &ActiveGame,
+    submit: &serenity::ModalSubmitInteraction,
+) -> Result<Resumed, CommandError> {
+    let mut session: HangmanSession = serde_json::from_str(&record.state)?;
+    
+    if !session.may_play(submit.user.id) {
+        return Ok(Resumed::Ignored);
+    }
+    
+    if session.handle_word_guess(ctx, data, &record.server_id, submit).await? {
+        Ok(Resumed::Finished)
+    } else {
+        Ok(Resumed::Continue(serde_json::to_string(&session)?))
+    }
+}
+
+/// Play Soviet-themed Hangman, alone or with the whole channel
+#[poise::command(slash_command, prefix_command)]
+pub async fn soviet_hangman(
+    ctx: crate::Context<'_>,
+    #[description = "Word difficulty: easy, medium or hard (default: any)"]
+    difficulty: Option<String>,
+    #[description = "Mode: solo (default), coop (everyone guesses together) or versus (points per letter)"]
+    mode: Option<String>,
+) -> Result<(), CommandError> {
+    let difficulty = match difficulty.as_deref() {
+        Some(name) => Some(WordDifficulty::from_name(name)
+            .ok_or("Difficulty must be `easy`, `medium` or `hard`, comrade.")?),
+        None => None,
+    };
+    
+    let mode = match mode.as_deref() {
+        Some(name) => HangmanMode::from_name(name)
+            .ok_or("Mode must be `solo`, `coop` or `versus`, comrade.")?,
+        None => HangmanMode::Solo,
+    };
+    
+    let server_id = ctx.guild_id().map(|id| id.to_string()).unwrap_or_else(|| "DM".to_string());
+    let entry = pick_word(
+        &ctx.data().db,
+        &server_id,
+        &ctx.author().id.to_string(),
+        active_games::HANGMAN,
+        difficulty,
+    ).await?;
+    
+    play_hangman(ctx, &server_id, entry, mode, None).await
+}
+
+// Play the server's word of the day alone, as the author's one daily attempt
+pub(crate) async fn play_daily_hangman(
+    ctx: crate::Context<'_>,
+    server_id: &str,
+    date: &str,
+    entry: WordEntry,
+) -> Result<(), CommandError> {
+    play_hangman(ctx, server_id, entry, HangmanMode::Solo, Some(date.to_string())).await
+}
+
+// Start a game of the author's on a new message and see it through
+async fn play_hangman(
+    ctx: crate::Context<'_>,
+    server_id: &str,
+    entry: WordEntry,
+    mode: HangmanMode,
+    daily: Option<String>,
+) -> Result<(), CommandError> {
+    let settings = ctx.data().db.get_hangman_settings(server_id).await?;
+    let input = HangmanInput::from_name(&settings.input_style).unwrap_or_default();
+    
+    // The starter is on the scoresheet even if they never guess
+    let mut session = HangmanSession {
+        player: ctx.author().id,
+        game: SovietHangman::new(entry),
+        show_page_2: false,
+        mode,
+        players: vec![HangmanPlayer {
+            user_id: ctx.author().id,
+            name: ctx.author().name.clone(),
+            revealed: 0,
+        }],
+        input,
+        started_at: Some(chrono::Utc::now().timestamp_millis()),
+        daily,
+    };
+    
+    // Initial message with the first page of the keyboard. A daily attempt is played
+    // where only its player can see it, so the word stays a secret for everyone else.
+    let msg = ctx.send(|m| {
+        m.ephemeral(session.daily.is_some())
+         .embed(|e| render_embed(e, &session, input.opening_description(), None))
+         .components(|c| create_keyboard(c, &session))
+    }).await?;
+    
+    let message = msg.message().await?;
+    let _live = ctx.data().games.track_live(message.id);
+    save_game(ctx, message.id, active_games::HANGMAN, &session, None).await?;
+    
+    // Create collectors for button and menu presses and for guesses typed into the pop-up
+    let author_id = session.player;
+    let mut presses = message.await_component_interactions(ctx)
+        .timeout(SESSION_TIMEOUT)
+        .filter(move |press| mode != HangmanMode::Solo || press.user.id == author_id)
+        .build();
+    let mut words = serenity::ModalInteractionCollectorBuilder::new(ctx)
+        .message_id(message.id)
+        .timeout(SESSION_TIMEOUT)
+        .filter(move |submit| mode != HangmanMode::Solo || submit.user.id == author_id)
+        .build();
+    
+    let played: Result<bool, CommandError> = async {
+        loop {
+            let finished = tokio::select! {
+                Some(press) = presses.next() => {
+                    session.handle_press(ctx.serenity_context(), ctx.data(), server_id, &press).await?
+                },
+                Some(submit) = words.next() => {
+                    session.handle_word_guess(ctx.serenity_context(), ctx.data(), server_id, &submit).await?
+                },
+                else => return Ok(false),
+            };
             
-            if game.game_over {
-                break;
+            if finished {
+                return Ok(true);
             }
+            save_game(ctx, message.id, active_games::HANGMAN, &session, None).await?;
         }
-    }
+    }.await;
+    
+    // Finished, expired or broken, the game no longer needs resuming
+    forget_game(ctx, message.id).await?;
     
-    // If we exit the loop without the game being over, the collector timed out
-    if !game.game_over {
+    // If the game didn't end, the collectors timed out and it counts as a loss
+    if !played? {
+        session.settle(ctx.serenity_context(), ctx.data(), server_id, ctx.channel_id()).await?;
         msg.edit(ctx, |m| {
             m.embed(|e| {
                 e.title("☭ Comrade Hangman ☭")
                  .description("Session Expired")
                  .color(serenity::Color::DARK_GREY)
-                 .thumbnail("https://upload.wikimedia.org/wikipedia/commons/thumb/a/a9/Soviet_Union_state_emblem.svg/240px-Soviet_Union_state_emblem.svg.png")
-                 .field("Word", &game.target_word, false)
+                 .thumbnail(THUMBNAIL_URL)
+                 .field("Word", &session.game.target_word, false)
                  .footer(|f| f.text("Your inactivity has been reported to the authorities."))
             })
             .components(|c| c) // Clear components
@@ -665,4 +1039,179 @@ pub async fn soviet_hangman(
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Configure Soviet Hangman for this server (Admin only)
+#[poise::command(slash_command, prefix_command, check = "check_if_admin")]
+pub async fn hangman_settings(
+    ctx: crate::Context<'_>,
+    #[description = "How letters are picked: buttons (two pages) or menus (one page)"]
+    input_style: Option<String>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+    
+    let db = &ctx.data().db;
+    let mut settings = db.get_hangman_settings(&server_id).await?;
+    
+    let changed = input_style.is_some();
+    if let Some(name) = input_style {
+        let input = HangmanInput::from_name(&name)
+            .ok_or("Input style must be either `buttons` or `menus`, comrade.")?;
+        settings.input_style = input.name().to_string();
+        db.set_hangman_settings(&server_id, &settings).await?;
+    }
+    
+    let input = HangmanInput::from_name(&settings.input_style).unwrap_or_default();
+    let layout = match input {
+        HangmanInput::Buttons => "Letter buttons on two pages, A-P and Q-Z",
+        HangmanInput::Menus => "Two letter menus, A-M and N-Z, on a single page",
+    };
+    
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title("☭ Ministry of Hangman ☭")
+             .description(if changed {
+                 "The hangman decree has been amended. New games will use it."
+             } else {
+                 "Current hangman decree for this collective."
+             })
+             .color(serenity::Color::RED)
+             .field("Input Style", format!("**{}** - {}", input.name(), layout), false)
+             .field("Typed Guesses", "Every game also takes a letter or the whole word through Type a Guess.", false)
+             .footer(|f| f.text("A uniform keyboard for a uniform people."))
+        })
+    }).await?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::word_banks::WordDifficulty;
+
+    fn comrade(id: u64, name: &str) -> serenity::User {
+        let mut user = serenity::User::default();
+        user.id = serenity::UserId(id);
+        user.name = name.to_string();
+        user
+    }
+
+    fn session(mode: HangmanMode, word: &str) -> HangmanSession {
+        HangmanSession {
+            player: serenity::UserId(1),
+            game: SovietHangman::new(WordEntry {
+                category: "Soviet Leaders".to_string(),
+                word: word.to_string(),
+                difficulty: WordDifficulty::Easy,
+            }),
+            show_page_2: false,
+            mode,
+            players: Vec::new(),
+            input: HangmanInput::Menus,
+            started_at: None,
+            daily: None,
+        }
+    }
+
+    fn shares(session: &HangmanSession) -> Vec<(u64, f64)> {
+        session.reward_shares().into_iter().map(|(p, amount)| (p.user_id.0, amount)).collect()
+    }
+
+    #[test]
+    fn menus_offer_only_unguessed_letters() {
+        let mut game = session(HangmanMode::Solo, "Lenin");
+        for letter in ['a', 'e', 'n', 'z'] {
+            game.guess_letter(&comrade(1, "lenin"), letter);
+        }
+
+        let mut components = serenity::CreateComponents::default();
+        create_keyboard(&mut components, &game);
+        assert_eq!(components.0.len(), 3, "both menus and the buttons fit on one page");
+
+        let letters = |row: usize| -> String {
+            components.0[row]["components"][0]["options"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|option| option["value"].as_str().unwrap())
+                .collect()
+        };
+        assert_eq!(letters(0), "BCDFGHIJKLM");
+        assert_eq!(letters(1), "OPQRSTUVWXY");
+    }
+
+    #[test]
+    fn only_the_starter_plays_solo() {
+        assert!(session(HangmanMode::Solo, "Lenin").may_play(serenity::UserId(1)));
+        assert!(!session(HangmanMode::Solo, "Lenin").may_play(serenity::UserId(2)));
+        assert!(session(HangmanMode::Coop, "Lenin").may_play(serenity::UserId(2)));
+    }
+
+    #[test]
+    fn cooperative_rewards_are_shared_evenly() {
+        let (lenin, stalin, trotsky) = (comrade(1, "lenin"), comrade(2, "stalin"), comrade(3, "trotsky"));
+        let mut game = session(HangmanMode::Coop, "Stalin");
+
+        game.guess_letter(&lenin, 's');
+        game.guess_letter(&trotsky, 'q');
+        assert!(shares(&game).is_empty(), "nothing is paid before the word is found");
+
+        game.guess_letter(&stalin, 'a');
+        game.guess_word(&stalin, " stalin ");
+        assert!(game.game.won);
+
+        // Trotsky guessed but revealed nothing
+        assert_eq!(shares(&game), [(1, 1.0), (2, 1.0)]);
+    }
+
+    #[test]
+    fn competitive_rewards_follow_revealed_letters() {
+        let (lenin, stalin) = (comrade(1, "lenin"), comrade(2, "stalin"));
+        let mut game = session(HangmanMode::Versus, "Kolkhoz");
+
+        game.guess_letter(&lenin, 'o');
+        game.guess_letter(&stalin, 'k');
+        game.guess_letter(&stalin, 'k');
+        assert_eq!(game.players[1].revealed, 2, "a repeated letter scores nothing");
+
+        game.guess_word(&lenin, "kolkhoz");
+        assert_eq!(game.players[0].revealed, 5);
+        assert_eq!(shares(&game), [(1, 1.43), (2, 0.57)]);
+    }
+
+    #[test]
+    fn wrong_words_cost_one_attempt_each() {
+        let lenin = comrade(1, "lenin");
+        let mut game = session(HangmanMode::Solo, "Red Army");
+
+        game.guess_word(&lenin, "Red Navy");
+        game.guess_word(&lenin, "red  navy");
+        assert_eq!(game.game.attempts_left, 5);
+
+        game.guess_word(&lenin, "red army");
+        assert!(game.game.won);
+        assert_eq!(game.game.display_word(), "RED ARMY");
+        assert_eq!(game.game.guesses, 2, "the repeated wrong word isn't counted");
+        assert_eq!(shares(&game), [(1, WordDifficulty::Easy.reward())]);
+    }
+
+    #[test]
+    fn share_grids_keep_the_word_secret() {
+        let lenin = comrade(1, "lenin");
+        let mut game = session(HangmanMode::Solo, "Lenin");
+
+        game.guess_letter(&lenin, 'e');
+        game.guess_letter(&lenin, 'z');
+        game.guess_word(&lenin, "Stalin");
+        game.guess_word(&lenin, "Lenin");
+
+        let grid = game.game.share_grid("2026-10-18");
+        assert_eq!(grid.lines().nth(1), Some("🟩⬛🟥🌟"));
+        assert!(grid.contains("Solved in 4 guesses, 4/6 lives left"));
+        assert!(!grid.to_uppercase().contains("LENIN"));
+    }
+}
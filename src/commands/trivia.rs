@@ -0,0 +1,790 @@
+use crate::CommandError;
+use crate::db::Database;
+use super::admin::check_if_admin;
+use super::game_engine::{run_game, Game, Outcome, Turn};
+use poise::serenity_prelude as serenity;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+// The question packs shipped with the bot
+const BUNDLED_PACKS: [&str; 3] = [
+    include_str!("../../assets/trivia/soviet_history.json"),
+    include_str!("../../assets/trivia/space_race.json"),
+    include_str!("../../assets/trivia/cold_war.json"),
+];
+
+const TRIVIA_QUESTIONS: std::ops::RangeInclusive<usize> = 1..=20;
+const DEFAULT_QUESTIONS: usize = 5;
+const TRIVIA_MAX_PLAYERS: usize = 10;
+
+// How long each question stays open, and how long a quiz waits to be started
+const QUESTION_TIME: Duration = Duration::from_secs(20);
+const LOBBY_TIME: Duration = Duration::from_secs(60);
+
+// A correct answer earns the most when given at once, falling to the least at the buzzer
+const MAX_POINTS: u32 = 1000;
+const MIN_POINTS: u32 = 500;
+
+// Limits on imported packs. Answers become button labels, which Discord caps at 80 characters.
+const ANSWER_COUNTS: std::ops::RangeInclusive<usize> = 2..=4;
+const MAX_QUESTION_LENGTH: usize = 300;
+const MAX_ANSWER_LENGTH: usize = 80;
+const MAX_PACK_NAME_LENGTH: usize = 40;
+const MAX_PACK_QUESTIONS: usize = 200;
+const MAX_PACK_BYTES: u64 = 100_000;
+const MAX_SERVER_PACKS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TriviaQuestion {
+    question: String,
+    answers: Vec<String>,
+    // Index of the right answer
+    correct: usize,
+}
+
+// A named set of questions, bundled or imported by a server as JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TriviaPack {
+    name: String,
+    #[serde(default)]
+    description: String,
+    questions: Vec<TriviaQuestion>,
+}
+
+impl TriviaPack {
+    // Read a pack and check it will fit on screen and in buttons
+    fn parse(json: &str) -> Result<Self, String> {
+        let mut pack: TriviaPack = serde_json::from_str(json).map_err(|e| format!("not a valid pack: {}", e))?;
+        pack.name = pack.name.trim().to_string();
+
+        if pack.name.is_empty() || pack.name.chars().count() > MAX_PACK_NAME_LENGTH {
+            return Err(format!("the name must be between 1 and {} characters", MAX_PACK_NAME_LENGTH));
+        }
+        if pack.questions.is_empty() || pack.questions.len() > MAX_PACK_QUESTIONS {
+            return Err(format!("a pack holds between 1 and {} questions", MAX_PACK_QUESTIONS));
+        }
+
+        for (number, question) in pack.questions.iter().enumerate() {
+            let number = number + 1;
+            if question.question.trim().is_empty() || question.question.chars().count() > MAX_QUESTION_LENGTH {
+                return Err(format!("question {} must be between 1 and {} characters", number, MAX_QUESTION_LENGTH));
+            }
+            if !ANSWER_COUNTS.contains(&question.answers.len()) {
+                return Err(format!(
+                    "question {} needs between {} and {} answers",
+                    number, ANSWER_COUNTS.start(), ANSWER_COUNTS.end()
+                ));
+            }
+            if question.answers.iter().any(|a| a.trim().is_empty() || a.chars().count() > MAX_ANSWER_LENGTH) {
+                return Err(format!("question {} has an answer that is empty or over {} characters", number, MAX_ANSWER_LENGTH));
+            }
+            if question.correct >= question.answers.len() {
+                return Err(format!("question {} marks an answer that doesn't exist as correct", number));
+            }
+        }
+
+        Ok(pack)
+    }
+
+    fn bundled() -> &'static [TriviaPack] {
+        static PACKS: OnceLock<Vec<TriviaPack>> = OnceLock::new();
+        PACKS.get_or_init(|| {
+            BUNDLED_PACKS
+                .iter()
+                .map(|json| TriviaPack::parse(json).expect("bundled trivia pack is malformed"))
+                .collect()
+        })
+    }
+
+    fn is_bundled(name: &str) -> bool {
+        TriviaPack::bundled().iter().any(|pack| pack.name.eq_ignore_ascii_case(name))
+    }
+}
+
+// The bundled packs followed by the server's own
+async fn server_packs(db: &Database, server_id: &str) -> Result<Vec<TriviaPack>, CommandError> {
+    let mut packs = TriviaPack::bundled().to_vec();
+
+    // Imported packs were checked on the way in
+    for (_, json) in db.get_trivia_packs(server_id).await? {
+        if let Ok(pack) = TriviaPack::parse(&json) {
+            packs.push(pack);
+        }
+    }
+
+    Ok(packs)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum TriviaMode {
+    // One comrade against the clock
+    Solo,
+    // Everyone who joins answers the same questions
+    Party,
+}
+
+impl TriviaMode {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "solo" => Some(TriviaMode::Solo),
+            "party" | "multiplayer" | "race" => Some(TriviaMode::Party),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Contestant {
+    user: serenity::User,
+    points: u32,
+    correct: usize,
+    // Their answer to the open question, if they've given one
+    answer: Option<usize>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Trivia {
+    mode: TriviaMode,
+    pack_name: String,
+    questions: Vec<TriviaQuestion>,
+    current: usize,
+    // Everyone playing, the comrade who started the quiz first
+    contestants: Vec<Contestant>,
+    // How long the open question has been shown, as of the last answer
+    elapsed: Duration,
+    // How the last question went, shown above the next
+    last_result: Option<String>,
+    started: bool,
+    game_over: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TriviaMove {
+    Start { by: serenity::UserId },
+    // Enter a party quiz before it starts
+    Join(serenity::User),
+    // `think_time` is how long the board was shown before the press
+    Answer { by: serenity::UserId, choice: usize, think_time: Duration },
+}
+
+// Points for a correct answer given this long after the question appeared
+fn points_for(answered_after: Duration) -> u32 {
+    let left = QUESTION_TIME.saturating_sub(answered_after).as_secs_f64() / QUESTION_TIME.as_secs_f64();
+    MIN_POINTS + ((MAX_POINTS - MIN_POINTS) as f64 * left).round() as u32
+}
+
+impl Trivia {
+    // Draws the questions at random and shuffles each one's answers
+    fn new(mode: TriviaMode, host: &serenity::User, pack_name: &str, pool: &[TriviaQuestion], count: usize) -> Self {
+        let mut rng = rand::thread_rng();
+        let questions = pool
+            .choose_multiple(&mut rng, count)
+            .map(|question| {
+                let right = question.answers[question.correct].clone();
+                let mut answers = question.answers.clone();
+                answers.shuffle(&mut rng);
+                let correct = answers.iter().position(|a| *a == right).unwrap_or(0);
+                TriviaQuestion { question: question.question.clone(), answers, correct }
+            })
+            .collect();
+
+        Trivia {
+            mode,
+            pack_name: pack_name.to_string(),
+            questions,
+            current: 0,
+            contestants: vec![Contestant { user: host.clone(), points: 0, correct: 0, answer: None }],
+            elapsed: Duration::ZERO,
+            last_result: None,
+            started: false,
+            game_over: false,
+        }
+    }
+
+    fn join(&mut self, user: serenity::User) -> bool {
+        if self.mode != TriviaMode::Party
+            || self.started
+            || self.contestants.len() >= TRIVIA_MAX_PLAYERS
+            || self.contestants.iter().any(|c| c.user.id == user.id)
+        {
+            return false;
+        }
+
+        self.contestants.push(Contestant { user, points: 0, correct: 0, answer: None });
+        true
+    }
+
+    fn answer(&mut self, by: serenity::UserId, choice: usize, think_time: Duration) -> bool {
+        if !self.started || self.game_over || choice >= self.questions[self.current].answers.len() {
+            return false;
+        }
+
+        // One answer each per question
+        let contestant = match self.contestants.iter().position(|c| c.user.id == by && c.answer.is_none()) {
+            Some(contestant) => contestant,
+            None => return false,
+        };
+
+        self.elapsed = (self.elapsed + think_time).min(QUESTION_TIME);
+        let contestant = &mut self.contestants[contestant];
+        contestant.answer = Some(choice);
+        if choice == self.questions[self.current].correct {
+            contestant.points += points_for(self.elapsed);
+            contestant.correct += 1;
+        }
+
+        if self.contestants.iter().all(|c| c.answer.is_some()) {
+            self.close_question();
+        }
+        true
+    }
+
+    // Sum up the open question and move on to the next, or end the quiz after the last
+    fn close_question(&mut self) {
+        let question = &self.questions[self.current];
+        let right = &question.answers[question.correct];
+
+        let mut result = format!("Question {}: the answer was **{}**.", self.current + 1, right);
+        if self.mode == TriviaMode::Party {
+            let (correct, wrong): (Vec<&Contestant>, Vec<&Contestant>) = self.contestants
+                .iter()
+                .filter(|c| c.answer.is_some())
+                .partition(|c| c.answer == Some(question.correct));
+            if !correct.is_empty() {
+                let names: Vec<&str> = correct.iter().map(|c| c.user.name.as_str()).collect();
+                result.push_str(&format!("\n✅ {}", names.join(", ")));
+            }
+            if !wrong.is_empty() {
+                let names: Vec<&str> = wrong.iter().map(|c| c.user.name.as_str()).collect();
+                result.push_str(&format!("\n❌ {}", names.join(", ")));
+            }
+        } else {
+            match self.contestants[0].answer {
+                Some(answer) if answer == question.correct => result.push_str(" Correct!"),
+                Some(_) => result.push_str(" Wrong!"),
+                None => result.push_str(" Too slow!"),
+            }
+        }
+        self.last_result = Some(result);
+
+        for contestant in &mut self.contestants {
+            contestant.answer = None;
+        }
+        self.elapsed = Duration::ZERO;
+        self.current += 1;
+        self.game_over = self.current >= self.questions.len();
+    }
+
+    // By points, then by correct answers
+    fn standings(&self) -> Vec<&Contestant> {
+        let mut standings: Vec<&Contestant> = self.contestants.iter().collect();
+        standings.sort_by_key(|c| (std::cmp::Reverse(c.points), std::cmp::Reverse(c.correct)));
+        standings
+    }
+
+    fn render_standings(&self) -> String {
+        self.standings()
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!(
+                "{}. **{}** - {} points ({}/{} correct)",
+                i + 1, c.user.name, c.points, c.correct, self.questions.len()
+            ))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Game for Trivia {
+    type Move = TriviaMove;
+
+    const KEY: &'static str = "trivia";
+    const NAME: &'static str = "Soviet Trivia";
+    // The clock means nothing across a restart
+    const RESUMABLE: bool = false;
+    const HIGHER_SCORES_WIN: bool = true;
+
+    fn turn(&self) -> Turn {
+        match self.mode {
+            _ if self.game_over => Turn::Over,
+            TriviaMode::Solo => Turn::Player(0),
+            TriviaMode::Party => Turn::Open,
+        }
+    }
+
+    fn legal_moves(&self) -> Vec<TriviaMove> {
+        let by = self.contestants[0].user.id;
+        if !self.started {
+            return vec![TriviaMove::Start { by }];
+        }
+        if self.game_over {
+            return Vec::new();
+        }
+        (0..self.questions[self.current].answers.len())
+            .map(|choice| TriviaMove::Answer { by, choice, think_time: Duration::ZERO })
+            .collect()
+    }
+
+    fn apply_move(&mut self, mv: TriviaMove) -> bool {
+        match mv {
+            // Only the comrade who called the quiz may start it
+            TriviaMove::Start { by } if !self.started && by == self.contestants[0].user.id => {
+                self.started = true;
+                true
+            },
+            TriviaMove::Start { .. } => false,
+            TriviaMove::Join(user) => self.join(user),
+            TriviaMove::Answer { by, choice, think_time } => self.answer(by, choice, think_time),
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.game_over
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        match self.mode {
+            _ if !self.game_over => None,
+            TriviaMode::Solo => Some(Outcome::Score(self.contestants[0].points as f64)),
+            TriviaMode::Party => Some(Outcome::Raced),
+        }
+    }
+
+    fn parse_move(&self, custom_id: &str, presser: &serenity::User, think_time: Duration) -> Option<TriviaMove> {
+        match custom_id {
+            "trivia_start" => return Some(TriviaMove::Start { by: presser.id }),
+            "trivia_join" => return Some(TriviaMove::Join(presser.clone())),
+            _ => {},
+        }
+
+        custom_id
+            .strip_prefix("trivia_answer_")
+            .and_then(|s| s.parse().ok())
+            .map(|choice| TriviaMove::Answer { by: presser.id, choice, think_time })
+    }
+
+    fn render(&self, players: &[serenity::User]) -> String {
+        let title = format!("**☭ Soviet Trivia: {} ☭**", self.pack_name);
+
+        if !self.started {
+            let call = match self.mode {
+                TriviaMode::Party => {
+                    let names: Vec<&str> = self.contestants.iter().map(|c| c.user.name.as_str()).collect();
+                    format!(
+                        "Comrade {} calls a quiz! Press Join to enter, then {} starts it.\n\n\
+                        Contestants ({}/{}): {}",
+                        players[0].name, players[0].name, names.len(), TRIVIA_MAX_PLAYERS, names.join(", ")
+                    )
+                },
+                TriviaMode::Solo => format!("Comrade {}, the Ministry of Education will now test you.", players[0].name),
+            };
+
+            return format!(
+                "{}\n\n{}\n\n\
+                Rules:\n\
+                • {} questions, {} seconds each\n\
+                • A right answer earns {} points at once, falling to {} at the buzzer\n\
+                • One answer per question, so choose carefully",
+                title, call, self.questions.len(), QUESTION_TIME.as_secs(), MAX_POINTS, MIN_POINTS
+            );
+        }
+
+        let last_result = self.last_result.as_deref().map(|r| format!("{}\n\n", r)).unwrap_or_default();
+
+        if self.game_over {
+            let verdict = match self.mode {
+                TriviaMode::Party => format!("**{}** is the most educated worker in the collective!", self.standings()[0].user.name),
+                TriviaMode::Solo => "The Ministry of Education has entered your score in your file.".to_string(),
+            };
+            return format!("{}\n\n{}**Quiz Complete!**\n{}\n\n{}", title, last_result, self.render_standings(), verdict);
+        }
+
+        // Discord counts the deadline down for us
+        let deadline = chrono::Utc::now().timestamp() + QUESTION_TIME.saturating_sub(self.elapsed).as_secs() as i64;
+        let question = &self.questions[self.current];
+        let answered = match self.mode {
+            TriviaMode::Party => format!(
+                "\n\nAnswered: {}/{}",
+                self.contestants.iter().filter(|c| c.answer.is_some()).count(),
+                self.contestants.len()
+            ),
+            TriviaMode::Solo => String::new(),
+        };
+
+        format!(
+            "{}\n\n{}**Question {}/{}** · closes <t:{}:R>\n{}{}",
+            title, last_result, self.current + 1, self.questions.len(), deadline, question.question, answered
+        )
+    }
+
+    fn render_abandoned(&self, _players: &[serenity::User]) -> String {
+        format!(
+            "**☭ Soviet Trivia: {} ☭**\n\n\
+            Quiz abandoned. The Ministry of Education notes your lack of curiosity.",
+            self.pack_name
+        )
+    }
+
+    fn components<'a>(&self, c: &'a mut serenity::CreateComponents) -> &'a mut serenity::CreateComponents {
+        create_trivia_buttons(c, self)
+    }
+
+    fn move_timeout(&self) -> Duration {
+        if self.started { QUESTION_TIME.saturating_sub(self.elapsed) } else { LOBBY_TIME }
+    }
+
+    // A question nobody finished answering closes at the buzzer
+    fn time_up(&mut self) -> bool {
+        if !self.started || self.game_over {
+            return false;
+        }
+        self.close_question();
+        true
+    }
+
+    fn format_score(score: f64) -> String {
+        format!("{:.0} points", score)
+    }
+
+    fn race_scores(&self) -> Vec<(serenity::User, f64)> {
+        if self.mode != TriviaMode::Party {
+            return Vec::new();
+        }
+
+        self.contestants
+            .iter()
+            .map(|c| (c.user.clone(), c.points as f64))
+            .collect()
+    }
+}
+
+// Start and join buttons before the quiz, then one button per answer. Empty once it's over.
+fn create_trivia_buttons<'a>(
+    c: &'a mut serenity::CreateComponents,
+    game: &Trivia,
+) -> &'a mut serenity::CreateComponents {
+    if !game.started {
+        return c.create_action_row(|row| {
+            if game.mode == TriviaMode::Party {
+                row.create_button(|b| {
+                    b.custom_id("trivia_join")
+                    .label("Join Quiz")
+                    .style(serenity::ButtonStyle::Primary)
+                    .emoji('✋')
+                    .disabled(game.contestants.len() >= TRIVIA_MAX_PLAYERS)
+                });
+            }
+            row.create_button(|b| {
+                b.custom_id("trivia_start")
+                .label("Start Quiz")
+                .style(serenity::ButtonStyle::Success)
+                .emoji('✅')
+            })
+        });
+    }
+
+    if game.game_over {
+        return c;
+    }
+
+    let letters = ['🇦', '🇧', '🇨', '🇩'];
+    for (choice, answer) in game.questions[game.current].answers.iter().enumerate() {
+        c.create_action_row(|row| {
+            row.create_button(|b| {
+                b.custom_id(format!("trivia_answer_{}", choice))
+                .label(answer)
+                .style(serenity::ButtonStyle::Secondary)
+                .emoji(letters[choice])
+            })
+        });
+    }
+    c
+}
+
+/// Answer Soviet trivia against the clock, alone or with the channel
+#[poise::command(slash_command, prefix_command, track_edits)]
+pub async fn trivia(
+    ctx: crate::Context<'_>,
+    #[description = "solo, or party to let everyone in the channel compete (default: solo)"]
+    mode: Option<String>,
+    #[description = "Question pack to draw from (default: every pack)"]
+    pack: Option<String>,
+    #[description = "Number of questions, 1-20 (default: 5)"]
+    questions: Option<usize>,
+) -> Result<(), CommandError> {
+    let mode = match mode.as_deref().map(TriviaMode::from_name) {
+        None => TriviaMode::Solo,
+        Some(Some(mode)) => mode,
+        Some(None) => {
+            ctx.say("Unknown mode, comrade. Choose `solo` or `party`.").await?;
+            return Ok(());
+        }
+    };
+
+    let count = questions.unwrap_or(DEFAULT_QUESTIONS);
+    if !TRIVIA_QUESTIONS.contains(&count) {
+        ctx.say(format!(
+            "A quiz has between {} and {} questions, comrade.",
+            TRIVIA_QUESTIONS.start(), TRIVIA_QUESTIONS.end()
+        )).await?;
+        return Ok(());
+    }
+
+    let server_id = ctx.guild_id().map(|id| id.to_string()).unwrap_or_else(|| "DM".to_string());
+    let packs = server_packs(&ctx.data().db, &server_id).await?;
+
+    let (pack_name, pool) = match pack {
+        Some(name) => match packs.iter().find(|p| p.name.eq_ignore_ascii_case(name.trim())) {
+            Some(pack) => (pack.name.clone(), pack.questions.clone()),
+            None => {
+                let names: Vec<String> = packs.iter().map(|p| format!("`{}`", p.name)).collect();
+                ctx.say(format!("Unknown pack, comrade. Choose from {}.", names.join(", "))).await?;
+                return Ok(());
+            }
+        },
+        None => ("Mixed".to_string(), packs.iter().flat_map(|p| p.questions.clone()).collect()),
+    };
+
+    // A small pack gives what it has
+    let count = count.min(pool.len());
+
+    run_game(ctx, None, None, |players| Trivia::new(mode, &players[0], &pack_name, &pool, count)).await
+}
+
+/// Manage this server's trivia question packs (Admin only)
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("trivia_pack_import", "trivia_pack_remove", "trivia_pack_list"),
+    check = "check_if_admin"
+)]
+pub async fn trivia_pack(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    ctx.say("Use `/trivia_pack import`, `/trivia_pack remove` or `/trivia_pack list`, comrade.").await?;
+    Ok(())
+}
+
+/// Import a question pack from a JSON file
+#[poise::command(slash_command, prefix_command, rename = "import", check = "check_if_admin")]
+pub async fn trivia_pack_import(
+    ctx: crate::Context<'_>,
+    #[description = "JSON file: {\"name\": ..., \"questions\": [{\"question\", \"answers\", \"correct\"}]}"]
+    file: serenity::Attachment,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    if file.size > MAX_PACK_BYTES {
+        ctx.say(format!("Packs may be at most {} KB, comrade.", MAX_PACK_BYTES / 1000)).await?;
+        return Ok(());
+    }
+
+    let bytes = file.download().await?;
+    let json = match String::from_utf8(bytes) {
+        Ok(json) => json,
+        Err(_) => {
+            ctx.say("That file isn't text, comrade. Upload the pack as a JSON file.").await?;
+            return Ok(());
+        }
+    };
+
+    let pack = match TriviaPack::parse(&json) {
+        Ok(pack) => pack,
+        Err(e) => {
+            ctx.say(format!("The Ministry of Education rejects this pack: {}.", e)).await?;
+            return Ok(());
+        }
+    };
+
+    if TriviaPack::is_bundled(&pack.name) {
+        ctx.say(format!("`{}` is one of the state's own packs, comrade. Choose another name.", pack.name)).await?;
+        return Ok(());
+    }
+
+    let db = &ctx.data().db;
+    let existing = db.get_trivia_packs(&server_id).await?;
+    let replacing = existing.iter().any(|(name, _)| name.eq_ignore_ascii_case(&pack.name));
+    if !replacing && existing.len() >= MAX_SERVER_PACKS {
+        ctx.say(format!(
+            "This server already has {} packs, the most allowed. Remove one with `/trivia_pack remove` first.",
+            MAX_SERVER_PACKS
+        )).await?;
+        return Ok(());
+    }
+
+    db.save_trivia_pack(&server_id, &pack.name, &serde_json::to_string(&pack)?, &ctx.author().id.to_string()).await?;
+
+    let verb = if replacing { "replaced" } else { "imported" };
+    ctx.say(format!(
+        "Pack **{}** {} with {} questions. Play it with `/trivia pack:{}`.",
+        pack.name, verb, pack.questions.len(), pack.name
+    )).await?;
+
+    Ok(())
+}
+
+/// Remove an imported question pack
+#[poise::command(slash_command, prefix_command, rename = "remove", check = "check_if_admin")]
+pub async fn trivia_pack_remove(
+    ctx: crate::Context<'_>,
+    #[description = "Name of the pack to remove"]
+    name: String,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    if TriviaPack::is_bundled(&name) {
+        ctx.say("The state's own packs cannot be removed, comrade.").await?;
+        return Ok(());
+    }
+
+    if ctx.data().db.remove_trivia_pack(&server_id, name.trim()).await? {
+        ctx.say(format!("Pack **{}** has been withdrawn from circulation.", name.trim())).await?;
+    } else {
+        ctx.say(format!("This server has no pack called **{}**, comrade.", name.trim())).await?;
+    }
+
+    Ok(())
+}
+
+/// List the question packs available in this server
+#[poise::command(slash_command, prefix_command, rename = "list", check = "check_if_admin")]
+pub async fn trivia_pack_list(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let packs = server_packs(&ctx.data().db, &server_id).await?;
+    let describe = |pack: &TriviaPack| {
+        let description = if pack.description.is_empty() { String::new() } else { format!(" - {}", pack.description) };
+        format!("**{}** ({} questions){}", pack.name, pack.questions.len(), description)
+    };
+
+    let bundled: Vec<String> = packs.iter().filter(|p| TriviaPack::is_bundled(&p.name)).map(describe).collect();
+    let imported: Vec<String> = packs.iter().filter(|p| !TriviaPack::is_bundled(&p.name)).map(describe).collect();
+    let imported = if imported.is_empty() {
+        "None yet. Add one with `/trivia_pack import`.".to_string()
+    } else {
+        imported.join("\n")
+    };
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title("☭ Ministry of Education ☭")
+             .description("Question packs approved for this collective.")
+             .color(serenity::Color::RED)
+             .field("State Packs", bundled.join("\n"), false)
+             .field(format!("Server Packs ({}/{})", packs.len() - bundled.len(), MAX_SERVER_PACKS), imported, false)
+             .footer(|f| f.text("Knowledge is the property of the people."))
+        })
+    }).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comrade(id: u64, name: &str) -> serenity::User {
+        let mut user = serenity::User::default();
+        user.id = serenity::UserId(id);
+        user.name = name.to_string();
+        user
+    }
+
+    fn party(players: &[serenity::User], questions: usize) -> Trivia {
+        let pool = &TriviaPack::bundled()[0].questions;
+        let mut game = Trivia::new(TriviaMode::Party, &players[0], "Test", pool, questions);
+        for player in &players[1..] {
+            assert!(game.apply_move(TriviaMove::Join(player.clone())));
+        }
+        assert!(game.apply_move(TriviaMove::Start { by: players[0].id }));
+        game
+    }
+
+    fn answer(game: &mut Trivia, by: &serenity::User, right: bool, secs: u64) -> bool {
+        let correct = game.questions[game.current].correct;
+        let choice = if right { correct } else { (correct + 1) % game.questions[game.current].answers.len() };
+        game.apply_move(TriviaMove::Answer { by: by.id, choice, think_time: Duration::from_secs(secs) })
+    }
+
+    #[test]
+    fn bundled_packs_are_valid_and_shuffled_fairly() {
+        let packs = TriviaPack::bundled();
+        assert_eq!(packs.len(), BUNDLED_PACKS.len());
+
+        let game = party(&[comrade(1, "lenin")], 10);
+        for question in &game.questions {
+            assert!(ANSWER_COUNTS.contains(&question.answers.len()));
+            assert_eq!(
+                question.answers[question.correct],
+                packs[0].questions.iter().find(|q| q.question == question.question).map(|q| q.answers[q.correct].clone()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn faster_correct_answers_earn_more() {
+        assert_eq!(points_for(Duration::ZERO), MAX_POINTS);
+        assert_eq!(points_for(QUESTION_TIME), MIN_POINTS);
+
+        let (lenin, trotsky, stalin) = (comrade(1, "lenin"), comrade(2, "trotsky"), comrade(3, "stalin"));
+        let mut game = party(&[lenin.clone(), trotsky.clone(), stalin.clone()], 2);
+
+        // Times add up between answers, since each one redraws the board
+        assert!(answer(&mut game, &lenin, true, 2));
+        assert!(!answer(&mut game, &lenin, true, 0), "one answer per question");
+        assert!(answer(&mut game, &trotsky, true, 6));
+        assert!(answer(&mut game, &stalin, false, 1));
+
+        assert_eq!(game.current, 1, "everyone answered, so the question closed");
+        assert_eq!(game.contestants[0].points, points_for(Duration::from_secs(2)));
+        assert_eq!(game.contestants[1].points, points_for(Duration::from_secs(8)));
+        assert!(game.contestants[0].points > game.contestants[1].points);
+        assert_eq!(game.contestants[2].points, 0);
+    }
+
+    #[test]
+    fn questions_close_at_the_buzzer() {
+        let (lenin, trotsky) = (comrade(1, "lenin"), comrade(2, "trotsky"));
+        let mut game = party(&[lenin.clone(), trotsky.clone()], 2);
+
+        assert!(answer(&mut game, &lenin, true, 5));
+        assert_eq!(game.move_timeout(), QUESTION_TIME - Duration::from_secs(5));
+
+        assert!(game.time_up());
+        assert_eq!(game.current, 1);
+        assert!(game.last_result.as_deref().unwrap().contains("✅ lenin"));
+
+        assert!(game.time_up());
+        assert!(game.is_terminal());
+        assert_eq!(game.outcome(), Some(Outcome::Raced));
+        assert!(!game.time_up());
+
+        let scores = game.race_scores();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[1].1, 0.0);
+    }
+
+    #[test]
+    fn malformed_packs_are_rejected() {
+        let question = |answers: &str, correct: usize| {
+            format!(r#"{{"name": "Test", "questions": [{{"question": "Who?", "answers": {}, "correct": {}}}]}}"#, answers, correct)
+        };
+
+        assert!(TriviaPack::parse(&question(r#"["Lenin", "Stalin"]"#, 1)).is_ok());
+        assert!(TriviaPack::parse(&question(r#"["Lenin", "Stalin"]"#, 2)).is_err());
+        assert!(TriviaPack::parse(&question(r#"["Lenin"]"#, 0)).is_err());
+        assert!(TriviaPack::parse(&question(r#"["A", "B", "C", "D", "E"]"#, 0)).is_err());
+        assert!(TriviaPack::parse(&question(&format!(r#"["{}", "B"]"#, "x".repeat(81)), 0)).is_err());
+        assert!(TriviaPack::parse(r#"{"name": " ", "questions": []}"#).is_err());
+        assert!(TriviaPack::parse("not json").is_err());
+    }
+}
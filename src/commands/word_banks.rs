@@ -0,0 +1,605 @@
+use crate::CommandError;
+use crate::db::{Database, WordBankRow};
+use super::admin::check_if_admin;
+use super::similarity::RelatednessGraph;
+use poise::serenity_prelude as serenity;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+// The categories and words shipped with the bot
+const BUNDLED_BANK: &str = include_str!("../../assets/word_bank.txt");
+
+// How many of a player's last words they won't be given again
+const RECENT_WORDS: usize = 5;
+
+// Most words a server may add of its own
+const MAX_CUSTOM_WORDS: usize = 500;
+
+// Longest category or word an admin may add
+const MAX_WORD_LENGTH: usize = 30;
+
+// Wins a day each comrade is paid for in each word game
+pub(crate) const WORD_REWARDS_PER_DAY: i64 = 10;
+
+// How hard a word is to guess
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum WordDifficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+impl WordDifficulty {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "easy" => Some(WordDifficulty::Easy),
+            "medium" | "normal" => Some(WordDifficulty::Medium),
+            "hard" => Some(WordDifficulty::Hard),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            WordDifficulty::Easy => "easy",
+            WordDifficulty::Medium => "medium",
+            WordDifficulty::Hard => "hard",
+        }
+    }
+
+    // Boops paid for finding a word of this difficulty, for up to WORD_REWARDS_PER_DAY
+    // wins a day in each word game
+    pub(crate) fn reward(&self) -> f64 {
+        match self {
+            WordDifficulty::Easy => 2.0,
+            WordDifficulty::Medium => 4.0,
+            WordDifficulty::Hard => 8.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WordEntry {
+    pub(crate) category: String,
+    pub(crate) word: String,
+    pub(crate) difficulty: WordDifficulty,
+}
+
+impl WordEntry {
+    fn is(&self, category: &str, word: &str) -> bool {
+        self.category.eq_ignore_ascii_case(category) && self.word.eq_ignore_ascii_case(word)
+    }
+}
+
+// Every word a game may choose from, by category
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WordBank {
+    entries: Vec<WordEntry>,
+}
+
+impl WordBank {
+    // `[Category]` starts a category, then lines like `easy: word, other word`; `#` starts a comment
+    pub(crate) fn parse(text: &str) -> Result<Self, String> {
+        let mut bank = WordBank::default();
+        let mut category: Option<String> = None;
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                category = Some(name.trim().to_string());
+                continue;
+            }
+
+            let category = category
+                .as_deref()
+                .ok_or_else(|| format!("line {}: words before the first [Category]", number + 1))?;
+            let (difficulty, words) = line
+                .split_once(':')
+                .ok_or_else(|| format!("line {}: expected `difficulty: word, word, ...`", number + 1))?;
+            let difficulty = WordDifficulty::from_name(difficulty)
+                .ok_or_else(|| format!("line {}: `{}` is not a difficulty", number + 1, difficulty.trim()))?;
+
+            for word in words.split(',').map(str::trim).filter(|w| !w.is_empty()) {
+                bank.insert(category, word, difficulty);
+            }
+        }
+
+        Ok(bank)
+    }
+
+    pub(crate) fn bundled() -> &'static WordBank {
+        static BANK: OnceLock<WordBank> = OnceLock::new();
+        BANK.get_or_init(|| WordBank::parse(BUNDLED_BANK).expect("bundled word bank is malformed"))
+    }
+
+    // The bundled bank with a server's own words added and its removed ones taken out
+    pub(crate) async fn for_server(db: &Database, server_id: &str) -> Result<WordBank, CommandError> {
+        let rows = db.get_bank_words(server_id).await?;
+        Ok(WordBank::bundled().with_changes(&rows))
+    }
+
+    fn with_changes(&self, rows: &[WordBankRow]) -> WordBank {
+        let mut bank = self.clone();
+
+        for row in rows {
+            if row.removed {
+                bank.entries.retain(|entry| !entry.is(&row.category, &row.word));
+            } else {
+                let difficulty = WordDifficulty::from_name(&row.difficulty).unwrap_or(WordDifficulty::Medium);
+                bank.insert(&row.category, &row.word, difficulty);
+            }
+        }
+
+        bank
+    }
+
+    // Add a word, or change its difficulty if the category already has it.
+    // A category keeps the spelling it was first given.
+    fn insert(&mut self, category: &str, word: &str, difficulty: WordDifficulty) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.is(category, word)) {
+            entry.difficulty = difficulty;
+            return;
+        }
+
+        let category = self
+            .category_name(category)
+            .unwrap_or(category)
+            .to_string();
+
+        self.entries.push(WordEntry {
+            category,
+            word: word.to_string(),
+            difficulty,
+        });
+    }
+
+    pub(crate) fn entries(&self) -> &[WordEntry] {
+        &self.entries
+    }
+
+    // The bank's spelling of a category
+    pub(crate) fn category_name(&self, category: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.category.eq_ignore_ascii_case(category))
+            .map(|entry| entry.category.as_str())
+    }
+
+    // Category names in the order they first appear
+    pub(crate) fn categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = Vec::new();
+        for entry in &self.entries {
+            if !categories.contains(&entry.category.as_str()) {
+                categories.push(&entry.category);
+            }
+        }
+        categories
+    }
+
+    pub(crate) fn words_in(&self, category: &str) -> Vec<&WordEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.category.eq_ignore_ascii_case(category))
+            .collect()
+    }
+
+    // Pick a word, preferring the given difficulty and avoiding the recent words.
+    // Both preferences give way rather than leave nothing to choose from.
+    pub(crate) fn choose(
+        &self,
+        difficulty: Option<WordDifficulty>,
+        recent: &[String],
+        rng: &mut impl Rng,
+    ) -> Option<&WordEntry> {
+        let matching: Vec<&WordEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| difficulty.is_none_or(|d| entry.difficulty == d))
+            .collect();
+        let candidates = if matching.is_empty() {
+            self.entries.iter().collect()
+        } else {
+            matching
+        };
+
+        // Drop the oldest recent words until something is left
+        for window in (0..=recent.len()).rev() {
+            let fresh: Vec<&&WordEntry> = candidates
+                .iter()
+                .filter(|entry| !recent[..window].iter().any(|seen| seen.eq_ignore_ascii_case(&entry.word)))
+                .collect();
+
+            if let Some(entry) = fresh.choose(rng) {
+                return Some(**entry);
+            }
+        }
+
+        None
+    }
+}
+
+// Pick a word for a player from their server's bank, avoiding the last few they were given
+pub(crate) async fn pick_word(
+    db: &Database,
+    server_id: &str,
+    user_id: &str,
+    game_type: &str,
+    difficulty: Option<WordDifficulty>,
+) -> Result<WordEntry, CommandError> {
+    let bank = WordBank::for_server(db, server_id).await?;
+    let recent = db.get_recent_words(user_id, server_id, game_type, RECENT_WORDS).await?;
+
+    let entry = bank
+        .choose(difficulty, &recent, &mut rand::thread_rng())
+        .cloned()
+        .ok_or("This server's word bank is empty. An admin can add words with `/wordbank add`.")?;
+
+    db.record_word_seen(user_id, server_id, game_type, &entry.word, RECENT_WORDS).await?;
+
+    Ok(entry)
+}
+
+// A stable seed for one server's puzzle on one day. FNV-1a, so the same day and
+// server give the same seed on every build and platform.
+pub(crate) fn daily_seed(date: &str, server_id: &str, game_type: &str) -> u64 {
+    format!("{}:{}:{}", date, server_id, game_type)
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+// The server's word of the day for a game, the same for every player. The first pick
+// is saved so later changes to the word bank can't change a puzzle already underway.
+pub(crate) async fn daily_word(
+    db: &Database,
+    server_id: &str,
+    game_type: &str,
+    date: &str,
+    difficulty: Option<WordDifficulty>,
+) -> Result<WordEntry, CommandError> {
+    if db.get_daily_word(server_id, game_type, date).await?.is_none() {
+        let bank = WordBank::for_server(db, server_id).await?;
+        let mut rng = StdRng::seed_from_u64(daily_seed(date, server_id, game_type));
+        let entry = bank
+            .choose(difficulty, &[], &mut rng)
+            .ok_or("This server's word bank is empty. An admin can add words with `/wordbank add`.")?;
+
+        db.save_daily_word(server_id, game_type, date, &entry.category, &entry.word, entry.difficulty.name()).await?;
+    }
+
+    let (category, word, difficulty) = db
+        .get_daily_word(server_id, game_type, date)
+        .await?
+        .ok_or("Today's puzzle could not be prepared, comrade.")?;
+
+    Ok(WordEntry {
+        category,
+        word,
+        difficulty: WordDifficulty::from_name(&difficulty).unwrap_or_default(),
+    })
+}
+
+// Words must fit the hangman keyboard: letters A to Z, with single spaces between them
+fn clean_word(word: &str) -> Result<String, String> {
+    let word = word.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if word.is_empty() || word.len() > MAX_WORD_LENGTH {
+        return Err(format!("Words must be between 1 and {} characters, comrade.", MAX_WORD_LENGTH));
+    }
+    if !word.chars().all(|c| c.is_ascii_alphabetic() || c == ' ') {
+        return Err(format!("`{}` may only contain the letters A to Z and spaces, comrade.", word));
+    }
+
+    Ok(word)
+}
+
+/// Manage the words used by Soviet Hangman and Kremlin Secrets (Admin only)
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("wordbank_add", "wordbank_remove", "wordbank_list"),
+    check = "check_if_admin"
+)]
+pub async fn wordbank(ctx: crate::Context<'_>) -> Result<(), CommandError> {
+    ctx.say("Use `/wordbank add`, `/wordbank remove` or `/wordbank list`, comrade.").await?;
+    Ok(())
+}
+
+/// Add words to a category, creating it if needed
+#[poise::command(slash_command, prefix_command, rename = "add", check = "check_if_admin")]
+pub async fn wordbank_add(
+    ctx: crate::Context<'_>,
+    #[description = "Category to add to (existing or new)"]
+    category: String,
+    #[description = "Words separated by commas"]
+    words: String,
+    #[description = "Difficulty of these words: easy, medium (default) or hard"]
+    difficulty: Option<String>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let difficulty = match difficulty.as_deref() {
+        Some(name) => WordDifficulty::from_name(name)
+            .ok_or("Difficulty must be `easy`, `medium` or `hard`, comrade.")?,
+        None => WordDifficulty::Medium,
+    };
+
+    let words = words
+        .split(',')
+        .filter(|w| !w.trim().is_empty())
+        .map(clean_word)
+        .collect::<Result<Vec<_>, _>>()?;
+    if words.is_empty() {
+        return Err("You must provide at least one word, comrade.".into());
+    }
+
+    let db = &ctx.data().db;
+    let bank = WordBank::for_server(db, &server_id).await?;
+
+    // New words join an existing category under its current name
+    let category = clean_word(&category)?;
+    let category = bank.category_name(&category).unwrap_or(&category).to_string();
+
+    let existing = db.get_bank_words(&server_id).await?;
+    let custom = existing.iter().filter(|row| !row.removed).count();
+    if custom + words.len() > MAX_CUSTOM_WORDS {
+        return Err(format!(
+            "This server may only add {} words of its own. Remove some with `/wordbank remove` first.",
+            MAX_CUSTOM_WORDS
+        ).into());
+    }
+
+    db.add_bank_words(&server_id, &category, &words, difficulty.name()).await?;
+
+    let mut reply = format!(
+        "☭ **{} word{} added to {}** ({}) ☭\n{}",
+        words.len(),
+        if words.len() == 1 { "" } else { "s" },
+        category,
+        difficulty.name(),
+        words.join(", ")
+    );
+
+    // Kremlin Secrets can only judge guesses at these words by their spelling
+    let graph = RelatednessGraph::bundled();
+    let unknown: Vec<&str> = words.iter().filter(|w| !graph.knows(w)).map(String::as_str).collect();
+    if !unknown.is_empty() {
+        reply.push_str(&format!(
+            "\n*Kremlin Secrets will score guesses for {} by spelling alone.*",
+            unknown.join(", ")
+        ));
+    }
+
+    ctx.say(reply).await?;
+
+    Ok(())
+}
+
+/// Remove a word, or a whole category, from this server's games
+#[poise::command(slash_command, prefix_command, rename = "remove", check = "check_if_admin")]
+pub async fn wordbank_remove(
+    ctx: crate::Context<'_>,
+    #[description = "Category to remove from"]
+    category: String,
+    #[description = "Word to remove (leave empty to remove the whole category)"]
+    word: Option<String>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let db = &ctx.data().db;
+    let bank = WordBank::for_server(db, &server_id).await?;
+
+    let category = match bank.category_name(category.trim()) {
+        Some(name) => name.to_string(),
+        None => {
+            ctx.say(format!("No category called {} exists in this server, comrade.", category.trim())).await?;
+            return Ok(());
+        }
+    };
+
+    let words: Vec<String> = bank
+        .words_in(&category)
+        .into_iter()
+        .filter(|entry| word.as_deref().is_none_or(|w| entry.word.eq_ignore_ascii_case(w.trim())))
+        .map(|entry| entry.word.clone())
+        .collect();
+
+    if words.is_empty() {
+        ctx.say(format!(
+            "{} has no word {}, comrade.",
+            category,
+            word.as_deref().unwrap_or_default().trim()
+        )).await?;
+        return Ok(());
+    }
+
+    db.remove_bank_words(&server_id, &category, &words).await?;
+
+    if word.is_some() {
+        ctx.say(format!("{} has been purged from {}.", words[0], category)).await?;
+    } else {
+        ctx.say(format!("{} and its {} words have been purged from the record.", category, words.len())).await?;
+    }
+
+    Ok(())
+}
+
+/// List this server's word categories, or the words in one of them
+#[poise::command(slash_command, prefix_command, rename = "list", check = "check_if_admin")]
+pub async fn wordbank_list(
+    ctx: crate::Context<'_>,
+    #[description = "Category to show the words of"]
+    category: Option<String>,
+) -> Result<(), CommandError> {
+    let server_id = match ctx.guild_id() {
+        Some(id) => id.to_string(),
+        None => return Err("This command can only be used in a server!".into()),
+    };
+
+    let bank = WordBank::for_server(&ctx.data().db, &server_id).await?;
+
+    if bank.entries().is_empty() {
+        ctx.say("This server's word bank is empty. Add words with `/wordbank add`.").await?;
+        return Ok(());
+    }
+
+    let (title, text) = match category {
+        Some(category) => {
+            let words = bank.words_in(category.trim());
+            if words.is_empty() {
+                ctx.say(format!("No category called {} exists in this server, comrade.", category.trim())).await?;
+                return Ok(());
+            }
+
+            let mut text = String::new();
+            for difficulty in [WordDifficulty::Easy, WordDifficulty::Medium, WordDifficulty::Hard] {
+                let names: Vec<&str> = words
+                    .iter()
+                    .filter(|entry| entry.difficulty == difficulty)
+                    .map(|entry| entry.word.as_str())
+                    .collect();
+                if !names.is_empty() {
+                    text.push_str(&format!("**{}:** {}\n", difficulty.name(), names.join(", ")));
+                }
+            }
+
+            (words[0].category.clone(), text)
+        },
+        None => {
+            let mut text = String::new();
+            for category in bank.categories() {
+                text.push_str(&format!("**{}** - {} words\n", category, bank.words_in(category).len()));
+            }
+
+            ("Ministry of Vocabulary".to_string(), text)
+        },
+    };
+
+    // Embed descriptions are limited to 4096 characters
+    let text = if text.chars().count() > 4000 {
+        format!("{}…", text.chars().take(4000).collect::<String>())
+    } else {
+        text
+    };
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title(format!("☭ {} ☭", title))
+             .description(text)
+             .color(serenity::Color::RED)
+             .footer(|f| f.text("Change the words with /wordbank add and /wordbank remove"))
+        })
+    }).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(category: &str, word: &str, difficulty: &str, removed: bool) -> WordBankRow {
+        WordBankRow {
+            category: category.to_string(),
+            word: word.to_string(),
+            difficulty: difficulty.to_string(),
+            removed,
+        }
+    }
+
+    #[test]
+    fn bundled_bank_covers_every_difficulty() {
+        let bank = WordBank::bundled();
+        assert!(bank.categories().len() >= 6);
+
+        for difficulty in [WordDifficulty::Easy, WordDifficulty::Medium, WordDifficulty::Hard] {
+            assert!(bank.entries().iter().any(|entry| entry.difficulty == difficulty));
+        }
+        for entry in bank.entries() {
+            assert_eq!(clean_word(&entry.word).as_deref(), Ok(entry.word.as_str()));
+        }
+    }
+
+    #[test]
+    fn malformed_banks_are_rejected() {
+        assert!(WordBank::parse("easy: Lenin").is_err());
+        assert!(WordBank::parse("[Leaders]\nLenin, Stalin").is_err());
+        assert!(WordBank::parse("[Leaders]\ntrivial: Lenin").is_err());
+        assert!(WordBank::parse("# nothing but comments\n\n").is_ok());
+    }
+
+    #[test]
+    fn server_changes_apply_over_the_bundled_bank() {
+        let bank = WordBank::parse("[Soviet Leaders]\neasy: Lenin, Stalin\nhard: Kosygin").unwrap();
+        let bank = bank.with_changes(&[
+            row("soviet leaders", "STALIN", "medium", true),
+            row("soviet leaders", "kosygin", "easy", false),
+            row("Soviet Leaders", "Beria", "hard", false),
+            row("Pets", "Laika", "easy", false),
+            row("Pets", "Laika", "easy", true),
+        ]);
+
+        let words: Vec<(&str, WordDifficulty)> = bank
+            .entries()
+            .iter()
+            .map(|entry| (entry.word.as_str(), entry.difficulty))
+            .collect();
+        assert_eq!(words, [
+            ("Lenin", WordDifficulty::Easy),
+            ("Kosygin", WordDifficulty::Easy),
+            ("Beria", WordDifficulty::Hard),
+        ]);
+        assert_eq!(bank.categories(), ["Soviet Leaders"]);
+    }
+
+    #[test]
+    fn difficulty_gives_way_when_no_word_matches() {
+        let bank = WordBank::parse("[Leaders]\neasy: Lenin, Stalin").unwrap();
+        let mut rng = StdRng::seed_from_u64(1917);
+
+        for _ in 0..20 {
+            let entry = bank.choose(Some(WordDifficulty::Easy), &[], &mut rng).unwrap();
+            assert_eq!(entry.difficulty, WordDifficulty::Easy);
+            assert!(bank.choose(Some(WordDifficulty::Hard), &[], &mut rng).is_some());
+        }
+        assert!(WordBank::default().choose(None, &[], &mut rng).is_none());
+    }
+
+    #[test]
+    fn recent_words_are_not_repeated() {
+        let bank = WordBank::parse("[Leaders]\neasy: Lenin, Stalin, Trotsky\nhard: Kosygin").unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..20 {
+            let recent = ["lenin".to_string(), "STALIN".to_string()];
+            assert_eq!(bank.choose(Some(WordDifficulty::Easy), &recent, &mut rng).unwrap().word, "Trotsky");
+        }
+
+        // With every word seen, the one seen longest ago comes back first
+        let recent = ["Trotsky".to_string(), "Lenin".to_string(), "Stalin".to_string()];
+        assert_eq!(bank.choose(Some(WordDifficulty::Easy), &recent, &mut rng).unwrap().word, "Stalin");
+    }
+
+    #[test]
+    fn daily_seeds_are_fixed_per_server_day_and_game() {
+        let seed = daily_seed("2026-10-18", "42", "soviet_hangman");
+        assert_eq!(seed, daily_seed("2026-10-18", "42", "soviet_hangman"));
+
+        assert_ne!(seed, daily_seed("2026-10-19", "42", "soviet_hangman"));
+        assert_ne!(seed, daily_seed("2026-10-18", "43", "soviet_hangman"));
+        assert_ne!(seed, daily_seed("2026-10-18", "42", "kremlin_secrets"));
+    }
+}
@@ -30,6 +30,7 @@ impl JobType {
         }
     }
     
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         match self {
             Self::Miner => "miner".to_string(),
@@ -74,6 +75,180 @@ impl JobType {
     }
 }
 
+// Per-server settings for passive boops income from chat messages
+#[derive(Debug, Clone)]
+pub struct ChatIncomeSettings {
+    pub enabled: bool,
+    pub reward: f64,
+    pub cooldown_secs: i64,
+    pub min_length: i64,
+    pub daily_cap: f64,
+}
+
+impl Default for ChatIncomeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            reward: 0.5,
+            cooldown_secs: 60,
+            min_length: 10,
+            daily_cap: 25.0,
+        }
+    }
+}
+
+// Per-server settings for Soviet Hangman
+#[derive(Debug, Clone)]
+pub struct HangmanSettings {
+    pub input_style: String,
+}
+
+impl Default for HangmanSettings {
+    fn default() -> Self {
+        Self {
+            input_style: "buttons".to_string(),
+        }
+    }
+}
+
+// Per-server automatic reaction rule
+#[derive(Debug, Clone)]
+pub struct AutoReactRule {
+    pub id: i64,
+    pub server_id: String,
+    pub is_regex: bool,
+    pub pattern: String,
+    pub emoji: String,
+    pub probability: f64,
+    pub channels: Vec<String>,
+    pub cooldown_secs: i64,
+}
+
+// Per-server settings for the KGB listener
+#[derive(Debug, Clone)]
+pub struct KgbSettings {
+    pub enabled: bool,
+    pub probability: f64,
+    pub channels: Vec<String>,
+    pub global_cooldown_secs: i64,
+    pub user_cooldown_secs: i64,
+}
+
+impl Default for KgbSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            probability: 0.005,
+            channels: Vec::new(),
+            global_cooldown_secs: 300,
+            user_cooldown_secs: 3600,
+        }
+    }
+}
+
+// One player's result in a finished game, for leaderboards and /stats.
+// `score` is lower-is-better, like every score in game_scores.
+#[derive(Debug, Clone)]
+pub struct GameResult {
+    pub user_id: String,
+    pub server_id: String,
+    pub username: String,
+    pub game_type: String,
+    pub score: f64,
+    pub won: bool,
+    pub guesses: i64,
+    pub duration_secs: Option<f64>,
+}
+
+// A comrade's record in one game, summed over their saved results
+#[derive(Debug, Clone)]
+pub struct GameStats {
+    pub game_type: String,
+    pub played: i64,
+    pub wins: i64,
+    pub losses: i64,
+    // Lowest and highest winning scores, for games where lower or higher is better
+    pub best_score: Option<f64>,
+    pub top_score: Option<f64>,
+    pub avg_guesses: Option<f64>,
+    pub avg_duration_secs: Option<f64>,
+}
+
+// A comrade's rating in one ranked game
+#[derive(Debug, Clone)]
+pub struct Rating {
+    pub rating: f64,
+    pub games: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub draws: i64,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            rating: 1200.0,
+            games: 0,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+        }
+    }
+}
+
+// How the boops held in a game's escrow are released
+#[derive(Debug, Clone)]
+pub enum EscrowOutcome {
+    // Return every stake to its owner
+    Refund,
+    // Pay the pot to the winner, minus a communal cut
+    Winner { user_id: String, communal_cut: f64 },
+    // Send the whole pot to the communal treasury
+    Forfeit,
+    // Pay one player what the house owes them, which may be more than the pot;
+    // whatever of the pot isn't paid out goes to the communal treasury
+    Payout { user_id: String, amount: f64 },
+}
+
+// A remark the KGB overheard and filed away
+#[derive(Debug, Clone)]
+pub struct DossierEntry {
+    pub remark: String,
+    pub trigger_word: Option<String>,
+    pub recorded_at: i64,
+}
+
+// A server's change to the word bank: a word of its own, or a hidden one when `removed`
+#[derive(Debug, Clone)]
+pub struct WordBankRow {
+    pub category: String,
+    pub word: String,
+    pub difficulty: String,
+    pub removed: bool,
+}
+
+// A comrade's go at a server's daily puzzle. Unfinished attempts have no result yet.
+#[derive(Debug, Clone)]
+pub struct DailyAttempt {
+    pub username: String,
+    pub won: Option<bool>,
+    pub guesses: Option<i64>,
+    pub grid: Option<String>,
+}
+
+// A game in progress, saved so it can be resumed after a restart.
+// `state` is the game's own JSON snapshot.
+#[derive(Debug, Clone)]
+pub struct ActiveGame {
+    pub message_id: String,
+    pub channel_id: String,
+    pub server_id: String,
+    pub game_type: String,
+    pub state: String,
+    pub escrow_id: Option<i64>,
+    pub updated_at: i64,
+}
+
 #[derive(Clone)]
 pub struct Database {
     conn: Arc<Mutex<AsyncConnection>>,
@@ -158,11 +333,279 @@ impl Database {
                     username TEXT NOT NULL,
                     game_type TEXT NOT NULL,
                     score REAL NOT NULL,
-                    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+                    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    won INTEGER,
+                    guesses INTEGER,
+                    duration_secs REAL
                 )",
                 [],
             )?;
-            
+
+            // Create chat income settings table (one row per server, defaults when missing)
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS chat_income_settings (
+                    server_id TEXT PRIMARY KEY,
+                    enabled INTEGER NOT NULL DEFAULT 1,
+                    reward REAL NOT NULL DEFAULT 0.5,
+                    cooldown_secs INTEGER NOT NULL DEFAULT 60,
+                    min_length INTEGER NOT NULL DEFAULT 10,
+                    daily_cap REAL NOT NULL DEFAULT 25.0
+                )",
+                [],
+            )?;
+
+            // Create chat income state table to track cooldowns, duplicates and daily totals
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS chat_income (
+                    user_id TEXT NOT NULL,
+                    server_id TEXT NOT NULL,
+                    last_award_at INTEGER,
+                    last_message TEXT,
+                    day TEXT,
+                    earned_today REAL DEFAULT 0.0,
+                    PRIMARY KEY (user_id, server_id)
+                )",
+                [],
+            )?;
+
+            // Create table counting each comrade's rewarded wins of each kind today
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS daily_rewards (
+                    user_id TEXT NOT NULL,
+                    server_id TEXT NOT NULL,
+                    kind TEXT NOT NULL,
+                    day TEXT NOT NULL,
+                    rewarded INTEGER NOT NULL DEFAULT 0,
+                    PRIMARY KEY (user_id, server_id, kind)
+                )",
+                [],
+            )?;
+
+            // Create auto-reaction rules table
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS autoreact_rules (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    server_id TEXT NOT NULL,
+                    is_regex INTEGER NOT NULL DEFAULT 0,
+                    pattern TEXT NOT NULL,
+                    emoji TEXT NOT NULL,
+                    probability REAL NOT NULL DEFAULT 1.0,
+                    channels TEXT NOT NULL DEFAULT '',
+                    cooldown_secs INTEGER NOT NULL DEFAULT 60
+                )",
+                [],
+            )?;
+
+            // Create auto-reaction cooldowns table to limit reactions per user
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS autoreact_cooldowns (
+                    rule_id INTEGER NOT NULL,
+                    user_id TEXT NOT NULL,
+                    last_reacted_at INTEGER NOT NULL,
+                    PRIMARY KEY (rule_id, user_id)
+                )",
+                [],
+            )?;
+
+            // Create KGB listener settings table (one row per server, defaults when missing)
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS kgb_settings (
+                    server_id TEXT PRIMARY KEY,
+                    enabled INTEGER NOT NULL DEFAULT 1,
+                    probability REAL NOT NULL DEFAULT 0.005,
+                    channels TEXT NOT NULL DEFAULT '',
+                    global_cooldown_secs INTEGER NOT NULL DEFAULT 300,
+                    user_cooldown_secs INTEGER NOT NULL DEFAULT 3600,
+                    last_triggered_at INTEGER
+                )",
+                [],
+            )?;
+
+            // Create KGB opt-out table for comrades who must never be surveilled
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS kgb_optouts (
+                    user_id TEXT NOT NULL,
+                    server_id TEXT NOT NULL,
+                    PRIMARY KEY (user_id, server_id)
+                )",
+                [],
+            )?;
+
+            // Create KGB per-user cooldowns table
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS kgb_cooldowns (
+                    user_id TEXT NOT NULL,
+                    server_id TEXT NOT NULL,
+                    last_triggered_at INTEGER NOT NULL,
+                    PRIMARY KEY (user_id, server_id)
+                )",
+                [],
+            )?;
+
+            // Create escrow tables for boops staked on games in progress
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS escrows (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    server_id TEXT NOT NULL,
+                    game_type TEXT NOT NULL,
+                    created_at INTEGER NOT NULL
+                )",
+                [],
+            )?;
+
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS escrow_stakes (
+                    escrow_id INTEGER NOT NULL,
+                    user_id TEXT NOT NULL,
+                    amount REAL NOT NULL,
+                    PRIMARY KEY (escrow_id, user_id)
+                )",
+                [],
+            )?;
+
+            // Create ratings table for ranked two-player games
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS ratings (
+                    user_id TEXT NOT NULL,
+                    server_id TEXT NOT NULL,
+                    game_type TEXT NOT NULL,
+                    username TEXT NOT NULL,
+                    rating REAL NOT NULL,
+                    games INTEGER NOT NULL DEFAULT 0,
+                    wins INTEGER NOT NULL DEFAULT 0,
+                    losses INTEGER NOT NULL DEFAULT 0,
+                    draws INTEGER NOT NULL DEFAULT 0,
+                    updated_at INTEGER NOT NULL,
+                    PRIMARY KEY (user_id, server_id, game_type)
+                )",
+                [],
+            )?;
+
+            // Create KGB dossiers table of overheard remarks
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS dossiers (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    user_id TEXT NOT NULL,
+                    server_id TEXT NOT NULL,
+                    remark TEXT NOT NULL,
+                    trigger_word TEXT,
+                    recorded_at INTEGER NOT NULL
+                )",
+                [],
+            )?;
+
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_dossiers_user ON dossiers (user_id, server_id)",
+                [],
+            )?;
+
+            // Create hangman settings table (one row per server, defaults when missing)
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS hangman_settings (
+                    server_id TEXT PRIMARY KEY,
+                    input_style TEXT NOT NULL DEFAULT 'buttons'
+                )",
+                [],
+            )?;
+
+            // Create word bank table of each server's own words. Rows marked removed hide
+            // a word, including words from the bundled bank.
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS word_banks (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    server_id TEXT NOT NULL,
+                    category TEXT NOT NULL COLLATE NOCASE,
+                    word TEXT NOT NULL COLLATE NOCASE,
+                    difficulty TEXT NOT NULL DEFAULT 'medium',
+                    removed INTEGER NOT NULL DEFAULT 0,
+                    UNIQUE (server_id, category, word)
+                )",
+                [],
+            )?;
+
+            // Create word history table so players aren't given the same word again soon
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS word_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    user_id TEXT NOT NULL,
+                    server_id TEXT NOT NULL,
+                    game_type TEXT NOT NULL,
+                    word TEXT NOT NULL,
+                    seen_at INTEGER NOT NULL
+                )",
+                [],
+            )?;
+
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_word_history_user ON word_history (user_id, server_id, game_type)",
+                [],
+            )?;
+
+            // Create trivia packs table of the question packs each server has imported.
+            // `questions` is the pack's JSON as uploaded, checked on import.
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS trivia_packs (
+                    server_id TEXT NOT NULL,
+                    name TEXT NOT NULL COLLATE NOCASE,
+                    questions TEXT NOT NULL,
+                    added_by TEXT NOT NULL,
+                    added_at INTEGER NOT NULL,
+                    PRIMARY KEY (server_id, name)
+                )",
+                [],
+            )?;
+
+            // Create daily puzzles table, fixing each server's word of the day per game
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS daily_puzzles (
+                    server_id TEXT NOT NULL,
+                    game_type TEXT NOT NULL,
+                    puzzle_date TEXT NOT NULL,
+                    category TEXT NOT NULL,
+                    word TEXT NOT NULL,
+                    difficulty TEXT NOT NULL,
+                    PRIMARY KEY (server_id, game_type, puzzle_date)
+                )",
+                [],
+            )?;
+
+            // Create daily attempts table, one row per comrade per puzzle
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS daily_attempts (
+                    server_id TEXT NOT NULL,
+                    game_type TEXT NOT NULL,
+                    puzzle_date TEXT NOT NULL,
+                    user_id TEXT NOT NULL,
+                    username TEXT NOT NULL,
+                    won INTEGER,
+                    guesses INTEGER,
+                    grid TEXT,
+                    started_at INTEGER NOT NULL,
+                    finished_at INTEGER,
+                    PRIMARY KEY (server_id, game_type, puzzle_date, user_id)
+                )",
+                [],
+            )?;
+
+            // Create active games table, keyed by the message the game is played on
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS active_games (
+                    message_id TEXT PRIMARY KEY,
+                    channel_id TEXT NOT NULL,
+                    server_id TEXT NOT NULL,
+                    game_type TEXT NOT NULL,
+                    state TEXT NOT NULL,
+                    escrow_id INTEGER,
+                    updated_at INTEGER NOT NULL
+                )",
+                [],
+            )?;
+
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_active_games_channel ON active_games (channel_id, game_type)",
+                [],
+            )?;
+
             Ok::<_, rusqlite::Error>(())
         }).await
     }
@@ -322,6 +765,24 @@ impl Database {
                 println!("Added current_distribution_round column to servers table");
             }
             
+            // Results of word games also record how they went. Older scores, and games
+            // that only keep a score, leave these empty.
+            for (column, definition) in [("won", "INTEGER"), ("guesses", "INTEGER"), ("duration_secs", "REAL")] {
+                let has_column = match conn.query_row(
+                    "SELECT COUNT(*) FROM pragma_table_info('game_scores') WHERE name = ?",
+                    params![column],
+                    |row| row.get::<_, i64>(0)
+                ) {
+                    Ok(count) => count > 0,
+                    Err(_) => false,
+                };
+                
+                if !has_column {
+                    conn.execute(&format!("ALTER TABLE game_scores ADD COLUMN {} {}", column, definition), [])?;
+                    println!("Added {} column to game_scores table", column);
+                }
+            }
+            
             // Check if distribution_claims table exists
             let has_claims_table = match conn.query_row(
                 "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='distribution_claims'",
@@ -1086,13 +1547,6 @@ impl Database {
                 params![server_id],
             )?;
             
-            // Get the new round number
-            let new_round: i64 = conn.query_row(
-                "SELECT current_distribution_round FROM servers WHERE server_id = ?",
-                params![server_id],
-                |row| row.get(0),
-            )?;
-            
             // Clear all previous distribution claims for this server to start fresh
             conn.execute(
                 "DELETE FROM distribution_claims WHERE server_id = ?",
@@ -1128,40 +1582,113 @@ impl Database {
         }).await
     }
     
-    pub async fn get_user_best_score(&self, user_id: &str, server_id: &str, game_type: &str) -> DbResult<Option<f64>> {
+    // Save a finished game that records whether it was won, how many guesses it took and how long
+    pub async fn save_game_result(&self, result: &GameResult) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let result = result.clone();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "INSERT INTO game_scores (user_id, server_id, username, game_type, score, won, guesses, duration_secs) 
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    result.user_id,
+                    result.server_id,
+                    result.username,
+                    result.game_type,
+                    result.score,
+                    result.won as i64,
+                    result.guesses,
+                    result.duration_secs
+                ],
+            )?;
+            Ok::<_, rusqlite::Error>(())
+        }).await
+    }
+    
+    // A comrade's record in every game they have a saved score in
+    pub async fn get_user_game_stats(&self, user_id: &str, server_id: &str) -> DbResult<Vec<GameStats>> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT game_type, COUNT(*), 
+                    SUM(CASE WHEN won = 1 THEN 1 ELSE 0 END), 
+                    SUM(CASE WHEN won = 0 THEN 1 ELSE 0 END), 
+                    MIN(CASE WHEN won IS NULL OR won = 1 THEN score END), 
+                    MAX(CASE WHEN won IS NULL OR won = 1 THEN score END), 
+                    AVG(guesses), AVG(duration_secs) 
+                 FROM game_scores WHERE user_id = ? AND server_id = ? 
+                 GROUP BY game_type ORDER BY game_type ASC"
+            )?;
+            
+            let rows = stmt.query_map(params![user_id, server_id], |row| {
+                Ok(GameStats {
+                    game_type: row.get(0)?,
+                    played: row.get(1)?,
+                    wins: row.get(2)?,
+                    losses: row.get(3)?,
+                    best_score: row.get(4)?,
+                    top_score: row.get(5)?,
+                    avg_guesses: row.get(6)?,
+                    avg_duration_secs: row.get(7)?,
+                })
+            })?;
+            
+            let mut stats = Vec::new();
+            for game in rows {
+                stats.push(game?);
+            }
+            
+            Ok::<Vec<GameStats>, rusqlite::Error>(stats)
+        }).await
+    }
+    
+    // Lost games don't count towards best scores. Most games score times or guesses,
+    // where lower is better; games scored in points pass `higher_is_better`.
+    pub async fn get_user_best_score(&self, user_id: &str, server_id: &str, game_type: &str, higher_is_better: bool) -> DbResult<Option<f64>> {
         let conn = self.conn.lock().await;
         let user_id = user_id.to_string();
         let server_id = server_id.to_string();
         let game_type = game_type.to_string();
+        let best = if higher_is_better { "MAX" } else { "MIN" };
         
         conn.call(move |conn| {
             let result = conn.query_row(
-                "SELECT MIN(score) FROM game_scores WHERE user_id = ? AND server_id = ? AND game_type = ?",
+                &format!(
+                    "SELECT {}(score) FROM game_scores WHERE user_id = ? AND server_id = ? AND game_type = ? 
+                     AND (won IS NULL OR won = 1)",
+                    best
+                ),
                 params![user_id, server_id, game_type],
-                |row| row.get(0),
+                |row| row.get::<_, Option<f64>>(0),
             );
             
             match result {
-                Ok(score) => Ok(Some(score)),
+                Ok(score) => Ok(score),
                 Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-                Err(e) => Err(e.into()),
+                Err(e) => Err(e),
             }
         }).await
     }
     
-    pub async fn get_server_leaderboard(&self, server_id: &str, game_type: &str, limit: usize) -> DbResult<Vec<(String, f64)>> {
+    pub async fn get_server_leaderboard(&self, server_id: &str, game_type: &str, limit: usize, higher_is_better: bool) -> DbResult<Vec<(String, f64)>> {
         let conn = self.conn.lock().await;
         let server_id = server_id.to_string();
         let game_type = game_type.to_string();
+        let (best, order) = if higher_is_better { ("MAX", "DESC") } else { ("MIN", "ASC") };
         
         conn.call(move |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT username, MIN(score) as best_score FROM game_scores 
-                WHERE server_id = ? AND game_type = ? 
+            let mut stmt = conn.prepare(&format!(
+                "SELECT username, {}(score) as best_score FROM game_scores 
+                WHERE server_id = ? AND game_type = ? AND (won IS NULL OR won = 1) 
                 GROUP BY user_id 
-                ORDER BY best_score ASC 
-                LIMIT ?"
-            )?;
+                ORDER BY best_score {} 
+                LIMIT ?",
+                best, order
+            ))?;
             
             let rows = stmt.query_map(params![server_id, game_type, limit as i64], |row| {
                 Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
@@ -1175,4 +1702,1479 @@ impl Database {
             Ok(results)
         }).await
     }
-} 
\ No newline at end of file
+
+    // Chat income functions
+    pub async fn get_chat_income_settings(&self, server_id: &str) -> DbResult<ChatIncomeSettings> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let result = conn.query_row(
+                "SELECT enabled, reward, cooldown_secs, min_length, daily_cap 
+                 FROM chat_income_settings WHERE server_id = ?",
+                params![server_id],
+                |row| {
+                    Ok(ChatIncomeSettings {
+                        enabled: row.get::<_, i64>(0)? != 0,
+                        reward: row.get(1)?,
+                        cooldown_secs: row.get(2)?,
+                        min_length: row.get(3)?,
+                        daily_cap: row.get(4)?,
+                    })
+                },
+            );
+            
+            match result {
+                Ok(settings) => Ok(settings),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(ChatIncomeSettings::default()),
+                Err(e) => Err(e),
+            }
+        }).await
+    }
+    
+    pub async fn set_chat_income_settings(&self, server_id: &str, settings: &ChatIncomeSettings) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let settings = settings.clone();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO chat_income_settings 
+                 (server_id, enabled, reward, cooldown_secs, min_length, daily_cap) 
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                params![
+                    server_id,
+                    settings.enabled as i64,
+                    settings.reward,
+                    settings.cooldown_secs,
+                    settings.min_length,
+                    settings.daily_cap
+                ],
+            )?;
+            Ok::<_, rusqlite::Error>(())
+        }).await
+    }
+    
+    // Award chat income for a message if it passes the cooldown, duplicate and daily cap checks.
+    // Returns the (communal, personal) amounts awarded, or None if nothing was earned.
+    pub async fn award_chat_income(
+        &self,
+        user_id: &str,
+        server_id: &str,
+        message: &str,
+        settings: &ChatIncomeSettings,
+    ) -> DbResult<Option<(f64, f64)>> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        let message = message.to_string();
+        let settings = settings.clone();
+        let now = chrono::Utc::now().timestamp();
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        
+        conn.call(move |conn| {
+            // Start a transaction
+            conn.execute("BEGIN TRANSACTION", [])?;
+            
+            // Get the user's previous chat income state
+            let state = conn.query_row(
+                "SELECT COALESCE(last_award_at, 0), COALESCE(last_message, ''), COALESCE(day, ''), COALESCE(earned_today, 0.0) 
+                 FROM chat_income WHERE user_id = ? AND server_id = ?",
+                params![user_id, server_id],
+                |row| Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, f64>(3)?,
+                )),
+            );
+            
+            let (last_award_at, last_message, day, earned_today) = match state {
+                Ok(state) => state,
+                Err(rusqlite::Error::QueryReturnedNoRows) => (0, String::new(), String::new(), 0.0),
+                Err(e) => {
+                    conn.execute("ROLLBACK", [])?;
+                    return Err(e);
+                }
+            };
+            
+            // The daily total resets when the UTC date changes
+            let earned_today = if day == today { earned_today } else { 0.0 };
+            
+            let is_duplicate = last_message == message;
+            let on_cooldown = now - last_award_at < settings.cooldown_secs;
+            let remaining_cap = (settings.daily_cap - earned_today).max(0.0);
+            let earned = ((settings.reward.min(remaining_cap)) * 100.0).round() / 100.0;
+            
+            if is_duplicate || on_cooldown || earned <= 0.0 {
+                // Remember the message anyway so repeated spam is still detected
+                conn.execute(
+                    "INSERT INTO chat_income (user_id, server_id, last_award_at, last_message, day, earned_today) 
+                     VALUES (?, ?, NULL, ?, ?, ?) 
+                     ON CONFLICT(user_id, server_id) DO UPDATE SET 
+                        last_message = excluded.last_message, day = excluded.day, earned_today = excluded.earned_today",
+                    params![user_id, server_id, message, today, earned_today],
+                )?;
+                conn.execute("COMMIT", [])?;
+                return Ok(None);
+            }
+            
+            // Same split as work: 90% to the communal pool, the rest to the worker
+            let communal_amount = (earned * 0.9 * 100.0).round() / 100.0;
+            let personal_amount = earned - communal_amount;
+            
+            // Add to the communal pool without starting a new distribution round
+            conn.execute(
+                "UPDATE servers SET communal_boops = communal_boops + ? WHERE server_id = ?",
+                params![communal_amount, server_id],
+            )?;
+            
+            conn.execute(
+                "UPDATE users SET boops = boops + ? WHERE user_id = ?",
+                params![personal_amount, user_id],
+            )?;
+            
+            conn.execute(
+                "INSERT INTO chat_income (user_id, server_id, last_award_at, last_message, day, earned_today) 
+                 VALUES (?, ?, ?, ?, ?, ?) 
+                 ON CONFLICT(user_id, server_id) DO UPDATE SET 
+                    last_award_at = excluded.last_award_at, last_message = excluded.last_message, 
+                    day = excluded.day, earned_today = excluded.earned_today",
+                params![user_id, server_id, now, message, today, earned_today + earned],
+            )?;
+            
+            // Commit the transaction
+            conn.execute("COMMIT", [])?;
+            
+            Ok::<Option<(f64, f64)>, rusqlite::Error>(Some((communal_amount, personal_amount)))
+        }).await
+    }
+
+    // Auto-reaction functions
+    pub async fn add_autoreact_rule(&self, rule: &AutoReactRule) -> DbResult<i64> {
+        let conn = self.conn.lock().await;
+        let rule = rule.clone();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "INSERT INTO autoreact_rules (server_id, is_regex, pattern, emoji, probability, channels, cooldown_secs) 
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    rule.server_id,
+                    rule.is_regex as i64,
+                    rule.pattern,
+                    rule.emoji,
+                    rule.probability,
+                    rule.channels.join(","),
+                    rule.cooldown_secs
+                ],
+            )?;
+            
+            Ok::<i64, rusqlite::Error>(conn.last_insert_rowid())
+        }).await
+    }
+    
+    pub async fn remove_autoreact_rule(&self, server_id: &str, rule_id: i64) -> DbResult<bool> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let removed = conn.execute(
+                "DELETE FROM autoreact_rules WHERE id = ? AND server_id = ?",
+                params![rule_id, server_id],
+            )?;
+            
+            if removed > 0 {
+                conn.execute(
+                    "DELETE FROM autoreact_cooldowns WHERE rule_id = ?",
+                    params![rule_id],
+                )?;
+            }
+            
+            Ok::<bool, rusqlite::Error>(removed > 0)
+        }).await
+    }
+    
+    pub async fn get_autoreact_rules(&self, server_id: &str) -> DbResult<Vec<AutoReactRule>> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, server_id, is_regex, pattern, emoji, probability, channels, cooldown_secs 
+                 FROM autoreact_rules WHERE server_id = ? ORDER BY id ASC"
+            )?;
+            
+            let rows = stmt.query_map(params![server_id], |row| {
+                let channels: String = row.get(6)?;
+                Ok(AutoReactRule {
+                    id: row.get(0)?,
+                    server_id: row.get(1)?,
+                    is_regex: row.get::<_, i64>(2)? != 0,
+                    pattern: row.get(3)?,
+                    emoji: row.get(4)?,
+                    probability: row.get(5)?,
+                    channels: channels
+                        .split(',')
+                        .filter(|c| !c.is_empty())
+                        .map(|c| c.to_string())
+                        .collect(),
+                    cooldown_secs: row.get(7)?,
+                })
+            })?;
+            
+            let mut rules = Vec::new();
+            for rule in rows {
+                rules.push(rule?);
+            }
+            
+            Ok::<Vec<AutoReactRule>, rusqlite::Error>(rules)
+        }).await
+    }
+    
+    // Check a user's cooldown for an auto-reaction rule and start a new one if it has expired.
+    // Returns true if the bot may react.
+    pub async fn try_start_autoreact_cooldown(&self, rule_id: i64, user_id: &str, cooldown_secs: i64) -> DbResult<bool> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            let result = conn.query_row(
+                "SELECT last_reacted_at FROM autoreact_cooldowns WHERE rule_id = ? AND user_id = ?",
+                params![rule_id, user_id],
+                |row| row.get::<_, i64>(0),
+            );
+            
+            let last_reacted_at = match result {
+                Ok(timestamp) => Some(timestamp),
+                Err(rusqlite::Error::QueryReturnedNoRows) => None,
+                Err(e) => return Err(e),
+            };
+            
+            if let Some(last) = last_reacted_at {
+                if now - last < cooldown_secs {
+                    return Ok(false);
+                }
+            }
+            
+            conn.execute(
+                "INSERT OR REPLACE INTO autoreact_cooldowns (rule_id, user_id, last_reacted_at) VALUES (?, ?, ?)",
+                params![rule_id, user_id, now],
+            )?;
+            
+            Ok::<bool, rusqlite::Error>(true)
+        }).await
+    }
+
+    // KGB listener functions
+    pub async fn get_kgb_settings(&self, server_id: &str) -> DbResult<KgbSettings> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let result = conn.query_row(
+                "SELECT enabled, probability, channels, global_cooldown_secs, user_cooldown_secs 
+                 FROM kgb_settings WHERE server_id = ?",
+                params![server_id],
+                |row| {
+                    let channels: String = row.get(2)?;
+                    Ok(KgbSettings {
+                        enabled: row.get::<_, i64>(0)? != 0,
+                        probability: row.get(1)?,
+                        channels: channels
+                            .split(',')
+                            .filter(|c| !c.is_empty())
+                            .map(|c| c.to_string())
+                            .collect(),
+                        global_cooldown_secs: row.get(3)?,
+                        user_cooldown_secs: row.get(4)?,
+                    })
+                },
+            );
+            
+            match result {
+                Ok(settings) => Ok(settings),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(KgbSettings::default()),
+                Err(e) => Err(e),
+            }
+        }).await
+    }
+    
+    pub async fn set_kgb_settings(&self, server_id: &str, settings: &KgbSettings) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let settings = settings.clone();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "INSERT INTO kgb_settings 
+                 (server_id, enabled, probability, channels, global_cooldown_secs, user_cooldown_secs) 
+                 VALUES (?, ?, ?, ?, ?, ?) 
+                 ON CONFLICT(server_id) DO UPDATE SET 
+                    enabled = excluded.enabled, probability = excluded.probability, channels = excluded.channels, 
+                    global_cooldown_secs = excluded.global_cooldown_secs, user_cooldown_secs = excluded.user_cooldown_secs",
+                params![
+                    server_id,
+                    settings.enabled as i64,
+                    settings.probability,
+                    settings.channels.join(","),
+                    settings.global_cooldown_secs,
+                    settings.user_cooldown_secs
+                ],
+            )?;
+            Ok::<_, rusqlite::Error>(())
+        }).await
+    }
+    
+    pub async fn set_kgb_optout(&self, user_id: &str, server_id: &str, opted_out: bool) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            if opted_out {
+                conn.execute(
+                    "INSERT OR IGNORE INTO kgb_optouts (user_id, server_id) VALUES (?, ?)",
+                    params![user_id, server_id],
+                )?;
+            } else {
+                conn.execute(
+                    "DELETE FROM kgb_optouts WHERE user_id = ? AND server_id = ?",
+                    params![user_id, server_id],
+                )?;
+            }
+            Ok::<_, rusqlite::Error>(())
+        }).await
+    }
+    
+    pub async fn is_kgb_opted_out(&self, user_id: &str, server_id: &str) -> DbResult<bool> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM kgb_optouts WHERE user_id = ? AND server_id = ?",
+                params![user_id, server_id],
+                |row| row.get(0),
+            )?;
+            Ok::<bool, rusqlite::Error>(count > 0)
+        }).await
+    }
+    
+    // Check the server-wide and per-user KGB cooldowns and start new ones if both have expired.
+    // Returns true if the listener may fire.
+    pub async fn try_start_kgb_cooldown(&self, user_id: &str, server_id: &str, settings: &KgbSettings) -> DbResult<bool> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        let global_cooldown = settings.global_cooldown_secs;
+        let user_cooldown = settings.user_cooldown_secs;
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            let global_last: i64 = match conn.query_row(
+                "SELECT COALESCE(last_triggered_at, 0) FROM kgb_settings WHERE server_id = ?",
+                params![server_id],
+                |row| row.get(0),
+            ) {
+                Ok(timestamp) => timestamp,
+                Err(rusqlite::Error::QueryReturnedNoRows) => 0,
+                Err(e) => return Err(e),
+            };
+            
+            if now - global_last < global_cooldown {
+                return Ok(false);
+            }
+            
+            let user_last: i64 = match conn.query_row(
+                "SELECT last_triggered_at FROM kgb_cooldowns WHERE user_id = ? AND server_id = ?",
+                params![user_id, server_id],
+                |row| row.get(0),
+            ) {
+                Ok(timestamp) => timestamp,
+                Err(rusqlite::Error::QueryReturnedNoRows) => 0,
+                Err(e) => return Err(e),
+            };
+            
+            if now - user_last < user_cooldown {
+                return Ok(false);
+            }
+            
+            // Start both cooldowns
+            conn.execute(
+                "INSERT INTO kgb_settings (server_id, last_triggered_at) VALUES (?, ?) 
+                 ON CONFLICT(server_id) DO UPDATE SET last_triggered_at = excluded.last_triggered_at",
+                params![server_id, now],
+            )?;
+            
+            conn.execute(
+                "INSERT OR REPLACE INTO kgb_cooldowns (user_id, server_id, last_triggered_at) VALUES (?, ?, ?)",
+                params![user_id, server_id, now],
+            )?;
+            
+            Ok::<bool, rusqlite::Error>(true)
+        }).await
+    }
+    
+    // File an overheard remark in a user's dossier, keeping only the most recent entries
+    pub async fn add_dossier_entry(
+        &self,
+        user_id: &str,
+        server_id: &str,
+        remark: &str,
+        trigger_word: Option<&str>,
+        max_entries: i64,
+    ) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        let remark = remark.to_string();
+        let trigger_word = trigger_word.map(|w| w.to_string());
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "INSERT INTO dossiers (user_id, server_id, remark, trigger_word, recorded_at) 
+                 VALUES (?, ?, ?, ?, ?)",
+                params![user_id, server_id, remark, trigger_word, now],
+            )?;
+            
+            // Shred the oldest pages once the file is full
+            conn.execute(
+                "DELETE FROM dossiers WHERE user_id = ? AND server_id = ? AND id NOT IN (
+                    SELECT id FROM dossiers WHERE user_id = ? AND server_id = ? 
+                    ORDER BY id DESC LIMIT ?
+                )",
+                params![user_id, server_id, user_id, server_id, max_entries],
+            )?;
+            
+            Ok::<_, rusqlite::Error>(())
+        }).await
+    }
+    
+    // Get the most recent entries of a user's dossier, newest first
+    pub async fn get_dossier(&self, user_id: &str, server_id: &str, limit: i64) -> DbResult<Vec<DossierEntry>> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT remark, trigger_word, recorded_at FROM dossiers 
+                 WHERE user_id = ? AND server_id = ? ORDER BY id DESC LIMIT ?"
+            )?;
+            
+            let rows = stmt.query_map(params![user_id, server_id, limit], |row| {
+                Ok(DossierEntry {
+                    remark: row.get(0)?,
+                    trigger_word: row.get(1)?,
+                    recorded_at: row.get(2)?,
+                })
+            })?;
+            
+            let mut entries = Vec::new();
+            for entry in rows {
+                entries.push(entry?);
+            }
+            
+            Ok::<Vec<DossierEntry>, rusqlite::Error>(entries)
+        }).await
+    }
+    
+    // Burn a user's dossier, returning the number of entries destroyed
+    pub async fn delete_dossier(&self, user_id: &str, server_id: &str) -> DbResult<usize> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let removed = conn.execute(
+                "DELETE FROM dossiers WHERE user_id = ? AND server_id = ?",
+                params![user_id, server_id],
+            )?;
+            Ok::<usize, rusqlite::Error>(removed)
+        }).await
+    }
+    
+    // Debit every stake and hold it in a new escrow, all or nothing.
+    // Returns None if any player cannot afford their stake.
+    pub async fn open_escrow(&self, server_id: &str, game_type: &str, stakes: &[(String, f64)]) -> DbResult<Option<i64>> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        let stakes = stakes.to_vec();
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            // Start a transaction
+            conn.execute("BEGIN TRANSACTION", [])?;
+            
+            if let Err(e) = conn.execute(
+                "INSERT INTO escrows (server_id, game_type, created_at) VALUES (?, ?, ?)",
+                params![server_id, game_type, now],
+            ) {
+                conn.execute("ROLLBACK", [])?;
+                return Err(e);
+            }
+            let escrow_id = conn.last_insert_rowid();
+            
+            for (user_id, amount) in &stakes {
+                let debited = match conn.execute(
+                    "UPDATE users SET boops = boops - ? WHERE user_id = ? AND boops >= ?",
+                    params![amount, user_id, amount],
+                ) {
+                    Ok(changed) => changed,
+                    Err(e) => {
+                        conn.execute("ROLLBACK", [])?;
+                        return Err(e);
+                    }
+                };
+                
+                // Someone can't cover their stake, so nobody pays
+                if debited == 0 {
+                    conn.execute("ROLLBACK", [])?;
+                    return Ok(None);
+                }
+                
+                if let Err(e) = conn.execute(
+                    "INSERT INTO escrow_stakes (escrow_id, user_id, amount) VALUES (?, ?, ?)",
+                    params![escrow_id, user_id, amount],
+                ) {
+                    conn.execute("ROLLBACK", [])?;
+                    return Err(e);
+                }
+            }
+            
+            // Commit the transaction
+            conn.execute("COMMIT", [])?;
+            
+            Ok::<Option<i64>, rusqlite::Error>(Some(escrow_id))
+        }).await
+    }
+    
+    // Add to a player's stake in an open escrow, as when doubling down. Returns false
+    // (and takes nothing) if they can't afford it.
+    pub async fn raise_escrow_stake(&self, escrow_id: i64, user_id: &str, amount: f64) -> DbResult<bool> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        
+        conn.call(move |conn| {
+            // Start a transaction
+            conn.execute("BEGIN TRANSACTION", [])?;
+            
+            let debited = match conn.execute(
+                "UPDATE users SET boops = boops - ? WHERE user_id = ? AND boops >= ?",
+                params![amount, user_id, amount],
+            ) {
+                Ok(changed) => changed,
+                Err(e) => {
+                    conn.execute("ROLLBACK", [])?;
+                    return Err(e);
+                }
+            };
+            
+            if debited == 0 {
+                conn.execute("ROLLBACK", [])?;
+                return Ok(false);
+            }
+            
+            if let Err(e) = conn.execute(
+                "UPDATE escrow_stakes SET amount = amount + ? WHERE escrow_id = ? AND user_id = ?",
+                params![amount, escrow_id, user_id],
+            ) {
+                conn.execute("ROLLBACK", [])?;
+                return Err(e);
+            }
+            
+            // Commit the transaction
+            conn.execute("COMMIT", [])?;
+            
+            Ok::<bool, rusqlite::Error>(true)
+        }).await
+    }
+    
+    // Pay a comrade a reward for a win of some kind, such as beating the computer, unless
+    // they have already been paid for `daily_limit` wins of that kind today (UTC).
+    // Returns whether they were paid.
+    pub async fn claim_daily_reward(
+        &self,
+        user_id: &str,
+        server_id: &str,
+        kind: &str,
+        reward: f64,
+        daily_limit: i64,
+    ) -> DbResult<bool> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        let kind = kind.to_string();
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        
+        conn.call(move |conn| {
+            // Start a transaction
+            conn.execute("BEGIN TRANSACTION", [])?;
+            
+            let state = conn.query_row(
+                "SELECT day, rewarded FROM daily_rewards WHERE user_id = ? AND server_id = ? AND kind = ?",
+                params![user_id, server_id, kind],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+            );
+            
+            let rewarded = match state {
+                // The count resets when the UTC date changes
+                Ok((day, rewarded)) if day == today => rewarded,
+                Ok(_) | Err(rusqlite::Error::QueryReturnedNoRows) => 0,
+                Err(e) => {
+                    conn.execute("ROLLBACK", [])?;
+                    return Err(e);
+                }
+            };
+            
+            if rewarded >= daily_limit {
+                conn.execute("ROLLBACK", [])?;
+                return Ok(false);
+            }
+            
+            if let Err(e) = conn.execute(
+                "UPDATE users SET boops = boops + ? WHERE user_id = ?",
+                params![reward, user_id],
+            ) {
+                conn.execute("ROLLBACK", [])?;
+                return Err(e);
+            }
+            
+            if let Err(e) = conn.execute(
+                "INSERT INTO daily_rewards (user_id, server_id, kind, day, rewarded) 
+                 VALUES (?, ?, ?, ?, ?) 
+                 ON CONFLICT(user_id, server_id, kind) DO UPDATE SET day = excluded.day, rewarded = excluded.rewarded",
+                params![user_id, server_id, kind, today, rewarded + 1],
+            ) {
+                conn.execute("ROLLBACK", [])?;
+                return Err(e);
+            }
+            
+            // Commit the transaction
+            conn.execute("COMMIT", [])?;
+            
+            Ok::<bool, rusqlite::Error>(true)
+        }).await
+    }
+    
+    // Release an escrow according to the game's outcome. Settling an escrow that was
+    // already released does nothing. Returns the amount paid to the winner, if any.
+    pub async fn settle_escrow(&self, escrow_id: i64, outcome: &EscrowOutcome) -> DbResult<f64> {
+        let conn = self.conn.lock().await;
+        let outcome = outcome.clone();
+        
+        conn.call(move |conn| {
+            // Start a transaction
+            conn.execute("BEGIN TRANSACTION", [])?;
+            
+            let result = settle_escrow_in_transaction(conn, escrow_id, &outcome);
+            
+            match result {
+                Ok(paid) => {
+                    conn.execute("COMMIT", [])?;
+                    Ok::<f64, rusqlite::Error>(paid)
+                },
+                Err(e) => {
+                    conn.execute("ROLLBACK", [])?;
+                    Err(e)
+                }
+            }
+        }).await
+    }
+    
+    // Refund every escrow left open by games interrupted when the bot stopped, except
+    // those still held by a saved game that can be resumed. Returns the number refunded.
+    pub async fn refund_open_escrows(&self) -> DbResult<usize> {
+        let conn = self.conn.lock().await;
+        
+        conn.call(move |conn| {
+            let escrow_ids: Vec<i64> = {
+                let mut stmt = conn.prepare(
+                    "SELECT id FROM escrows WHERE id NOT IN 
+                     (SELECT escrow_id FROM active_games WHERE escrow_id IS NOT NULL)"
+                )?;
+                let rows = stmt.query_map([], |row| row.get(0))?;
+                rows.collect::<Result<_, _>>()?
+            };
+            
+            for escrow_id in &escrow_ids {
+                conn.execute("BEGIN TRANSACTION", [])?;
+                
+                match settle_escrow_in_transaction(conn, *escrow_id, &EscrowOutcome::Refund) {
+                    Ok(_) => conn.execute("COMMIT", [])?,
+                    Err(e) => {
+                        conn.execute("ROLLBACK", [])?;
+                        return Err(e);
+                    }
+                };
+            }
+            
+            Ok::<usize, rusqlite::Error>(escrow_ids.len())
+        }).await
+    }
+    
+    // Get a comrade's rating in a game, or the starting rating if they have never played it
+    pub async fn get_rating(&self, user_id: &str, server_id: &str, game_type: &str) -> DbResult<Rating> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        
+        conn.call(move |conn| {
+            let result = conn.query_row(
+                "SELECT rating, games, wins, losses, draws FROM ratings 
+                 WHERE user_id = ? AND server_id = ? AND game_type = ?",
+                params![user_id, server_id, game_type],
+                |row| Ok(Rating {
+                    rating: row.get(0)?,
+                    games: row.get(1)?,
+                    wins: row.get(2)?,
+                    losses: row.get(3)?,
+                    draws: row.get(4)?,
+                }),
+            );
+            
+            match result {
+                Ok(rating) => Ok(rating),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(Rating::default()),
+                Err(e) => Err(e),
+            }
+        }).await
+    }
+    
+    // Save both players' new ratings after a match in a single transaction.
+    // Each player is given as (user_id, username, rating).
+    pub async fn save_match_ratings(
+        &self,
+        server_id: &str,
+        game_type: &str,
+        players: [(&str, &str, &Rating); 2],
+    ) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        let players: Vec<(String, String, Rating)> = players
+            .iter()
+            .map(|(user_id, username, rating)| (user_id.to_string(), username.to_string(), (*rating).clone()))
+            .collect();
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            // Start a transaction
+            conn.execute("BEGIN TRANSACTION", [])?;
+            
+            for (user_id, username, rating) in &players {
+                if let Err(e) = conn.execute(
+                    "INSERT INTO ratings (user_id, server_id, game_type, username, rating, games, wins, losses, draws, updated_at) 
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) 
+                     ON CONFLICT(user_id, server_id, game_type) DO UPDATE SET 
+                        username = excluded.username, rating = excluded.rating, games = excluded.games, 
+                        wins = excluded.wins, losses = excluded.losses, draws = excluded.draws, 
+                        updated_at = excluded.updated_at",
+                    params![
+                        user_id, server_id, game_type, username, rating.rating,
+                        rating.games, rating.wins, rating.losses, rating.draws, now
+                    ],
+                ) {
+                    conn.execute("ROLLBACK", [])?;
+                    return Err(e);
+                }
+            }
+            
+            // Commit the transaction
+            conn.execute("COMMIT", [])?;
+            
+            Ok::<_, rusqlite::Error>(())
+        }).await
+    }
+    
+    // Get all of a comrade's ratings in this server, with their position on each leaderboard
+    pub async fn get_user_ratings(&self, user_id: &str, server_id: &str) -> DbResult<Vec<(String, Rating, i64)>> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT r.game_type, r.rating, r.games, r.wins, r.losses, r.draws, 
+                    (SELECT COUNT(*) FROM ratings o 
+                     WHERE o.server_id = r.server_id AND o.game_type = r.game_type AND o.rating > r.rating) + 1 
+                 FROM ratings r WHERE r.user_id = ? AND r.server_id = ? ORDER BY r.game_type ASC"
+            )?;
+            
+            let rows = stmt.query_map(params![user_id, server_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    Rating {
+                        rating: row.get(1)?,
+                        games: row.get(2)?,
+                        wins: row.get(3)?,
+                        losses: row.get(4)?,
+                        draws: row.get(5)?,
+                    },
+                    row.get::<_, i64>(6)?,
+                ))
+            })?;
+            
+            let mut results = Vec::new();
+            for row in rows {
+                results.push(row?);
+            }
+            
+            Ok::<Vec<(String, Rating, i64)>, rusqlite::Error>(results)
+        }).await
+    }
+    
+    // Get the highest rated comrades in a game
+    pub async fn get_rating_leaderboard(&self, server_id: &str, game_type: &str, limit: usize) -> DbResult<Vec<(String, Rating)>> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT username, rating, games, wins, losses, draws FROM ratings 
+                 WHERE server_id = ? AND game_type = ? 
+                 ORDER BY rating DESC 
+                 LIMIT ?"
+            )?;
+            
+            let rows = stmt.query_map(params![server_id, game_type, limit as i64], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    Rating {
+                        rating: row.get(1)?,
+                        games: row.get(2)?,
+                        wins: row.get(3)?,
+                        losses: row.get(4)?,
+                        draws: row.get(5)?,
+                    },
+                ))
+            })?;
+            
+            let mut results = Vec::new();
+            for row in rows {
+                results.push(row?);
+            }
+            
+            Ok::<Vec<(String, Rating)>, rusqlite::Error>(results)
+        }).await
+    }
+
+    // Save a game in progress, replacing any earlier snapshot of the same game
+    pub async fn save_active_game(&self, game: &ActiveGame) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let game = game.clone();
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "INSERT INTO active_games (message_id, channel_id, server_id, game_type, state, escrow_id, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(message_id) DO UPDATE SET state = excluded.state, updated_at = excluded.updated_at",
+                params![game.message_id, game.channel_id, game.server_id, game.game_type, game.state, game.escrow_id, now],
+            )?;
+            Ok::<(), rusqlite::Error>(())
+        }).await
+    }
+    
+    // Get the saved game played on a message, if there is one
+    pub async fn get_active_game(&self, message_id: &str) -> DbResult<Option<ActiveGame>> {
+        let conn = self.conn.lock().await;
+        let message_id = message_id.to_string();
+        
+        conn.call(move |conn| {
+            let result = conn.query_row(
+                "SELECT message_id, channel_id, server_id, game_type, state, escrow_id, updated_at 
+                 FROM active_games WHERE message_id = ?",
+                params![message_id],
+                active_game_from_row,
+            );
+            
+            match result {
+                Ok(game) => Ok(Some(game)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e),
+            }
+        }).await
+    }
+    
+    // Get every saved game of one type in a channel, oldest first
+    pub async fn get_active_games_in_channel(&self, channel_id: &str, game_type: &str) -> DbResult<Vec<ActiveGame>> {
+        let conn = self.conn.lock().await;
+        let channel_id = channel_id.to_string();
+        let game_type = game_type.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT message_id, channel_id, server_id, game_type, state, escrow_id, updated_at 
+                 FROM active_games WHERE channel_id = ? AND game_type = ? ORDER BY updated_at"
+            )?;
+            
+            let rows = stmt.query_map(params![channel_id, game_type], active_game_from_row)?;
+            
+            let mut games = Vec::new();
+            for game in rows {
+                games.push(game?);
+            }
+            
+            Ok::<Vec<ActiveGame>, rusqlite::Error>(games)
+        }).await
+    }
+    
+    // Get the channels with saved games of one type
+    pub async fn get_active_game_channels(&self, game_type: &str) -> DbResult<Vec<String>> {
+        let conn = self.conn.lock().await;
+        let game_type = game_type.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare("SELECT DISTINCT channel_id FROM active_games WHERE game_type = ?")?;
+            let rows = stmt.query_map(params![game_type], |row| row.get::<_, String>(0))?;
+            
+            let mut channels = Vec::new();
+            for channel in rows {
+                channels.push(channel?);
+            }
+            
+            Ok::<Vec<String>, rusqlite::Error>(channels)
+        }).await
+    }
+    
+    // Forget a saved game once it has finished
+    pub async fn delete_active_game(&self, message_id: &str) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let message_id = message_id.to_string();
+        
+        conn.call(move |conn| {
+            conn.execute("DELETE FROM active_games WHERE message_id = ?", params![message_id])?;
+            Ok::<(), rusqlite::Error>(())
+        }).await
+    }
+    
+    // Run at startup: drop saved games untouched for `max_age_secs`, or for
+    // `correspondence_max_age_secs` if their type is in `correspondence`, and restart the idle
+    // clock of the rest, so time spent offline doesn't count against their players.
+    // Escrows of dropped games are left for refund_open_escrows. Returns the number dropped.
+    pub async fn prune_active_games(
+        &self,
+        max_age_secs: i64,
+        correspondence: &[&str],
+        correspondence_max_age_secs: i64,
+    ) -> DbResult<usize> {
+        let conn = self.conn.lock().await;
+        let now = chrono::Utc::now().timestamp();
+        let correspondence: Vec<String> = correspondence.iter().map(|game_type| game_type.to_string()).collect();
+        
+        conn.call(move |conn| {
+            let stale: Vec<String> = {
+                let mut stmt = conn.prepare("SELECT message_id, game_type, updated_at FROM active_games")?;
+                let rows = stmt.query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+                })?;
+                
+                let mut stale = Vec::new();
+                for row in rows {
+                    let (message_id, game_type, updated_at) = row?;
+                    let max_age = if correspondence.contains(&game_type) { correspondence_max_age_secs } else { max_age_secs };
+                    if updated_at < now - max_age {
+                        stale.push(message_id);
+                    }
+                }
+                stale
+            };
+            
+            for message_id in &stale {
+                conn.execute("DELETE FROM active_games WHERE message_id = ?", params![message_id])?;
+            }
+            conn.execute("UPDATE active_games SET updated_at = ?", params![now])?;
+            Ok::<usize, rusqlite::Error>(stale.len())
+        }).await
+    }
+    
+    // Hangman functions
+    pub async fn get_hangman_settings(&self, server_id: &str) -> DbResult<HangmanSettings> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let result = conn.query_row(
+                "SELECT input_style FROM hangman_settings WHERE server_id = ?",
+                params![server_id],
+                |row| Ok(HangmanSettings { input_style: row.get(0)? }),
+            );
+            
+            match result {
+                Ok(settings) => Ok(settings),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(HangmanSettings::default()),
+                Err(e) => Err(e),
+            }
+        }).await
+    }
+    
+    pub async fn set_hangman_settings(&self, server_id: &str, settings: &HangmanSettings) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let settings = settings.clone();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO hangman_settings (server_id, input_style) VALUES (?, ?)",
+                params![server_id, settings.input_style],
+            )?;
+            Ok::<_, rusqlite::Error>(())
+        }).await
+    }
+    
+    // Word bank functions
+    // Add words to a server's category, bringing back any that were removed
+    pub async fn add_bank_words(&self, server_id: &str, category: &str, words: &[String], difficulty: &str) -> DbResult<usize> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let category = category.to_string();
+        let words = words.to_vec();
+        let difficulty = difficulty.to_string();
+        
+        conn.call(move |conn| {
+            conn.execute("BEGIN TRANSACTION", [])?;
+            for word in &words {
+                conn.execute(
+                    "INSERT INTO word_banks (server_id, category, word, difficulty, removed) 
+                     VALUES (?, ?, ?, ?, 0) 
+                     ON CONFLICT(server_id, category, word) DO UPDATE SET 
+                        difficulty = excluded.difficulty, removed = 0",
+                    params![server_id, category, word, difficulty],
+                )?;
+            }
+            conn.execute("COMMIT", [])?;
+            
+            Ok::<usize, rusqlite::Error>(words.len())
+        }).await
+    }
+    
+    // Hide words from a server's category, whether the server added them or they are bundled
+    pub async fn remove_bank_words(&self, server_id: &str, category: &str, words: &[String]) -> DbResult<usize> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let category = category.to_string();
+        let words = words.to_vec();
+        
+        conn.call(move |conn| {
+            conn.execute("BEGIN TRANSACTION", [])?;
+            for word in &words {
+                conn.execute(
+                    "INSERT INTO word_banks (server_id, category, word, removed) 
+                     VALUES (?, ?, ?, 1) 
+                     ON CONFLICT(server_id, category, word) DO UPDATE SET removed = 1",
+                    params![server_id, category, word],
+                )?;
+            }
+            conn.execute("COMMIT", [])?;
+            
+            Ok::<usize, rusqlite::Error>(words.len())
+        }).await
+    }
+    
+    pub async fn get_bank_words(&self, server_id: &str) -> DbResult<Vec<WordBankRow>> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT category, word, difficulty, removed FROM word_banks WHERE server_id = ? ORDER BY id ASC"
+            )?;
+            
+            let rows = stmt.query_map(params![server_id], |row| {
+                Ok(WordBankRow {
+                    category: row.get(0)?,
+                    word: row.get(1)?,
+                    difficulty: row.get(2)?,
+                    removed: row.get::<_, i64>(3)? != 0,
+                })
+            })?;
+            
+            let mut words = Vec::new();
+            for word in rows {
+                words.push(word?);
+            }
+            
+            Ok::<Vec<WordBankRow>, rusqlite::Error>(words)
+        }).await
+    }
+    
+    // The words a player was last given in a game, most recent first
+    pub async fn get_recent_words(&self, user_id: &str, server_id: &str, game_type: &str, limit: usize) -> DbResult<Vec<String>> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT word FROM word_history WHERE user_id = ? AND server_id = ? AND game_type = ? 
+                 ORDER BY id DESC LIMIT ?"
+            )?;
+            
+            let rows = stmt.query_map(params![user_id, server_id, game_type, limit as i64], |row| row.get(0))?;
+            
+            let mut words = Vec::new();
+            for word in rows {
+                words.push(word?);
+            }
+            
+            Ok::<Vec<String>, rusqlite::Error>(words)
+        }).await
+    }
+    
+    // Remember a word a player was given, keeping only their last `keep` for that game
+    pub async fn record_word_seen(&self, user_id: &str, server_id: &str, game_type: &str, word: &str, keep: usize) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let user_id = user_id.to_string();
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        let word = word.to_string();
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "INSERT INTO word_history (user_id, server_id, game_type, word, seen_at) VALUES (?, ?, ?, ?, ?)",
+                params![user_id, server_id, game_type, word, now],
+            )?;
+            
+            conn.execute(
+                "DELETE FROM word_history WHERE user_id = ? AND server_id = ? AND game_type = ? AND id NOT IN (
+                    SELECT id FROM word_history WHERE user_id = ? AND server_id = ? AND game_type = ? 
+                    ORDER BY id DESC LIMIT ?
+                 )",
+                params![user_id, server_id, game_type, user_id, server_id, game_type, keep as i64],
+            )?;
+            
+            Ok::<(), rusqlite::Error>(())
+        }).await
+    }
+    
+    // A server's word of the day for a game as (category, word, difficulty), if one has been picked
+    pub async fn get_daily_word(&self, server_id: &str, game_type: &str, date: &str) -> DbResult<Option<(String, String, String)>> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        let date = date.to_string();
+        
+        conn.call(move |conn| {
+            let result = conn.query_row(
+                "SELECT category, word, difficulty FROM daily_puzzles 
+                 WHERE server_id = ? AND game_type = ? AND puzzle_date = ?",
+                params![server_id, game_type, date],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            );
+            
+            match result {
+                Ok(word) => Ok(Some(word)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e),
+            }
+        }).await
+    }
+    
+    // Fix a server's word of the day. Whichever word is saved first stays.
+    pub async fn save_daily_word(&self, server_id: &str, game_type: &str, date: &str, category: &str, word: &str, difficulty: &str) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        let date = date.to_string();
+        let category = category.to_string();
+        let word = word.to_string();
+        let difficulty = difficulty.to_string();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO daily_puzzles (server_id, game_type, puzzle_date, category, word, difficulty) 
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                params![server_id, game_type, date, category, word, difficulty],
+            )?;
+            Ok::<(), rusqlite::Error>(())
+        }).await
+    }
+    
+    // Claim a comrade's one attempt at a daily puzzle. Returns false if they have already had it.
+    pub async fn start_daily_attempt(&self, server_id: &str, game_type: &str, date: &str, user_id: &str, username: &str) -> DbResult<bool> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        let date = date.to_string();
+        let user_id = user_id.to_string();
+        let username = username.to_string();
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO daily_attempts (server_id, game_type, puzzle_date, user_id, username, started_at) 
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                params![server_id, game_type, date, user_id, username, now],
+            )?;
+            Ok::<bool, rusqlite::Error>(inserted == 1)
+        }).await
+    }
+    
+    // Record how a comrade's daily attempt went, from the result saved for the game
+    pub async fn finish_daily_attempt(&self, date: &str, result: &GameResult, grid: &str) -> DbResult<()> {
+        let conn = self.conn.lock().await;
+        let date = date.to_string();
+        let result = result.clone();
+        let grid = grid.to_string();
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            conn.execute(
+                "UPDATE daily_attempts SET won = ?, guesses = ?, grid = ?, finished_at = ? 
+                 WHERE server_id = ? AND game_type = ? AND puzzle_date = ? AND user_id = ? AND finished_at IS NULL",
+                params![result.won as i64, result.guesses, grid, now, result.server_id, result.game_type, date, result.user_id],
+            )?;
+            Ok::<(), rusqlite::Error>(())
+        }).await
+    }
+    
+    pub async fn get_daily_attempt(&self, server_id: &str, game_type: &str, date: &str, user_id: &str) -> DbResult<Option<DailyAttempt>> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        let date = date.to_string();
+        let user_id = user_id.to_string();
+        
+        conn.call(move |conn| {
+            let result = conn.query_row(
+                "SELECT username, won, guesses, grid FROM daily_attempts 
+                 WHERE server_id = ? AND game_type = ? AND puzzle_date = ? AND user_id = ?",
+                params![server_id, game_type, date, user_id],
+                |row| Ok(DailyAttempt {
+                    username: row.get(0)?,
+                    won: row.get::<_, Option<i64>>(1)?.map(|won| won != 0),
+                    guesses: row.get(2)?,
+                    grid: row.get(3)?,
+                }),
+            );
+            
+            match result {
+                Ok(attempt) => Ok(Some(attempt)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e),
+            }
+        }).await
+    }
+    
+    // Everyone who has finished a daily puzzle, solvers first by fewest guesses then
+    // by who was quickest, followed by those who failed
+    pub async fn get_daily_leaderboard(&self, server_id: &str, game_type: &str, date: &str, limit: usize) -> DbResult<Vec<DailyAttempt>> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let game_type = game_type.to_string();
+        let date = date.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT username, won, guesses, grid FROM daily_attempts 
+                 WHERE server_id = ? AND game_type = ? AND puzzle_date = ? AND finished_at IS NOT NULL 
+                 ORDER BY won DESC, guesses ASC, finished_at - started_at ASC 
+                 LIMIT ?"
+            )?;
+            
+            let rows = stmt.query_map(params![server_id, game_type, date, limit as i64], |row| {
+                Ok(DailyAttempt {
+                    username: row.get(0)?,
+                    won: row.get::<_, Option<i64>>(1)?.map(|won| won != 0),
+                    guesses: row.get(2)?,
+                    grid: row.get(3)?,
+                })
+            })?;
+            
+            let mut attempts = Vec::new();
+            for attempt in rows {
+                attempts.push(attempt?);
+            }
+            
+            Ok::<Vec<DailyAttempt>, rusqlite::Error>(attempts)
+        }).await
+    }
+    
+    // Save a server's imported trivia pack, replacing any pack of the same name.
+    // Returns true if it replaced one.
+    pub async fn save_trivia_pack(&self, server_id: &str, name: &str, questions: &str, added_by: &str) -> DbResult<bool> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let name = name.to_string();
+        let questions = questions.to_string();
+        let added_by = added_by.to_string();
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.call(move |conn| {
+            let existing: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM trivia_packs WHERE server_id = ? AND name = ?",
+                params![server_id, name],
+                |row| row.get(0),
+            )?;
+            
+            conn.execute(
+                "INSERT OR REPLACE INTO trivia_packs (server_id, name, questions, added_by, added_at) 
+                 VALUES (?, ?, ?, ?, ?)",
+                params![server_id, name, questions, added_by, now],
+            )?;
+            
+            Ok::<bool, rusqlite::Error>(existing > 0)
+        }).await
+    }
+    
+    // A server's imported trivia packs as (name, questions JSON), in the order they were added
+    pub async fn get_trivia_packs(&self, server_id: &str) -> DbResult<Vec<(String, String)>> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        
+        conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT name, questions FROM trivia_packs WHERE server_id = ? ORDER BY added_at ASC, name ASC"
+            )?;
+            
+            let rows = stmt.query_map(params![server_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            
+            let mut packs = Vec::new();
+            for pack in rows {
+                packs.push(pack?);
+            }
+            
+            Ok::<Vec<(String, String)>, rusqlite::Error>(packs)
+        }).await
+    }
+    
+    // Returns false if the server has no pack of that name
+    pub async fn remove_trivia_pack(&self, server_id: &str, name: &str) -> DbResult<bool> {
+        let conn = self.conn.lock().await;
+        let server_id = server_id.to_string();
+        let name = name.to_string();
+        
+        conn.call(move |conn| {
+            let removed = conn.execute(
+                "DELETE FROM trivia_packs WHERE server_id = ? AND name = ?",
+                params![server_id, name],
+            )?;
+            Ok::<bool, rusqlite::Error>(removed > 0)
+        }).await
+    }
+} 
+
+// Pay out and delete an escrow. Must run inside a transaction.
+fn settle_escrow_in_transaction(
+    conn: &rusqlite::Connection,
+    escrow_id: i64,
+    outcome: &EscrowOutcome,
+) -> Result<f64, rusqlite::Error> {
+    let server_id: String = match conn.query_row(
+        "SELECT server_id FROM escrows WHERE id = ?",
+        params![escrow_id],
+        |row| row.get(0),
+    ) {
+        Ok(server_id) => server_id,
+        // Already settled
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(0.0),
+        Err(e) => return Err(e),
+    };
+    
+    let stakes: Vec<(String, f64)> = {
+        let mut stmt = conn.prepare("SELECT user_id, amount FROM escrow_stakes WHERE escrow_id = ?")?;
+        let rows = stmt.query_map(params![escrow_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    let pot: f64 = stakes.iter().map(|(_, amount)| amount).sum();
+    
+    let paid = match outcome {
+        EscrowOutcome::Refund => {
+            for (user_id, amount) in &stakes {
+                conn.execute(
+                    "UPDATE users SET boops = boops + ? WHERE user_id = ?",
+                    params![amount, user_id],
+                )?;
+            }
+            0.0
+        },
+        EscrowOutcome::Winner { user_id, communal_cut } => {
+            let cut = ((pot * communal_cut) * 100.0).round() / 100.0;
+            let paid = pot - cut;
+            
+            conn.execute(
+                "UPDATE users SET boops = boops + ? WHERE user_id = ?",
+                params![paid, user_id],
+            )?;
+            conn.execute(
+                "UPDATE servers SET communal_boops = communal_boops + ? WHERE server_id = ?",
+                params![cut, server_id],
+            )?;
+            paid
+        },
+        EscrowOutcome::Forfeit => {
+            conn.execute(
+                "UPDATE servers SET communal_boops = communal_boops + ? WHERE server_id = ?",
+                params![pot, server_id],
+            )?;
+            0.0
+        },
+        EscrowOutcome::Payout { user_id, amount } => {
+            conn.execute(
+                "UPDATE users SET boops = boops + ? WHERE user_id = ?",
+                params![amount, user_id],
+            )?;
+            conn.execute(
+                "UPDATE servers SET communal_boops = communal_boops + ? WHERE server_id = ?",
+                params![(pot - amount).max(0.0), server_id],
+            )?;
+            *amount
+        },
+    };
+    
+    conn.execute("DELETE FROM escrow_stakes WHERE escrow_id = ?", params![escrow_id])?;
+    conn.execute("DELETE FROM escrows WHERE id = ?", params![escrow_id])?;
+    
+    Ok(paid)
+}
+
+fn active_game_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ActiveGame> {
+    Ok(ActiveGame {
+        message_id: row.get(0)?,
+        channel_id: row.get(1)?,
+        server_id: row.get(2)?,
+        game_type: row.get(3)?,
+        state: row.get(4)?,
+        escrow_id: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn daily_rewards_stop_at_the_limit_for_each_kind() {
+        let path = std::env::temp_dir().join(format!("boopato-daily-rewards-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(path.to_str().unwrap()).await.unwrap();
+
+        db.add_server("1", "Politburo").await.unwrap();
+        db.ensure_user_exists("7", "1", "lenin").await.unwrap();
+        let before = db.get_user_boops("7").await.unwrap();
+
+        for _ in 0..3 {
+            assert!(db.claim_daily_reward("7", "1", "soviet_hangman", 2.0, 3).await.unwrap());
+        }
+        assert!(!db.claim_daily_reward("7", "1", "soviet_hangman", 2.0, 3).await.unwrap());
+
+        // Each kind of win has a limit of its own
+        assert!(db.claim_daily_reward("7", "1", "kremlin_secrets", 4.0, 3).await.unwrap());
+
+        assert_eq!(db.get_user_boops("7").await.unwrap(), before + 10.0);
+
+        drop(db);
+        let _ = std::fs::remove_file(&path);
+    }
+}
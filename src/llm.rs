@@ -0,0 +1,259 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Most retries allowed, and the longest wait between them, whatever the config says
+const MAX_RETRIES: u32 = 5;
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+// Define our own error type to simplify error handling
+pub type LlmResult<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+// A single chat message in the OpenAI-compatible format
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self { role: "system".to_string(), content: content.into() }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self { role: "user".to_string(), content: content.into() }
+    }
+}
+
+// Anything that can turn a conversation into a reply
+#[async_trait]
+pub trait LlmClient: Send + Sync {
+    async fn complete(&self, messages: &[ChatMessage], temperature: f32, max_tokens: u32) -> LlmResult<String>;
+}
+
+// Build the configured LLM client from environment variables.
+//
+// LLM_PROVIDER=mock uses the in-process mock. Otherwise an OpenAI-compatible client is
+// created when either an API key (LLM_API_KEY or GROQ_API_KEY) or LLM_BASE_URL is set,
+// so local servers like llama.cpp or Ollama work without a key.
+pub fn from_env() -> Option<Arc<dyn LlmClient>> {
+    if env::var("LLM_PROVIDER").map(|p| p.eq_ignore_ascii_case("mock")).unwrap_or(false) {
+        println!("Using mock LLM client");
+        return Some(Arc::new(MockLlmClient::new("The KGB is watching, comrade...")));
+    }
+
+    let api_key = env::var("LLM_API_KEY").or_else(|_| env::var("GROQ_API_KEY")).ok();
+    let base_url = env::var("LLM_BASE_URL").ok();
+
+    if api_key.is_none() && base_url.is_none() {
+        return None;
+    }
+
+    let config = OpenAiConfig {
+        base_url: base_url.unwrap_or_else(|| OpenAiConfig::default().base_url),
+        model: env::var("LLM_MODEL").unwrap_or_else(|_| OpenAiConfig::default().model),
+        api_key,
+        timeout: env::var("LLM_TIMEOUT_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(OpenAiConfig::default().timeout),
+        max_retries: env::var("LLM_MAX_RETRIES")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .map(|retries| retries.min(MAX_RETRIES))
+            .unwrap_or(OpenAiConfig::default().max_retries),
+    };
+
+    println!("Using LLM {} at {}", config.model, config.base_url);
+
+    match OpenAiClient::new(config) {
+        Ok(client) => Some(Arc::new(client)),
+        Err(e) => {
+            eprintln!("Failed to create LLM client: {}", e);
+            None
+        }
+    }
+}
+
+// Configuration for an OpenAI-compatible chat completions endpoint
+#[derive(Debug, Clone)]
+pub struct OpenAiConfig {
+    pub base_url: String,
+    pub model: String,
+    pub api_key: Option<String>,
+    pub timeout: Duration,
+    pub max_retries: u32,
+}
+
+impl Default for OpenAiConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://api.groq.com/openai/v1".to_string(),
+            model: "llama-3.3-70b-versatile".to_string(),
+            api_key: None,
+            timeout: Duration::from_secs(15),
+            max_retries: 2,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    temperature: f32,
+    max_tokens: u32,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChatCompletionChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatCompletionChoice>,
+}
+
+// Client for any server speaking the OpenAI chat completions API (Groq, llama.cpp, Ollama, ...)
+pub struct OpenAiClient {
+    client: reqwest::Client,
+    config: OpenAiConfig,
+}
+
+impl OpenAiClient {
+    pub fn new(config: OpenAiConfig) -> LlmResult<Self> {
+        // Reuse a single HTTP client so connections are pooled between calls
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()?;
+
+        Ok(Self { client, config })
+    }
+
+    fn completions_url(&self) -> String {
+        format!("{}/chat/completions", self.config.base_url.trim_end_matches('/'))
+    }
+
+    async fn send_once(&self, request: &ChatCompletionRequest<'_>) -> LlmResult<String> {
+        let mut builder = self.client.post(self.completions_url()).json(request);
+
+        if let Some(api_key) = &self.config.api_key {
+            builder = builder.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = builder
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ChatCompletionResponse>()
+            .await?;
+
+        match response.choices.into_iter().next() {
+            Some(choice) => Ok(choice.message.content.trim().to_string()),
+            None => Err("LLM returned no choices".into()),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for OpenAiClient {
+    async fn complete(&self, messages: &[ChatMessage], temperature: f32, max_tokens: u32) -> LlmResult<String> {
+        let request = ChatCompletionRequest {
+            model: &self.config.model,
+            messages,
+            temperature,
+            max_tokens,
+        };
+
+        let mut attempt = 0;
+        loop {
+            match self.send_once(&request).await {
+                Ok(reply) => return Ok(reply),
+                Err(e) if attempt < self.config.max_retries => {
+                    eprintln!("LLM request failed (attempt {}): {}", attempt + 1, e);
+
+                    // Back off a little more after each failure
+                    tokio::time::sleep(retry_delay(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+// Wait before retry number `attempt` (from zero), doubling each time up to MAX_BACKOFF
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_millis(2u64.saturating_pow(attempt).saturating_mul(500)).min(MAX_BACKOFF)
+}
+
+// In-process client that never touches the network. Replies are taken from a
+// queue (falling back to a default reply) and every prompt is recorded.
+pub struct MockLlmClient {
+    default_reply: Option<String>,
+    replies: Mutex<VecDeque<String>>,
+    requests: Mutex<Vec<Vec<ChatMessage>>>,
+}
+
+#[allow(dead_code)]
+impl MockLlmClient {
+    pub fn new(default_reply: &str) -> Self {
+        Self {
+            default_reply: Some(default_reply.to_string()),
+            replies: Mutex::new(VecDeque::new()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    // A mock whose every call fails, for exercising fallbacks
+    pub fn failing() -> Self {
+        Self {
+            default_reply: None,
+            replies: Mutex::new(VecDeque::new()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn push_reply(&self, reply: &str) {
+        self.replies.lock().unwrap().push_back(reply.to_string());
+    }
+
+    pub fn requests(&self) -> Vec<Vec<ChatMessage>> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl LlmClient for MockLlmClient {
+    async fn complete(&self, messages: &[ChatMessage], _temperature: f32, _max_tokens: u32) -> LlmResult<String> {
+        self.requests.lock().unwrap().push(messages.to_vec());
+
+        if let Some(reply) = self.replies.lock().unwrap().pop_front() {
+            return Ok(reply);
+        }
+
+        match &self.default_reply {
+            Some(reply) => Ok(reply.clone()),
+            None => Err("mock LLM failure".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(retry_delay(0), Duration::from_millis(500));
+        assert_eq!(retry_delay(2), Duration::from_secs(2));
+        assert_eq!(retry_delay(10), MAX_BACKOFF);
+        assert_eq!(retry_delay(u32::MAX), MAX_BACKOFF);
+    }
+}
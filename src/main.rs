@@ -1,12 +1,12 @@
 mod commands;
 mod db;
+mod llm;
 
 use poise::serenity_prelude as serenity;
 use dotenv::dotenv;
 use std::env;
 use rand::Rng;
-use serde::{Deserialize, Serialize};
-use reqwest;
+use std::sync::Arc;
 
 // Define a type for the user data that will be passed to all command functions
 type CommandError = Box<dyn std::error::Error + Send + Sync>;
@@ -15,6 +15,8 @@ type Context<'a> = poise::Context<'a, Data, CommandError>;
 // User data that is stored and accessible in all command functions
 pub struct Data {
     db: db::Database,
+    llm: Option<Arc<dyn llm::LlmClient>>,
+    games: Arc<commands::GameRegistry>,
     // voting_state: Arc<Mutex<commands::VotingState>>,
 }
 
@@ -30,6 +32,32 @@ async fn main() {
     let db_path = "boopato.db";
     let database = db::Database::new(db_path).await.expect("Failed to initialize database");
     
+    // Drop saved games too old to resume; the rest continue where they left off
+    match database.prune_active_games(
+        SAVED_GAME_MAX_AGE_SECS,
+        &commands::correspondence_games(),
+        commands::CORRESPONDENCE_IDLE_SECS,
+    ).await {
+        Ok(0) => {},
+        Ok(count) => println!("Dropped {} stale saved games", count),
+        Err(e) => eprintln!("Failed to prune saved games: {}", e),
+    }
+    
+    // Return any boops left in escrow by interrupted games that can't be resumed
+    match database.refund_open_escrows().await {
+        Ok(0) => {},
+        Ok(count) => println!("Refunded {} interrupted game wagers", count),
+        Err(e) => eprintln!("Failed to refund interrupted game wagers: {}", e),
+    }
+    
+    // Note where saved games are waiting for guesses typed in chat
+    let games = Arc::new(commands::GameRegistry::default());
+    match commands::watch_resumable_channels(&database, &games).await {
+        Ok(0) => {},
+        Ok(count) => println!("Resuming saved games in {} channels", count),
+        Err(e) => eprintln!("Failed to load saved game channels: {}", e),
+    }
+    
     // Define the framework configuration with all commands
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
@@ -47,12 +75,28 @@ async fn main() {
                 commands::reset_server(),
                 commands::distribute(),
                 commands::sync_users(),
+                commands::chat_income(),
+                commands::autoreact(),
+                commands::kgb(),
+                commands::dossier(),
                 commands::game(),
                 commands::tictactoe(),
                 commands::clicker(),
                 commands::connect4(),
+                commands::battleship(),
+                commands::chess(),
+                commands::checkers(),
+                commands::blackjack(),
+                commands::rank(),
+                commands::leaderboard(),
+                commands::stats(),
                 commands::kremlin_secrets(),
                 commands::soviet_hangman(),
+                commands::daily(),
+                commands::trivia(),
+                commands::trivia_pack(),
+                commands::wordbank(),
+                commands::hangman_settings(),
                 commands::redistribute(),
                 commands::schedule_redistribution(),
                 commands::about(),
@@ -113,7 +157,9 @@ async fn main() {
                 }
                 
                 Ok(Data {
-                    db: database
+                    db: database,
+                    llm: llm::from_env(),
+                    games,
                 })
             })
         });
@@ -136,6 +182,11 @@ async fn event_handler(
                 return Ok(());
             }
             
+            // Guesses for a resumed Kremlin Secrets game arrive as ordinary messages
+            if let Err(e) = commands::route_message(ctx, data, new_message).await {
+                eprintln!("Failed to resume game: {}", e);
+            }
+            
             // Get user and server info
             let user_id = new_message.author.id.to_string();
             let username = new_message.author.name.clone();
@@ -165,49 +216,96 @@ async fn event_handler(
                 if let Err(e) = data.db.add_message_count(&user_id).await {
                     eprintln!("Failed to increment message count: {}", e);
                 }
-                
-                // Check for collective words and react with a suitable standard emoji
+
+                // Award passive chat income for participating in the conversation
+                match data.db.get_chat_income_settings(&server_id).await {
+                    Ok(settings) if settings.enabled => {
+                        let normalized = normalize_chat_message(&new_message.content);
+
+                        // Short messages never earn anything and don't count towards duplicate detection
+                        if normalized.chars().count() >= settings.min_length.max(0) as usize {
+                            if let Err(e) = data.db.award_chat_income(&user_id, &server_id, &normalized, &settings).await {
+                                eprintln!("Failed to award chat income: {}", e);
+                            }
+                        }
+                    },
+                    Ok(_) => {},
+                    Err(e) => eprintln!("Failed to get chat income settings: {}", e),
+                }
+
                 let content = new_message.content.to_lowercase();
-                let collective_words = ["we", "our", "together", "comrade", "collective", "unity"];
                 
-                let should_react = collective_words.iter().any(|&word| {
-                    content.split(|c: char| !c.is_alphanumeric()).any(|part| part == word)
-                });
-                
-                // Random chance (10%) to react even if there's a match to avoid being too spammy
-                let should_react_random = {
-                    let mut rng = rand::thread_rng();
-                    should_react && rng.gen_bool(1.0)
-                };
-                
-                if should_react_random {
-                    // React with the custom USSR Hammer emoji
-                    let custom_emoji = serenity::ReactionType::Custom {
-                        animated: false,
-                        id: serenity::EmojiId(1353614767545389097),
-                        name: Some("USSR_Hammer".to_string()),
-                    };
-                    
-                    if let Err(e) = new_message.react(ctx, custom_emoji).await {
-                        eprintln!("Failed to react with emoji: {}", e);
-                    }
+                // Apply this server's auto-reaction rules
+                match data.db.get_autoreact_rules(&server_id).await {
+                    Ok(rules) => {
+                        let channel_id = new_message.channel_id.to_string();
+                        
+                        for rule in rules {
+                            // Skip rules restricted to other channels
+                            if !rule.channels.is_empty() && !rule.channels.contains(&channel_id) {
+                                continue;
+                            }
+                            
+                            if !commands::autoreact_matches(&rule, &new_message.content) {
+                                continue;
+                            }
+                            
+                            // Roll the rule's reaction chance before touching the cooldown
+                            let should_react = {
+                                let mut rng = rand::thread_rng();
+                                rng.gen_bool(rule.probability.clamp(0.0, 1.0))
+                            };
+                            
+                            if !should_react {
+                                continue;
+                            }
+                            
+                            match data.db.try_start_autoreact_cooldown(rule.id, &user_id, rule.cooldown_secs).await {
+                                Ok(true) => {},
+                                Ok(false) => continue,
+                                Err(e) => {
+                                    eprintln!("Failed to check reaction cooldown: {}", e);
+                                    continue;
+                                }
+                            }
+                            
+                            match rule.emoji.parse::<serenity::ReactionType>() {
+                                Ok(reaction) => {
+                                    if let Err(e) = new_message.react(ctx, reaction).await {
+                                        eprintln!("Failed to react with emoji: {}", e);
+                                    }
+                                },
+                                Err(_) => eprintln!("Invalid emoji in reaction rule #{}", rule.id),
+                            }
+                        }
+                    },
+                    Err(e) => eprintln!("Failed to get reaction rules: {}", e),
                 }
                 
                 // Implement KGB listener functionality - random chance of the bot "overhearing" conversations
-                // Only trigger on longer messages (>20 chars) with very low probability (0.5%)
-                let should_trigger_kgb = {
-                    let mut rng = rand::thread_rng();
-                    content.len() > 20 && rng.gen_bool(1.0)
-                };
+                // Only trigger on longer messages (>20 chars), subject to the server's surveillance directives
+                let should_trigger_kgb = content.len() > 20
+                    && should_trigger_kgb(data, new_message, &server_id, &user_id).await;
                 
                 if should_trigger_kgb {
                     // Trigger KGB response
-                    if let Err(e) = kgb_listener(ctx, new_message, &content).await {
+                    if let Err(e) = kgb_listener(ctx, data, new_message, &content).await {
                         eprintln!("Failed to process KGB listener: {}", e);
                     }
                 }
             }
         },
+        poise::Event::InteractionCreate { interaction: serenity::Interaction::MessageComponent(press) } => {
+            // Resume saved games whose commands were interrupted by a restart
+            if let Err(e) = commands::route_component_interaction(ctx, data, press).await {
+                eprintln!("Failed to resume game: {}", e);
+            }
+        },
+        poise::Event::InteractionCreate { interaction: serenity::Interaction::ModalSubmit(submit) } => {
+            if let Err(e) = commands::route_modal_submit(ctx, data, submit).await {
+                eprintln!("Failed to resume game: {}", e);
+            }
+        },
         poise::Event::GuildMemberAddition { new_member } => {
             // Skip bots
             if new_member.user.bot {
@@ -338,107 +436,195 @@ async fn event_handler(
     Ok(())
 }
 
+// Normalize a chat message for chat income checks so trivial variations
+// (case, repeated whitespace) still count as duplicates
+fn normalize_chat_message(content: &str) -> String {
+    content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// Decide whether the KGB listener overhears a message. Every check here runs
+// before any LLM call is made, and the cooldowns only start once all others pass.
+async fn should_trigger_kgb(
+    data: &Data,
+    message: &serenity::Message,
+    server_id: &str,
+    user_id: &str,
+) -> bool {
+    let settings = match data.db.get_kgb_settings(server_id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("Failed to get KGB settings: {}", e);
+            return false;
+        }
+    };
+    
+    if !settings.enabled {
+        return false;
+    }
+    
+    // Only listen in the allowed channels
+    if !settings.channels.is_empty() && !settings.channels.contains(&message.channel_id.to_string()) {
+        return false;
+    }
+    
+    // Roll the server's trigger probability
+    let roll = {
+        let mut rng = rand::thread_rng();
+        rng.gen_bool(settings.probability.clamp(0.0, 1.0))
+    };
+    
+    if !roll {
+        return false;
+    }
+    
+    // Comrades who opted out are never surveilled
+    match data.db.is_kgb_opted_out(user_id, server_id).await {
+        Ok(false) => {},
+        Ok(true) => return false,
+        Err(e) => {
+            eprintln!("Failed to check KGB opt-out: {}", e);
+            return false;
+        }
+    }
+    
+    match data.db.try_start_kgb_cooldown(user_id, server_id, &settings).await {
+        Ok(allowed) => allowed,
+        Err(e) => {
+            eprintln!("Failed to check KGB cooldown: {}", e);
+            false
+        }
+    }
+}
+
+// Saved games older than this are not resumed at startup
+const SAVED_GAME_MAX_AGE_SECS: i64 = 24 * 60 * 60;
+
+// Phrases the KGB listens for most closely, with the canned reply used when no LLM is available
+const KGB_TRIGGERS: [(&str, &str); 5] = [
+    ("revolution", "The KGB is watching your revolutionary activities with great interest, comrade..."),
+    ("overthrow", "Plotting against the state, are we? The KGB has noted your... enthusiasm."),
+    ("capitalism", "Ah, discussing the enemy's economic system? The KGB approves of your educational pursuits."),
+    ("freedom", "Freedom? The KGB reminds you that true freedom comes through service to the state."),
+    ("western", "The KGB advises caution when discussing western influences, comrade."),
+];
+
+// Generic replies for messages without specific triggers
+const KGB_GENERIC_RESPONSES: [&str; 5] = [
+    "The KGB has noted your conversation, comrade. Carry on.",
+    "Your words have been recorded for future reference. Glory to the motherland!",
+    "The eyes of the state see all, comrade. Your dedication is... noted.",
+    "The KGB would like to remind you that loyalty to the collective is paramount.",
+    "The KGB appreciates your contribution to the discourse, comrade.",
+];
+
+// How many overheard remarks a dossier keeps, and how many are shown to the LLM
+const DOSSIER_MAX_ENTRIES: i64 = 20;
+const DOSSIER_PROMPT_ENTRIES: i64 = 5;
+
+// Longest remark that gets filed in a dossier
+const DOSSIER_REMARK_MAX_CHARS: usize = 200;
+
 // KGB listener feature - bot "overhears" conversations and comments
 async fn kgb_listener(
     ctx: &serenity::Context, 
+    data: &Data,
     message: &serenity::Message,
     content: &str
 ) -> Result<(), CommandError> {
-    // Check for specific trigger phrases with higher priority
-    let triggers = [
-        ("revolution", "The KGB is watching your revolutionary activities with great interest, comrade..."),
-        ("overthrow", "Plotting against the state, are we? The KGB has noted your... enthusiasm."),
-        ("capitalism", "Ah, discussing the enemy's economic system? The KGB approves of your educational pursuits."),
-        ("freedom", "Freedom? The KGB reminds you that true freedom comes through service to the state."),
-        ("western", "The KGB advises caution when discussing western influences, comrade."),
-    ];
+    let user_id = message.author.id.to_string();
+    let server_id = message.guild_id.map(|id| id.to_string()).unwrap_or_default();
     
-    // Try to fetch Groq API key from environment variables
-    let groq_api_key = env::var("GROQ_API_KEY").ok();
+    // Pull the comrade's file so the agent can bring up past offenses
+    let history = match data.db.get_dossier(&user_id, &server_id, DOSSIER_PROMPT_ENTRIES).await {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("Failed to get dossier: {}", e);
+            Vec::new()
+        }
+    };
     
-    if let Some(api_key) = groq_api_key {
-        // We have an API key, so let's use Groq to generate a response
+    if data.llm.is_some() {
         // Create a typing indicator to show the bot is thinking
         let _ = message.channel_id.broadcast_typing(&ctx.http).await;
+    }
+    
+    let response = kgb_response(data.llm.as_deref(), content, &history).await;
+    message.reply(ctx, response).await?;
+    
+    // File the remark for next time
+    let remark: String = message.content.chars().take(DOSSIER_REMARK_MAX_CHARS).collect();
+    let trigger_word = kgb_trigger(content).map(|(word, _)| *word);
+    
+    if let Err(e) = data.db.add_dossier_entry(&user_id, &server_id, &remark, trigger_word, DOSSIER_MAX_ENTRIES).await {
+        eprintln!("Failed to file dossier entry: {}", e);
+    }
+    
+    Ok(())
+}
+
+// Find the first high-priority trigger phrase in a message
+fn kgb_trigger(content: &str) -> Option<&'static (&'static str, &'static str)> {
+    KGB_TRIGGERS.iter().find(|(trigger, _)| content.contains(trigger))
+}
+
+// Pick the KGB's reply to an overheard message, asking the LLM first and
+// falling back to pre-written responses if it isn't configured or fails
+async fn kgb_response(llm: Option<&dyn llm::LlmClient>, content: &str, history: &[db::DossierEntry]) -> String {
+    let trigger = kgb_trigger(content);
+    
+    if let Some(llm) = llm {
+        let trigger_word = trigger.map(|(word, _)| *word).unwrap_or("");
         
-        // Check if any high-priority triggers match first
-        for (trigger, _) in triggers.iter() {
-            if content.contains(trigger) {
-                // For triggered words, use Groq API to generate a themed response
-                if let Ok(response) = generate_kgb_response(&api_key, content, trigger).await {
-                    // If we get a valid response, use it
-                    let _ = message.reply(ctx, response).await;
-                    return Ok(());
-                }
-            }
-        }
-        
-        // If no specific triggers or API call failed, fall back to generic responses
-        if let Ok(response) = generate_kgb_response(&api_key, content, "").await {
-            let _ = message.reply(ctx, response).await;
-            return Ok(());
+        match generate_kgb_response(llm, content, trigger_word, history).await {
+            Ok(response) if !response.trim().is_empty() => return response.trim().to_string(),
+            Ok(_) => eprintln!("LLM returned an empty KGB response"),
+            Err(e) => eprintln!("Failed to generate KGB response: {}", e),
         }
     }
     
-    // Fallback to pre-written responses if Groq API isn't available or fails
-    
     // Check if any high-priority triggers match
-    for (trigger, response) in triggers.iter() {
-        if content.contains(trigger) {
-            let _ = message.reply(ctx, response).await;
-            return Ok(());
-        }
+    if let Some((_, response)) = trigger {
+        return response.to_string();
     }
     
     // For messages without specific triggers, use a generic response
-    let generic_responses = [
-        "The KGB has noted your conversation, comrade. Carry on.",
-        "Your words have been recorded for future reference. Glory to the motherland!",
-        "The eyes of the state see all, comrade. Your dedication is... noted.",
-        "The KGB would like to remind you that loyalty to the collective is paramount.",
-        "The KGB appreciates your contribution to the discourse, comrade."
-    ];
-    
-    // Get the random index before await
     let response_idx = {
         let mut rng = rand::thread_rng();
-        rng.gen_range(0..generic_responses.len())
+        rng.gen_range(0..KGB_GENERIC_RESPONSES.len())
     };
-    let response = generic_responses[response_idx];
-    let _ = message.reply(ctx, response).await;
     
-    Ok(())
-}
-
-// API structures for Groq
-#[derive(Serialize, Deserialize, Debug)]
-struct GroqMessage {
-    role: String,
-    content: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct GroqRequest {
-    model: String,
-    messages: Vec<GroqMessage>,
-    temperature: f32,
-    max_tokens: u32,
+    KGB_GENERIC_RESPONSES[response_idx].to_string()
 }
 
-#[derive(Deserialize, Debug)]
-struct GroqResponseChoice {
-    message: GroqMessage,
-}
-
-#[derive(Deserialize, Debug)]
-struct GroqResponse {
-    choices: Vec<GroqResponseChoice>,
+// Summarize a comrade's dossier for the LLM, newest entries first
+fn dossier_prompt(history: &[db::DossierEntry]) -> Option<String> {
+    if history.is_empty() {
+        return None;
+    }
+    
+    let mut prompt = String::from("Your dossier on this citizen records these earlier overheard remarks:\n");
+    for entry in history {
+        match &entry.trigger_word {
+            Some(word) => prompt.push_str(&format!("- \"{}\" (flagged for '{}')\n", entry.remark, word)),
+            None => prompt.push_str(&format!("- \"{}\"\n", entry.remark)),
+        }
+    }
+    prompt.push_str("Where it fits, remind them that their past offenses have not been forgotten.");
+    
+    Some(prompt)
 }
 
-// Function to generate KGB-themed responses using Groq API
-async fn generate_kgb_response(api_key: &str, user_message: &str, trigger_word: &str) -> Result<String, reqwest::Error> {
-    let client = reqwest::Client::new();
-    
+// Function to generate KGB-themed responses using the configured LLM
+async fn generate_kgb_response(
+    llm: &dyn llm::LlmClient,
+    user_message: &str,
+    trigger_word: &str,
+    history: &[db::DossierEntry]
+) -> llm::LlmResult<String> {
     // Create system prompt based on whether there's a trigger word
     let system_prompt = if trigger_word.is_empty() {
         "You are a KGB agent in 1970s Soviet Union who has overheard a conversation. Respond with a brief, ominous but subtly humorous comment (1-2 sentences only). Your tone should be surveillance-oriented, slightly intimidating, with dark humor. Use occasional Russian terms or Soviet phrases. Always imply you're monitoring citizens for loyalty. Never break character or acknowledge you're an AI.".to_string()
@@ -446,38 +632,104 @@ async fn generate_kgb_response(api_key: &str, user_message: &str, trigger_word:
         format!("You are a KGB agent in 1970s Soviet Union who has overheard someone mention '{}'. Respond with a brief, ominous but subtly humorous comment specifically about this topic (1-2 sentences only). Your tone should be surveillance-oriented, slightly intimidating, with dark humor. Use occasional Russian terms or Soviet phrases. Always imply you're monitoring citizens for loyalty. Never break character or acknowledge you're an AI.", trigger_word)
     };
     
-    // Build request payload
-    let request = GroqRequest {
-        model: "llama-3.3-70b-versatile".to_string(), // Use Llama 3.3 model
-        messages: vec![
-            GroqMessage {
-                role: "system".to_string(),
-                content: system_prompt.to_string(),
-            },
-            GroqMessage {
-                role: "user".to_string(),
-                content: format!("Someone said: \"{}\"", user_message),
-            },
-        ],
-        temperature: 0.7,
-        max_tokens: 100,
-    };
+    let mut messages = vec![llm::ChatMessage::system(system_prompt)];
+    
+    if let Some(dossier) = dossier_prompt(history) {
+        messages.push(llm::ChatMessage::system(dossier));
+    }
     
-    // Send request to Groq API
-    let response = client
-        .post("https://api.groq.com/openai/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .json(&request)
-        .send()
-        .await?
-        .json::<GroqResponse>()
-        .await?;
+    messages.push(llm::ChatMessage::user(format!("Someone said: \"{}\"", user_message)));
     
-    // Extract and return the AI-generated response
-    if let Some(choice) = response.choices.first() {
-        Ok(choice.message.content.clone())
-    } else {
-        // Fallback if no response
-        Ok("The KGB is watching, comrade...".to_string())
+    llm.complete(&messages, 0.7, 100).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm::MockLlmClient;
+
+    #[tokio::test]
+    async fn kgb_uses_llm_reply_with_trigger_in_prompt() {
+        let mock = MockLlmClient::new("unused");
+        mock.push_reply("  The revolution will be televised... to the KGB.  ");
+
+        let response = kgb_response(Some(&mock), "we should start a revolution tomorrow", &[]).await;
+        assert_eq!(response, "The revolution will be televised... to the KGB.");
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0][0].role, "system");
+        assert!(requests[0][0].content.contains("'revolution'"));
+        assert!(requests[0][1].content.contains("we should start a revolution tomorrow"));
+    }
+
+    #[tokio::test]
+    async fn kgb_generic_prompt_without_trigger() {
+        let mock = MockLlmClient::new("Noted, comrade.");
+
+        let response = kgb_response(Some(&mock), "what is everyone having for dinner", &[]).await;
+        assert_eq!(response, "Noted, comrade.");
+        assert!(mock.requests()[0][0].content.contains("overheard a conversation"));
+    }
+
+    #[tokio::test]
+    async fn kgb_prompt_includes_dossier() {
+        let mock = MockLlmClient::new("We remember, comrade.");
+        let history = vec![
+            db::DossierEntry {
+                remark: "down with the overthrow of the state".to_string(),
+                trigger_word: Some("overthrow".to_string()),
+                recorded_at: 0,
+            },
+            db::DossierEntry {
+                remark: "i like turnips".to_string(),
+                trigger_word: None,
+                recorded_at: 0,
+            },
+        ];
+
+        let response = kgb_response(Some(&mock), "anyone want to grab lunch", &history).await;
+        assert_eq!(response, "We remember, comrade.");
+
+        let request = &mock.requests()[0];
+        assert_eq!(request.len(), 3);
+        assert_eq!(request[1].role, "system");
+        assert!(request[1].content.contains("\"down with the overthrow of the state\" (flagged for 'overthrow')"));
+        assert!(request[1].content.contains("\"i like turnips\""));
+        assert_eq!(request[2].role, "user");
+    }
+
+    #[tokio::test]
+    async fn kgb_prompt_without_dossier_has_no_history() {
+        let mock = MockLlmClient::new("Noted.");
+
+        kgb_response(Some(&mock), "a perfectly innocent message", &[]).await;
+        assert_eq!(mock.requests()[0].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn kgb_falls_back_when_llm_fails() {
+        let mock = MockLlmClient::failing();
+
+        let response = kgb_response(Some(&mock), "capitalism is on my mind today", &[]).await;
+        assert_eq!(response, KGB_TRIGGERS[2].1);
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn kgb_falls_back_on_empty_reply() {
+        let mock = MockLlmClient::new("");
+
+        let response = kgb_response(Some(&mock), "nothing suspicious going on here", &[]).await;
+        assert!(KGB_GENERIC_RESPONSES.contains(&response.as_str()));
+    }
+
+    #[tokio::test]
+    async fn kgb_without_llm_uses_canned_responses() {
+        let response = kgb_response(None, "i dream of western jeans", &[]).await;
+        assert_eq!(response, KGB_TRIGGERS[4].1);
+
+        let response = kgb_response(None, "just a regular message here", &[]).await;
+        assert!(KGB_GENERIC_RESPONSES.contains(&response.as_str()));
     }
 }